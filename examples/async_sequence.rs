@@ -0,0 +1,48 @@
+/// A loading screen and a scripted sequence written as linear async code.
+use kapp::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+fn main() {
+    run_async(run);
+}
+
+async fn run(app: Application, events: Events) {
+    let window = app.new_window().title("Async Sequence").build().unwrap();
+
+    // Report progress on a separate task while "loading".
+    let loaded = Rc::new(Cell::new(false));
+    {
+        let loaded = loaded.clone();
+        spawn_local(async move {
+            while !loaded.get() {
+                println!("Loading...");
+                sleep(Duration::from_millis(250)).await;
+            }
+        });
+    }
+    sleep(Duration::from_secs(1)).await;
+    loaded.set(true);
+
+    window.request_redraw();
+    events.next_draw(window.id).await;
+    println!("Drew the first frame. Press any key to continue.");
+
+    events
+        .next_matching(|event| matches!(event, Event::KeyDown { .. }))
+        .await;
+    for step in 1..=3 {
+        println!("Step {}", step);
+        sleep(Duration::from_millis(500)).await;
+    }
+    println!("Sequence finished");
+
+    loop {
+        match events.next().await {
+            Event::WindowCloseRequested { .. } => app.quit(),
+            Event::Draw { .. } => {}
+            _ => {}
+        }
+    }
+}
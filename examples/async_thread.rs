@@ -0,0 +1,73 @@
+/// Awaits a value computed on another thread without blocking the event loop.
+/// The thread wakes the program when it finishes.
+use kapp::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+fn main() {
+    run_async(run);
+}
+
+async fn run(app: Application, events: Events) {
+    let _window = app.new_window().title("Async Thread").build().unwrap();
+
+    let answer = spawn_thread(|| {
+        std::thread::sleep(Duration::from_secs(1));
+        42
+    })
+    .await;
+    println!("The answer is {}", answer);
+
+    loop {
+        match events.next().await {
+            Event::WindowCloseRequested { .. } => app.quit(),
+            Event::Draw { .. } => {}
+            _ => {}
+        }
+    }
+}
+
+struct Shared<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+struct ThreadFuture<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// Runs a function on another thread. The future completes when the function returns.
+fn spawn_thread<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> ThreadFuture<T> {
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+    let thread_shared = shared.clone();
+    std::thread::spawn(move || {
+        let result = f();
+        let mut shared = thread_shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+    ThreadFuture { shared }
+}
+
+impl<T> Future for ThreadFuture<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/// Ticks once a second without requesting redraws.
+/// Press P to poll continuously, and W to go back to waiting.
+use kapp::*;
+use std::time::{Duration, Instant};
+
+fn main() {
+    let (app, event_loop) = initialize();
+    let _window = app.new_window().title("Control Flow").build().unwrap();
+
+    let tick = Duration::from_secs(1);
+    app.set_control_flow(ControlFlow::WaitUntil(Instant::now() + tick));
+
+    let mut polls = 0;
+    event_loop.run(move |event| match event {
+        Event::ResumeTimeReached => {
+            println!("Tick");
+            app.set_control_flow(ControlFlow::WaitUntil(Instant::now() + tick));
+        }
+        Event::EventsCleared => polls += 1,
+        Event::KeyDown { key: Key::P, .. } => {
+            polls = 0;
+            app.set_control_flow(ControlFlow::Poll);
+        }
+        Event::KeyDown { key: Key::W, .. } => {
+            println!("Polled {} times", polls);
+            app.set_control_flow(ControlFlow::WaitUntil(Instant::now() + tick));
+        }
+        Event::WindowCloseRequested { .. } => app.quit(),
+        _ => {}
+    });
+}
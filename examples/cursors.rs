@@ -0,0 +1,49 @@
+/// Cycles through every cursor when a key is pressed.
+use kapp::*;
+
+fn main() {
+    let (app, event_loop) = initialize();
+    let _window = app.new_window().title("Cursors").build().unwrap();
+
+    // A 16x16 red square with a transparent center and the hotspot in the middle.
+    let mut rgba = Vec::new();
+    for y in 0..16 {
+        for x in 0..16 {
+            let border = !(2..14).contains(&x) || !(2..14).contains(&y);
+            rgba.extend_from_slice(&[255, 0, 0, if border { 255 } else { 0 }]);
+        }
+    }
+    let custom_cursor = app.create_cursor(&rgba, 16, 16, (8, 8));
+
+    let cursors = [
+        Cursor::Arrow,
+        Cursor::IBeam,
+        Cursor::PointingHand,
+        Cursor::OpenHand,
+        Cursor::ClosedHand,
+        Cursor::ResizeNS,
+        Cursor::ResizeEW,
+        Cursor::ResizeNESW,
+        Cursor::ResizeNWSE,
+        Cursor::Crosshair,
+        Cursor::NotAllowed,
+        Cursor::Wait,
+        Cursor::Progress,
+        Cursor::Move,
+        Cursor::Help,
+        Cursor::ZoomIn,
+        Cursor::ZoomOut,
+        Cursor::Custom(custom_cursor),
+    ];
+    let mut index = 0;
+
+    event_loop.run(move |event| match event {
+        Event::KeyDown { .. } => {
+            index = (index + 1) % cursors.len();
+            println!("{:?}", cursors[index]);
+            app.set_cursor(cursors[index]);
+        }
+        Event::WindowCloseRequested { .. } => app.quit(),
+        _ => {}
+    });
+}
@@ -0,0 +1,31 @@
+/// Loads "assets" on a background thread that reports its progress to the event loop.
+use kapp::*;
+use std::time::Duration;
+
+fn main() {
+    let (app, event_loop) = initialize();
+    let _window = app.new_window().title("Event Loop Proxy").build().unwrap();
+
+    let proxy = app.proxy();
+    std::thread::spawn(move || {
+        for asset in 1..=3 {
+            std::thread::sleep(Duration::from_millis(300));
+            proxy.send_user_event(Box::new(format!("Loaded asset {}", asset)));
+        }
+        proxy.wake();
+    });
+
+    event_loop.run(move |event| match event {
+        Event::User { data } => {
+            if let Some(message) = data.take_as::<String>() {
+                println!("{}", message);
+            }
+        }
+        Event::Wake => {
+            println!("Finished loading");
+            app.quit();
+        }
+        Event::WindowCloseRequested { .. } => app.quit(),
+        _ => {}
+    });
+}
@@ -0,0 +1,50 @@
+extern crate kapp;
+use kapp::*;
+use std::time::Duration;
+
+// Run with `cargo run --example headless --no-default-features --features headless`
+fn main() {
+    let (app, event_loop) = initialize();
+    let window = app
+        .new_window()
+        .title("Headless")
+        .size(400, 300)
+        .build()
+        .unwrap();
+
+    // Input that a display would normally provide.
+    let mut simulator = InputSimulator::new(&app, window.id);
+    simulator.type_string("Hi");
+    simulator.click(10.0, 20.0, PointerButton::Primary);
+    queue_event(Event::WindowCloseRequested {
+        window_id: window.id,
+    });
+
+    window.set_size(800, 600);
+    println!("{:?}", window_record(window.id).unwrap());
+
+    // The clipboard is stored in memory.
+    app.clipboard().set_text("Copied");
+    println!("Clipboard: {:?}", app.clipboard().get_text());
+
+    // Screens can be changed to test how a program responds.
+    let mut screens = app.screens();
+    screens[0].scale = 2.0;
+    set_screens(screens);
+
+    // Fullscreen is recorded and reported like a real platform.
+    let video_mode = app.screens()[0].video_modes[1];
+    window.set_fullscreen(Fullscreen::Exclusive(video_mode));
+    println!("Fullscreen: {}", window.is_fullscreen());
+    window.set_fullscreen(Fullscreen::Off);
+
+    // Timers use a virtual clock, so they're only due once time is advanced.
+    app.set_timer(Duration::from_millis(500), true);
+    advance_time(Duration::from_secs(1));
+
+    event_loop.run(move |event| match event {
+        Event::WindowCloseRequested { .. } => app.quit(),
+        Event::EventsCleared => {}
+        _ => println!("{:?}", event),
+    });
+}
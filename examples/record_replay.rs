@@ -0,0 +1,38 @@
+extern crate kapp;
+use kapp::*;
+
+// Run with `cargo run --example record_replay --features serde -- record`
+// to record events to `events.txt`.
+// Then run `cargo run --example record_replay --features serde` to replay them.
+fn main() {
+    let record = std::env::args().any(|arg| arg == "record");
+
+    let (app, event_loop) = initialize();
+    let window = app.new_window().title("Record and Replay").build().unwrap();
+
+    let mut recorder = if record {
+        Some(EventRecorder::create("events.txt").unwrap())
+    } else {
+        None
+    };
+    let mut player = if record {
+        None
+    } else {
+        Some(EventPlayer::open(&app, "events.txt", &[window.id], ReplaySpeed::Original).unwrap())
+    };
+
+    event_loop.run(move |event| {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(&event).unwrap();
+        }
+        if let Some(player) = player.as_mut() {
+            player.handle_event(&event);
+        }
+
+        match event {
+            Event::WindowCloseRequested { .. } => app.quit(),
+            Event::Draw { .. } | Event::EventsCleared => {}
+            _ => println!("{:?}", event),
+        }
+    });
+}
@@ -0,0 +1,42 @@
+/// Lists the screens and reports when they change.
+/// Press B for borderless fullscreen, E for exclusive fullscreen, and Escape to leave fullscreen.
+use kapp::*;
+
+fn main() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().title("Screens").build().unwrap();
+
+    for screen in app.screens() {
+        println!("{:?}", screen);
+    }
+
+    event_loop.run(move |event| match event {
+        Event::ScreenAdded { .. } | Event::ScreenRemoved { .. } | Event::ScreenChanged { .. } => {
+            println!("{:?}", event);
+            for screen in app.screens() {
+                println!("{:?}", screen);
+            }
+        }
+        Event::WindowMoved { .. } => println!("Window is on: {:?}", window.current_screen()),
+        Event::KeyDown { key: Key::B, .. } => window.set_fullscreen(Fullscreen::default()),
+        Event::KeyDown { key: Key::E, .. } => {
+            // The largest mode with the highest refresh rate.
+            let video_mode = window.current_screen().and_then(|screen| {
+                screen.video_modes.into_iter().max_by(|a, b| {
+                    (a.size.0 * a.size.1, a.refresh_rate)
+                        .partial_cmp(&(b.size.0 * b.size.1, b.refresh_rate))
+                        .unwrap()
+                })
+            });
+            if let Some(video_mode) = video_mode {
+                window.set_fullscreen(Fullscreen::Exclusive(video_mode));
+            }
+        }
+        Event::KeyDown {
+            key: Key::Escape, ..
+        } => window.set_fullscreen(Fullscreen::Off),
+        Event::WindowFullscreened { .. } | Event::WindowRestored { .. } => println!("{:?}", event),
+        Event::WindowCloseRequested { .. } => app.quit(),
+        _ => {}
+    });
+}
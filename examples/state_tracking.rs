@@ -12,6 +12,9 @@ fn main() {
 
         match event {
             Event::WindowCloseRequested { .. } => app.quit(),
+            Event::WindowResized { window_id, .. } => {
+                println!("Window state: {:?}", app.window_state(window_id))
+            }
             _ => {}
         }
     });
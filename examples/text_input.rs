@@ -9,7 +9,7 @@ fn main() {
     event_loop.run(move |event| match event {
         // Just log text input related events.
         Event::IMEComposition { .. }
-        | Event::IMEEndComposition
+        | Event::IMEEndComposition { .. }
         | Event::CharacterReceived { .. } => println!("{:?}", event),
         Event::WindowCloseRequested { .. } => app.quit(),
         _ => {}
@@ -12,6 +12,8 @@ pub struct GLContextAttributes {
     pub webgl_version: WebGLVersion,
     /// Mac specific, should the framebuffer be allocated with a higher resolution.
     pub high_resolution_framebuffer: bool,
+    /// Should the window show through where alpha is below 1.
+    pub transparent: bool,
 }
 
 pub enum WebGLVersion {
@@ -104,4 +106,15 @@ impl GLContextBuilder {
         self.gl_attributes.high_resolution_framebuffer = value;
         self
     }
+
+    /// Sets if what's drawn should be blended with what's behind the window using its alpha.
+    /// The window must also be transparent.
+    /// Supported on macOS, Windows, and web, which are the platforms with a `GLContext`.
+    pub fn transparent(&mut self, transparent: bool) -> &mut Self {
+        self.gl_attributes.transparent = transparent;
+        if transparent {
+            self.gl_attributes.alpha_bits = self.gl_attributes.alpha_bits.max(8);
+        }
+        self
+    }
 }
@@ -20,5 +20,7 @@ pub use web::GLContext;
 
 pub mod prelude {
     pub use super::common::{GLContextBuilder, GLContextTrait, SetWindowError, VSync};
+    // There's no GLContext for Linux yet.
+    #[cfg(any(target_os = "macos", target_os = "windows", target_arch = "wasm32"))]
     pub use super::GLContext;
 }
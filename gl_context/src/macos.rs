@@ -57,6 +57,10 @@ impl GLContextBuilder {
                 msg_send![gl_context, initWithFormat: pixel_format shareContext: nil];
             let () = msg_send![gl_context, makeCurrentContext];
 
+            if self.gl_attributes.transparent {
+                let () = msg_send![gl_context, setValues:&(0 as i32) forParameter:NSOpenGLCPSurfaceOpacity];
+            }
+
             Ok(GLContext {
                 gl_context,
                 pixel_format,
@@ -84,6 +88,7 @@ impl GLContext {
                 srgb: true,
                 webgl_version: WebGLVersion::None,
                 high_resolution_framebuffer: false,
+                transparent: false,
             },
         }
     }
@@ -240,6 +245,7 @@ impl Drop for GLContext {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum NSOpenGLContextParameter {
     NSOpenGLCPSwapInterval = 222,
+    NSOpenGLCPSurfaceOpacity = 236,
 }
 
 #[repr(u64)]
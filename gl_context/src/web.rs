@@ -14,7 +14,8 @@ impl GLContextBuilder {
 
         // These should be configurable
         let mut context_attributes = web_sys::WebGlContextAttributes::new();
-        context_attributes.alpha(false); // Disable the canvas background transparency.
+        // The canvas background is only transparent if the context is.
+        context_attributes.alpha(self.gl_attributes.transparent);
 
         let context = match self.gl_attributes.webgl_version {
             WebGLVersion::One => {
@@ -80,6 +81,7 @@ impl GLContext {
                 srgb: true,
                 webgl_version: WebGLVersion::One,
                 high_resolution_framebuffer: false,
+                transparent: false,
             },
         }
     }
@@ -33,6 +33,7 @@ impl GLContext {
                 srgb: true,
                 webgl_version: WebGLVersion::None,
                 high_resolution_framebuffer: false,
+                transparent: false,
             },
         }
     }
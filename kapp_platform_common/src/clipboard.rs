@@ -0,0 +1,166 @@
+/// Which of the system's clipboards to use.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClipboardSelection {
+    /// The clipboard used by copy and paste.
+    Clipboard,
+    /// The X11 primary selection, which holds the most recently selected text
+    /// and is pasted with the middle mouse button.
+    /// Other platforms don't have a primary selection.
+    Primary,
+}
+
+/// The MIME type text is stored as.
+pub const TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
+
+/// The MIME type images are stored as.
+/// BMP is used because it can be read and written without a compression library.
+pub const IMAGE_MIME_TYPE: &str = "image/bmp";
+
+/// An image with 8 bit RGBA pixels, stored row by row from the top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+// Sizes of the BMP file header and the version 4 info header.
+const FILE_HEADER_SIZE: usize = 14;
+const INFO_HEADER_SIZE: usize = 108;
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+
+impl ClipboardImage {
+    /// Encodes the image as a 32 bit BMP file with an alpha channel.
+    pub fn to_bmp(&self) -> Vec<u8> {
+        let pixels_size = self.width as usize * self.height as usize * 4;
+        let offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE;
+        let mut bmp = Vec::with_capacity(offset + pixels_size);
+
+        // File header
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&((offset + pixels_size) as u32).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&(offset as u32).to_le_bytes());
+
+        // Info header
+        bmp.extend_from_slice(&(INFO_HEADER_SIZE as u32).to_le_bytes());
+        bmp.extend_from_slice(&(self.width as i32).to_le_bytes());
+        bmp.extend_from_slice(&(self.height as i32).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes()); // Planes
+        bmp.extend_from_slice(&32u16.to_le_bytes()); // Bits per pixel
+        bmp.extend_from_slice(&BI_BITFIELDS.to_le_bytes());
+        bmp.extend_from_slice(&(pixels_size as u32).to_le_bytes());
+        bmp.extend_from_slice(&2835i32.to_le_bytes()); // 72 DPI
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&[0; 8]); // Palette sizes
+        for mask in &[0x00FF_0000u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+            bmp.extend_from_slice(&mask.to_le_bytes());
+        }
+        bmp.extend_from_slice(b"BGRs"); // sRGB, stored backwards.
+        bmp.extend_from_slice(&[0; 48]); // Endpoints and gamma are unused for sRGB.
+
+        // Rows are stored from the bottom up.
+        for row in self.data.chunks_exact(self.width as usize * 4).rev() {
+            for pixel in row.chunks_exact(4) {
+                bmp.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+            }
+        }
+        bmp
+    }
+
+    /// Decodes an uncompressed 24 or 32 bit BMP file.
+    /// Returns `None` if the data isn't a BMP file or uses a format that isn't supported.
+    pub fn from_bmp(bmp: &[u8]) -> Option<Self> {
+        let u16_at = |i: usize| Some(u16::from_le_bytes([*bmp.get(i)?, *bmp.get(i + 1)?]));
+        let u32_at = |i: usize| {
+            Some(u32::from_le_bytes([
+                *bmp.get(i)?,
+                *bmp.get(i + 1)?,
+                *bmp.get(i + 2)?,
+                *bmp.get(i + 3)?,
+            ]))
+        };
+
+        if bmp.get(0..2)? != b"BM" {
+            return None;
+        }
+        let offset = u32_at(10)? as usize;
+        let header_size = u32_at(14)? as usize;
+        let width = u32_at(18)? as i32;
+        let height = u32_at(22)? as i32;
+        let bits_per_pixel = u16_at(28)?;
+        let compression = u32_at(30)?;
+        if width <= 0 || height == 0 || !(bits_per_pixel == 24 || bits_per_pixel == 32) {
+            return None;
+        }
+
+        let (red_mask, green_mask, blue_mask, alpha_mask) = match compression {
+            // The fourth byte of 32 bit pixels is usually unused, so the image is treated as opaque.
+            BI_RGB => (0xFF_0000, 0xFF00, 0xFF, 0),
+            BI_BITFIELDS if bits_per_pixel == 32 => {
+                // The masks follow the first 40 bytes of the header, even for older headers without them.
+                let masks = FILE_HEADER_SIZE + 40;
+                let alpha_mask = if header_size >= 56 {
+                    u32_at(masks + 12)?
+                } else {
+                    0
+                };
+                (
+                    u32_at(masks)?,
+                    u32_at(masks + 4)?,
+                    u32_at(masks + 8)?,
+                    alpha_mask,
+                )
+            }
+            _ => return None,
+        };
+
+        let width = width as usize;
+        let top_down = height < 0;
+        let height = height.unsigned_abs() as usize;
+        let bytes_per_pixel = bits_per_pixel as usize / 8;
+        // Rows are padded to a multiple of 4 bytes.
+        let stride = (width * bytes_per_pixel + 3) & !3;
+        let pixels = bmp.get(offset..offset.checked_add(stride.checked_mul(height)?)?)?;
+
+        let mut data = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            let row = if top_down { y } else { height - 1 - y };
+            let row = &pixels[row * stride..row * stride + width * bytes_per_pixel];
+            for pixel in row.chunks_exact(bytes_per_pixel) {
+                let mut value = 0u32;
+                for (i, byte) in pixel.iter().enumerate() {
+                    value |= (*byte as u32) << (i * 8);
+                }
+                data.extend_from_slice(&[
+                    channel(value, red_mask),
+                    channel(value, green_mask),
+                    channel(value, blue_mask),
+                    if alpha_mask == 0 {
+                        255
+                    } else {
+                        channel(value, alpha_mask)
+                    },
+                ]);
+            }
+        }
+
+        Some(Self {
+            width: width as u32,
+            height: height as u32,
+            data,
+        })
+    }
+}
+
+/// Extracts a color channel and scales it to 8 bits.
+fn channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let max = mask >> mask.trailing_zeros();
+    let channel = (value & mask) >> mask.trailing_zeros();
+    (channel as u64 * 255 / max as u64) as u8
+}
@@ -0,0 +1,65 @@
+//! This file stores how the event loop waits for events.
+//! Backends call 'timeout' before waiting and 'resume_if_reached' once
+//! events are processed, before `EventsCleared` is sent.
+use crate::Event;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// How the event loop waits when there are no events to process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlFlow {
+    /// Wait until an event arrives. This is the default.
+    Wait,
+    /// Run the event loop again as soon as it finishes without waiting,
+    /// sending `EventsCleared` each time.
+    Poll,
+    /// Wait until an event arrives or the deadline is reached.
+    /// `Event::ResumeTimeReached` is sent once the deadline is reached,
+    /// and the control flow returns to `Wait`.
+    WaitUntil(Instant),
+}
+
+thread_local!(
+    static CONTROL_FLOW: Cell<ControlFlow> = const { Cell::new(ControlFlow::Wait) };
+);
+
+pub fn set_control_flow(control_flow: ControlFlow) {
+    CONTROL_FLOW.with(|c| c.set(control_flow));
+}
+
+pub fn control_flow() -> ControlFlow {
+    CONTROL_FLOW.with(|c| c.get())
+}
+
+/// How long the event loop may wait for events, or `None` to wait until one arrives.
+/// Waiting also stops when the next timer in 'timers' is due.
+pub fn timeout() -> Option<Duration> {
+    let timeout = match control_flow() {
+        ControlFlow::Wait => None,
+        ControlFlow::Poll => Some(Duration::from_secs(0)),
+        ControlFlow::WaitUntil(deadline) => {
+            Some(deadline.saturating_duration_since(Instant::now()))
+        }
+    };
+    match (timeout, crate::timers::next_timeout()) {
+        (Some(timeout), Some(timer_timeout)) => Some(timeout.min(timer_timeout)),
+        (timeout, timer_timeout) => timeout.or(timer_timeout),
+    }
+}
+
+/// Converts a timeout to milliseconds for platform wait calls.
+/// It's rounded up so a deadline has passed when the wait ends.
+pub fn timeout_milliseconds(timeout: Duration) -> i32 {
+    timeout.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32
+}
+
+/// Sends `ResumeTimeReached` if the `WaitUntil` deadline has passed.
+pub fn resume_if_reached() {
+    if let ControlFlow::WaitUntil(deadline) = control_flow() {
+        if Instant::now() >= deadline {
+            // The program may set a new deadline in response.
+            set_control_flow(ControlFlow::Wait);
+            crate::event_receiver::send_event(Event::ResumeTimeReached);
+        }
+    }
+}
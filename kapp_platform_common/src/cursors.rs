@@ -1,7 +1,65 @@
+/// A system cursor.
+/// Platforms without a cursor show the closest one they have, as noted on each variant.
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cursor {
     Arrow,
     IBeam,
     PointingHand,
+    /// `PointingHand` on Windows and with SDL.
     ClosedHand,
+    /// `PointingHand` on Windows and with SDL.
     OpenHand,
+    /// Resizing up and down.
+    ResizeNS,
+    /// Resizing left and right.
+    ResizeEW,
+    /// Resizing from the top right or bottom left corner.
+    ResizeNESW,
+    /// Resizing from the top left or bottom right corner.
+    ResizeNWSE,
+    Crosshair,
+    NotAllowed,
+    /// The program is busy and can't be interacted with.
+    Wait,
+    /// The program is busy but can still be interacted with.
+    Progress,
+    Move,
+    /// `Arrow` with SDL.
+    Help,
+    /// `Arrow` on Windows and with SDL.
+    ZoomIn,
+    /// `Arrow` on Windows and with SDL.
+    ZoomOut,
+    /// An image created with `create_cursor`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(CustomCursor),
 }
+
+/// A cursor made from an image.
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub struct CustomCursor {
+    raw_id: *mut std::ffi::c_void,
+}
+
+impl CustomCursor {
+    /// Constructs a new CustomCursor
+    /// There should never be a reason to call this directly.
+    pub fn new(raw_id: *mut std::ffi::c_void) -> Self {
+        Self { raw_id }
+    }
+
+    /// # Safety
+    ///
+    /// Returns the raw cursor pointer.
+    /// On Windows this is an HCURSOR, on MacOS an NSCursor and on X11 an X Cursor.
+    /// On SDL this is a pointer to an SDL_Cursor.
+    /// On other platforms it's an index.
+    pub unsafe fn raw(self) -> *mut std::ffi::c_void {
+        self.raw_id
+    }
+}
+
+// raw_id is only used as a unique identifier
+// or carefully used on the UI thread if the platform requires it.
+unsafe impl Send for CustomCursor {}
@@ -0,0 +1,115 @@
+//! This file provides `EventLoopProxy`, which lets other threads wake the event loop.
+//! Messages from proxies are queued for the thread that created them and each platform
+//! provides a function that wakes its event loop from another thread.
+//! Backends call 'send_proxy_events' once events are processed,
+//! before `EventsCleared` is sent.
+use crate::Event;
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Wakes the event loop and sends it events from any thread.
+/// Created with `Application::proxy`.
+#[derive(Clone)]
+pub struct EventLoopProxy {
+    messages: Messages,
+    wake: Arc<dyn Fn() + Send + Sync>,
+}
+
+enum ProxyMessage {
+    Wake,
+    User(Box<dyn Any + Send>),
+}
+
+type Messages = Arc<Mutex<VecDeque<ProxyMessage>>>;
+
+thread_local!(
+    // Each event loop has its own queue, so event loops on other threads aren't woken.
+    static MESSAGES: Messages = Arc::new(Mutex::new(VecDeque::new()));
+);
+
+impl EventLoopProxy {
+    /// Constructs a proxy that calls `wake` after each message is queued.
+    /// `wake` is called from other threads while the event loop runs.
+    /// Must be called on the event loop's thread.
+    pub fn new(wake: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            messages: MESSAGES.with(|messages| messages.clone()),
+            wake: Arc::new(wake),
+        }
+    }
+
+    /// Sends `Event::Wake` to the event loop.
+    pub fn wake(&self) {
+        self.send(ProxyMessage::Wake);
+    }
+
+    /// Sends `Event::User` with the data to the event loop.
+    pub fn send_user_event(&self, data: Box<dyn Any + Send>) {
+        self.send(ProxyMessage::User(data));
+    }
+
+    fn send(&self, message: ProxyMessage) {
+        self.messages.lock().unwrap().push_back(message);
+        (self.wake)();
+    }
+}
+
+impl std::fmt::Debug for EventLoopProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventLoopProxy").finish()
+    }
+}
+
+/// The data sent with `EventLoopProxy::send_user_event`.
+/// Clones of an event share the data, so it can only be taken once.
+#[derive(Clone)]
+pub struct UserEvent(Arc<Mutex<Option<Box<dyn Any + Send>>>>);
+
+impl UserEvent {
+    /// Takes the data, or returns `None` if it's already been taken.
+    pub fn take(&self) -> Option<Box<dyn Any + Send>> {
+        self.0.lock().unwrap().take()
+    }
+
+    /// Takes the data if it's a `T`, otherwise it's left for another call.
+    pub fn take_as<T: Any>(&self) -> Option<T> {
+        let mut data = self.0.lock().unwrap();
+        match data.take()?.downcast::<T>() {
+            Ok(value) => Some(*value),
+            Err(other) => {
+                *data = Some(other);
+                None
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for UserEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserEvent").finish()
+    }
+}
+
+/// Returns if a proxy has sent messages that haven't been sent as events yet.
+pub fn proxy_events_pending() -> bool {
+    MESSAGES.with(|messages| !messages.lock().unwrap().is_empty())
+}
+
+/// Sends an event for each message from a proxy, in the order they were sent.
+pub fn send_proxy_events() {
+    // The lock is released before each event so the program can use proxies in response.
+    while let Some(message) = next_message() {
+        let event = match message {
+            ProxyMessage::Wake => Event::Wake,
+            ProxyMessage::User(data) => Event::User {
+                data: UserEvent(Arc::new(Mutex::new(Some(data)))),
+            },
+        };
+        crate::event_receiver::send_event(event);
+    }
+}
+
+fn next_message() -> Option<ProxyMessage> {
+    MESSAGES.with(|messages| messages.lock().unwrap().pop_front())
+}
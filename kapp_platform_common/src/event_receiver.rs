@@ -5,45 +5,54 @@ use crate::Event;
 /// In that case push the events to an overflow queue that is processed
 /// when the callback is available again.
 use std::cell::RefCell;
+use std::collections::VecDeque;
+
+type ProgramCallback = Box<dyn 'static + FnMut(Event)>;
 
 thread_local!(
-    static PROGRAM_CALLBACK: RefCell<Box<dyn 'static + FnMut(Event)>> =
-        RefCell::new(Box::new(|_| {}));
-    static OVERFLOW_EVENTS: RefCell<Vec<Event>> = RefCell::new(Vec::new());
+    static PROGRAM_CALLBACK: RefCell<Option<ProgramCallback>> = const { RefCell::new(None) };
+    static OVERFLOW_EVENTS: RefCell<VecDeque<Event>> = const { RefCell::new(VecDeque::new()) };
 );
 
 pub fn set_callback(callback: Box<dyn FnMut(Event)>) {
     PROGRAM_CALLBACK.with(|p| {
-        let _ = p.replace(callback);
+        let _ = p.replace(Some(callback));
     });
 }
 
 /// Sends an event to the user callback
+/// Events sent before the callback is set are sent with the first event after it's set.
 pub fn send_event(event: Event) {
     // try_with because events may be sent during destruction, which should be ignored.
     let _ = PROGRAM_CALLBACK.try_with(|p| {
         if let Ok(mut callback) = p.try_borrow_mut() {
-            (callback.as_mut())(event);
-
-            // Flush events here to somewhat preserve the ordering of events.
-            flush_overflow_events(&mut callback);
-        } else {
-            // If the callback is in use then push the event to overflow events to be
-            // processed later.
-            OVERFLOW_EVENTS.with(|events| {
-                events.borrow_mut().push(event);
-            });
+            if let Some(callback) = callback.as_mut() {
+                // Events waiting for the callback were sent first, so they're processed first.
+                flush_overflow_events(callback);
+                (callback.as_mut())(event);
+
+                // Flush events here to somewhat preserve the ordering of events.
+                flush_overflow_events(callback);
+                return;
+            }
         }
+
+        // If the callback is in use, or not set yet, then push the event to overflow events
+        // to be processed later.
+        OVERFLOW_EVENTS.with(|events| {
+            events.borrow_mut().push_back(event);
+        });
     });
 }
 
 /// Sends events that could not be sent because the user callback was borrowed.
+/// Events are sent in the order they were received.
 fn flush_overflow_events(callback: &mut Box<dyn 'static + FnMut(Event)>) {
     // Temporarily borrow the overflow event queue and pop from it to avoid
     // holding a reference to it during the callback.
     let mut next_event = {
         OVERFLOW_EVENTS
-            .try_with(|events| events.borrow_mut().pop())
+            .try_with(|events| events.borrow_mut().pop_front())
             .unwrap_or(None)
     };
     while let Some(event) = next_event {
@@ -51,7 +60,7 @@ fn flush_overflow_events(callback: &mut Box<dyn 'static + FnMut(Event)>) {
 
         next_event = {
             OVERFLOW_EVENTS
-                .try_with(|events| events.borrow_mut().pop())
+                .try_with(|events| events.borrow_mut().pop_front())
                 .unwrap_or(None)
         };
     }
@@ -1,9 +1,18 @@
-use crate::keys::Key;
+use crate::keys::{Key, LogicalKey};
+use crate::ClipboardSelection;
+use crate::Fullscreen;
+use crate::Modifiers;
+use crate::ScreenId;
+use crate::TimerId;
+use crate::UserEvent;
 use crate::WindowId;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Input and system events.
 /// All user input events have timestamps.
+/// Input and text events have the `WindowId` of the window they were sent to,
+/// which is the focused window for keyboard events.
 /// Timestamps on MacOS and Windows represent time since the computer was turned on.
 /// On Web timestamps represent time since the current document was created.
 /// Precision of timestamps varies between platforms.
@@ -12,6 +21,7 @@ use std::time::Duration;
 // f64 is used for all input events.
 // u32 is used for window positioning and movements.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Event {
     /// A recommendation for when a window should draw.
@@ -32,6 +42,7 @@ pub enum Event {
     /// `KeyDown`
     CharacterReceived {
         character: char,
+        window_id: WindowId,
     },
     /// The composition status of the IME (Input Method Editor).
     /// This event is fired when a keystroke causes the composition status to change.
@@ -39,33 +50,78 @@ pub enum Event {
     /// user input, prepended to the caret position.
     IMEComposition {
         composition: String,
+        window_id: WindowId,
     },
     /// IME composition has ended.
     /// The "intermediate" visualization should be cleared.
-    IMEEndComposition,
+    IMEEndComposition {
+        window_id: WindowId,
+    },
     // ------------------- Input Events ---------------------
     /// A key is pressed.
     /// For text input use the `CharacterReceived` event instead.
+    ///
+    /// `key` is the key's physical position, named by the key at that position on a US keyboard.
+    /// `scancode` is the platform's code for the physical key, or 0 if it isn't known.
+    /// The scancode is not reported on Web.
+    /// `logical_key` is the key's meaning in the current keyboard layout,
+    /// which should be used for shortcuts like Control + Z.
     KeyDown {
         key: Key,
+        scancode: u32,
+        logical_key: LogicalKey,
+        window_id: WindowId,
+        modifiers: Modifiers,
         timestamp: Duration,
     },
     /// A key is released.
+    /// The fields are the same as `KeyDown`'s.
     KeyUp {
         key: Key,
+        scancode: u32,
+        logical_key: LogicalKey,
+        window_id: WindowId,
+        modifiers: Modifiers,
         timestamp: Duration,
     },
     /// A repeat of a held key.
     KeyRepeat {
         key: Key,
+        scancode: u32,
+        logical_key: LogicalKey,
+        window_id: WindowId,
+        modifiers: Modifiers,
         timestamp: Duration,
     },
+    /// The modifier keys held or lock keys enabled have changed.
+    /// Sent before the event that caused the change.
+    ModifiersChanged {
+        modifiers: Modifiers,
+    },
     /// The pointer position has changed.
     /// Reports physical coordinates in relation to the pointer's window
+    ///
+    /// `pointer_id` tells apart pointers from the same source, like each finger touching the screen.
+    /// A touch keeps its id from `PointerDown` to `PointerUp`, but ids may be reused afterwards.
+    /// The mouse's id is always 0.
+    ///
+    /// `pressure` is from 0.0 to 1.0, and is 0.0 if the pointer doesn't report pressure.
+    /// On Web a mouse reports a pressure of 0.5 while a button is held.
+    /// `tilt_x` and `tilt_y` are a pen's tilt in degrees from -90 to 90.
+    /// `contact_width` and `contact_height` are the size of a touch's contact area, or 0.0 if unknown.
+    ///
+    /// Pressure is reported on SDL and Web. Tilt and contact size are only reported on Web.
     PointerMoved {
         x: f64,
         y: f64,
         source: PointerSource,
+        pointer_id: u64,
+        pressure: f64,
+        tilt_x: f64,
+        tilt_y: f64,
+        contact_width: f64,
+        contact_height: f64,
+        window_id: WindowId,
         timestamp: Duration,
     },
     /// How much the mouse has moved regardless of the pointer position.
@@ -73,15 +129,25 @@ pub enum Event {
     MouseMotion {
         delta_x: f64,
         delta_y: f64,
+        window_id: WindowId,
         timestamp: Duration,
     },
     /// A pointer, mouse, touch or, or stylus has been pressed down.
     /// Note that this is sent by multiple web events, not just web's "pointerdown" event.
+    /// See `PointerMoved` for the meaning of the pointer fields.
     PointerDown {
         x: f64,
         y: f64,
         source: PointerSource,
+        pointer_id: u64,
         button: PointerButton,
+        pressure: f64,
+        tilt_x: f64,
+        tilt_y: f64,
+        contact_width: f64,
+        contact_height: f64,
+        window_id: WindowId,
+        modifiers: Modifiers,
         timestamp: Duration,
     },
     /// Reports physical coordinates in relation to the pointer's window
@@ -89,7 +155,15 @@ pub enum Event {
         x: f64,
         y: f64,
         source: PointerSource,
+        pointer_id: u64,
         button: PointerButton,
+        pressure: f64,
+        tilt_x: f64,
+        tilt_y: f64,
+        contact_width: f64,
+        contact_height: f64,
+        window_id: WindowId,
+        modifiers: Modifiers,
         timestamp: Duration,
     },
     /// Occurs when pressing a mouse button twice in quick succession.
@@ -99,6 +173,7 @@ pub enum Event {
         x: f64,
         y: f64,
         button: PointerButton,
+        window_id: WindowId,
         timestamp: Duration,
     },
     /// Occurs when pressing a mouse button twice in quick succession.
@@ -109,6 +184,7 @@ pub enum Event {
         x: f64,
         y: f64,
         button: PointerButton,
+        window_id: WindowId,
         timestamp: Duration,
     },
     /// Occurs when pressing a mouse button twice in quick succession.
@@ -118,6 +194,7 @@ pub enum Event {
         x: f64,
         y: f64,
         button: PointerButton,
+        window_id: WindowId,
         timestamp: Duration,
     },
     /// If delta_x is set it horizontal scrolling from something like a trackpad.
@@ -129,12 +206,14 @@ pub enum Event {
         delta_x: f64,
         delta_y: f64,
         window_id: WindowId,
+        modifiers: Modifiers,
         timestamp: Duration,
     },
     /// A number corresponding to a pinch gesture.
     /// Presently only sent on MacOS.
     PinchGesture {
         delta: f64,
+        window_id: WindowId,
         timestamp: Duration,
     },
     // ------------------- Window Events  ---------------------
@@ -145,11 +224,17 @@ pub enum Event {
     WindowMaximized {
         window_id: WindowId,
     },
+    /// Sent when the window enters fullscreen or changes how it's fullscreen.
+    /// `fullscreen` is the fullscreen now active, which is never `Off`.
     WindowFullscreened {
+        fullscreen: Fullscreen,
         window_id: WindowId,
     },
     /// A window is 'restored' when it returns from being minimized, maximized, or fullscreened.
+    /// `fullscreen` is the fullscreen the window returned to,
+    /// which is `Off` unless the window was minimized while fullscreen.
     WindowRestored {
+        fullscreen: Fullscreen,
         window_id: WindowId,
     },
     /// When the window has begun resizing.
@@ -190,7 +275,47 @@ pub enum Event {
         scale: f64,
         window_id: WindowId,
     },
+    // ------------------- Drag and Drop Events  ---------------------
+    /// Something is being dragged over the window.
+    /// Drops are accepted unless `set_drop_accepted(false)` is called in response to
+    /// this event or `DragMoved`.
+    /// Only sent on X11 and web. SDL only reports drops.
+    DragEntered {
+        window_id: WindowId,
+    },
+    DragMoved {
+        x: f64,
+        y: f64,
+        window_id: WindowId,
+    },
+    /// The drag left the window, was cancelled, or its drop was rejected.
+    DragLeft {
+        window_id: WindowId,
+    },
+    Dropped {
+        data: DropData,
+        window_id: WindowId,
+    },
+    // ------------------- Screen Events  ---------------------
+    /// A screen was connected.
+    /// Not sent for the screens connected when the program starts.
+    /// Not sent on Web.
+    ScreenAdded {
+        screen_id: ScreenId,
+    },
+    ScreenRemoved {
+        screen_id: ScreenId,
+    },
+    /// A screen's position, size, work area, scale, or refresh rate changed.
+    ScreenChanged {
+        screen_id: ScreenId,
+    },
     // ------------------- Application Events  ---------------------
+    /// The contents of a clipboard have changed, including when this program changed them.
+    /// Only sent on X11 with the XFixes extension, SDL, and headless.
+    ClipboardChanged {
+        selection: ClipboardSelection,
+    },
     /// The application is going to quit immediately after this event is processed.
     /// Perform any final cleanup that's necessary. The quit cannot be cancelled.
     Quit,
@@ -198,9 +323,28 @@ pub enum Event {
     QuitRequested,
     /// When the event loop sends its last event
     EventsCleared,
+    /// The deadline set with `ControlFlow::WaitUntil` was reached.
+    /// Sent before `EventsCleared`.
+    ResumeTimeReached,
+    /// A timer started with `Application::set_timer` is due.
+    /// Sent before `EventsCleared`.
+    Timer {
+        timer_id: TimerId,
+    },
+    /// Sent after `EventLoopProxy::wake` is called from any thread.
+    /// Sent before `EventsCleared`.
+    Wake,
+    /// Sent with the data passed to `EventLoopProxy::send_user_event`.
+    /// Sent before `EventsCleared`.
+    /// The data can't be serialized, so this event is skipped.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    User {
+        data: UserEvent,
+    },
 }
 
 #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PointerSource {
     Mouse,
     Touch,
@@ -208,7 +352,18 @@ pub enum PointerSource {
     Unknown,
 }
 
+/// What was dropped onto a window.
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DropData {
+    /// Files dropped from a file manager.
+    /// On web only the file names are known.
+    Paths(Vec<PathBuf>),
+    Text(String),
+}
+
 #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PointerButton {
     None,
     Primary,
@@ -0,0 +1,21 @@
+use crate::{ScreenId, VideoMode};
+
+/// How a window covers a screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fullscreen {
+    Off,
+    /// The window covers the screen without changing its video mode.
+    /// `None` uses the screen the window is on.
+    Borderless(Option<ScreenId>),
+    /// The screen switches to the video mode while the window covers it.
+    /// The screen's previous video mode returns when the window leaves fullscreen.
+    /// Platforms that can't change video modes use `Borderless` on the mode's screen instead.
+    Exclusive(VideoMode),
+}
+
+impl Default for Fullscreen {
+    fn default() -> Self {
+        Fullscreen::Borderless(None)
+    }
+}
@@ -0,0 +1,15 @@
+/// A window icon with 8 bit RGBA pixels, stored row by row from the top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Icon {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Returns the icon closest in size to `size`, preferring larger icons that can be scaled down.
+pub fn closest_icon(icons: &[Icon], size: u32) -> Option<&Icon> {
+    icons.iter().min_by_key(|icon| {
+        let icon_size = icon.width.max(icon.height);
+        (icon_size < size, (icon_size as i64 - size as i64).abs())
+    })
+}
@@ -1,6 +1,34 @@
-#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
-#[non_exhaustive]
-pub enum Key {
+// Declares `Key` along with a table of the names of each key.
+macro_rules! keys {
+    ($($(#[$attribute:meta])* $key:ident,)*) => {
+        #[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[non_exhaustive]
+        pub enum Key {
+            $($(#[$attribute])* $key,)*
+        }
+
+        impl Key {
+            /// The name of the key, which matches its variant name.
+            /// Names are stable and can be stored in files.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Key::$key => stringify!($key),)*
+                }
+            }
+
+            /// Returns the key with a name returned by `Key::name`.
+            pub fn from_name(name: &str) -> Option<Key> {
+                match name {
+                    $(stringify!($key) => Some(Key::$key),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+keys! {
     Unknown,
     // --------- International and IME (Input method editor) keys --------------
     KanaHangul,
@@ -235,3 +263,31 @@ pub enum Key {
     Play,
     Zoom,
 }
+
+/// The meaning of a key in the current keyboard layout.
+/// On an AZERTY keyboard the key in the position of `Key::Q` is `LogicalKey::Character('a')`.
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LogicalKey {
+    /// The character the key produces without modifiers.
+    /// Letters are lowercase.
+    Character(char),
+    /// A key that doesn't produce a visible character, like `Return` or `LeftShift`.
+    Key(Key),
+}
+
+impl LogicalKey {
+    /// Returns `Character` if the character is visible, otherwise `Key(key)`.
+    pub fn from_character(character: Option<char>, key: Key) -> Self {
+        match character {
+            Some(character) if !character.is_control() && !character.is_whitespace() => {
+                let mut lowercase = character.to_lowercase();
+                match (lowercase.next(), lowercase.next()) {
+                    (Some(lowercase), None) => LogicalKey::Character(lowercase),
+                    _ => LogicalKey::Character(character),
+                }
+            }
+            _ => LogicalKey::Key(key),
+        }
+    }
+}
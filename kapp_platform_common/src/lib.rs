@@ -5,22 +5,38 @@
 ///   Each event has documented behavior that must be conformed to.
 ///   event_receiver should be used on platforms where calls to a platform
 ///   functions can trigger events.
+mod clipboard;
+pub mod control_flow;
 mod cursors;
+pub mod event_loop_proxy;
 pub mod event_receiver;
 mod events;
+mod fullscreen;
+mod icon;
 mod keys;
+mod modifiers;
 mod platform_traits;
 pub mod redraw_manager;
+mod screen;
 mod screen_id;
-mod window_id;
+pub mod timers;
+pub mod window_id;
 mod window_parameters;
 
-pub use cursors::Cursor;
-pub use events::{Event, PointerButton, PointerSource};
-pub use keys::Key;
+pub use clipboard::{ClipboardImage, ClipboardSelection, IMAGE_MIME_TYPE, TEXT_MIME_TYPE};
+pub use control_flow::ControlFlow;
+pub use cursors::{Cursor, CustomCursor};
+pub use event_loop_proxy::{EventLoopProxy, UserEvent};
+pub use events::{DropData, Event, PointerButton, PointerSource};
+pub use fullscreen::Fullscreen;
+pub use icon::{closest_icon, Icon};
+pub use keys::{Key, LogicalKey};
+pub use modifiers::Modifiers;
 pub use platform_traits::{PlatformApplicationTrait, PlatformEventLoopTrait};
 pub use raw_window_handle;
 pub use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+pub use screen::{screen_changes, Screen, VideoMode};
 pub use screen_id::ScreenId;
+pub use timers::TimerId;
 pub use window_id::{RawWindowHandleTrait, WindowId};
 pub use window_parameters::WindowParameters;
@@ -0,0 +1,146 @@
+use crate::Key;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// The modifier keys held and lock keys enabled when an input event occurred.
+/// Events include their own change, so the `KeyDown` for a shift key has `SHIFT` set.
+///
+/// `META` is the Command key on MacOS, the Windows key on Windows, and the Super key on Linux.
+/// ```
+/// # use kapp_platform_common::Modifiers;
+/// let modifiers = Modifiers::CONTROL | Modifiers::SHIFT;
+/// assert!(modifiers.contains(Modifiers::CONTROL));
+/// assert!(!modifiers.alt());
+/// ```
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Modifiers {
+    bits: u8,
+}
+
+impl Modifiers {
+    pub const SHIFT: Modifiers = Modifiers { bits: 1 << 0 };
+    pub const CONTROL: Modifiers = Modifiers { bits: 1 << 1 };
+    pub const ALT: Modifiers = Modifiers { bits: 1 << 2 };
+    pub const META: Modifiers = Modifiers { bits: 1 << 3 };
+    pub const CAPS_LOCK: Modifiers = Modifiers { bits: 1 << 4 };
+    pub const NUM_LOCK: Modifiers = Modifiers { bits: 1 << 5 };
+    const ALL_BITS: u8 = (1 << 6) - 1;
+
+    pub const fn empty() -> Self {
+        Self { bits: 0 }
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.bits
+    }
+
+    /// Bits that don't correspond to a modifier are ignored.
+    pub const fn from_bits_truncate(bits: u8) -> Self {
+        Self {
+            bits: bits & Self::ALL_BITS,
+        }
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns true if all the modifiers in `other` are set.
+    pub const fn contains(self, other: Modifiers) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    pub fn insert(&mut self, other: Modifiers) {
+        self.bits |= other.bits;
+    }
+
+    pub fn remove(&mut self, other: Modifiers) {
+        self.bits &= !other.bits;
+    }
+
+    pub fn set(&mut self, other: Modifiers, value: bool) {
+        if value {
+            self.insert(other)
+        } else {
+            self.remove(other)
+        }
+    }
+
+    pub const fn shift(self) -> bool {
+        self.contains(Self::SHIFT)
+    }
+
+    pub const fn control(self) -> bool {
+        self.contains(Self::CONTROL)
+    }
+
+    pub const fn alt(self) -> bool {
+        self.contains(Self::ALT)
+    }
+
+    pub const fn meta(self) -> bool {
+        self.contains(Self::META)
+    }
+
+    pub const fn caps_lock(self) -> bool {
+        self.contains(Self::CAPS_LOCK)
+    }
+
+    pub const fn num_lock(self) -> bool {
+        self.contains(Self::NUM_LOCK)
+    }
+
+    /// Returns the modifiers after a key is pressed or released.
+    /// Used by platforms that report the modifiers from before a key event.
+    pub fn after_key(mut self, key: Key, pressed: bool) -> Self {
+        let modifier = match key {
+            Key::Shift | Key::LeftShift | Key::RightShift => Self::SHIFT,
+            Key::Control | Key::LeftControl | Key::RightControl => Self::CONTROL,
+            Key::LeftAlt | Key::RightAlt => Self::ALT,
+            Key::Meta | Key::LeftMeta | Key::RightMeta | Key::LeftGui | Key::RightGui => Self::META,
+            // Lock keys toggle when they're pressed.
+            Key::CapsLock | Key::NumLock => {
+                if pressed {
+                    let lock = if key == Key::CapsLock {
+                        Self::CAPS_LOCK
+                    } else {
+                        Self::NUM_LOCK
+                    };
+                    self.set(lock, !self.contains(lock));
+                }
+                return self;
+            }
+            _ => return self,
+        };
+        self.set(modifier, pressed);
+        self
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, other: Self) {
+        self.bits |= other.bits;
+    }
+}
+
+impl BitAnd for Modifiers {
+    type Output = Self;
+    fn bitand(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+}
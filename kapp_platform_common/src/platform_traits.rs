@@ -1,5 +1,9 @@
 /// These are the core functions to be implemented by each platform.
-use crate::{raw_window_handle::RawWindowHandle, Cursor, WindowId, WindowParameters};
+use crate::{
+    raw_window_handle::RawWindowHandle, ClipboardSelection, ControlFlow, Cursor, CustomCursor,
+    Event, EventLoopProxy, Fullscreen, Icon, Screen, ScreenId, TimerId, WindowId, WindowParameters,
+};
+use std::time::Duration;
 pub trait PlatformApplicationTrait {
     type EventLoop: PlatformEventLoopTrait;
 
@@ -11,15 +15,63 @@ pub trait PlatformApplicationTrait {
     /// Sets window size with physical coordinates.
     fn set_window_size(&mut self, window_id: WindowId, width: u32, height: u32);
     fn set_window_title(&mut self, window_id: WindowId, title: &str);
+    /// Sets the window's icon from one or more sizes of the same image.
+    /// Platforms that only use one size should pick the closest with `closest_icon`.
+    /// An empty slice restores the default icon where possible.
+    fn set_window_icon(&mut self, window_id: WindowId, icon: &[Icon]);
+    /// Sets whether the user can resize the window.
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool);
+    /// Sets the smallest size the user can resize the window to, in physical coordinates.
+    /// `None` removes the limit.
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>);
+    /// Sets the largest size the user can resize the window to, in physical coordinates.
+    /// `None` removes the limit.
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>);
+    /// Shows or hides the window's titlebar and borders.
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool);
+    /// Sets whether the window stays above windows that aren't always on top.
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool);
+    /// Shows or hides the window. Hidden windows can still be drawn to.
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool);
+    /// Sets the opacity of the whole window, from 0.0 for invisible to 1.0 for opaque.
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32);
+    /// Sets the rectangles that receive pointer input, as (x, y, width, height) in
+    /// physical coordinates relative to the window's content.
+    /// Pointer input elsewhere passes through to what's behind the window.
+    /// `None` restores input for the whole window.
+    /// Returns false if the platform can't use the region,
+    /// in which case the whole window receives input.
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool;
     fn minimize_window(&mut self, window_id: WindowId);
     fn maximize_window(&mut self, window_id: WindowId);
-    fn fullscreen_window(&mut self, window_id: WindowId);
+    /// Changes whether and how the window covers a screen.
+    /// `Fullscreen::Borderless(None)` should use the screen the window is on.
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen);
     /// Returns the window to the state where it's not minimized, maximized, or fullscreen
     fn restore_window(&mut self, window_id: WindowId);
     fn close_window(&mut self, window_id: WindowId);
 
     fn get_window_size(&mut self, _window_id: WindowId) -> (u32, u32);
     fn get_window_scale(&mut self, _window_id: WindowId) -> f64;
+    /// Returns the window's position in physical coordinates, as set by `set_window_position`.
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32);
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool;
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool;
+    /// Returns how the window covers a screen, or `Fullscreen::Off` if it doesn't.
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen;
+    /// Returns true if the window receives keyboard input.
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool;
+    /// Returns true if the window is shown. Minimized windows are still visible.
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool;
+
+    /// Returns the connected screens with the primary screen first.
+    fn screens(&mut self) -> Vec<Screen>;
+    /// Returns the screen the window is mostly on, or `None` if it isn't known.
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId>;
 
     /// Requests that the a Draw event be sent for the window.
     /// Draw events should either be sent at the end of an event loop,
@@ -42,8 +94,32 @@ pub trait PlatformApplicationTrait {
     /// The termination should occur before any requested draw events.
     fn quit(&self);
 
+    /// Sets how the event loop waits for events, stored with `control_flow::set_control_flow`.
+    /// The event loop should account for it the next time it would wait,
+    /// even if it's changed from outside the event loop's callback.
+    fn set_control_flow(&mut self, control_flow: ControlFlow);
+
+    /// Starts a timer that sends `Event::Timer` after the duration,
+    /// and then after every duration if it repeats.
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId;
+    /// Stops a timer. No more `Event::Timer` should be sent for it, even if it's already due.
+    fn cancel_timer(&mut self, timer_id: TimerId);
+
+    /// Creates a proxy whose wake function makes the event loop process proxy events
+    /// even while it's waiting.
+    fn proxy(&mut self) -> EventLoopProxy;
+
     /// Sets the cursor in a way that persists between all windows for the current program.
     fn set_cursor(&mut self, cursor: Cursor);
+    /// Creates a cursor from 8 bit RGBA pixels, stored row by row from the top left.
+    /// The hotspot is the pixel that's positioned at the pointer.
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor;
 
     /// Hides the cursor or this application until a call to show cursor.
     fn hide_cursor(&mut self);
@@ -64,9 +140,30 @@ pub trait PlatformApplicationTrait {
         height: f64,
     );
 
+    /// Returns the MIME types of the data on the clipboard.
+    fn clipboard_mime_types(&mut self, selection: ClipboardSelection) -> Vec<String>;
+    /// Returns the clipboard's data of a MIME type, or `None` if it has no data of that type.
+    /// Text is requested as `TEXT_MIME_TYPE` and should be converted from the platform's text types.
+    fn get_clipboard_data(
+        &mut self,
+        selection: ClipboardSelection,
+        mime_type: &str,
+    ) -> Option<Vec<u8>>;
+    /// Replaces the clipboard's contents with data of a MIME type.
+    fn set_clipboard_data(&mut self, selection: ClipboardSelection, mime_type: &str, data: &[u8]);
+
+    /// Accepts or rejects the drop of what's currently being dragged over a window.
+    /// Drops are accepted by default when a new drag enters a window.
+    fn set_drop_accepted(&mut self, accepted: bool);
+
     /// Returns a RawWindowHandle as defined in the raw_window_handle crate
     /// https://github.com/rust-windowing/raw-window-handle
     fn raw_window_handle(&self, window: WindowId) -> RawWindowHandle;
+
+    /// Sends an event as if it came from the operating system.
+    /// Injected events should reach the program the same way as the platform's own events,
+    /// after any events already waiting to be sent.
+    fn inject_event(&mut self, event: Event);
 }
 
 pub trait PlatformEventLoopTrait {
@@ -8,8 +8,8 @@ use crate::{Event, WindowId};
 use std::cell::RefCell;
 
 thread_local!(
-    static DRAW_REQUESTS: RefCell<Vec<WindowId>> = RefCell::new(Vec::new());
-    static DRAW_REQUESTS_SWAP: RefCell<Vec<WindowId>> = RefCell::new(Vec::new());
+    static DRAW_REQUESTS: RefCell<Vec<WindowId>> = const { RefCell::new(Vec::new()) };
+    static DRAW_REQUESTS_SWAP: RefCell<Vec<WindowId>> = const { RefCell::new(Vec::new()) };
 );
 
 pub fn add_draw_request(window_id: WindowId) {
@@ -37,6 +37,12 @@ pub fn draw(window_id: WindowId) {
     });
 }
 
+/// Forgets every draw request, for platforms that start a new application on the same thread.
+pub fn clear_draw_requests() {
+    DRAW_REQUESTS.with(|requests| requests.borrow_mut().clear());
+    DRAW_REQUESTS_SWAP.with(|swap| swap.borrow_mut().clear());
+}
+
 pub fn draw_requests_count() -> usize {
     DRAW_REQUESTS.with(|d| d.borrow().len())
 }
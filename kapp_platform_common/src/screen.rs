@@ -0,0 +1,72 @@
+use crate::{Event, ScreenId};
+
+/// A display connected to the computer.
+/// Positions and sizes are in physical coordinates.
+/// The origin (0,0) is the upper left corner of the primary screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screen {
+    pub id: ScreenId,
+    /// The name the OS gives the screen, or an empty string if it doesn't name screens.
+    pub name: String,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    /// The part of the screen not covered by taskbars, docks, or panels.
+    pub work_area_position: (i32, i32),
+    pub work_area_size: (u32, u32),
+    /// The scale factor windows on this screen should apply to UI.
+    pub scale: f64,
+    /// Refreshes per second, or `None` if the platform doesn't report it.
+    pub refresh_rate: Option<f64>,
+    /// The video modes the screen can be switched to with `Fullscreen::Exclusive`.
+    /// Empty on Wayland and Web, where programs can't change video modes.
+    pub video_modes: Vec<VideoMode>,
+}
+
+/// A resolution, refresh rate, and bit depth a screen can display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoMode {
+    pub screen_id: ScreenId,
+    /// The resolution in physical pixels.
+    pub size: (u32, u32),
+    /// Refreshes per second.
+    pub refresh_rate: f64,
+    /// Bits per pixel.
+    pub bit_depth: u32,
+}
+
+impl Screen {
+    /// Returns true if the physical point is within the screen.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.position.0
+            && y >= self.position.1
+            && ((x - self.position.0) as u32) < self.size.0
+            && ((y - self.position.1) as u32) < self.size.1
+    }
+}
+
+/// Returns the events describing how the screens changed from `old` to `new`.
+/// Platforms that only report that something about the screens changed
+/// should compare the screens before and after with this.
+pub fn screen_changes(old: &[Screen], new: &[Screen]) -> Vec<Event> {
+    let mut events = Vec::new();
+    for screen in old {
+        if !new.iter().any(|s| s.id == screen.id) {
+            events.push(Event::ScreenRemoved {
+                screen_id: screen.id,
+            });
+        }
+    }
+    for screen in new {
+        match old.iter().find(|s| s.id == screen.id) {
+            None => events.push(Event::ScreenAdded {
+                screen_id: screen.id,
+            }),
+            Some(old_screen) if old_screen != screen => events.push(Event::ScreenChanged {
+                screen_id: screen.id,
+            }),
+            _ => {}
+        }
+    }
+    events
+}
@@ -1,11 +1,12 @@
 /// A unique ID associated per screen.
+/// With the `serde` feature a ScreenId is serialized as its raw id.
 #[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
 pub struct ScreenId {
     raw_id: *mut std::ffi::c_void,
 }
 
 impl ScreenId {
-    /// Constructs a new WindowId
+    /// Constructs a new ScreenId
     /// There should never be a reason to call this directly.
     pub fn new(raw_id: *mut std::ffi::c_void) -> Self {
         Self { raw_id }
@@ -13,8 +14,11 @@ impl ScreenId {
 
     /// # Safety
     ///
-    /// Returns the raw screen pointer.
-    /// On MacOS this is a pointer to the NSScreen object.
+    /// Returns the raw screen id.
+    /// On MacOS this is the screen's CGDirectDisplayID, on Windows an HMONITOR
+    /// and on X11 an XRandR output.
+    /// On Wayland this is the wl_output's name in the registry.
+    /// On SDL this is the display index.
     /// On Web this is just '0'
     pub unsafe fn raw(self) -> *mut std::ffi::c_void {
         self.raw_id
@@ -24,3 +28,20 @@ impl ScreenId {
 // raw_id is only used as a unique identifier
 // or carefully used on the UI thread if the platform requires it.
 unsafe impl Send for ScreenId {}
+
+// Screen ids are ids rather than pointers on every platform but Windows,
+// and HMONITORs are handles, so the raw id is kept as is.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ScreenId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.raw_id as u64)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ScreenId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw_id = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(Self::new(raw_id as usize as *mut std::ffi::c_void))
+    }
+}
@@ -0,0 +1,98 @@
+//! This file provides timers for platforms that wait for events with a timeout.
+//! 'control_flow::timeout' includes the time until the next timer is due,
+//! and backends call 'send_due_timers' once events are processed,
+//! before `EventsCleared` is sent.
+//! Platforms with their own timers only use 'new_timer_id'.
+use crate::Event;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Identifies a timer started with `Application::set_timer`.
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimerId(u64);
+
+impl TimerId {
+    /// Constructs a TimerId from a raw id returned by `raw`.
+    /// There should never be a reason to call this directly.
+    pub fn from_raw(raw_id: u64) -> Self {
+        Self(raw_id)
+    }
+
+    pub fn raw(self) -> u64 {
+        self.0
+    }
+}
+
+static NEXT_TIMER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Returns an id that no other timer has used.
+pub fn new_timer_id() -> TimerId {
+    TimerId(NEXT_TIMER_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+struct Timer {
+    timer_id: TimerId,
+    deadline: Instant,
+    interval: Duration,
+    repeat: bool,
+}
+
+thread_local!(
+    static TIMERS: RefCell<Vec<Timer>> = const { RefCell::new(Vec::new()) };
+);
+
+pub fn add_timer(duration: Duration, repeat: bool) -> TimerId {
+    let timer_id = new_timer_id();
+    TIMERS.with(|t| {
+        t.borrow_mut().push(Timer {
+            timer_id,
+            deadline: Instant::now() + duration,
+            interval: duration,
+            repeat,
+        })
+    });
+    timer_id
+}
+
+pub fn remove_timer(timer_id: TimerId) {
+    TIMERS.with(|t| t.borrow_mut().retain(|timer| timer.timer_id != timer_id));
+}
+
+/// The time until the next timer is due, or `None` if there are no timers.
+pub fn next_timeout() -> Option<Duration> {
+    TIMERS.with(|t| {
+        let timers = t.borrow();
+        let deadline = timers.iter().map(|timer| timer.deadline).min()?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    })
+}
+
+/// Sends `Event::Timer` for each timer that's due, earliest first.
+/// Repeating timers are rescheduled and other timers are removed.
+pub fn send_due_timers() {
+    let now = Instant::now();
+    // The borrow is released before each event so the program can start and cancel timers.
+    while let Some(timer_id) = TIMERS.with(|t| take_due_timer(&mut t.borrow_mut(), now)) {
+        crate::event_receiver::send_event(Event::Timer { timer_id });
+    }
+}
+
+fn take_due_timer(timers: &mut Vec<Timer>, now: Instant) -> Option<TimerId> {
+    let index = timers
+        .iter()
+        .enumerate()
+        .filter(|(_, timer)| timer.deadline <= now)
+        .min_by_key(|(_, timer)| timer.deadline)?
+        .0;
+    let timer = &mut timers[index];
+    let timer_id = timer.timer_id;
+    if timer.repeat {
+        // A timer that's fallen behind skips the times it missed instead of firing repeatedly.
+        timer.deadline = (timer.deadline + timer.interval).max(now + timer.interval);
+    } else {
+        timers.remove(index);
+    }
+    Some(timer_id)
+}
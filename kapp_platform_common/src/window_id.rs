@@ -1,4 +1,9 @@
+use std::cell::RefCell;
+
 /// A unique ID associated with a Window.
+/// With the `serde` feature a WindowId is serialized as the order its window was created in
+/// since the application was initialized, not as its raw pointer.
+/// Only windows that are open on the current thread can be serialized or deserialized.
 #[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
 pub struct WindowId {
     raw_id: *mut std::ffi::c_void,
@@ -29,3 +34,85 @@ unsafe impl Sync for WindowId {}
 pub trait RawWindowHandleTrait {
     fn raw_window_handle(&self) -> raw_window_handle::RawWindowHandle;
 }
+
+struct WindowIndices {
+    next_index: u64,
+    windows: Vec<(u64, WindowId)>,
+}
+
+// Each open window's index in the order windows were created.
+thread_local!(static WINDOW_INDICES: RefCell<WindowIndices> = const {
+    RefCell::new(WindowIndices {
+        next_index: 0,
+        windows: Vec::new(),
+    })
+});
+
+/// Gives a new window the next index in creation order.
+/// Called when a window is created.
+pub fn register_window(window_id: WindowId) {
+    WINDOW_INDICES.with(|indices| {
+        let mut indices = indices.borrow_mut();
+        let index = indices.next_index;
+        indices.next_index += 1;
+        indices.windows.push((index, window_id));
+    })
+}
+
+/// Forgets a closed window's index.
+pub fn unregister_window(window_id: WindowId) {
+    WINDOW_INDICES.with(|indices| {
+        indices
+            .borrow_mut()
+            .windows
+            .retain(|(_, id)| *id != window_id)
+    })
+}
+
+/// Forgets all windows and starts counting from 0 again.
+/// Called when an application is created.
+pub fn reset_window_indices() {
+    WINDOW_INDICES.with(|indices| {
+        let mut indices = indices.borrow_mut();
+        indices.next_index = 0;
+        indices.windows.clear();
+    })
+}
+
+/// Returns an open window's index in creation order.
+pub fn window_index(window_id: WindowId) -> Option<u64> {
+    WINDOW_INDICES.with(|indices| {
+        let indices = indices.borrow();
+        let (index, _) = indices.windows.iter().find(|(_, id)| *id == window_id)?;
+        Some(*index)
+    })
+}
+
+/// Returns the open window with an index, if there is one.
+pub fn window_from_index(index: u64) -> Option<WindowId> {
+    WINDOW_INDICES.with(|indices| {
+        let indices = indices.borrow();
+        let (_, window_id) = indices.windows.iter().find(|(i, _)| *i == index)?;
+        Some(*window_id)
+    })
+}
+
+// Raw window pointers are meaningless outside of the current process,
+// so WindowIds are serialized as their index instead.
+#[cfg(feature = "serde")]
+impl serde::Serialize for WindowId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let index = window_index(*self)
+            .ok_or_else(|| serde::ser::Error::custom("window isn't open on this thread"))?;
+        serializer.serialize_u64(index)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WindowId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u64::deserialize(deserializer)?;
+        window_from_index(index)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown window {}", index)))
+    }
+}
@@ -1,3 +1,5 @@
+use crate::Icon;
+
 #[derive(Clone)]
 pub struct WindowParameters {
     pub position: Option<(u32, u32)>,
@@ -5,7 +7,12 @@ pub struct WindowParameters {
     pub minimum_size: Option<(u32, u32)>,
     pub maximum_size: Option<(u32, u32)>,
     pub resizable: bool,
-    /// Only does anything on MacOS
+    /// On MacOS the content extends under a transparent titlebar.
+    /// Other platforms create the window without decorations.
     pub without_titlebar: bool,
+    /// Parts of the window drawn with alpha below 1 show what's behind the window.
+    pub transparent: bool,
     pub title: String,
+    /// The window's icon at one or more sizes. Empty for the platform's default icon.
+    pub icon: Vec<Icon>,
 }
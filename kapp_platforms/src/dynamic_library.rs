@@ -0,0 +1,91 @@
+//! Loads system libraries at runtime instead of linking to them.
+//! This keeps Linux builds free of system development packages and lets
+//! a single binary run on systems that are missing some of the libraries.
+use std::ffi::{c_void, CString};
+use std::os::raw::{c_char, c_int};
+
+#[link(name = "dl")]
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+const RTLD_LAZY: c_int = 0x0001;
+
+pub struct DynamicLibrary {
+    handle: *mut c_void,
+}
+
+impl DynamicLibrary {
+    /// Opens the first library in `names` that can be found.
+    pub fn open(names: &[&str]) -> Option<Self> {
+        names.iter().find_map(|name| {
+            let name = CString::new(*name).unwrap();
+            let handle = unsafe { dlopen(name.as_ptr(), RTLD_LAZY) };
+            if handle.is_null() {
+                None
+            } else {
+                Some(Self { handle })
+            }
+        })
+    }
+
+    /// Returns the address of a symbol in the library.
+    pub fn address(&self, name: &str) -> Option<*mut c_void> {
+        let name = CString::new(name).unwrap();
+        let address = unsafe { dlsym(self.handle, name.as_ptr()) };
+        if address.is_null() {
+            None
+        } else {
+            Some(address)
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `T` must be a function pointer type matching the symbol's actual signature.
+    pub unsafe fn function<T: Copy>(&self, name: &str) -> Option<T> {
+        assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<*mut c_void>());
+        self.address(name)
+            .map(|address| std::mem::transmute_copy(&address))
+    }
+}
+
+// Libraries are never closed, so the handle remains valid for the lifetime of the program.
+unsafe impl Send for DynamicLibrary {}
+unsafe impl Sync for DynamicLibrary {}
+
+/// Declares a struct of function pointers loaded from a system library.
+/// Functions are called like `(xlib.XOpenDisplay)(std::ptr::null())`
+/// Exported variables can be declared after the functions with `static`
+/// and are stored as pointers to the variable.
+macro_rules! dynamic_library {
+    (
+        pub struct $name:ident($($library_name:literal),+ $(,)?) {
+            $(pub $function:ident: $function_type:ty,)*
+            $(static $symbol:ident: $symbol_type:ty,)*
+        }
+    ) => {
+        #[allow(non_snake_case)]
+        pub struct $name {
+            pub library: $crate::dynamic_library::DynamicLibrary,
+            $(pub $function: $function_type,)*
+            $(pub $symbol: *const $symbol_type,)*
+        }
+
+        impl $name {
+            /// Returns `None` if the library or any of its symbols could not be found.
+            pub fn open() -> Option<Self> {
+                let library =
+                    $crate::dynamic_library::DynamicLibrary::open(&[$($library_name),+])?;
+                unsafe {
+                    Some(Self {
+                        $($function: library.function(stringify!($function))?,)*
+                        $($symbol: library.address(stringify!($symbol))? as *const $symbol_type,)*
+                        library,
+                    })
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,716 @@
+//! A platform without a display, for running programs in CI or on servers.
+//! Windows are in-memory records of the state set through `PlatformApplicationTrait`.
+//! Input comes from a queue that the program fills with `queue_event`.
+//! A window has focus after a queued `WindowGainedFocus` for it is sent.
+//! There's one 1920x1080 screen until the program changes them with `set_screens`.
+//! Clipboards are stored in memory.
+//! Drops rejected with `set_drop_accepted(false)` are sent as `DragLeft`.
+//! Timers use a virtual clock that only moves when the program calls `advance_time`.
+//! `ControlFlow::WaitUntil` deadlines are `Instant`s, which can't be virtual,
+//! so they're waited for on the wall clock. Use timers for deterministic tests.
+//! Like a real platform the event loop runs until the program quits.
+//! A program that's idle and can't be woken by an `EventLoopProxy` would wait forever,
+//! so the event loop sends `Event::Quit` and returns instead.
+use kapp_platform_common::*;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+pub mod prelude {
+    pub use super::*;
+    pub use kapp_platform_common::*;
+}
+
+/// The state of a headless window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowRecord {
+    pub title: String,
+    pub position: (u32, u32),
+    pub size: (u32, u32),
+    pub minimum_size: Option<(u32, u32)>,
+    pub maximum_size: Option<(u32, u32)>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub always_on_top: bool,
+    pub transparent: bool,
+    pub opacity: f32,
+    /// The rectangles that receive pointer input, or `None` for the whole window.
+    pub input_region: Option<Vec<(u32, u32, u32, u32)>>,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: Fullscreen,
+    pub focused: bool,
+    pub visible: bool,
+    pub icon: Vec<Icon>,
+}
+
+struct VirtualTimer {
+    timer_id: TimerId,
+    deadline: Duration, // The virtual time when the timer is next due.
+    interval: Duration,
+    repeat: bool,
+}
+
+// Global singleton data shared by the application struct and the event loop.
+struct ApplicationData {
+    windows: HashMap<WindowId, WindowRecord>,
+    next_window_id: usize,
+    next_cursor_id: usize,
+    events: VecDeque<Event>,
+    clipboards: HashMap<ClipboardSelection, (String, Vec<u8>)>, // The MIME type and data.
+    screens: Vec<Screen>,
+    time: Duration, // The virtual time since the platform started.
+    timers: Vec<VirtualTimer>,
+    drop_accepted: bool,
+    actually_quit: bool, // Set when quit is called. Indicates the program should quit.
+}
+
+thread_local!(static APPLICATION_DATA: RefCell<ApplicationData> = RefCell::new(ApplicationData {
+    windows: HashMap::new(),
+    next_window_id: 1,
+    next_cursor_id: 1,
+    events: VecDeque::new(),
+    clipboards: HashMap::new(),
+    screens: default_screens(),
+    time: Duration::from_secs(0),
+    timers: Vec::new(),
+    drop_accepted: true,
+    actually_quit: false,
+}));
+
+/// Other threads wake the event loop with this condition variable.
+/// Each thread has its own, so event loops on other threads aren't woken.
+#[derive(Default)]
+struct Wake {
+    mutex: Mutex<()>,
+    condvar: Condvar,
+    // The number of proxies, counted so the program doesn't quit while one could wake it.
+    proxies: AtomicUsize,
+}
+
+impl Wake {
+    fn notify(&self) {
+        let _lock = self.mutex.lock().unwrap();
+        self.condvar.notify_all();
+    }
+}
+
+thread_local!(
+    static WAKE: Arc<Wake> = Arc::new(Wake::default());
+);
+
+/// Shared by a proxy and its clones, and dropped with the last of them.
+struct ProxyCount(Arc<Wake>);
+
+impl ProxyCount {
+    fn new() -> Self {
+        let wake = WAKE.with(|wake| wake.clone());
+        wake.proxies.fetch_add(1, Ordering::SeqCst);
+        Self(wake)
+    }
+}
+
+impl Drop for ProxyCount {
+    fn drop(&mut self) {
+        self.0.proxies.fetch_sub(1, Ordering::SeqCst);
+        // The event loop may be waiting for a proxy that can no longer wake it.
+        self.0.notify();
+    }
+}
+
+/// Waits until a proxy sends a message or the timeout passes.
+/// Without a timeout it stops waiting when there are no proxies left to send one.
+fn wait_for_proxies(timeout: Option<Duration>) {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let wake = WAKE.with(|wake| wake.clone());
+    let mut lock = wake.mutex.lock().unwrap();
+    while !event_loop_proxy::proxy_events_pending() {
+        match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                lock = wake.condvar.wait_timeout(lock, deadline - now).unwrap().0;
+            }
+            None => {
+                if wake.proxies.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                lock = wake.condvar.wait(lock).unwrap();
+            }
+        }
+    }
+}
+
+fn with_data<R>(f: impl FnOnce(&mut ApplicationData) -> R) -> R {
+    APPLICATION_DATA.with(|d| f(&mut d.borrow_mut()))
+}
+
+/// Adds an event to the end of the queue.
+/// The event loop sends queued events to the program in order.
+pub fn queue_event(event: Event) {
+    with_data(|data| data.events.push_back(event));
+}
+
+/// Returns the current state of a window, or `None` if it's closed.
+pub fn window_record(window_id: WindowId) -> Option<WindowRecord> {
+    with_data(|data| data.windows.get(&window_id).cloned())
+}
+
+/// Replaces the screens and queues the events a real platform would send.
+/// The first screen is the primary screen.
+pub fn set_screens(screens: Vec<Screen>) {
+    with_data(|data| {
+        let events = screen_changes(&data.screens, &screens);
+        data.events.extend(events);
+        data.screens = screens;
+    })
+}
+
+/// Moves the virtual clock forward and queues `Event::Timer` for each timer that's due,
+/// in the order they're due. A repeating timer is queued each time it's due.
+pub fn advance_time(duration: Duration) {
+    with_data(|data| {
+        let end = data.time + duration;
+        while let Some(index) = data
+            .timers
+            .iter()
+            .enumerate()
+            .filter(|(_, timer)| timer.deadline <= end)
+            .min_by_key(|(_, timer)| timer.deadline)
+            .map(|(index, _)| index)
+        {
+            let timer = &mut data.timers[index];
+            let timer_id = timer.timer_id;
+            data.time = timer.deadline;
+            if timer.repeat {
+                timer.deadline += timer.interval;
+            } else {
+                data.timers.remove(index);
+            }
+            data.events.push_back(Event::Timer { timer_id });
+        }
+        data.time = end;
+    })
+}
+
+fn default_screens() -> Vec<Screen> {
+    let id = ScreenId::new(std::ptr::dangling_mut::<c_void>());
+    let video_modes = [(1920, 1080), (1280, 720), (800, 600)]
+        .iter()
+        .map(|&size| VideoMode {
+            screen_id: id,
+            size,
+            refresh_rate: 60.0,
+            bit_depth: 32,
+        })
+        .collect();
+    vec![Screen {
+        id,
+        name: "Headless".to_string(),
+        position: (0, 0),
+        size: (1920, 1080),
+        work_area_position: (0, 0),
+        work_area_size: (1920, 1080),
+        scale: 1.0,
+        refresh_rate: Some(60.0),
+        video_modes,
+    }]
+}
+
+/// Changes a window's record and queues the event a real platform would send.
+fn update_window(window_id: WindowId, f: impl FnOnce(&mut WindowRecord) -> Option<Event>) {
+    with_data(|data| {
+        if let Some(event) = data.windows.get_mut(&window_id).and_then(f) {
+            data.events.push_back(event);
+        }
+    })
+}
+
+pub struct PlatformApplication {}
+
+impl PlatformApplicationTrait for PlatformApplication {
+    type EventLoop = PlatformEventLoop;
+
+    fn new() -> Self {
+        // Reset the platform so a new application starts without the previous one's state,
+        // even if the previous application is still held by its event loop's callback.
+        with_data(|data| {
+            data.windows.clear();
+            data.next_window_id = 1;
+            data.next_cursor_id = 1;
+            data.events.clear();
+            data.clipboards.clear();
+            data.screens = default_screens();
+            data.time = Duration::from_secs(0);
+            data.timers.clear();
+            data.drop_accepted = true;
+            data.actually_quit = false;
+        });
+        redraw_manager::clear_draw_requests();
+        control_flow::set_control_flow(ControlFlow::Wait);
+        Self {}
+    }
+
+    fn event_loop(&mut self) -> Self::EventLoop {
+        PlatformEventLoop {}
+    }
+
+    fn set_window_position(&mut self, window_id: WindowId, x: u32, y: u32) {
+        update_window(window_id, |window| {
+            if window.position == (x, y) {
+                return None;
+            }
+            window.position = (x, y);
+            Some(Event::WindowMoved { x, y, window_id })
+        })
+    }
+
+    fn set_window_size(&mut self, window_id: WindowId, width: u32, height: u32) {
+        update_window(window_id, |window| {
+            if window.size == (width, height) {
+                return None;
+            }
+            window.size = (width, height);
+            redraw_manager::add_draw_request(window_id);
+            Some(Event::WindowResized {
+                width,
+                height,
+                window_id,
+            })
+        })
+    }
+
+    fn set_window_title(&mut self, window_id: WindowId, title: &str) {
+        update_window(window_id, |window| {
+            window.title = title.to_string();
+            None
+        })
+    }
+
+    fn set_window_icon(&mut self, window_id: WindowId, icon: &[Icon]) {
+        update_window(window_id, |window| {
+            window.icon = icon.to_vec();
+            None
+        })
+    }
+
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        update_window(window_id, |window| {
+            window.resizable = resizable;
+            None
+        })
+    }
+
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        update_window(window_id, |window| {
+            window.minimum_size = size;
+            None
+        })
+    }
+
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        update_window(window_id, |window| {
+            window.maximum_size = size;
+            None
+        })
+    }
+
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        update_window(window_id, |window| {
+            window.decorations = decorations;
+            None
+        })
+    }
+
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool) {
+        update_window(window_id, |window| {
+            window.always_on_top = always_on_top;
+            None
+        })
+    }
+
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool) {
+        update_window(window_id, |window| {
+            window.visible = visible;
+            None
+        })
+    }
+
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32) {
+        update_window(window_id, |window| {
+            window.opacity = opacity;
+            None
+        })
+    }
+
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        update_window(window_id, |window| {
+            window.input_region = region.map(|region| region.to_vec());
+            None
+        });
+        true
+    }
+
+    fn minimize_window(&mut self, window_id: WindowId) {
+        update_window(window_id, |window| {
+            if window.minimized {
+                return None;
+            }
+            window.minimized = true;
+            Some(Event::WindowMinimized { window_id })
+        })
+    }
+
+    fn maximize_window(&mut self, window_id: WindowId) {
+        update_window(window_id, |window| {
+            if window.maximized && !window.minimized && window.fullscreen == Fullscreen::Off {
+                return None;
+            }
+            window.minimized = false;
+            window.maximized = true;
+            window.fullscreen = Fullscreen::Off;
+            Some(Event::WindowMaximized { window_id })
+        })
+    }
+
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
+        if fullscreen == Fullscreen::Off {
+            let fullscreen = window_record(window_id).map(|window| window.fullscreen);
+            if fullscreen.is_some_and(|f| f != Fullscreen::Off) {
+                self.restore_window(window_id);
+            }
+            return;
+        }
+
+        // Record which screen the window covers, as real platforms report it.
+        let fullscreen = match fullscreen {
+            Fullscreen::Borderless(None) => Fullscreen::Borderless(self.window_screen(window_id)),
+            fullscreen => fullscreen,
+        };
+        update_window(window_id, |window| {
+            if window.fullscreen == fullscreen && !window.minimized {
+                return None;
+            }
+            window.minimized = false;
+            window.fullscreen = fullscreen;
+            Some(Event::WindowFullscreened {
+                fullscreen,
+                window_id,
+            })
+        })
+    }
+
+    fn restore_window(&mut self, window_id: WindowId) {
+        update_window(window_id, |window| {
+            if !window.minimized && !window.maximized && window.fullscreen == Fullscreen::Off {
+                return None;
+            }
+            // Unminimizing a fullscreen window returns it to fullscreen.
+            if !window.minimized {
+                window.fullscreen = Fullscreen::Off;
+            }
+            window.minimized = false;
+            window.maximized = false;
+            Some(Event::WindowRestored {
+                fullscreen: window.fullscreen,
+                window_id,
+            })
+        })
+    }
+
+    fn close_window(&mut self, window_id: WindowId) {
+        with_data(|data| data.windows.remove(&window_id));
+    }
+
+    fn get_window_size(&mut self, window_id: WindowId) -> (u32, u32) {
+        with_data(|data| data.windows.get(&window_id).map_or((0, 0), |w| w.size))
+    }
+
+    fn get_window_scale(&mut self, _window_id: WindowId) -> f64 {
+        1.0
+    }
+
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32) {
+        window_record(window_id).map_or((0, 0), |w| w.position)
+    }
+
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool {
+        window_record(window_id).is_some_and(|w| w.minimized)
+    }
+
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        window_record(window_id).is_some_and(|w| w.maximized)
+    }
+
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        window_record(window_id).map_or(Fullscreen::Off, |w| w.fullscreen)
+    }
+
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        window_record(window_id).is_some_and(|w| w.focused)
+    }
+
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        window_record(window_id).is_some_and(|w| w.visible)
+    }
+
+    fn screens(&mut self) -> Vec<Screen> {
+        with_data(|data| data.screens.clone())
+    }
+
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        with_data(|data| {
+            let (x, y) = data.windows.get(&window_id)?.position;
+            data.screens
+                .iter()
+                .find(|screen| screen.contains(x as i32, y as i32))
+                .or_else(|| data.screens.first())
+                .map(|screen| screen.id)
+        })
+    }
+
+    fn redraw_window(&mut self, window_id: WindowId) {
+        redraw_manager::add_draw_request(window_id);
+    }
+
+    fn lock_mouse_position(&mut self) {}
+    fn unlock_mouse_position(&mut self) {}
+
+    fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
+        with_data(|data| {
+            // Window ids only need to be unique.
+            let window_id = WindowId::new(data.next_window_id as *mut c_void);
+            data.next_window_id += 1;
+            data.windows.insert(
+                window_id,
+                WindowRecord {
+                    title: window_parameters.title.clone(),
+                    position: window_parameters.position.unwrap_or((0, 0)),
+                    size: window_parameters.size.unwrap_or((500, 500)),
+                    minimum_size: window_parameters.minimum_size,
+                    maximum_size: window_parameters.maximum_size,
+                    resizable: window_parameters.resizable,
+                    decorations: !window_parameters.without_titlebar,
+                    always_on_top: false,
+                    transparent: window_parameters.transparent,
+                    opacity: 1.0,
+                    input_region: None,
+                    minimized: false,
+                    maximized: false,
+                    fullscreen: Fullscreen::Off,
+                    focused: false,
+                    visible: true,
+                    icon: window_parameters.icon.clone(),
+                },
+            );
+
+            // When a window is created immediately request that it should redraw
+            redraw_manager::add_draw_request(window_id);
+            window_id
+        })
+    }
+
+    fn quit(&self) {
+        with_data(|data| data.actually_quit = true);
+    }
+
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        control_flow::set_control_flow(control_flow);
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        let timer_id = timers::new_timer_id();
+        with_data(|data| {
+            data.timers.push(VirtualTimer {
+                timer_id,
+                deadline: data.time + duration,
+                interval: duration,
+                repeat,
+            })
+        });
+        timer_id
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        with_data(|data| {
+            data.timers.retain(|timer| timer.timer_id != timer_id);
+            // Events queued by `advance_time` aren't sent after the timer is cancelled.
+            data.events
+                .retain(|event| !matches!(event, Event::Timer { timer_id: id } if *id == timer_id));
+        })
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        let count = ProxyCount::new();
+        EventLoopProxy::new(move || count.0.notify())
+    }
+
+    fn set_cursor(&mut self, _cursor: Cursor) {}
+    fn create_cursor(
+        &mut self,
+        _rgba: &[u8],
+        _width: u32,
+        _height: u32,
+        _hotspot: (u32, u32),
+    ) -> CustomCursor {
+        with_data(|data| {
+            // Cursor ids only need to be unique.
+            let cursor = CustomCursor::new(data.next_cursor_id as *mut c_void);
+            data.next_cursor_id += 1;
+            cursor
+        })
+    }
+    fn hide_cursor(&mut self) {}
+    fn show_cursor(&mut self) {}
+
+    fn start_text_input(&mut self) {}
+    fn end_text_input(&mut self) {}
+    fn set_text_input_rectangle(
+        &mut self,
+        _window_id: WindowId,
+        _x: f64,
+        _y: f64,
+        _width: f64,
+        _height: f64,
+    ) {
+    }
+
+    fn clipboard_mime_types(&mut self, selection: ClipboardSelection) -> Vec<String> {
+        with_data(|data| {
+            data.clipboards
+                .get(&selection)
+                .map(|(mime_type, _)| vec![mime_type.clone()])
+                .unwrap_or_default()
+        })
+    }
+
+    fn get_clipboard_data(
+        &mut self,
+        selection: ClipboardSelection,
+        mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        with_data(|data| match data.clipboards.get(&selection) {
+            Some((stored_mime_type, stored_data)) if stored_mime_type == mime_type => {
+                Some(stored_data.clone())
+            }
+            _ => None,
+        })
+    }
+
+    fn set_clipboard_data(
+        &mut self,
+        selection: ClipboardSelection,
+        mime_type: &str,
+        contents: &[u8],
+    ) {
+        with_data(|data| {
+            data.clipboards
+                .insert(selection, (mime_type.to_string(), contents.to_vec()));
+            data.events.push_back(Event::ClipboardChanged { selection });
+        })
+    }
+
+    fn set_drop_accepted(&mut self, accepted: bool) {
+        with_data(|data| data.drop_accepted = accepted);
+    }
+
+    fn raw_window_handle(&self, _window_id: WindowId) -> RawWindowHandle {
+        panic!("Headless windows do not have a raw window handle")
+    }
+
+    fn inject_event(&mut self, event: Event) {
+        queue_event(event);
+    }
+}
+
+/// Focus only changes through queued events, as it would when a user clicks a window.
+fn update_focus(event: &Event) {
+    with_data(|data| match event {
+        Event::WindowGainedFocus { window_id } => {
+            for (id, window) in data.windows.iter_mut() {
+                window.focused = id == window_id;
+            }
+        }
+        Event::WindowLostFocus { window_id } => {
+            if let Some(window) = data.windows.get_mut(window_id) {
+                window.focused = false;
+            }
+        }
+        _ => {}
+    })
+}
+
+/// A rejected drop is sent as `DragLeft`, as X11 does.
+fn accept_drop(event: Event) -> Event {
+    with_data(|data| match event {
+        Event::DragEntered { .. } => {
+            data.drop_accepted = true;
+            event
+        }
+        Event::Dropped { window_id, .. } if !data.drop_accepted => Event::DragLeft { window_id },
+        event => event,
+    })
+}
+
+pub struct PlatformEventLoop {}
+
+impl PlatformEventLoopTrait for PlatformEventLoop {
+    fn run(&self, callback: Box<dyn FnMut(Event)>) {
+        event_receiver::set_callback(callback);
+
+        loop {
+            // Only proxies can send events while waiting, so a `WaitUntil` deadline
+            // is waited for on the wall clock unless a proxy sends one first.
+            if with_data(|data| data.events.is_empty())
+                && redraw_manager::draw_requests_count() == 0
+            {
+                if let Some(timeout) = control_flow::timeout() {
+                    wait_for_proxies(Some(timeout));
+                }
+            }
+
+            // Process all events.
+            // Events queued by the program while it handles these are sent as well.
+            while let Some(event) = with_data(|data| data.events.pop_front()) {
+                update_focus(&event);
+                event_receiver::send_event(accept_drop(event));
+            }
+            event_loop_proxy::send_proxy_events();
+            control_flow::resume_if_reached();
+
+            // When there are no events remaining, we're at the end of the event loop
+            event_receiver::send_event(Event::EventsCleared);
+
+            // Only proxies can produce events while the program waits.
+            // Waiting for a deadline or polling keeps it running.
+            let idle = with_data(|data| data.events.is_empty())
+                && redraw_manager::draw_requests_count() == 0
+                && control_flow::control_flow() == ControlFlow::Wait;
+            if idle {
+                wait_for_proxies(None);
+            }
+            // Nothing can wake a program that's still idle, so it's done.
+            let stuck = idle && !event_loop_proxy::proxy_events_pending();
+            if with_data(|data| data.actually_quit) || stuck {
+                event_receiver::send_event(Event::Quit);
+                break;
+            }
+
+            // Send a draw event for each window that needs to be drawn.
+            redraw_manager::begin_draw_flush();
+            while let Some(window_id) = redraw_manager::get_draw_request() {
+                event_receiver::send_event(Event::Draw { window_id });
+            }
+        }
+    }
+}
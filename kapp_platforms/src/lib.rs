@@ -1,21 +1,46 @@
-#[cfg(all(target_os = "macos", not(feature = "SDL")))]
+#[cfg(all(target_os = "macos", not(feature = "SDL"), not(feature = "headless")))]
 mod macos;
-#[cfg(all(target_os = "macos", not(feature = "SDL")))]
+#[cfg(all(target_os = "macos", not(feature = "SDL"), not(feature = "headless")))]
 pub use macos::*;
-#[cfg(all(target_os = "macos", not(feature = "SDL")))]
+#[cfg(all(target_os = "macos", not(feature = "SDL"), not(feature = "headless")))]
 #[macro_use]
 extern crate objc;
 
-#[cfg(all(target_arch = "wasm32", not(feature = "SDL")))]
+#[cfg(all(
+    target_arch = "wasm32",
+    not(feature = "SDL"),
+    not(feature = "headless")
+))]
 mod web;
-#[cfg(all(target_arch = "wasm32", not(feature = "SDL")))]
+#[cfg(all(
+    target_arch = "wasm32",
+    not(feature = "SDL"),
+    not(feature = "headless")
+))]
 pub use web::*;
-#[cfg(all(target_os = "windows", not(feature = "SDL")))]
+#[cfg(all(target_os = "windows", not(feature = "SDL"), not(feature = "headless")))]
 mod windows;
-#[cfg(all(target_os = "windows", not(feature = "SDL")))]
+#[cfg(all(target_os = "windows", not(feature = "SDL"), not(feature = "headless")))]
 pub use windows::*;
 
-#[cfg(feature = "SDL")]
+#[cfg(all(target_os = "linux", not(feature = "SDL"), not(feature = "headless")))]
+#[macro_use]
+mod dynamic_library;
+#[cfg(all(target_os = "linux", not(feature = "SDL"), not(feature = "headless")))]
+mod linux;
+#[cfg(all(target_os = "linux", not(feature = "SDL"), not(feature = "headless")))]
+mod wayland;
+#[cfg(all(target_os = "linux", not(feature = "SDL"), not(feature = "headless")))]
+mod x11;
+#[cfg(all(target_os = "linux", not(feature = "SDL"), not(feature = "headless")))]
+pub use linux::*;
+
+#[cfg(all(feature = "SDL", not(feature = "headless")))]
 mod sdl;
-#[cfg(feature = "SDL")]
+#[cfg(all(feature = "SDL", not(feature = "headless")))]
 pub use sdl::*;
+
+#[cfg(feature = "headless")]
+mod headless;
+#[cfg(feature = "headless")]
+pub use headless::*;
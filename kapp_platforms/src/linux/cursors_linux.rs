@@ -0,0 +1,28 @@
+use kapp_platform_common::Cursor;
+
+/// Returns the names of a cursor in X cursor themes, which both X11 and Wayland use.
+/// Cursor names vary between themes so a few alternatives are tried.
+/// The CSS names are from the freedesktop cursor spec and the others are older X11 names.
+pub fn cursor_names(cursor: Cursor) -> &'static [&'static [u8]] {
+    match cursor {
+        Cursor::Arrow => &[b"left_ptr\0", b"default\0"],
+        Cursor::IBeam => &[b"xterm\0", b"text\0"],
+        Cursor::PointingHand => &[b"hand2\0", b"pointer\0"],
+        Cursor::OpenHand => &[b"openhand\0", b"grab\0", b"hand1\0"],
+        Cursor::ClosedHand => &[b"closedhand\0", b"grabbing\0", b"fleur\0"],
+        Cursor::ResizeNS => &[b"ns-resize\0", b"sb_v_double_arrow\0", b"size_ver\0"],
+        Cursor::ResizeEW => &[b"ew-resize\0", b"sb_h_double_arrow\0", b"size_hor\0"],
+        Cursor::ResizeNESW => &[b"nesw-resize\0", b"size_bdiag\0", b"bottom_left_corner\0"],
+        Cursor::ResizeNWSE => &[b"nwse-resize\0", b"size_fdiag\0", b"bottom_right_corner\0"],
+        Cursor::Crosshair => &[b"crosshair\0", b"cross\0"],
+        Cursor::NotAllowed => &[b"not-allowed\0", b"crossed_circle\0", b"forbidden\0"],
+        Cursor::Wait => &[b"wait\0", b"watch\0"],
+        Cursor::Progress => &[b"progress\0", b"left_ptr_watch\0"],
+        Cursor::Move => &[b"move\0", b"fleur\0", b"size_all\0"],
+        Cursor::Help => &[b"help\0", b"question_arrow\0", b"whats_this\0"],
+        Cursor::ZoomIn => &[b"zoom-in\0"],
+        Cursor::ZoomOut => &[b"zoom-out\0"],
+        // Custom cursors aren't part of the theme.
+        Cursor::Custom(_) => &[],
+    }
+}
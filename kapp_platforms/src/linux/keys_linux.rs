@@ -0,0 +1,175 @@
+use kapp_platform_common::{Key, Key::*};
+
+/// Both X11 and Wayland report Linux evdev scancodes.
+/// The evdev codes are listed here:
+/// https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
+pub fn evdev_code_to_key(code: u32) -> Key {
+    match code {
+        1 => Escape,
+        2 => Digit1,
+        3 => Digit2,
+        4 => Digit3,
+        5 => Digit4,
+        6 => Digit5,
+        7 => Digit6,
+        8 => Digit7,
+        9 => Digit8,
+        10 => Digit9,
+        11 => Digit0,
+        12 => Minus,
+        13 => Equal,
+        14 => Backspace,
+        15 => Tab,
+        16 => Q,
+        17 => W,
+        18 => E,
+        19 => R,
+        20 => T,
+        21 => Y,
+        22 => U,
+        23 => I,
+        24 => O,
+        25 => P,
+        26 => BracketLeft,
+        27 => BracketRight,
+        28 => Return,
+        29 => LeftControl,
+        30 => A,
+        31 => S,
+        32 => D,
+        33 => F,
+        34 => G,
+        35 => H,
+        36 => J,
+        37 => K,
+        38 => L,
+        39 => Semicolon,
+        40 => Quote,
+        41 => Backquote,
+        42 => LeftShift,
+        43 => Backslash,
+        44 => Z,
+        45 => X,
+        46 => C,
+        47 => V,
+        48 => B,
+        49 => N,
+        50 => M,
+        51 => Comma,
+        52 => Period,
+        53 => Slash,
+        54 => RightShift,
+        55 => NumPadMultiply,
+        56 => LeftAlt,
+        57 => Space,
+        58 => CapsLock,
+        59 => F1,
+        60 => F2,
+        61 => F3,
+        62 => F4,
+        63 => F5,
+        64 => F6,
+        65 => F7,
+        66 => F8,
+        67 => F9,
+        68 => F10,
+        69 => NumLock,
+        70 => ScrollLock,
+        71 => NumPad7,
+        72 => NumPad8,
+        73 => NumPad9,
+        74 => NumPadSubtract,
+        75 => NumPad4,
+        76 => NumPad5,
+        77 => NumPad6,
+        78 => NumPadAdd,
+        79 => NumPad1,
+        80 => NumPad2,
+        81 => NumPad3,
+        82 => NumPad0,
+        83 => NumPadDecimal,
+        86 => Oem102,
+        87 => F11,
+        88 => F12,
+        96 => NumPadEnter,
+        97 => RightControl,
+        98 => NumPadDivide,
+        99 => PrintScreen,
+        100 => RightAlt,
+        102 => Home,
+        103 => Up,
+        104 => PageUp,
+        105 => Left,
+        106 => Right,
+        107 => End,
+        108 => Down,
+        109 => PageDown,
+        110 => Insert,
+        111 => Delete,
+        113 => Mute,
+        114 => VolumeDown,
+        115 => VolumeUp,
+        116 => Power,
+        117 => NumPadEquals,
+        119 => Pause,
+        121 => NumPadSeparator,
+        125 => LeftMeta,
+        126 => RightMeta,
+        127 => ContextMenu,
+        128 => Stop,
+        129 => Again,
+        131 => Undo,
+        133 => Copy,
+        135 => Paste,
+        136 => Find,
+        137 => Cut,
+        138 => Help,
+        140 => Calculator,
+        142 => Sleep,
+        150 => Www,
+        155 => Mail,
+        156 => BrowserBookmarks,
+        158 => BrowserBack,
+        159 => BrowserForward,
+        161 => Eject,
+        163 => MediaNextTrack,
+        164 => MediaPlayPause,
+        165 => MediaPreviousTrack,
+        166 => MediaStop,
+        167 => MediaRecord,
+        168 => MediaRewind,
+        172 => BrowserHome,
+        173 => BrowserRefresh,
+        183 => F13,
+        184 => F14,
+        185 => F15,
+        186 => F16,
+        187 => F17,
+        188 => F18,
+        189 => F19,
+        190 => F20,
+        191 => F21,
+        192 => F22,
+        193 => F23,
+        194 => F24,
+        200 => MediaPlay,
+        201 => MediaPause,
+        208 => MediaFastForward,
+        217 => BrowserSearch,
+        224 => BrightnessDown,
+        225 => BrightnessUp,
+        _ => Unknown,
+    }
+}
+
+/// Returns the character for a keysym.
+/// Only Latin-1 keysyms and keysyms that directly encode a Unicode code point are converted.
+/// Keysyms are listed here:
+/// https://gitlab.freedesktop.org/xorg/proto/xorgproto/-/blob/master/include/X11/keysymdef.h
+pub fn keysym_to_character(keysym: u32) -> Option<char> {
+    match keysym {
+        0x20..=0x7E | 0xA0..=0xFF => std::char::from_u32(keysym),
+        0x0100_0000..=0x0110_FFFF => std::char::from_u32(keysym - 0x0100_0000),
+        _ => None,
+    }
+}
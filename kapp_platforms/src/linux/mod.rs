@@ -0,0 +1,233 @@
+//! Linux has two windowing systems, so one binary picks between them at runtime.
+//! Wayland is used when a Wayland compositor is available, otherwise X11 is used.
+//! The Wayland session variable is checked first because Wayland sessions usually
+//! also set `DISPLAY` for XWayland.
+pub(crate) mod cursors_linux;
+pub(crate) mod keys_linux;
+pub(crate) mod wake_pipe_linux;
+
+use crate::{wayland, x11};
+use kapp_platform_common::*;
+use std::time::Duration;
+
+pub mod prelude {
+    pub use super::{PlatformApplication, PlatformEventLoop};
+    pub use kapp_platform_common::*;
+}
+
+pub enum PlatformApplication {
+    X11(x11::PlatformApplication),
+    Wayland(wayland::PlatformApplication),
+}
+
+pub enum PlatformEventLoop {
+    X11(x11::PlatformEventLoop),
+    Wayland(wayland::PlatformEventLoop),
+}
+
+/// Forwards a call to whichever backend is in use.
+macro_rules! backend {
+    ($self:ident, $backend:ident => $call:expr) => {
+        match $self {
+            Self::X11($backend) => $call,
+            Self::Wayland($backend) => $call,
+        }
+    };
+}
+
+impl PlatformApplicationTrait for PlatformApplication {
+    type EventLoop = PlatformEventLoop;
+
+    fn new() -> Self {
+        let wayland_display = std::env::var_os("WAYLAND_DISPLAY").filter(|d| !d.is_empty());
+        if wayland_display.is_some() {
+            if let Some(application) = wayland::PlatformApplication::try_new() {
+                return Self::Wayland(application);
+            }
+        }
+        Self::X11(x11::PlatformApplication::new())
+    }
+
+    fn event_loop(&mut self) -> Self::EventLoop {
+        match self {
+            Self::X11(application) => PlatformEventLoop::X11(application.event_loop()),
+            Self::Wayland(application) => PlatformEventLoop::Wayland(application.event_loop()),
+        }
+    }
+
+    fn set_window_position(&mut self, window_id: WindowId, x: u32, y: u32) {
+        backend!(self, a => a.set_window_position(window_id, x, y))
+    }
+    fn set_window_size(&mut self, window_id: WindowId, width: u32, height: u32) {
+        backend!(self, a => a.set_window_size(window_id, width, height))
+    }
+    fn set_window_title(&mut self, window_id: WindowId, title: &str) {
+        backend!(self, a => a.set_window_title(window_id, title))
+    }
+    fn set_window_icon(&mut self, window_id: WindowId, icon: &[Icon]) {
+        backend!(self, a => a.set_window_icon(window_id, icon))
+    }
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        backend!(self, a => a.set_window_resizable(window_id, resizable))
+    }
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        backend!(self, a => a.set_window_minimum_size(window_id, size))
+    }
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        backend!(self, a => a.set_window_maximum_size(window_id, size))
+    }
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        backend!(self, a => a.set_window_decorations(window_id, decorations))
+    }
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool) {
+        backend!(self, a => a.set_window_always_on_top(window_id, always_on_top))
+    }
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool) {
+        backend!(self, a => a.set_window_visible(window_id, visible))
+    }
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32) {
+        backend!(self, a => a.set_window_opacity(window_id, opacity))
+    }
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        backend!(self, a => a.set_window_input_region(window_id, region))
+    }
+    fn minimize_window(&mut self, window_id: WindowId) {
+        backend!(self, a => a.minimize_window(window_id))
+    }
+    fn maximize_window(&mut self, window_id: WindowId) {
+        backend!(self, a => a.maximize_window(window_id))
+    }
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
+        backend!(self, a => a.set_window_fullscreen(window_id, fullscreen))
+    }
+    fn restore_window(&mut self, window_id: WindowId) {
+        backend!(self, a => a.restore_window(window_id))
+    }
+    fn close_window(&mut self, window_id: WindowId) {
+        backend!(self, a => a.close_window(window_id))
+    }
+    fn get_window_size(&mut self, window_id: WindowId) -> (u32, u32) {
+        backend!(self, a => a.get_window_size(window_id))
+    }
+    fn get_window_scale(&mut self, window_id: WindowId) -> f64 {
+        backend!(self, a => a.get_window_scale(window_id))
+    }
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32) {
+        backend!(self, a => a.get_window_position(window_id))
+    }
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool {
+        backend!(self, a => a.is_window_minimized(window_id))
+    }
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        backend!(self, a => a.is_window_maximized(window_id))
+    }
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        backend!(self, a => a.get_window_fullscreen(window_id))
+    }
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        backend!(self, a => a.window_has_focus(window_id))
+    }
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        backend!(self, a => a.is_window_visible(window_id))
+    }
+    fn screens(&mut self) -> Vec<Screen> {
+        backend!(self, a => a.screens())
+    }
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        backend!(self, a => a.window_screen(window_id))
+    }
+    fn redraw_window(&mut self, window_id: WindowId) {
+        backend!(self, a => a.redraw_window(window_id))
+    }
+    fn lock_mouse_position(&mut self) {
+        backend!(self, a => a.lock_mouse_position())
+    }
+    fn unlock_mouse_position(&mut self) {
+        backend!(self, a => a.unlock_mouse_position())
+    }
+    fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
+        backend!(self, a => a.new_window(window_parameters))
+    }
+    fn quit(&self) {
+        backend!(self, a => a.quit())
+    }
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        backend!(self, a => a.set_control_flow(control_flow))
+    }
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        backend!(self, a => a.set_timer(duration, repeat))
+    }
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        backend!(self, a => a.cancel_timer(timer_id))
+    }
+    fn proxy(&mut self) -> EventLoopProxy {
+        backend!(self, a => a.proxy())
+    }
+    fn set_cursor(&mut self, cursor: Cursor) {
+        backend!(self, a => a.set_cursor(cursor))
+    }
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        backend!(self, a => a.create_cursor(rgba, width, height, hotspot))
+    }
+    fn hide_cursor(&mut self) {
+        backend!(self, a => a.hide_cursor())
+    }
+    fn show_cursor(&mut self) {
+        backend!(self, a => a.show_cursor())
+    }
+    fn start_text_input(&mut self) {
+        backend!(self, a => a.start_text_input())
+    }
+    fn end_text_input(&mut self) {
+        backend!(self, a => a.end_text_input())
+    }
+    fn set_text_input_rectangle(
+        &mut self,
+        window_id: WindowId,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    ) {
+        backend!(self, a => a.set_text_input_rectangle(window_id, x, y, width, height))
+    }
+    fn clipboard_mime_types(&mut self, selection: ClipboardSelection) -> Vec<String> {
+        backend!(self, a => a.clipboard_mime_types(selection))
+    }
+    fn get_clipboard_data(
+        &mut self,
+        selection: ClipboardSelection,
+        mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        backend!(self, a => a.get_clipboard_data(selection, mime_type))
+    }
+    fn set_clipboard_data(&mut self, selection: ClipboardSelection, mime_type: &str, data: &[u8]) {
+        backend!(self, a => a.set_clipboard_data(selection, mime_type, data))
+    }
+    fn set_drop_accepted(&mut self, accepted: bool) {
+        backend!(self, a => a.set_drop_accepted(accepted))
+    }
+    fn raw_window_handle(&self, window_id: WindowId) -> RawWindowHandle {
+        backend!(self, a => a.raw_window_handle(window_id))
+    }
+
+    fn inject_event(&mut self, event: Event) {
+        backend!(self, a => a.inject_event(event))
+    }
+}
+
+impl PlatformEventLoopTrait for PlatformEventLoop {
+    fn run(&self, callback: Box<dyn FnMut(Event)>) {
+        backend!(self, event_loop => event_loop.run(callback))
+    }
+}
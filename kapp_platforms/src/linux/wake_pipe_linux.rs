@@ -0,0 +1,74 @@
+use kapp_platform_common::EventLoopProxy;
+use std::os::raw::{c_int, c_void};
+use std::sync::Arc;
+
+// From libc, which is always linked.
+const O_NONBLOCK: c_int = 0o4000;
+const O_CLOEXEC: c_int = 0o2000000;
+
+extern "C" {
+    fn pipe2(fds: *mut c_int, flags: c_int) -> c_int;
+    fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+    fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+    fn close(fd: c_int) -> c_int;
+}
+
+/// The ends of the pipe, shared by the event loop and its proxies.
+/// They're closed when the last of them is dropped, so a proxy that outlives
+/// the application never writes to a closed or reused file descriptor.
+struct PipeFds {
+    read_fd: c_int,
+    write_fd: c_int,
+}
+
+impl Drop for PipeFds {
+    fn drop(&mut self) {
+        unsafe {
+            close(self.read_fd);
+            close(self.write_fd);
+        }
+    }
+}
+
+/// A pipe that other threads write to so a waiting event loop wakes.
+/// The event loop polls the read end alongside its display connection.
+pub struct WakePipe {
+    fds: Arc<PipeFds>,
+}
+
+impl WakePipe {
+    pub fn new() -> Self {
+        let mut fds = [-1; 2];
+        // Both ends are non-blocking so a full pipe never blocks a proxy or the event loop.
+        unsafe {
+            if pipe2(fds.as_mut_ptr(), O_NONBLOCK | O_CLOEXEC) != 0 {
+                panic!("Could not create a pipe to wake the event loop");
+            }
+        }
+        Self {
+            fds: Arc::new(PipeFds {
+                read_fd: fds[0],
+                write_fd: fds[1],
+            }),
+        }
+    }
+
+    pub fn read_fd(&self) -> c_int {
+        self.fds.read_fd
+    }
+
+    /// Empties the pipe once the event loop has woken.
+    pub fn clear(&self) {
+        let mut buffer = [0u8; 64];
+        let buffer_pointer = buffer.as_mut_ptr() as *mut c_void;
+        unsafe { while read(self.fds.read_fd, buffer_pointer, buffer.len()) > 0 {} }
+    }
+
+    pub fn proxy(&self) -> EventLoopProxy {
+        let fds = self.fds.clone();
+        EventLoopProxy::new(move || unsafe {
+            // If the pipe is full the event loop is already going to wake.
+            write(fds.write_fd, [1u8].as_ptr() as *const c_void, 1);
+        })
+    }
+}
@@ -47,6 +47,19 @@ pub mod Sels {
     pub static mut pointingHandCursor: *const c_void = null();
     pub static mut openHandCursor: *const c_void = null();
     pub static mut closedHandCursor: *const c_void = null();
+    pub static mut resizeUpDownCursor: *const c_void = null();
+    pub static mut resizeLeftRightCursor: *const c_void = null();
+    pub static mut crosshairCursor: *const c_void = null();
+    pub static mut operationNotAllowedCursor: *const c_void = null();
+    // Cursors that aren't part of the public API.
+    pub static mut _windowResizeNorthEastSouthWestCursor: *const c_void = null();
+    pub static mut _windowResizeNorthWestSouthEastCursor: *const c_void = null();
+    pub static mut busyButClickableCursor: *const c_void = null();
+    pub static mut _moveCursor: *const c_void = null();
+    pub static mut _helpCursor: *const c_void = null();
+    pub static mut _zoomInCursor: *const c_void = null();
+    pub static mut _zoomOutCursor: *const c_void = null();
+    pub static mut respondsToSelector: *const c_void = null();
     pub static mut set: *const c_void = null();
     pub static mut unhide: *const c_void = null();
     pub static mut hide: *const c_void = null();
@@ -66,6 +79,7 @@ pub mod Sels {
     pub static mut applicationShouldTerminateAfterLastWindowClosed: *const c_void = null();
     pub static mut applicationShouldTerminate: *const c_void = null();
     pub static mut applicationWillTerminate: *const c_void = null();
+    pub static mut applicationDidChangeScreenParameters: *const c_void = null();
     pub static mut window: *const c_void = null();
     pub static mut keyCode: *const c_void = null();
     pub static mut isARepeat: *const c_void = null();
@@ -127,6 +141,18 @@ pub mod Sels {
         pointingHandCursor = get_sel("pointingHandCursor");
         openHandCursor = get_sel("openHandCursor");
         closedHandCursor = get_sel("closedHandCursor");
+        resizeUpDownCursor = get_sel("resizeUpDownCursor");
+        resizeLeftRightCursor = get_sel("resizeLeftRightCursor");
+        crosshairCursor = get_sel("crosshairCursor");
+        operationNotAllowedCursor = get_sel("operationNotAllowedCursor");
+        _windowResizeNorthEastSouthWestCursor = get_sel("_windowResizeNorthEastSouthWestCursor");
+        _windowResizeNorthWestSouthEastCursor = get_sel("_windowResizeNorthWestSouthEastCursor");
+        busyButClickableCursor = get_sel("busyButClickableCursor");
+        _moveCursor = get_sel("_moveCursor");
+        _helpCursor = get_sel("_helpCursor");
+        _zoomInCursor = get_sel("_zoomInCursor");
+        _zoomOutCursor = get_sel("_zoomOutCursor");
+        respondsToSelector = get_sel("respondsToSelector:");
         set = get_sel("set");
         unhide = get_sel("unhide");
         hide = get_sel("hide");
@@ -146,6 +172,7 @@ pub mod Sels {
             get_sel("applicationShouldTerminateAfterLastWindowClosed:");
         applicationShouldTerminate = get_sel("applicationShouldTerminate:");
         applicationWillTerminate = get_sel("applicationWillTerminate:");
+        applicationDidChangeScreenParameters = get_sel("applicationDidChangeScreenParameters:");
         window = get_sel("window");
         keyCode = get_sel("keyCode");
         isARepeat = get_sel("isARepeat");
@@ -250,6 +277,10 @@ pub const NSTerminateNow: NSUInteger = 1;
 pub const NSTerminateCancel: NSUInteger = 0;
 
 pub const NSEventModifierFlagCapsLock: NSUInteger = 1 << 16;
+pub const NSEventModifierFlagShift: NSUInteger = 1 << 17;
+pub const NSEventModifierFlagControl: NSUInteger = 1 << 18;
+pub const NSEventModifierFlagOption: NSUInteger = 1 << 19;
+pub const NSEventModifierFlagCommand: NSUInteger = 1 << 20;
 
 pub const kCFRunLoopBeforeWaiting: CFRunLoopActivity = 1 << 5;
 
@@ -257,8 +288,12 @@ pub const NSWindowStyleMaskTitled: NSUInteger = 1;
 pub const NSWindowStyleMaskClosable: NSUInteger = 1 << 1;
 pub const NSWindowStyleMaskMiniaturizable: NSUInteger = 1 << 2;
 pub const NSWindowStyleMaskResizable: NSUInteger = 1 << 3;
+pub const NSWindowStyleMaskFullScreen: NSUInteger = 1 << 14;
 pub const NSWindowStyleMaskFullSizeContentView: NSUInteger = 1 << 15;
 
+pub const NSNormalWindowLevel: NSInteger = 0;
+pub const NSFloatingWindowLevel: NSInteger = 3;
+
 pub const NSBackingStoreBuffered: NSUInteger = 2;
 pub const UTF8_ENCODING: usize = 4;
 
@@ -296,14 +331,48 @@ extern "C" {
     pub fn CFRunLoopAddSource(rl: CFRunLoopRef, source: CFRunLoopSourceRef, mode: CFRunLoopMode);
     #[allow(dead_code)]
     pub fn CFRunLoopSourceInvalidate(source: CFRunLoopSourceRef);
-    // pub fn CFRunLoopSourceSignal(source: CFRunLoopSourceRef);
+    pub fn CFRunLoopSourceSignal(source: CFRunLoopSourceRef);
+
+    pub fn CFAbsoluteTimeGetCurrent() -> CFAbsoluteTime;
+    // The context is a `CFRunLoopTimerContext`, which isn't used.
+    pub fn CFRunLoopTimerCreate(
+        allocator: CFAllocatorRef,
+        fire_date: CFAbsoluteTime,
+        interval: CFTimeInterval,
+        flags: CFOptionFlags,
+        order: CFIndex,
+        callout: CFRunLoopTimerCallBack,
+        context: *const c_void,
+    ) -> CFRunLoopTimerRef;
+    pub fn CFRunLoopAddTimer(rl: CFRunLoopRef, timer: CFRunLoopTimerRef, mode: CFRunLoopMode);
+    pub fn CFRunLoopTimerSetNextFireDate(timer: CFRunLoopTimerRef, fire_date: CFAbsoluteTime);
 }
 
 extern "C" {
     // pub fn CGWarpMouseCursorPosition(new_cursor_position: CGPoint) -> i32;
     pub fn CGAssociateMouseAndMouseCursorPosition(connected: bool) -> i32;
+
+    // The returned array is an NSArray of CGDisplayModes.
+    pub fn CGDisplayCopyAllDisplayModes(
+        display: CGDirectDisplayID,
+        options: *const c_void,
+    ) -> *mut Object;
+    pub fn CGDisplayModeGetPixelWidth(mode: CGDisplayModeRef) -> usize;
+    pub fn CGDisplayModeGetPixelHeight(mode: CGDisplayModeRef) -> usize;
+    pub fn CGDisplayModeGetRefreshRate(mode: CGDisplayModeRef) -> f64;
+    pub fn CGDisplayModeCopyPixelEncoding(mode: CGDisplayModeRef) -> CFStringRef;
+    pub fn CGDisplaySetDisplayMode(
+        display: CGDirectDisplayID,
+        mode: CGDisplayModeRef,
+        options: *const c_void,
+    ) -> i32;
+    pub fn CGRestorePermanentDisplayConfiguration();
 }
 
+pub type CGDirectDisplayID = u32;
+pub enum CGDisplayMode {}
+pub type CGDisplayModeRef = *mut CGDisplayMode;
+
 #[allow(non_snake_case)]
 #[repr(C)]
 pub struct CFRunLoopSourceContext {
@@ -330,6 +399,10 @@ pub type CFRunLoopRef = *mut CFRunLoop;
 pub type CFRunLoopMode = CFStringRef;
 pub enum CFRunLoopObserver {}
 pub type CFRunLoopObserverRef = *mut CFRunLoopObserver;
+pub enum CFRunLoopTimer {}
+pub type CFRunLoopTimerRef = *mut CFRunLoopTimer;
+pub type CFAbsoluteTime = f64;
+pub type CFTimeInterval = f64;
 
 pub type CFStringRef = *const Object; // CFString
 pub type CFIndex = std::os::raw::c_long;
@@ -338,6 +411,7 @@ pub type CFRunLoopActivity = CFOptionFlags;
 
 pub type CFRunLoopObserverCallBack =
     extern "C" fn(observer: CFRunLoopObserverRef, activity: CFRunLoopActivity, info: *mut c_void);
+pub type CFRunLoopTimerCallBack = extern "C" fn(timer: CFRunLoopTimerRef, info: *mut c_void);
 
 // https://developer.apple.com/documentation/corefoundation/cfrunloopobservercontext?language=objc
 #[repr(C)]
@@ -3,7 +3,9 @@ use super::window_mac::WindowState;
 use kapp_platform_common::*;
 
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::c_void;
+use std::time::Duration;
 
 thread_local!(pub(crate) static APPLICATION_DATA: RefCell<Box<ApplicationData>> = RefCell::new(Box::new(ApplicationData::new())));
 
@@ -14,6 +16,12 @@ pub(crate) struct ApplicationData {
     pub actually_terminate: bool, // Set when quit is called. Indicates the program should quit.
     pub text_input_enabled: bool, // Should text input be sent in addition to KeyDown events?
     pub mouse_lock: bool,
+    /// The screens when they last changed, to find which changed on the next notification.
+    pub screens: Vec<Screen>,
+    /// The fullscreen each window asked for, reported when the window enters fullscreen.
+    pub fullscreens: HashMap<WindowId, Fullscreen>,
+    /// Wakes the run loop when the control flow stops waiting.
+    pub wait_timer: CFRunLoopTimerRef,
 }
 
 impl ApplicationData {
@@ -24,10 +32,34 @@ impl ApplicationData {
             actually_terminate: false,
             text_input_enabled: false,
             mouse_lock: false,
+            screens: Vec::new(),
+            fullscreens: HashMap::new(),
+            wait_timer: std::ptr::null_mut(),
         }
     }
 }
 
+/// Windows without a titlebar can't become the key window unless a subclass allows it.
+fn ns_window_declaration() -> *const objc::runtime::Class {
+    extern "C" fn yes(_: &Object, _: Sel) -> BOOL {
+        YES
+    }
+
+    let superclass = class!(NSWindow);
+    let mut decl = ClassDecl::new("kappNSWindowClass", superclass).unwrap();
+    unsafe {
+        decl.add_method(
+            sel!(canBecomeKeyWindow),
+            yes as extern "C" fn(&Object, Sel) -> BOOL,
+        );
+        decl.add_method(
+            sel!(canBecomeMainWindow),
+            yes as extern "C" fn(&Object, Sel) -> BOOL,
+        );
+    }
+    decl.register()
+}
+
 fn window_delegate_declaration() -> *const objc::runtime::Class {
     let superclass = unsafe { &*NSResponderClass };
     let mut decl = ClassDecl::new("kappWindowClass", superclass).unwrap();
@@ -49,6 +81,8 @@ fn application_delegate_declaration() -> *const objc::runtime::Class {
     decl.register()
 }
 
+/// Proxies signal this source to wake the run loop from other threads.
+/// Its events are sent at the end of the loop, so the source doesn't handle them.
 fn create_run_loop_source() -> CFRunLoopSourceRef {
     extern "C" fn event_loop_proxy_handler(_: *mut std::ffi::c_void) {}
 
@@ -64,11 +98,34 @@ fn create_run_loop_source() -> CFRunLoopSourceRef {
     }
 }
 
+/// The timer's fire date is set at the end of each loop, from the control flow.
+/// It repeats so that it remains valid after firing.
+fn create_wait_timer() -> CFRunLoopTimerRef {
+    extern "C" fn wait_timer_handler(_: CFRunLoopTimerRef, _: *mut std::ffi::c_void) {}
+
+    unsafe {
+        let timer = CFRunLoopTimerCreate(
+            std::ptr::null_mut(),
+            f64::MAX,
+            f64::MAX,
+            0,
+            0,
+            wait_timer_handler,
+            std::ptr::null(),
+        );
+        CFRunLoopAddTimer(CFRunLoopGetMain(), timer, kCFRunLoopCommonModes);
+        timer
+    }
+}
+
 extern "C" fn control_flow_end_handler(
     _: CFRunLoopObserverRef,
     _: CFRunLoopActivity,
     _: *mut std::ffi::c_void,
 ) {
+    event_loop_proxy::send_proxy_events();
+    control_flow::resume_if_reached();
+    timers::send_due_timers();
     event_receiver::send_event(Event::EventsCleared);
 
     redraw_manager::begin_draw_flush();
@@ -136,12 +193,31 @@ extern "C" fn control_flow_end_handler(
             CFRunLoopWakeUp(rl);
         }
     }
+
+    // Wake up the main loop when the control flow's deadline passes,
+    // or immediately when polling.
+    if let Ok(wait_timer) = APPLICATION_DATA.try_with(|d| d.borrow().wait_timer) {
+        unsafe {
+            let fire_date = match control_flow::timeout() {
+                Some(timeout) => CFAbsoluteTimeGetCurrent() + timeout.as_secs_f64(),
+                None => f64::MAX,
+            };
+            CFRunLoopTimerSetNextFireDate(wait_timer, fire_date);
+        }
+    }
 }
 
 pub struct PlatformEventLoop {
     ns_application: *mut Object,
 }
 
+/// The run loop source proxies signal. It's never released so proxies can outlive the application.
+struct RunLoopSource(CFRunLoopSourceRef);
+
+// `CFRunLoopSourceSignal` and `CFRunLoopWakeUp` can be called from any thread.
+unsafe impl Send for RunLoopSource {}
+unsafe impl Sync for RunLoopSource {}
+
 impl PlatformEventLoopTrait for PlatformEventLoop {
     fn run(&self, callback: Box<dyn FnMut(Event)>) {
         event_receiver::set_callback(callback);
@@ -154,10 +230,11 @@ impl PlatformEventLoopTrait for PlatformEventLoop {
 
 pub struct PlatformApplication {
     // application_data: Rc<RefCell<ApplicationData>>,
+    ns_window_class: *const objc::runtime::Class,
     window_class: *const objc::runtime::Class,
     view_class: *const objc::runtime::Class,
     ns_application: *mut Object,
-    _run_loop_custom_event_source: CFRunLoopSourceRef,
+    run_loop_custom_event_source: CFRunLoopSourceRef,
 }
 
 impl PlatformApplicationTrait for PlatformApplication {
@@ -210,17 +287,22 @@ impl PlatformApplicationTrait for PlatformApplication {
                 std::ptr::null(),
             );
             CFRunLoopAddObserver(CFRunLoopGetMain(), observer, kCFRunLoopCommonModes);
+            let wait_timer = create_wait_timer();
 
             // Store the application in a thread local.
             APPLICATION_DATA.with(|d| {
-                d.borrow_mut().ns_application = ns_application;
+                let mut data = d.borrow_mut();
+                data.ns_application = ns_application;
+                data.screens = screens();
+                data.wait_timer = wait_timer;
             });
 
             Self {
+                ns_window_class: ns_window_declaration(),
                 window_class: window_delegate_declaration(),
                 view_class: view_delegate_declaration(),
                 ns_application,
-                _run_loop_custom_event_source: run_loop_custom_event_source,
+                run_loop_custom_event_source,
             }
         }
     }
@@ -269,6 +351,107 @@ impl PlatformApplicationTrait for PlatformApplication {
         }
     }
 
+    fn set_window_icon(&mut self, _window_id: WindowId, icon: &[Icon]) {
+        unsafe { set_application_icon(icon) }
+    }
+
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        unsafe {
+            update_style_mask(window_id.raw() as *mut Object, |style_mask| {
+                if resizable {
+                    style_mask | NSWindowStyleMaskResizable
+                } else {
+                    style_mask & !NSWindowStyleMaskResizable
+                }
+            });
+        }
+    }
+
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        unsafe {
+            let window = window_id.raw() as *mut Object;
+            let backing_scale: CGFloat = msg_send![window, backingScaleFactor];
+            let (width, height) = size.unwrap_or((0, 0));
+            // Includes the titlebar
+            let () = msg_send![window, setMinSize: NSSize::new(width as f64 / backing_scale, height as f64 / backing_scale)];
+        }
+    }
+
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        unsafe {
+            let window = window_id.raw() as *mut Object;
+            let backing_scale: CGFloat = msg_send![window, backingScaleFactor];
+            // The default maximum size is the largest float.
+            let size = size.map_or(
+                NSSize::new(f32::MAX as f64, f32::MAX as f64),
+                |(width, height)| {
+                    NSSize::new(width as f64 / backing_scale, height as f64 / backing_scale)
+                },
+            );
+            // Includes the titlebar
+            let () = msg_send![window, setMaxSize: size];
+        }
+    }
+
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        let titled =
+            NSWindowStyleMaskTitled | NSWindowStyleMaskClosable | NSWindowStyleMaskMiniaturizable;
+        unsafe {
+            update_style_mask(window_id.raw() as *mut Object, |style_mask| {
+                if decorations {
+                    style_mask | titled
+                } else {
+                    style_mask & !titled
+                }
+            });
+        }
+    }
+
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool) {
+        let level = if always_on_top {
+            NSFloatingWindowLevel
+        } else {
+            NSNormalWindowLevel
+        };
+        unsafe {
+            let () = msg_send![window_id.raw() as *mut Object, setLevel: level];
+        }
+    }
+
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool) {
+        unsafe {
+            let window = window_id.raw() as *mut Object;
+            if visible {
+                let () = msg_send![window, makeKeyAndOrderFront: nil];
+            } else {
+                let () = msg_send![window, orderOut: nil];
+            }
+        }
+    }
+
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32) {
+        unsafe {
+            let () = msg_send![window_id.raw() as *mut Object, setAlphaValue: opacity as CGFloat];
+        }
+    }
+
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        // A window can only ignore all mouse events or none of them.
+        let ignore_mouse_events = region.is_some_and(|region| region.is_empty());
+        unsafe {
+            let () = msg_send![
+                window_id.raw() as *mut Object,
+                setIgnoresMouseEvents: if ignore_mouse_events { YES } else { NO }
+            ];
+        }
+        // Other regions can't be used, so the whole window receives input.
+        region.is_none_or(|region| region.is_empty())
+    }
+
     fn minimize_window(&mut self, window_id: WindowId) {
         unsafe {
             let () = msg(window_id.raw() as *mut Object, Sels::miniaturize, (nil,));
@@ -280,13 +463,59 @@ impl PlatformApplicationTrait for PlatformApplication {
         // There is no analogous behavior?
     }
 
-    fn fullscreen_window(&mut self, window_id: WindowId) {
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
         unsafe {
-            let () = msg(
-                window_id.raw() as *mut Object,
-                Sels::toggleFullScreen,
-                (nil,),
-            );
+            let window = window_id.raw() as *mut Object;
+            let style_mask: NSUInteger = msg_send![window, styleMask];
+            let is_fullscreen = style_mask & NSWindowStyleMaskFullScreen != 0;
+
+            // Windows become fullscreen on the screen they're on, so they're moved first.
+            let screen_id = match fullscreen {
+                Fullscreen::Off => {
+                    if is_fullscreen {
+                        let () = msg(window, Sels::toggleFullScreen, (nil,));
+                    }
+                    return;
+                }
+                Fullscreen::Borderless(None) => self.window_screen(window_id),
+                Fullscreen::Borderless(Some(screen_id)) => Some(screen_id),
+                Fullscreen::Exclusive(video_mode) => Some(video_mode.screen_id),
+            };
+            let fullscreen = match fullscreen {
+                Fullscreen::Borderless(_) => Fullscreen::Borderless(screen_id),
+                fullscreen => fullscreen,
+            };
+
+            let old_fullscreen = APPLICATION_DATA.with(|d| {
+                d.borrow_mut()
+                    .fullscreens
+                    .insert(window_id, fullscreen)
+                    .unwrap_or(Fullscreen::Off)
+            });
+            if let Fullscreen::Exclusive(_) = old_fullscreen {
+                CGRestorePermanentDisplayConfiguration();
+            }
+            if let Fullscreen::Exclusive(video_mode) = fullscreen {
+                set_display_mode(&video_mode);
+            }
+
+            if is_fullscreen {
+                // MacOS only sends a notification when the window enters fullscreen.
+                if old_fullscreen != fullscreen {
+                    event_receiver::send_event(Event::WindowFullscreened {
+                        fullscreen,
+                        window_id,
+                    });
+                }
+            } else {
+                if screen_id != self.window_screen(window_id) {
+                    if let Some(ns_screen) = screen_id.and_then(|id| ns_screen(id)) {
+                        let frame: CGRect = msg(ns_screen, Sels::frame, ());
+                        let () = msg_send![window, setFrameOrigin: frame.origin];
+                    }
+                }
+                let () = msg(window, Sels::toggleFullScreen, (nil,));
+            }
         }
     }
 
@@ -295,7 +524,11 @@ impl PlatformApplicationTrait for PlatformApplication {
     }
 
     fn close_window(&mut self, window_id: WindowId) {
+        let fullscreen = APPLICATION_DATA.with(|d| d.borrow_mut().fullscreens.remove(&window_id));
         unsafe {
+            if let Some(Fullscreen::Exclusive(_)) = fullscreen {
+                CGRestorePermanentDisplayConfiguration();
+            }
             let () = msg(window_id.raw() as *mut Object, Sels::close, ());
         }
     }
@@ -324,6 +557,72 @@ impl PlatformApplicationTrait for PlatformApplication {
         get_backing_scale(window_id)
     }
 
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32) {
+        unsafe {
+            let window = window_id.raw() as *mut Object;
+            let screen: *const Object = msg(window, Sels::screen, ());
+            if screen.is_null() {
+                return (0, 0);
+            }
+            let screen_frame: CGRect = msg(screen, Sels::frame, ());
+            let frame: CGRect = msg(window, Sels::frame, ());
+
+            // The same top left corner `set_window_position` sets.
+            let backing_scale = get_backing_scale(window_id);
+            let top = screen_frame.size.height - (frame.origin.y + frame.size.height);
+            (
+                (frame.origin.x * backing_scale).max(0.) as u32,
+                (top * backing_scale).max(0.) as u32,
+            )
+        }
+    }
+
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool {
+        unsafe {
+            let miniaturized: BOOL = msg_send![window_id.raw() as *mut Object, isMiniaturized];
+            miniaturized == YES
+        }
+    }
+
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        unsafe {
+            let zoomed: BOOL = msg_send![window_id.raw() as *mut Object, isZoomed];
+            zoomed == YES
+        }
+    }
+
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        unsafe {
+            let style_mask: NSUInteger = msg_send![window_id.raw() as *mut Object, styleMask];
+            if style_mask & NSWindowStyleMaskFullScreen == 0 {
+                return Fullscreen::Off;
+            }
+        }
+        let fullscreen = APPLICATION_DATA.with(|d| d.borrow().fullscreens.get(&window_id).copied());
+        fullscreen.unwrap_or_else(|| Fullscreen::Borderless(self.window_screen(window_id)))
+    }
+
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        unsafe {
+            let key_window: BOOL = msg_send![window_id.raw() as *mut Object, isKeyWindow];
+            key_window == YES
+        }
+    }
+
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        // `isVisible` is false for minimized windows.
+        let visible: BOOL = unsafe { msg_send![window_id.raw() as *mut Object, isVisible] };
+        visible == YES || self.is_window_minimized(window_id)
+    }
+
+    fn screens(&mut self) -> Vec<Screen> {
+        unsafe { screens() }
+    }
+
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        unsafe { window_screen_id(window_id.raw()) }
+    }
+
     fn lock_mouse_position(&mut self) {
         unsafe {
             CGAssociateMouseAndMouseCursorPosition(false);
@@ -352,11 +651,43 @@ impl PlatformApplicationTrait for PlatformApplication {
                 Cursor::PointingHand => msg(ns_cursor, Sels::pointingHandCursor, ()),
                 Cursor::OpenHand => msg(ns_cursor, Sels::openHandCursor, ()),
                 Cursor::ClosedHand => msg(ns_cursor, Sels::closedHandCursor, ()),
+                Cursor::ResizeNS => msg(ns_cursor, Sels::resizeUpDownCursor, ()),
+                Cursor::ResizeEW => msg(ns_cursor, Sels::resizeLeftRightCursor, ()),
+                Cursor::Crosshair => msg(ns_cursor, Sels::crosshairCursor, ()),
+                Cursor::NotAllowed => msg(ns_cursor, Sels::operationNotAllowedCursor, ()),
+                Cursor::ResizeNESW => private_cursor(Sels::_windowResizeNorthEastSouthWestCursor),
+                Cursor::ResizeNWSE => private_cursor(Sels::_windowResizeNorthWestSouthEastCursor),
+                // MacOS only has one busy cursor.
+                Cursor::Wait | Cursor::Progress => private_cursor(Sels::busyButClickableCursor),
+                Cursor::Move => private_cursor(Sels::_moveCursor),
+                Cursor::Help => private_cursor(Sels::_helpCursor),
+                Cursor::ZoomIn => private_cursor(Sels::_zoomInCursor),
+                Cursor::ZoomOut => private_cursor(Sels::_zoomOutCursor),
+                Cursor::Custom(custom_cursor) => custom_cursor.raw() as *mut Object,
             }
         };
         let () = unsafe { msg(cursor, Sels::set, ()) };
     }
 
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        unsafe {
+            let image = new_image(rgba, width, height);
+
+            // Custom cursors are kept for the lifetime of the program.
+            let hotspot = NSPoint::new(hotspot.0 as CGFloat, hotspot.1 as CGFloat);
+            let cursor: *mut Object = msg_send![class!(NSCursor), alloc];
+            let cursor: *mut Object = msg_send![cursor, initWithImage: image hotSpot: hotspot];
+            let () = msg_send![image, release];
+            CustomCursor::new(cursor as *mut c_void)
+        }
+    }
+
     fn hide_cursor(&mut self) {
         // For every call to 'hide' an 'unhide' must be called to make the cursor visible.
         // Because of this 'unhide' is always called before every call to hide.
@@ -376,8 +707,15 @@ impl PlatformApplicationTrait for PlatformApplication {
     }
 
     fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
-        let result =
-            super::window_mac::build(window_parameters, self.window_class, self.view_class);
+        let result = super::window_mac::build(
+            window_parameters,
+            self.ns_window_class,
+            self.window_class,
+            self.view_class,
+        );
+        if !window_parameters.icon.is_empty() {
+            unsafe { set_application_icon(&window_parameters.icon) };
+        }
         result.unwrap()
     }
 
@@ -391,6 +729,50 @@ impl PlatformApplicationTrait for PlatformApplication {
         // to give the user program a chance to process events.
     }
 
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        // The wait timer is updated before the run loop next waits.
+        control_flow::set_control_flow(control_flow);
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        timers::add_timer(duration, repeat)
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        timers::remove_timer(timer_id);
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        let source = RunLoopSource(self.run_loop_custom_event_source);
+        EventLoopProxy::new(move || unsafe {
+            CFRunLoopSourceSignal(source.0);
+            CFRunLoopWakeUp(CFRunLoopGetMain());
+        })
+    }
+
+    fn clipboard_mime_types(&mut self, _selection: ClipboardSelection) -> Vec<String> {
+        // Clipboard access isn't implemented on MacOS yet.
+        Vec::new()
+    }
+    fn get_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+    fn set_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+        _data: &[u8],
+    ) {
+    }
+
+    fn set_drop_accepted(&mut self, _accepted: bool) {
+        // Drag and drop isn't implemented on MacOS yet.
+    }
+
     fn raw_window_handle(&self, window_id: WindowId) -> RawWindowHandle {
         unsafe {
             let ns_window = window_id.raw();
@@ -430,6 +812,10 @@ impl PlatformApplicationTrait for PlatformApplication {
             (*window_state).text_input_rectangle = (x, y, width, height);
         }
     }
+
+    fn inject_event(&mut self, event: Event) {
+        event_receiver::send_event(event);
+    }
 }
 
 pub fn get_backing_scale(window_id: WindowId) -> CGFloat {
@@ -442,3 +828,225 @@ impl Drop for PlatformApplication {
         self.quit();
     }
 }
+
+/// Returns a cursor that isn't part of the public NSCursor API,
+/// or the arrow if the cursor doesn't exist on this version of MacOS.
+unsafe fn private_cursor(selector: *const c_void) -> *mut Object {
+    let ns_cursor = &*NSCursorClass;
+    let responds: BOOL = msg(
+        ns_cursor,
+        Sels::respondsToSelector,
+        (Sel::from_ptr(selector),),
+    );
+    if responds == YES {
+        msg(ns_cursor, selector, ())
+    } else {
+        msg(ns_cursor, Sels::arrowCursor, ())
+    }
+}
+
+/// Creates an NSImage from RGBA pixels. The image must be released.
+unsafe fn new_image(rgba: &[u8], width: u32, height: u32) -> *mut Object {
+    // NSImage can read BMP files, which avoids describing the pixel format to NSBitmapImageRep.
+    let bmp = ClipboardImage {
+        width,
+        height,
+        data: rgba.to_vec(),
+    }
+    .to_bmp();
+    let data: *mut Object = msg_send![
+        class!(NSData),
+        dataWithBytes: bmp.as_ptr() as *const c_void
+        length: bmp.len()
+    ];
+    let image: *mut Object = msg_send![class!(NSImage), alloc];
+    msg_send![image, initWithData: data]
+}
+
+/// Screens are identified by their CGDirectDisplayID because NSScreen objects
+/// are replaced when the screens change.
+/// Returns the `NSScreen` with the id, or `None` if it's been disconnected.
+unsafe fn ns_screen(id: ScreenId) -> Option<*mut Object> {
+    let ns_screens: *mut Object = msg_send![class!(NSScreen), screens];
+    let count: NSUInteger = msg_send![ns_screens, count];
+    (0..count)
+        .map(|i| msg_send![ns_screens, objectAtIndex: i])
+        .find(|ns_screen| screen_id(*ns_screen) == id)
+}
+
+pub(crate) unsafe fn window_screen_id(window: *mut c_void) -> Option<ScreenId> {
+    // Windows that are entirely offscreen have no screen.
+    let ns_screen: *mut Object = msg(window as *mut Object, Sels::screen, ());
+    if ns_screen.is_null() {
+        None
+    } else {
+        Some(screen_id(ns_screen))
+    }
+}
+
+unsafe fn screen_id(ns_screen: *mut Object) -> ScreenId {
+    let key = NSString::new("NSScreenNumber");
+    let description: *mut Object = msg_send![ns_screen, deviceDescription];
+    let number: *mut Object = msg_send![description, objectForKey: key.raw];
+    let display_id: u32 = msg_send![number, unsignedIntValue];
+    ScreenId::new(display_id as usize as *mut c_void)
+}
+
+/// Returns the screens with the primary screen first.
+/// Cocoa positions screens in points from the bottom left of the primary screen,
+/// so frames are flipped and multiplied by each screen's scale.
+pub(crate) unsafe fn screens() -> Vec<Screen> {
+    let ns_screens: *mut Object = msg_send![class!(NSScreen), screens];
+    let count: NSUInteger = msg_send![ns_screens, count];
+    let mut primary_height = 0.0;
+    let mut screens = Vec::new();
+    for i in 0..count {
+        let ns_screen: *mut Object = msg_send![ns_screens, objectAtIndex: i];
+        let frame: CGRect = msg(ns_screen, Sels::frame, ());
+        let visible_frame: CGRect = msg_send![ns_screen, visibleFrame];
+        let scale: CGFloat = msg(ns_screen, Sels::backingScaleFactor, ());
+        if i == 0 {
+            primary_height = frame.size.height;
+        }
+        let physical = |rect: &CGRect| {
+            let top = primary_height - (rect.origin.y + rect.size.height);
+            (
+                ((rect.origin.x * scale) as i32, (top * scale) as i32),
+                (
+                    (rect.size.width * scale) as u32,
+                    (rect.size.height * scale) as u32,
+                ),
+            )
+        };
+        let (position, size) = physical(&frame);
+        let (work_area_position, work_area_size) = physical(&visible_frame);
+
+        // `localizedName` requires MacOS 10.15 and `maximumFramesPerSecond` requires MacOS 12.
+        let responds: BOOL = msg_send![ns_screen, respondsToSelector: sel!(localizedName)];
+        let name = if responds == YES {
+            let name: *mut Object = msg_send![ns_screen, localizedName];
+            let utf8: *const std::os::raw::c_char = msg_send![name, UTF8String];
+            std::ffi::CStr::from_ptr(utf8)
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            String::new()
+        };
+        let responds: BOOL = msg_send![ns_screen, respondsToSelector: sel!(maximumFramesPerSecond)];
+        let refresh_rate = if responds == YES {
+            let frames_per_second: NSInteger = msg_send![ns_screen, maximumFramesPerSecond];
+            Some(frames_per_second as f64).filter(|rate| *rate > 0.0)
+        } else {
+            None
+        };
+
+        let id = screen_id(ns_screen);
+        let mut video_modes = Vec::new();
+        for_each_display_mode(id, |video_mode, _| {
+            // Modes that only differ in scaling are listed more than once.
+            if !video_modes.contains(&video_mode) {
+                video_modes.push(video_mode);
+            }
+        });
+
+        screens.push(Screen {
+            id,
+            name,
+            position,
+            size,
+            work_area_position,
+            work_area_size,
+            scale,
+            refresh_rate,
+            video_modes,
+        });
+    }
+    screens
+}
+
+/// Calls `f` with each of the screen's display modes.
+/// The display mode is only valid during the call.
+unsafe fn for_each_display_mode(
+    screen_id: ScreenId,
+    mut f: impl FnMut(VideoMode, CGDisplayModeRef),
+) {
+    let display_modes =
+        CGDisplayCopyAllDisplayModes(screen_id.raw() as CGDirectDisplayID, std::ptr::null());
+    if display_modes.is_null() {
+        return;
+    }
+    let count: NSUInteger = msg_send![display_modes, count];
+    for i in 0..count {
+        let mode: CGDisplayModeRef = msg_send![display_modes, objectAtIndex: i];
+        // The encoding describes each bit of a pixel, such as "--------RRRRRRRRGGGGGGGGBBBBBBBB".
+        let encoding = CGDisplayModeCopyPixelEncoding(mode) as *mut Object;
+        let bit_depth = if encoding.is_null() {
+            32
+        } else {
+            let length: NSUInteger = msg_send![encoding, length];
+            let () = msg_send![encoding, release];
+            length as u32
+        };
+        let video_mode = VideoMode {
+            screen_id,
+            size: (
+                CGDisplayModeGetPixelWidth(mode) as u32,
+                CGDisplayModeGetPixelHeight(mode) as u32,
+            ),
+            refresh_rate: CGDisplayModeGetRefreshRate(mode),
+            bit_depth,
+        };
+        f(video_mode, mode);
+    }
+    let () = msg_send![display_modes, release];
+}
+
+/// Switches the screen to the closest display mode to `video_mode`.
+/// `CGRestorePermanentDisplayConfiguration` returns screens to their previous modes.
+unsafe fn set_display_mode(video_mode: &VideoMode) {
+    let mut closest: Option<(f64, VideoMode)> = None;
+    for_each_display_mode(video_mode.screen_id, |mode, _| {
+        let difference = (mode.refresh_rate - video_mode.refresh_rate).abs();
+        if mode.size == video_mode.size
+            && mode.bit_depth == video_mode.bit_depth
+            && closest.map_or(true, |(closest, _)| difference < closest)
+        {
+            closest = Some((difference, mode));
+        }
+    });
+    if let Some((_, closest)) = closest {
+        let mut set = false;
+        for_each_display_mode(video_mode.screen_id, |mode, display_mode| {
+            if mode == closest && !set {
+                CGDisplaySetDisplayMode(
+                    mode.screen_id.raw() as CGDirectDisplayID,
+                    display_mode,
+                    std::ptr::null(),
+                );
+                set = true;
+            }
+        });
+    }
+}
+
+unsafe fn update_style_mask(window: *mut Object, change: impl FnOnce(NSUInteger) -> NSUInteger) {
+    let style_mask: NSUInteger = msg_send![window, styleMask];
+    let () = msg_send![window, setStyleMask: change(style_mask)];
+}
+
+/// MacOS windows don't have icons, so the application's dock icon is set instead.
+/// The dock scales the icon so the largest size is used.
+/// An empty icon restores the application's default icon.
+unsafe fn set_application_icon(icon: &[Icon]) {
+    let ns_application: *mut Object = msg(&*NSApplicationClass, Sels::sharedApplication, ());
+    match icon.iter().max_by_key(|icon| icon.width * icon.height) {
+        Some(icon) => {
+            let image = new_image(&icon.data, icon.width, icon.height);
+            let () = msg_send![ns_application, setApplicationIconImage: image];
+            let () = msg_send![image, release];
+        }
+        None => {
+            let () = msg_send![ns_application, setApplicationIconImage: nil];
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use super::apple::*;
-use super::application_mac::APPLICATION_DATA;
+use super::application_mac::{window_screen_id, APPLICATION_DATA};
 use super::window_mac::WindowState;
-use kapp_platform_common::{Event, Key, PointerButton, PointerSource, WindowId};
+use kapp_platform_common::{
+    screen_changes, Event, Fullscreen, Key, LogicalKey, Modifiers, PointerButton, PointerSource,
+    WindowId,
+};
 use objc::runtime::Protocol;
-use std::ffi::c_void;
+use std::ffi::{c_void, CStr};
 
 // ------------------------ Window Events --------------------------
 extern "C" fn window_did_move(_this: &Object, _sel: Sel, ns_notification: *mut Object) {
@@ -33,21 +36,38 @@ extern "C" fn window_did_miniaturize(_this: &Object, _sel: Sel, ns_notification:
 
 extern "C" fn window_did_deminiaturize(_this: &Object, _sel: Sel, ns_notification: *mut Object) {
     let window: *mut c_void = unsafe { msg(ns_notification, Sels::object, ()) };
+    // Fullscreen windows can't be minimized on MacOS.
     self::submit_event(Event::WindowRestored {
+        fullscreen: Fullscreen::Off,
         window_id: WindowId::new(window),
     });
 }
 
 extern "C" fn window_did_enter_fullscreen(_this: &Object, _sel: Sel, ns_notification: *mut Object) {
     let window: *mut c_void = unsafe { msg(ns_notification, Sels::object, ()) };
+    let window_id = WindowId::new(window);
+    // Windows made fullscreen with the green button are borderless on their screen.
+    let fullscreen = APPLICATION_DATA.with(|d| {
+        *d.borrow_mut()
+            .fullscreens
+            .entry(window_id)
+            .or_insert_with(|| Fullscreen::Borderless(unsafe { window_screen_id(window) }))
+    });
     self::submit_event(Event::WindowFullscreened {
-        window_id: WindowId::new(window),
+        fullscreen,
+        window_id,
     });
 }
 extern "C" fn window_did_exit_fullscreen(_this: &Object, _sel: Sel, ns_notification: *mut Object) {
     let window: *mut c_void = unsafe { msg(ns_notification, Sels::object, ()) };
+    let window_id = WindowId::new(window);
+    let fullscreen = APPLICATION_DATA.with(|d| d.borrow_mut().fullscreens.remove(&window_id));
+    if let Some(Fullscreen::Exclusive(_)) = fullscreen {
+        unsafe { CGRestorePermanentDisplayConfiguration() };
+    }
     self::submit_event(Event::WindowRestored {
-        window_id: WindowId::new(window),
+        fullscreen: Fullscreen::Off,
+        window_id,
     });
 }
 
@@ -222,6 +242,19 @@ extern "C" fn application_will_terminate(_this: &Object, _sel: Sel, _application
     self::submit_event(Event::Quit {});
 }
 
+extern "C" fn application_did_change_screen_parameters(
+    _this: &Object,
+    _sel: Sel,
+    _notification: *mut Object,
+) {
+    let screens = unsafe { super::application_mac::screens() };
+    let old_screens =
+        APPLICATION_DATA.with(|d| std::mem::replace(&mut d.borrow_mut().screens, screens.clone()));
+    for event in screen_changes(&old_screens, &screens) {
+        self::submit_event(event);
+    }
+}
+
 pub fn add_application_events_to_decl(decl: &mut ClassDecl) {
     unsafe {
         decl.add_method(
@@ -237,6 +270,10 @@ pub fn add_application_events_to_decl(decl: &mut ClassDecl) {
             Sel::from_ptr(Sels::applicationWillTerminate),
             application_will_terminate as extern "C" fn(&Object, Sel, *mut Object),
         );
+        decl.add_method(
+            Sel::from_ptr(Sels::applicationDidChangeScreenParameters),
+            application_did_change_screen_parameters as extern "C" fn(&Object, Sel, *mut Object),
+        );
     }
 }
 // ------------------------ End Application Events --------------------------
@@ -249,17 +286,26 @@ extern "C" fn draw_rect(this: &Object, _sel: Sel, _rect: CGRect) {
 
 extern "C" fn key_down(this: &Object, _sel: Sel, event: *mut Object) {
     unsafe {
-        let key_code = msg(event, Sels::keyCode, ());
+        let key_code: u16 = msg(event, Sels::keyCode, ());
         let repeat: bool = msg(event, Sels::isARepeat, ());
         let key = super::keys_mac::virtual_keycode_to_key(key_code);
+        let logical_key = get_logical_key(event, key);
         let kapp_event = if repeat {
             Event::KeyRepeat {
                 key,
+                scancode: key_code as u32,
+                logical_key,
+                window_id: get_window_id(this),
+                modifiers: get_modifiers(event),
                 timestamp: get_timestamp(event),
             }
         } else {
             Event::KeyDown {
                 key,
+                scancode: key_code as u32,
+                logical_key,
+                window_id: get_window_id(this),
+                modifiers: get_modifiers(event),
                 timestamp: get_timestamp(event),
             }
         };
@@ -274,11 +320,16 @@ extern "C" fn key_down(this: &Object, _sel: Sel, event: *mut Object) {
     }
 }
 
-extern "C" fn key_up(_this: &Object, _sel: Sel, event: *mut Object) {
+extern "C" fn key_up(this: &Object, _sel: Sel, event: *mut Object) {
     unsafe {
-        let key_code = msg(event, Sels::keyCode, ());
+        let key_code: u16 = msg(event, Sels::keyCode, ());
+        let key = super::keys_mac::virtual_keycode_to_key(key_code);
         self::submit_event(Event::KeyUp {
-            key: super::keys_mac::virtual_keycode_to_key(key_code),
+            key,
+            scancode: key_code as u32,
+            logical_key: get_logical_key(event, key),
+            window_id: get_window_id(this),
+            modifiers: get_modifiers(event),
             timestamp: get_timestamp(event),
         });
     }
@@ -287,7 +338,7 @@ extern "C" fn key_up(_this: &Object, _sel: Sel, event: *mut Object) {
 // https://developer.apple.com/documentation/appkit/nsresponder/1527647-flagschanged?language=objc
 // This should be changed to keep track of the modifier state and only update if they were previously pressed.
 // Caps lock keyup events are only registered when the key switches to an off state.
-extern "C" fn flags_changed(_this: &Object, _sel: Sel, event: *mut Object) {
+extern "C" fn flags_changed(this: &Object, _sel: Sel, event: *mut Object) {
     fn get_modifier_state(modifier_flags: u64) -> [bool; 9] {
         [
             modifier_flags & NSEventModifierFlagCapsLock == NSEventModifierFlagCapsLock,
@@ -322,10 +373,20 @@ extern "C" fn flags_changed(_this: &Object, _sel: Sel, event: *mut Object) {
     let flag_state_old = get_modifier_state(modifier_flags_old);
     let flag_state_new = get_modifier_state(modifier_flags_new);
 
+    let key_code: u16 = unsafe { msg(event, Sels::keyCode, ()) };
+    let modifiers = get_modifiers(event);
+    if modifiers != flags_to_modifiers(modifier_flags_old) {
+        self::submit_event(Event::ModifiersChanged { modifiers });
+    }
+
     for i in 0..8 {
         if !flag_state_old[i] && flag_state_new[i] {
             self::submit_event(Event::KeyDown {
                 key: KEYS[i],
+                scancode: key_code as u32,
+                logical_key: LogicalKey::Key(KEYS[i]),
+                window_id: get_window_id(this),
+                modifiers,
                 timestamp: get_timestamp(event),
             })
         }
@@ -333,6 +394,10 @@ extern "C" fn flags_changed(_this: &Object, _sel: Sel, event: *mut Object) {
         if flag_state_old[i] && !flag_state_new[i] {
             self::submit_event(Event::KeyUp {
                 key: KEYS[i],
+                scancode: key_code as u32,
+                logical_key: LogicalKey::Key(KEYS[i]),
+                window_id: get_window_id(this),
+                modifiers,
                 timestamp: get_timestamp(event),
             })
         }
@@ -354,6 +419,14 @@ extern "C" fn mouse_down(this: &Object, _sel: Sel, event: *mut Object) {
         y,
         button: PointerButton::Primary,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: get_window_id(this),
+        modifiers: get_modifiers(event),
         timestamp: get_timestamp(event),
     });
     let click_count: c_int = unsafe { msg(event, Sels::clickCount, ()) };
@@ -362,6 +435,7 @@ extern "C" fn mouse_down(this: &Object, _sel: Sel, event: *mut Object) {
             x,
             y,
             button: PointerButton::Primary,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
     }
@@ -374,6 +448,14 @@ extern "C" fn mouse_up(this: &Object, _sel: Sel, event: *mut Object) {
         y,
         button: PointerButton::Primary,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: get_window_id(this),
+        modifiers: get_modifiers(event),
         timestamp: get_timestamp(event),
     });
     let click_count: c_int = unsafe { msg(event, Sels::clickCount, ()) };
@@ -382,12 +464,14 @@ extern "C" fn mouse_up(this: &Object, _sel: Sel, event: *mut Object) {
             x,
             y,
             button: PointerButton::Primary,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
         self::submit_event(Event::DoubleClick {
             x,
             y,
             button: PointerButton::Primary,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
     }
@@ -401,6 +485,14 @@ extern "C" fn right_mouse_down(this: &Object, _sel: Sel, event: *mut Object) {
         y,
         button: PointerButton::Secondary,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: get_window_id(this),
+        modifiers: get_modifiers(event),
         timestamp: get_timestamp(event),
     });
     let click_count: c_int = unsafe { msg(event, Sels::clickCount, ()) };
@@ -409,6 +501,7 @@ extern "C" fn right_mouse_down(this: &Object, _sel: Sel, event: *mut Object) {
             x,
             y,
             button: PointerButton::Secondary,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
     }
@@ -422,6 +515,14 @@ extern "C" fn right_mouse_up(this: &Object, _sel: Sel, event: *mut Object) {
         y,
         button: PointerButton::Secondary,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: get_window_id(this),
+        modifiers: get_modifiers(event),
         timestamp: get_timestamp(event),
     });
     let click_count: c_int = unsafe { msg(event, Sels::clickCount, ()) };
@@ -430,12 +531,14 @@ extern "C" fn right_mouse_up(this: &Object, _sel: Sel, event: *mut Object) {
             x,
             y,
             button: PointerButton::Secondary,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
         self::submit_event(Event::DoubleClick {
             x,
             y,
             button: PointerButton::Secondary,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
     }
@@ -457,6 +560,14 @@ extern "C" fn other_mouse_down(this: &Object, _sel: Sel, event: *mut Object) {
         y,
         button,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: get_window_id(this),
+        modifiers: get_modifiers(event),
         timestamp: get_timestamp(event),
     });
     let click_count: c_int = unsafe { msg(event, Sels::clickCount, ()) };
@@ -465,6 +576,7 @@ extern "C" fn other_mouse_down(this: &Object, _sel: Sel, event: *mut Object) {
             x,
             y,
             button,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
     }
@@ -486,6 +598,14 @@ extern "C" fn other_mouse_up(this: &Object, _sel: Sel, event: *mut Object) {
         y,
         button,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: get_window_id(this),
+        modifiers: get_modifiers(event),
         timestamp: get_timestamp(event),
     });
     let click_count: c_int = unsafe { msg(event, Sels::clickCount, ()) };
@@ -494,12 +614,14 @@ extern "C" fn other_mouse_up(this: &Object, _sel: Sel, event: *mut Object) {
             x,
             y,
             button,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
         self::submit_event(Event::DoubleClick {
             x,
             y,
             button,
+            window_id: get_window_id(this),
             timestamp: get_timestamp(event),
         });
     }
@@ -527,6 +649,7 @@ extern "C" fn scroll_wheel(_this: &mut Object, _sel: Sel, event: *mut Object) {
         self::submit_event(Event::Scroll {
             delta_x,
             delta_y,
+            modifiers: get_modifiers(event),
             timestamp: get_timestamp(event),
             window_id: WindowId::new(window),
         });
@@ -538,11 +661,12 @@ extern "C" fn accepts_first_responder(_this: &Object, _sel: Sel) -> BOOL {
 }
 
 // https://developer.apple.com/documentation/appkit/nsresponder/1525862-magnifywithevent
-extern "C" fn magnify_with_event(_this: &Object, _sel: Sel, event: *mut Object) {
+extern "C" fn magnify_with_event(this: &Object, _sel: Sel, event: *mut Object) {
     let magnification: CGFloat = unsafe { msg(event, Sels::magnification, ()) };
 
     self::submit_event(Event::PinchGesture {
         delta: magnification,
+        window_id: get_window_id(this),
         timestamp: get_timestamp(event),
     });
 }
@@ -623,7 +747,7 @@ extern "C" fn attributed_substring_for_proposed_range(
 }
 
 extern "C" fn insert_text(
-    _this: &Object,
+    this: &Object,
     _sel: Sel,
     string: *mut Object,
     _replacement_range: NSRange,
@@ -644,7 +768,10 @@ extern "C" fn insert_text(
 
         // Each character received is submitted as an individual event.
         for c in string.chars() {
-            self::submit_event(Event::CharacterReceived { character: c });
+            self::submit_event(Event::CharacterReceived {
+                character: c,
+                window_id: get_window_id(this),
+            });
         }
     }
 }
@@ -836,6 +963,56 @@ fn submit_event(event: Event) {
     kapp_platform_common::event_receiver::send_event(event);
 }
 
+// MacOS does not have a num lock.
+fn flags_to_modifiers(modifier_flags: NSUInteger) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(
+        Modifiers::CAPS_LOCK,
+        modifier_flags & NSEventModifierFlagCapsLock != 0,
+    );
+    modifiers.set(
+        Modifiers::SHIFT,
+        modifier_flags & NSEventModifierFlagShift != 0,
+    );
+    modifiers.set(
+        Modifiers::CONTROL,
+        modifier_flags & NSEventModifierFlagControl != 0,
+    );
+    modifiers.set(
+        Modifiers::ALT,
+        modifier_flags & NSEventModifierFlagOption != 0,
+    );
+    modifiers.set(
+        Modifiers::META,
+        modifier_flags & NSEventModifierFlagCommand != 0,
+    );
+    modifiers
+}
+
+fn get_modifiers(event: *mut Object) -> Modifiers {
+    let modifier_flags: NSUInteger = unsafe { msg(event, Sels::modifierFlags, ()) };
+    flags_to_modifiers(modifier_flags)
+}
+
+fn get_logical_key(event: *mut Object, key: Key) -> LogicalKey {
+    unsafe {
+        let characters: *mut Object = msg_send![event, charactersIgnoringModifiers];
+        let utf8_string: *const std::os::raw::c_char = msg_send![characters, UTF8String];
+        let characters = if utf8_string.is_null() {
+            ""
+        } else {
+            CStr::from_ptr(utf8_string).to_str().unwrap_or("")
+        };
+        super::keys_mac::characters_to_logical_key(characters, key)
+    }
+}
+
+/// The `WindowId` of the window a view belongs to.
+fn get_window_id(view: &Object) -> WindowId {
+    let window: *mut c_void = unsafe { msg(view, Sels::window, ()) };
+    WindowId::new(window)
+}
+
 fn get_timestamp(event: *mut Object) -> std::time::Duration {
     let number: f64 = unsafe { msg(event, Sels::timestamp, ()) };
     std::time::Duration::from_secs_f64(number)
@@ -876,6 +1053,7 @@ fn send_mouse_move(this: &Object, event: *mut Object) {
     submit_event(Event::MouseMotion {
         delta_x,
         delta_y,
+        window_id: get_window_id(this),
         timestamp,
     });
 
@@ -885,6 +1063,13 @@ fn send_mouse_move(this: &Object, event: *mut Object) {
             x,
             y,
             source: PointerSource::Mouse,
+            pointer_id: 0,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: get_window_id(this),
             timestamp,
         });
     }
@@ -1,4 +1,4 @@
-use kapp_platform_common::{Key, Key::*};
+use kapp_platform_common::{Key, Key::*, LogicalKey};
 
 pub fn virtual_keycode_to_key(key_in: u16) -> Key {
     match key_in {
@@ -116,3 +116,16 @@ pub fn virtual_keycode_to_key(key_in: u16) -> Key {
         _ => Unknown,
     }
 }
+
+/// Converts an `NSEvent`'s `charactersIgnoringModifiers` to a logical key.
+/// MacOS ignores every modifier except shift, which is undone for letters by `LogicalKey`.
+pub fn characters_to_logical_key(characters: &str, key: Key) -> LogicalKey {
+    let mut characters = characters.chars();
+    match (characters.next(), characters.next()) {
+        // Keys like the arrow keys produce characters in a private use area.
+        // https://developer.apple.com/documentation/appkit/1535851-function-key_unicode_values
+        (Some('\u{F700}'..='\u{F8FF}'), None) => LogicalKey::Key(key),
+        (Some(character), None) => LogicalKey::from_character(Some(character), key),
+        _ => LogicalKey::Key(key),
+    }
+}
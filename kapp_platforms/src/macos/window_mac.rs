@@ -10,6 +10,7 @@ pub(crate) struct WindowState {
 
 pub(crate) fn build(
     window_parameters: &WindowParameters,
+    ns_window_class: *const objc::runtime::Class,
     window_class: *const objc::runtime::Class,
     view_class: *const objc::runtime::Class,
 ) -> Result<WindowId, ()> {
@@ -30,7 +31,7 @@ pub(crate) fn build(
         }
 
         // This allocation will be released when the window is dropped.
-        let ns_window: *mut Object = msg_send![class!(NSWindow), alloc];
+        let ns_window: *mut Object = msg_send![ns_window_class, alloc];
         let () = msg_send![
             ns_window,
             initWithContentRect:rect.clone()
@@ -44,6 +45,12 @@ pub(crate) fn build(
             let () = msg_send![ns_window, setTitleVisibility: 1];
         }
 
+        if window_parameters.transparent {
+            let clear_color: *mut Object = msg_send![class!(NSColor), clearColor];
+            let () = msg_send![ns_window, setOpaque: NO];
+            let () = msg_send![ns_window, setBackgroundColor: clear_color];
+        }
+
         let backing_scale: CGFloat = msg_send![ns_window, backingScaleFactor];
 
         if let Some(position) = window_parameters.position {
@@ -1,6 +1,6 @@
-use fermium::scancode::*;
+use fermium::{keyboard::*, keycode::*, scancode::*};
 
-use kapp_platform_common::{Key, Key::*};
+use kapp_platform_common::{Key, Key::*, LogicalKey};
 pub fn scancode_to_key(key_in: SDL_Scancode) -> Key {
     match key_in {
         SDL_SCANCODE_0 => Digit0,
@@ -115,3 +115,140 @@ pub fn scancode_to_key(key_in: SDL_Scancode) -> Key {
         _ => Key::Unknown,
     }
 }
+
+/// The reverse of `scancode_to_key`, used to push simulated key events to SDL.
+pub fn key_to_scancode(key: Key) -> SDL_Scancode {
+    match key {
+        Digit0 => SDL_SCANCODE_0,
+        Digit1 => SDL_SCANCODE_1,
+        Digit2 => SDL_SCANCODE_2,
+        Digit3 => SDL_SCANCODE_3,
+        Digit4 => SDL_SCANCODE_4,
+        Digit5 => SDL_SCANCODE_5,
+        Digit6 => SDL_SCANCODE_6,
+        Digit7 => SDL_SCANCODE_7,
+        Digit8 => SDL_SCANCODE_8,
+        Digit9 => SDL_SCANCODE_9,
+        A => SDL_SCANCODE_A,
+        B => SDL_SCANCODE_B,
+        C => SDL_SCANCODE_C,
+        D => SDL_SCANCODE_D,
+        E => SDL_SCANCODE_E,
+        F => SDL_SCANCODE_F,
+        G => SDL_SCANCODE_G,
+        H => SDL_SCANCODE_H,
+        I => SDL_SCANCODE_I,
+        J => SDL_SCANCODE_J,
+        K => SDL_SCANCODE_K,
+        L => SDL_SCANCODE_L,
+        M => SDL_SCANCODE_M,
+        N => SDL_SCANCODE_N,
+        O => SDL_SCANCODE_O,
+        P => SDL_SCANCODE_P,
+        Q => SDL_SCANCODE_Q,
+        R => SDL_SCANCODE_R,
+        S => SDL_SCANCODE_S,
+        T => SDL_SCANCODE_T,
+        U => SDL_SCANCODE_U,
+        V => SDL_SCANCODE_V,
+        W => SDL_SCANCODE_W,
+        X => SDL_SCANCODE_X,
+        Y => SDL_SCANCODE_Y,
+        Z => SDL_SCANCODE_Z,
+        Backquote => SDL_SCANCODE_GRAVE,
+        Backslash => SDL_SCANCODE_BACKSLASH,
+        Comma => SDL_SCANCODE_COMMA,
+        Equal => SDL_SCANCODE_EQUALS,
+        BracketLeft => SDL_SCANCODE_LEFTBRACKET,
+        Minus => SDL_SCANCODE_MINUS,
+        Period => SDL_SCANCODE_PERIOD,
+        BracketRight => SDL_SCANCODE_RIGHTBRACKET,
+        Semicolon => SDL_SCANCODE_SEMICOLON,
+        Slash => SDL_SCANCODE_SLASH,
+        Backspace => SDL_SCANCODE_BACKSPACE,
+        CapsLock => SDL_SCANCODE_CAPSLOCK,
+        Delete => SDL_SCANCODE_DELETE,
+        Down => SDL_SCANCODE_DOWN,
+        End => SDL_SCANCODE_END,
+        Return => SDL_SCANCODE_RETURN,
+        Escape => SDL_SCANCODE_ESCAPE,
+        F1 => SDL_SCANCODE_F1,
+        F2 => SDL_SCANCODE_F2,
+        F3 => SDL_SCANCODE_F3,
+        F4 => SDL_SCANCODE_F4,
+        F5 => SDL_SCANCODE_F5,
+        F6 => SDL_SCANCODE_F6,
+        F7 => SDL_SCANCODE_F7,
+        F8 => SDL_SCANCODE_F8,
+        F9 => SDL_SCANCODE_F9,
+        F10 => SDL_SCANCODE_F10,
+        F11 => SDL_SCANCODE_F11,
+        F12 => SDL_SCANCODE_F12,
+        F13 => SDL_SCANCODE_F13,
+        F14 => SDL_SCANCODE_F14,
+        F15 => SDL_SCANCODE_F15,
+        F16 => SDL_SCANCODE_F16,
+        F17 => SDL_SCANCODE_F17,
+        F18 => SDL_SCANCODE_F18,
+        F19 => SDL_SCANCODE_F19,
+        F20 => SDL_SCANCODE_F20,
+        Home => SDL_SCANCODE_HOME,
+        Insert => SDL_SCANCODE_INSERT,
+        Left => SDL_SCANCODE_LEFT,
+        LeftAlt => SDL_SCANCODE_LALT,
+        LeftControl => SDL_SCANCODE_LCTRL,
+        LeftShift => SDL_SCANCODE_LSHIFT,
+        Meta => SDL_SCANCODE_LGUI,
+        Menu => SDL_SCANCODE_MENU,
+        NumLock => SDL_SCANCODE_NUMLOCKCLEAR,
+        PageDown => SDL_SCANCODE_PAGEDOWN,
+        PageUp => SDL_SCANCODE_PAGEUP,
+        Right => SDL_SCANCODE_RIGHT,
+        RightAlt => SDL_SCANCODE_RALT,
+        RightControl => SDL_SCANCODE_RCTRL,
+        RightShift => SDL_SCANCODE_RSHIFT,
+        Space => SDL_SCANCODE_SPACE,
+        Tab => SDL_SCANCODE_TAB,
+        Up => SDL_SCANCODE_UP,
+        NumPad0 => SDL_SCANCODE_KP_0,
+        NumPad1 => SDL_SCANCODE_KP_1,
+        NumPad2 => SDL_SCANCODE_KP_2,
+        NumPad3 => SDL_SCANCODE_KP_3,
+        NumPad4 => SDL_SCANCODE_KP_4,
+        NumPad5 => SDL_SCANCODE_KP_5,
+        NumPad6 => SDL_SCANCODE_KP_6,
+        NumPad7 => SDL_SCANCODE_KP_7,
+        NumPad8 => SDL_SCANCODE_KP_8,
+        NumPad9 => SDL_SCANCODE_KP_9,
+        NumPadAdd => SDL_SCANCODE_KP_PLUS,
+        NumPadDecimal => SDL_SCANCODE_KP_DECIMAL,
+        NumPadDivide => SDL_SCANCODE_KP_DIVIDE,
+        NumPadEnter => SDL_SCANCODE_KP_ENTER,
+        NumPadEquals => SDL_SCANCODE_KP_EQUALS,
+        NumPadMultiply => SDL_SCANCODE_KP_MULTIPLY,
+        NumPadSubtract => SDL_SCANCODE_KP_MINUS,
+        _ => SDL_SCANCODE_UNKNOWN,
+    }
+}
+
+// SDL keycodes for keys that don't produce a character have this bit set.
+const SCANCODE_MASK: u32 = 1 << 30;
+
+/// SDL keycodes are the character a key produces in the current layout, without modifiers.
+pub fn keycode_to_logical_key(keycode: SDL_Keycode, key: Key) -> LogicalKey {
+    let keycode = keycode as u32;
+    let character = if keycode & SCANCODE_MASK == 0 {
+        std::char::from_u32(keycode)
+    } else {
+        None
+    };
+    LogicalKey::from_character(character, key)
+}
+
+/// The reverse of `keycode_to_logical_key`, used to push simulated key events to SDL.
+pub fn logical_key_to_keycode(logical_key: LogicalKey) -> SDL_Keycode {
+    match logical_key {
+        LogicalKey::Character(character) => character as SDL_Keycode,
+        LogicalKey::Key(key) => unsafe { SDL_GetKeyFromScancode(key_to_scancode(key)) },
+    }
+}
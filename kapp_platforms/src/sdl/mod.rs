@@ -1,11 +1,18 @@
 mod keys_sdl;
+use kapp_platform_common::event_receiver::send_event;
 use kapp_platform_common::*;
 use keys_sdl::*;
 
-use fermium::{events::*, keyboard::*, mouse::*, rect::*, stdinc::*, touch::*, video::*, *};
+use fermium::{
+    clipboard::*, events::*, keyboard::*, keycode::*, mouse::*, rect::*, stdinc::*, surface::*,
+    timer::*, touch::*, video::*, *,
+};
 
-use core::cell::Cell;
+use core::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ffi::{CStr, CString};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
 
 pub mod prelude {
@@ -15,9 +22,43 @@ pub mod prelude {
 
 pub struct PlatformApplication {
     // These cursors are deallocated with `SDL_FreeCursor` in PlatformApplication's Drop
-    arrow_cursor: *mut SDL_Cursor,
-    ibeam_cursor: *mut SDL_Cursor,
-    open_hand_cursor: *mut SDL_Cursor,
+    /// System cursors, which are created the first time they're used.
+    system_cursors: HashMap<Cursor, *mut SDL_Cursor>,
+    custom_cursors: Vec<*mut SDL_Cursor>,
+}
+
+// Registered when the application starts. They're statics because timers push their events
+// from SDL's timer thread.
+/// The event type proxies push to wake the event loop.
+static PROXY_EVENT_TYPE: AtomicU32 = AtomicU32::new(0);
+/// The event type timers push when they fire.
+static TIMER_EVENT_TYPE: AtomicU32 = AtomicU32::new(0);
+
+impl PlatformApplication {
+    fn system_cursor(&mut self, cursor: Cursor) -> *mut SDL_Cursor {
+        *self.system_cursors.entry(cursor).or_insert_with(|| {
+            let system_cursor = match cursor {
+                Cursor::Arrow | Cursor::Custom(_) => SDL_SYSTEM_CURSOR_ARROW,
+                Cursor::IBeam => SDL_SYSTEM_CURSOR_IBEAM,
+                // SDL has no open or closed hand cursors.
+                Cursor::PointingHand | Cursor::OpenHand | Cursor::ClosedHand => {
+                    SDL_SYSTEM_CURSOR_HAND
+                }
+                Cursor::ResizeNS => SDL_SYSTEM_CURSOR_SIZENS,
+                Cursor::ResizeEW => SDL_SYSTEM_CURSOR_SIZEWE,
+                Cursor::ResizeNESW => SDL_SYSTEM_CURSOR_SIZENESW,
+                Cursor::ResizeNWSE => SDL_SYSTEM_CURSOR_SIZENWSE,
+                Cursor::Crosshair => SDL_SYSTEM_CURSOR_CROSSHAIR,
+                Cursor::NotAllowed => SDL_SYSTEM_CURSOR_NO,
+                Cursor::Wait => SDL_SYSTEM_CURSOR_WAIT,
+                Cursor::Progress => SDL_SYSTEM_CURSOR_WAITARROW,
+                Cursor::Move => SDL_SYSTEM_CURSOR_SIZEALL,
+                // SDL has no help or zoom cursors.
+                Cursor::Help | Cursor::ZoomIn | Cursor::ZoomOut => SDL_SYSTEM_CURSOR_ARROW,
+            };
+            unsafe { SDL_CreateSystemCursor(system_cursor) }
+        })
+    }
 }
 
 impl PlatformApplicationTrait for PlatformApplication {
@@ -25,11 +66,14 @@ impl PlatformApplicationTrait for PlatformApplication {
     fn new() -> Self {
         unsafe {
             assert!(SDL_Init(SDL_INIT_EVERYTHING) == 0);
+            SCREENS.with(|s| *s.borrow_mut() = screens());
+            // Each type is registered separately so proxies and timers can't be confused.
+            PROXY_EVENT_TYPE.store(SDL_RegisterEvents(1), Ordering::Relaxed);
+            TIMER_EVENT_TYPE.store(SDL_RegisterEvents(1), Ordering::Relaxed);
 
             Self {
-                arrow_cursor: SDL_CreateSystemCursor(SDL_SYSTEM_CURSOR_ARROW),
-                ibeam_cursor: SDL_CreateSystemCursor(SDL_SYSTEM_CURSOR_IBEAM),
-                open_hand_cursor: SDL_CreateSystemCursor(SDL_SYSTEM_CURSOR_HAND),
+                system_cursors: HashMap::new(),
+                custom_cursors: Vec::new(),
             }
         }
     }
@@ -58,6 +102,71 @@ impl PlatformApplicationTrait for PlatformApplication {
             SDL_SetWindowTitle(window_id.raw() as *mut SDL_Window, c_string.as_ptr());
         }
     }
+    fn set_window_icon(&mut self, window_id: WindowId, icon: &[Icon]) {
+        unsafe {
+            set_icon(window_id.raw() as *mut SDL_Window, icon);
+        }
+    }
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        unsafe {
+            SDL_SetWindowResizable(window_id.raw() as *mut SDL_Window, sdl_bool(resizable));
+        }
+    }
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        // SDL rejects sizes of 0, so the smallest size it allows is used as no limit.
+        let (width, height) = size.unwrap_or((1, 1));
+        unsafe {
+            SDL_SetWindowMinimumSize(
+                window_id.raw() as *mut SDL_Window,
+                width.max(1) as i32,
+                height.max(1) as i32,
+            );
+        }
+    }
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        // SDL rejects sizes of 0, so the largest size it allows is used as no limit.
+        let (width, height) = size.unwrap_or((i32::MAX as u32, i32::MAX as u32));
+        unsafe {
+            SDL_SetWindowMaximumSize(
+                window_id.raw() as *mut SDL_Window,
+                width.clamp(1, i32::MAX as u32) as i32,
+                height.clamp(1, i32::MAX as u32) as i32,
+            );
+        }
+    }
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        unsafe {
+            SDL_SetWindowBordered(window_id.raw() as *mut SDL_Window, sdl_bool(decorations));
+        }
+    }
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool) {
+        unsafe {
+            SDL_SetWindowAlwaysOnTop(window_id.raw() as *mut SDL_Window, sdl_bool(always_on_top));
+        }
+    }
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool) {
+        unsafe {
+            if visible {
+                SDL_ShowWindow(window_id.raw() as *mut SDL_Window);
+            } else {
+                SDL_HideWindow(window_id.raw() as *mut SDL_Window);
+            }
+        }
+    }
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32) {
+        unsafe {
+            SDL_SetWindowOpacity(window_id.raw() as *mut SDL_Window, opacity);
+        }
+    }
+    fn set_window_input_region(
+        &mut self,
+        _window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        // SDL_SetWindowHitTest can only mark areas for dragging and resizing the window,
+        // not pass pointer input through it, so only the whole window can receive input.
+        region.is_none()
+    }
     fn minimize_window(&mut self, window_id: WindowId) {
         unsafe {
             SDL_MinimizeWindow(window_id.raw() as *mut SDL_Window);
@@ -93,17 +202,118 @@ impl PlatformApplicationTrait for PlatformApplication {
         logical_width as f64 / physical_width as f64
     }
 
-    fn fullscreen_window(&mut self, window_id: WindowId) {
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32) {
+        let mut x = 0;
+        let mut y = 0;
         unsafe {
-            SDL_SetWindowFullscreen(window_id.raw() as *mut SDL_Window, SDL_WINDOW_FULLSCREEN.0);
+            SDL_GetWindowPosition(window_id.raw() as *mut SDL_Window, &mut x, &mut y);
+        }
+        (x.max(0) as u32, y.max(0) as u32)
+    }
+
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool {
+        window_flags(window_id) & SDL_WINDOW_MINIMIZED.0 != 0
+    }
+
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        window_flags(window_id) & SDL_WINDOW_MAXIMIZED.0 != 0
+    }
+
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        // `SDL_WINDOW_FULLSCREEN_DESKTOP` includes the `SDL_WINDOW_FULLSCREEN` flag.
+        if window_flags(window_id) & SDL_WINDOW_FULLSCREEN.0 == 0 {
+            Fullscreen::Off
+        } else {
+            fullscreen(window_id)
+        }
+    }
+
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        window_flags(window_id) & SDL_WINDOW_INPUT_FOCUS.0 != 0
+    }
+
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        window_flags(window_id) & SDL_WINDOW_SHOWN.0 != 0
+    }
+
+    fn screens(&mut self) -> Vec<Screen> {
+        screens()
+    }
+
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        let index = unsafe { SDL_GetWindowDisplayIndex(window_id.raw() as *mut SDL_Window) };
+        if index < 0 {
+            None
+        } else {
+            Some(screen_id_from_sdl(index))
+        }
+    }
+
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
+        unsafe {
+            let window = window_id.raw() as *mut SDL_Window;
+            let flags = match fullscreen {
+                Fullscreen::Off => 0,
+                Fullscreen::Borderless(screen_id) => {
+                    if let Some(screen_id) = screen_id {
+                        move_to_display(window, screen_id.raw() as usize as i32);
+                    }
+                    SDL_WINDOW_FULLSCREEN_DESKTOP.0
+                }
+                Fullscreen::Exclusive(mode) => {
+                    let index = mode.screen_id.raw() as usize as i32;
+                    move_to_display(window, index);
+                    let mut requested: SDL_DisplayMode = std::mem::zeroed();
+                    requested.w = mode.size.0 as i32;
+                    requested.h = mode.size.1 as i32;
+                    requested.refresh_rate = mode.refresh_rate.round() as i32;
+                    let mut closest: SDL_DisplayMode = std::mem::zeroed();
+                    if !SDL_GetClosestDisplayMode(index, &requested, &mut closest).is_null() {
+                        SDL_SetWindowDisplayMode(window, &closest);
+                    }
+                    SDL_WINDOW_FULLSCREEN.0
+                }
+            };
+            if SDL_SetWindowFullscreen(window, flags) != 0 {
+                return;
+            }
+        }
+
+        // SDL doesn't send an event when fullscreen changes.
+        let fullscreen = match fullscreen {
+            Fullscreen::Borderless(None) => Fullscreen::Borderless(self.window_screen(window_id)),
+            fullscreen => fullscreen,
+        };
+        let old = FULLSCREENS.with(|f| f.borrow_mut().insert(window_id, fullscreen));
+        let old = old.unwrap_or(Fullscreen::Off);
+        if fullscreen == Fullscreen::Off {
+            if old != Fullscreen::Off {
+                send_event(Event::WindowRestored {
+                    fullscreen,
+                    window_id,
+                });
+            }
+        } else if old != fullscreen {
+            send_event(Event::WindowFullscreened {
+                fullscreen,
+                window_id,
+            });
         }
     }
     fn restore_window(&mut self, window_id: WindowId) {
         unsafe {
-            SDL_RestoreWindow(window_id.raw() as *mut SDL_Window);
+            let window = window_id.raw() as *mut SDL_Window;
+            // `SDL_RestoreWindow` doesn't leave fullscreen.
+            if window_flags(window_id) & SDL_WINDOW_MINIMIZED.0 == 0
+                && fullscreen(window_id) != Fullscreen::Off
+            {
+                self.set_window_fullscreen(window_id, Fullscreen::Off);
+            }
+            SDL_RestoreWindow(window);
         }
     }
     fn close_window(&mut self, window_id: WindowId) {
+        FULLSCREENS.with(|f| f.borrow_mut().remove(&window_id));
         unsafe {
             SDL_DestroyWindow(window_id.raw() as *mut SDL_Window);
         }
@@ -139,9 +349,8 @@ impl PlatformApplicationTrait for PlatformApplication {
         if window_parameters.resizable {
             flags |= SDL_WINDOW_RESIZABLE;
         }
-
-        if window_parameters.resizable {
-            flags |= SDL_WINDOW_RESIZABLE;
+        if window_parameters.without_titlebar {
+            flags |= SDL_WINDOW_BORDERLESS;
         }
         unsafe {
             let window = SDL_CreateWindow(
@@ -153,7 +362,6 @@ impl PlatformApplicationTrait for PlatformApplication {
                 flags.0,
             );
 
-            // How can min / max sizes be unset later?
             if let Some((min_width, min_height)) = window_parameters.minimum_size {
                 SDL_SetWindowMinimumSize(window, min_width as i32, min_height as i32)
             }
@@ -164,6 +372,7 @@ impl PlatformApplicationTrait for PlatformApplication {
 
             let c_string = std::ffi::CString::new(window_parameters.title.clone()).unwrap();
             SDL_SetWindowTitle(window, c_string.as_ptr());
+            set_icon(window, &window_parameters.icon);
 
             let window_id = WindowId::new(window as *mut c_void);
             // When a window is created immediately request that it should redraw
@@ -180,17 +389,78 @@ impl PlatformApplicationTrait for PlatformApplication {
         }
     }
 
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        control_flow::set_control_flow(control_flow);
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        let timer_id = timers::new_timer_id();
+        // The callback only receives this parameter, so it holds the id and if the timer repeats.
+        let parameter = (timer_id.raw() << 1 | repeat as u64) as usize as *mut c_void;
+        let interval = duration.as_millis().min(u32::MAX as u128) as u32;
+        unsafe {
+            let sdl_timer_id = SDL_AddTimer(interval, Some(timer_callback), parameter);
+            TIMERS.with(|t| t.borrow_mut().insert(timer_id, sdl_timer_id));
+        }
+        timer_id
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        if let Some(sdl_timer_id) = TIMERS.with(|t| t.borrow_mut().remove(&timer_id)) {
+            unsafe {
+                SDL_RemoveTimer(sdl_timer_id);
+            }
+        }
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        let proxy_event_type = PROXY_EVENT_TYPE.load(Ordering::Relaxed);
+        EventLoopProxy::new(move || unsafe {
+            // `SDL_PushEvent` can be called from any thread.
+            // The event only wakes the event loop, which sends the proxy's events.
+            let mut event: SDL_Event = std::mem::zeroed();
+            event.user.type_ = proxy_event_type;
+            SDL_PushEvent(&mut event);
+        })
+    }
+
     fn set_cursor(&mut self, cursor: Cursor) {
         let cursor = match cursor {
-            Cursor::IBeam => self.ibeam_cursor,
-            Cursor::OpenHand => self.open_hand_cursor,
-            _ => self.arrow_cursor,
+            Cursor::Custom(custom_cursor) => unsafe { custom_cursor.raw() as *mut SDL_Cursor },
+            _ => self.system_cursor(cursor),
         };
         unsafe {
             SDL_SetCursor(cursor);
         }
     }
 
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        unsafe {
+            let surface = rgba_surface(rgba, width, height);
+            let cursor = if surface.is_null() {
+                std::ptr::null_mut()
+            } else {
+                let cursor = SDL_CreateColorCursor(surface, hotspot.0 as i32, hotspot.1 as i32);
+                SDL_FreeSurface(surface);
+                cursor
+            };
+
+            if cursor.is_null() {
+                // If the cursor couldn't be created the arrow is used instead.
+                CustomCursor::new(self.system_cursor(Cursor::Arrow) as *mut c_void)
+            } else {
+                self.custom_cursors.push(cursor);
+                CustomCursor::new(cursor as *mut c_void)
+            }
+        }
+    }
+
     fn hide_cursor(&mut self) {
         unsafe {
             SDL_ShowCursor(SDL_DISABLE);
@@ -202,6 +472,54 @@ impl PlatformApplicationTrait for PlatformApplication {
         }
     }
 
+    fn clipboard_mime_types(&mut self, selection: ClipboardSelection) -> Vec<String> {
+        // SDL only supports text on the regular clipboard.
+        if selection == ClipboardSelection::Clipboard
+            && unsafe { SDL_HasClipboardText() } == SDL_TRUE
+        {
+            vec![TEXT_MIME_TYPE.to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+    fn get_clipboard_data(
+        &mut self,
+        selection: ClipboardSelection,
+        mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        if selection != ClipboardSelection::Clipboard || mime_type != TEXT_MIME_TYPE {
+            return None;
+        }
+        unsafe {
+            // SDL returns an empty string if the clipboard has no text.
+            let text = SDL_GetClipboardText();
+            if text.is_null() {
+                return None;
+            }
+            let data = CStr::from_ptr(text).to_bytes().to_vec();
+            SDL_free(text as *mut c_void);
+            if data.is_empty() {
+                None
+            } else {
+                Some(data)
+            }
+        }
+    }
+    fn set_clipboard_data(&mut self, selection: ClipboardSelection, mime_type: &str, data: &[u8]) {
+        if selection != ClipboardSelection::Clipboard || mime_type != TEXT_MIME_TYPE {
+            return;
+        }
+        if let Ok(text) = CString::new(data) {
+            unsafe {
+                SDL_SetClipboardText(text.as_ptr());
+            }
+        }
+    }
+
+    fn set_drop_accepted(&mut self, _accepted: bool) {
+        // SDL only reports drops after they happen, so they can't be rejected.
+    }
+
     fn raw_window_handle(&self, window_id: WindowId) -> RawWindowHandle {
         unsafe {
             use syswm::*;
@@ -312,15 +630,33 @@ impl PlatformApplicationTrait for PlatformApplication {
             SDL_SetTextInputRect(&mut rectangle);
         }
     }
+
+    fn inject_event(&mut self, event: Event) {
+        // SDL has no modifier event, but `process_event` sends one
+        // when it sees the new modifier state.
+        if let Event::ModifiersChanged { modifiers } = event {
+            unsafe { SDL_SetModState(modifiers_to_sdl_modifiers(modifiers) as _) };
+            return;
+        }
+
+        // Events are pushed to SDL's queue so they're translated by `process_event`
+        // like SDL's own events. Events SDL can't represent are sent directly.
+        if let Some(mut sdl_event) = event_to_sdl_event(&event) {
+            if unsafe { SDL_PushEvent(&mut sdl_event) } == 1 {
+                return;
+            }
+        }
+        send_event(event);
+    }
 }
 
 // When the application is dropped, quit the program.
 impl Drop for PlatformApplication {
     fn drop(&mut self) {
         unsafe {
-            SDL_FreeCursor(self.arrow_cursor);
-            SDL_FreeCursor(self.ibeam_cursor);
-            SDL_FreeCursor(self.open_hand_cursor);
+            for cursor in self.system_cursors.values().chain(&self.custom_cursors) {
+                SDL_FreeCursor(*cursor);
+            }
             SDL_Quit();
         }
     }
@@ -328,37 +664,264 @@ impl Drop for PlatformApplication {
 
 thread_local! {
     static ACTUALLY_QUIT: Cell<bool> = Cell::new(false);
+    static MODIFIERS: Cell<Modifiers> = Cell::new(Modifiers::empty());
+    // SDL sends an event per dropped file, so they're collected until the drop completes.
+    static DROPPED_PATHS: RefCell<Vec<PathBuf>> = RefCell::new(Vec::new());
+    // The screens when they last changed, to find which changed on the next display event.
+    static SCREENS: RefCell<Vec<Screen>> = RefCell::new(Vec::new());
+    // SDL doesn't report which display mode a fullscreen window uses, so it's recorded when set.
+    static FULLSCREENS: RefCell<HashMap<WindowId, Fullscreen>> = RefCell::new(HashMap::new());
+    // The timers that haven't finished or been cancelled.
+    static TIMERS: RefCell<HashMap<TimerId, SDL_TimerID>> = RefCell::new(HashMap::new());
+}
+
+/// SDL calls this from its timer thread, so the timer is pushed to the event queue
+/// as a `TIMER_EVENT_TYPE` event. Returning 0 stops the timer.
+unsafe extern "C" fn timer_callback(interval: u32, parameter: *mut c_void) -> u32 {
+    let mut event: SDL_Event = std::mem::zeroed();
+    event.user.type_ = TIMER_EVENT_TYPE.load(Ordering::Relaxed);
+    event.user.data1 = parameter;
+    SDL_PushEvent(&mut event);
+    if parameter as usize & 1 == 1 {
+        interval
+    } else {
+        0
+    }
+}
+
+fn process_timer_event(parameter: *mut c_void) {
+    let timer_id = TimerId::from_raw(parameter as usize as u64 >> 1);
+    let repeat = parameter as usize & 1 == 1;
+    // A timer may have been cancelled after SDL pushed its event.
+    let active = TIMERS.with(|t| {
+        let mut timers = t.borrow_mut();
+        if repeat {
+            timers.contains_key(&timer_id)
+        } else {
+            timers.remove(&timer_id).is_some()
+        }
+    });
+    if active {
+        send_event(Event::Timer { timer_id });
+    }
 }
 
-fn process_event(callback: &mut Box<dyn FnMut(Event)>, event: &SDL_Event) {
+/// Moves a window to the upper left of a display so SDL makes it fullscreen there.
+unsafe fn move_to_display(window: *mut SDL_Window, index: i32) {
+    let mut bounds: SDL_Rect = std::mem::zeroed();
+    if SDL_GetDisplayBounds(index, &mut bounds) == 0 {
+        SDL_SetWindowPosition(window, bounds.x, bounds.y);
+    }
+}
+
+fn fullscreen(window_id: WindowId) -> Fullscreen {
+    FULLSCREENS.with(|f| {
+        f.borrow()
+            .get(&window_id)
+            .copied()
+            .unwrap_or(Fullscreen::Off)
+    })
+}
+
+// Screens are identified by their display index.
+fn screen_id_from_sdl(index: i32) -> ScreenId {
+    ScreenId::new(index as usize as *mut c_void)
+}
+
+fn screens() -> Vec<Screen> {
+    unsafe {
+        (0..SDL_GetNumVideoDisplays())
+            .map(|index| {
+                let name = SDL_GetDisplayName(index);
+                let name = if name.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(name).to_string_lossy().into_owned()
+                };
+
+                let mut bounds: SDL_Rect = std::mem::zeroed();
+                SDL_GetDisplayBounds(index, &mut bounds);
+                let mut work_area: SDL_Rect = std::mem::zeroed();
+                if SDL_GetDisplayUsableBounds(index, &mut work_area) != 0 {
+                    work_area = bounds;
+                }
+
+                // SDL reports DPI rather than a scale, so it's compared to the usual 96 DPI.
+                let mut dpi = 0.0;
+                let scale = if SDL_GetDisplayDPI(
+                    index,
+                    &mut dpi,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                ) == 0
+                    && dpi > 0.0
+                {
+                    dpi as f64 / 96.0
+                } else {
+                    1.0
+                };
+
+                let mut mode: SDL_DisplayMode = std::mem::zeroed();
+                let refresh_rate =
+                    if SDL_GetCurrentDisplayMode(index, &mut mode) == 0 && mode.refresh_rate != 0 {
+                        Some(mode.refresh_rate as f64)
+                    } else {
+                        None
+                    };
+
+                let video_modes = (0..SDL_GetNumDisplayModes(index))
+                    .filter_map(|mode_index| {
+                        let mut mode: SDL_DisplayMode = std::mem::zeroed();
+                        if SDL_GetDisplayMode(index, mode_index, &mut mode) != 0 {
+                            return None;
+                        }
+                        Some(VideoMode {
+                            screen_id: screen_id_from_sdl(index),
+                            size: (mode.w as u32, mode.h as u32),
+                            refresh_rate: mode.refresh_rate as f64,
+                            // The same as `SDL_BITSPERPIXEL`.
+                            bit_depth: (mode.format >> 8) & 0xFF,
+                        })
+                    })
+                    .collect();
+
+                Screen {
+                    id: screen_id_from_sdl(index),
+                    name,
+                    position: (bounds.x, bounds.y),
+                    size: (bounds.w as u32, bounds.h as u32),
+                    work_area_position: (work_area.x, work_area.y),
+                    work_area_size: (work_area.w as u32, work_area.h as u32),
+                    scale,
+                    refresh_rate,
+                    video_modes,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Wraps RGBA pixels in a surface without copying them.
+/// The surface must be freed before the pixels.
+unsafe fn rgba_surface(rgba: &[u8], width: u32, height: u32) -> *mut SDL_Surface {
+    // The masks describe where each channel's byte is in a pixel read as a u32.
+    // SDL only reads from the pixels.
+    SDL_CreateRGBSurfaceFrom(
+        rgba.as_ptr() as *mut c_void,
+        width as i32,
+        height as i32,
+        32,
+        width as i32 * 4,
+        u32::from_ne_bytes([0xFF, 0, 0, 0]),
+        u32::from_ne_bytes([0, 0xFF, 0, 0]),
+        u32::from_ne_bytes([0, 0, 0xFF, 0]),
+        u32::from_ne_bytes([0, 0, 0, 0xFF]),
+    )
+}
+
+/// SDL takes a single icon and scales it as needed, so the largest size is used.
+/// SDL can't restore the default icon, so an empty icon is ignored.
+unsafe fn set_icon(window: *mut SDL_Window, icon: &[Icon]) {
+    if let Some(icon) = icon.iter().max_by_key(|icon| icon.width * icon.height) {
+        let surface = rgba_surface(&icon.data, icon.width, icon.height);
+        if !surface.is_null() {
+            SDL_SetWindowIcon(window, surface);
+            SDL_FreeSurface(surface);
+        }
+    }
+}
+
+fn sdl_modifiers_to_modifiers(state: u32) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, state & KMOD_SHIFT as u32 != 0);
+    modifiers.set(Modifiers::CONTROL, state & KMOD_CTRL as u32 != 0);
+    modifiers.set(Modifiers::ALT, state & KMOD_ALT as u32 != 0);
+    modifiers.set(Modifiers::META, state & KMOD_GUI as u32 != 0);
+    modifiers.set(Modifiers::CAPS_LOCK, state & KMOD_CAPS as u32 != 0);
+    modifiers.set(Modifiers::NUM_LOCK, state & KMOD_NUM as u32 != 0);
+    modifiers
+}
+
+fn modifiers_to_sdl_modifiers(modifiers: Modifiers) -> u32 {
+    let mut state = 0;
+    if modifiers.shift() {
+        state |= KMOD_LSHIFT as u32;
+    }
+    if modifiers.control() {
+        state |= KMOD_LCTRL as u32;
+    }
+    if modifiers.alt() {
+        state |= KMOD_LALT as u32;
+    }
+    if modifiers.meta() {
+        state |= KMOD_LGUI as u32;
+    }
+    if modifiers.caps_lock() {
+        state |= KMOD_CAPS as u32;
+    }
+    if modifiers.num_lock() {
+        state |= KMOD_NUM as u32;
+    }
+    state
+}
+
+/// Sends a `ModifiersChanged` event if the modifiers differ from the last event's.
+fn update_modifiers(state: u32) -> Modifiers {
+    let modifiers = sdl_modifiers_to_modifiers(state);
+    if MODIFIERS.with(|m| m.replace(modifiers)) != modifiers {
+        send_event(Event::ModifiersChanged { modifiers });
+    }
+    modifiers
+}
+
+fn process_event(event: &SDL_Event) {
     unsafe {
         match event.type_ {
-            SDL_QUIT => callback(Event::QuitRequested),
+            // The proxy's events are sent once the queue is drained.
+            event_type if event_type == PROXY_EVENT_TYPE.load(Ordering::Relaxed) => {}
+            event_type if event_type == TIMER_EVENT_TYPE.load(Ordering::Relaxed) => {
+                process_timer_event(event.user.data1)
+            }
+            SDL_QUIT => send_event(Event::QuitRequested),
+            SDL_CLIPBOARDUPDATE => send_event(Event::ClipboardChanged {
+                selection: ClipboardSelection::Clipboard,
+            }),
+            SDL_DISPLAYEVENT => {
+                // Displays are compared instead of matching on the display event
+                // because a display being removed changes the index of the ones after it.
+                let new_screens = screens();
+                let old_screens = SCREENS.with(|s| s.replace(new_screens.clone()));
+                for event in screen_changes(&old_screens, &new_screens) {
+                    send_event(event);
+                }
+            }
             SDL_WINDOWEVENT => {
                 let window_event = event.window;
-                let window_id =
-                    WindowId::new(SDL_GetWindowFromID(window_event.windowID) as *mut c_void);
+                let window_id = window_id_from_sdl(window_event.windowID);
                 match window_event.event {
-                    SDL_WINDOWEVENT_MINIMIZED => callback(Event::WindowMinimized { window_id }),
-                    SDL_WINDOWEVENT_MAXIMIZED => callback(Event::WindowMaximized { window_id }),
+                    SDL_WINDOWEVENT_MINIMIZED => send_event(Event::WindowMinimized { window_id }),
+                    SDL_WINDOWEVENT_MAXIMIZED => send_event(Event::WindowMaximized { window_id }),
                     // There is no SDL_WINDOWEVENT_FULLSCREENED
                     // There is no equivalent to WindowStartResize
                     // There is no equivalent to WindowEndResize
                     // There is no equivalent to WindowScaleChanged
-                    SDL_WINDOWEVENT_RESTORED => callback(Event::WindowRestored { window_id }),
-                    SDL_WINDOWEVENT_MOVED => callback(Event::WindowMoved {
+                    SDL_WINDOWEVENT_RESTORED => send_event(Event::WindowRestored {
+                        fullscreen: fullscreen(window_id),
+                        window_id,
+                    }),
+                    SDL_WINDOWEVENT_MOVED => send_event(Event::WindowMoved {
                         window_id,
                         x: window_event.data1 as u32,
                         y: window_event.data2 as u32,
                     }),
                     SDL_WINDOWEVENT_FOCUS_GAINED => {
-                        callback(Event::WindowGainedFocus { window_id })
+                        send_event(Event::WindowGainedFocus { window_id })
                     }
-                    SDL_WINDOWEVENT_FOCUS_LOST => callback(Event::WindowLostFocus { window_id }),
-                    SDL_WINDOWEVENT_CLOSE => callback(Event::WindowCloseRequested { window_id }),
+                    SDL_WINDOWEVENT_FOCUS_LOST => send_event(Event::WindowLostFocus { window_id }),
+                    SDL_WINDOWEVENT_CLOSE => send_event(Event::WindowCloseRequested { window_id }),
                     // Presently SDL will block during resizing, which isn't ideal and doesn't match the other
                     // `kapp` platforms. There are ways to alleviate it, but investigation is required.
-                    SDL_WINDOWEVENT_SIZE_CHANGED => callback(Event::WindowResized {
+                    SDL_WINDOWEVENT_SIZE_CHANGED => send_event(Event::WindowResized {
                         window_id,
                         width: window_event.data1 as u32,
                         height: window_event.data2 as u32,
@@ -372,49 +935,86 @@ fn process_event(callback: &mut Box<dyn FnMut(Event)>, event: &SDL_Event) {
                 // Are milliseconds the correct units?
                 let timestamp = Duration::from_millis(keyboard_event.timestamp as u64);
 
-                let key = scancode_to_key(keyboard_event.keysym.scancode);
+                let scancode = keyboard_event.keysym.scancode;
+                let key = scancode_to_key(scancode);
+                let logical_key = keycode_to_logical_key(keyboard_event.keysym.sym, key);
+                let window_id = window_id_from_sdl(keyboard_event.windowID);
+                // SDL updates the modifier state before sending the key event.
+                let modifiers = update_modifiers(keyboard_event.keysym.mod_ as u32);
                 match keyboard_event.type_ {
                     SDL_KEYDOWN => {
                         if keyboard_event.repeat > 0 {
-                            callback(Event::KeyRepeat { key, timestamp })
+                            send_event(Event::KeyRepeat {
+                                key,
+                                scancode: scancode as u32,
+                                logical_key,
+                                window_id,
+                                modifiers,
+                                timestamp,
+                            })
                         } else {
-                            callback(Event::KeyDown { key, timestamp })
+                            send_event(Event::KeyDown {
+                                key,
+                                scancode: scancode as u32,
+                                logical_key,
+                                window_id,
+                                modifiers,
+                                timestamp,
+                            })
                         }
                     }
-                    SDL_KEYUP => callback(Event::KeyUp { key, timestamp }),
+                    SDL_KEYUP => send_event(Event::KeyUp {
+                        key,
+                        scancode: scancode as u32,
+                        logical_key,
+                        window_id,
+                        modifiers,
+                        timestamp,
+                    }),
                     _ => {}
                 }
             }
             SDL_MOUSEMOTION => {
                 let mouse_motion_event = event.motion;
+                let window_id = window_id_from_sdl(mouse_motion_event.windowID);
+
+                // Touches are sent from the finger events instead.
+                if mouse_motion_event.which == SDL_TOUCH_MOUSEID {
+                    return;
+                }
 
                 // Are milliseconds the correct units?
                 let timestamp = Duration::from_millis(mouse_motion_event.timestamp as u64);
-                let source = match mouse_motion_event.which {
-                    SDL_TOUCH_MOUSEID => PointerSource::Touch,
-                    _ => PointerSource::Mouse,
-                };
 
                 // Do these need to be scaled by the window DPI?
-                callback(Event::MouseMotion {
+                send_event(Event::MouseMotion {
                     delta_x: mouse_motion_event.xrel as f64,
                     delta_y: mouse_motion_event.yrel as f64,
+                    window_id,
                     timestamp,
                 });
-                callback(Event::PointerMoved {
+                send_event(Event::PointerMoved {
                     x: mouse_motion_event.x as f64,
                     y: mouse_motion_event.y as f64,
-                    source,
+                    source: PointerSource::Mouse,
+                    pointer_id: 0,
+                    pressure: 0.0,
+                    tilt_x: 0.0,
+                    tilt_y: 0.0,
+                    contact_width: 0.0,
+                    contact_height: 0.0,
+                    window_id,
                     timestamp,
                 });
             }
             SDL_MOUSEBUTTONDOWN => {
                 let event = event.button;
+                let window_id = window_id_from_sdl(event.windowID);
 
-                let source = match event.which {
-                    SDL_TOUCH_MOUSEID => PointerSource::Touch,
-                    _ => PointerSource::Mouse,
-                };
+                // Touches are sent from the finger events instead.
+                if event.which == SDL_TOUCH_MOUSEID {
+                    return;
+                }
 
                 // Are milliseconds the correct units?
                 let timestamp = Duration::from_millis(event.timestamp as u64);
@@ -427,36 +1027,48 @@ fn process_event(callback: &mut Box<dyn FnMut(Event)>, event: &SDL_Event) {
                     _ => PointerButton::Unknown,
                 };
 
-                callback(Event::PointerDown {
+                let modifiers = update_modifiers(SDL_GetModState() as u32);
+                send_event(Event::PointerDown {
                     x: event.x as f64,
                     y: event.y as f64,
-                    source,
+                    source: PointerSource::Mouse,
+                    pointer_id: 0,
                     button,
+                    pressure: 0.0,
+                    tilt_x: 0.0,
+                    tilt_y: 0.0,
+                    contact_width: 0.0,
+                    contact_height: 0.0,
+                    window_id,
+                    modifiers,
                     timestamp,
                 });
 
                 if event.clicks == 2 {
-                    callback(Event::DoubleClickDown {
+                    send_event(Event::DoubleClickDown {
                         x: event.x as f64,
                         y: event.y as f64,
                         button,
+                        window_id,
                         timestamp,
                     });
-                    callback(Event::DoubleClick {
+                    send_event(Event::DoubleClick {
                         x: event.x as f64,
                         y: event.y as f64,
                         button,
+                        window_id,
                         timestamp,
                     });
                 }
             }
             SDL_MOUSEBUTTONUP => {
                 let event = event.button;
+                let window_id = window_id_from_sdl(event.windowID);
 
-                let source = match event.which {
-                    SDL_TOUCH_MOUSEID => PointerSource::Touch,
-                    _ => PointerSource::Mouse,
-                };
+                // Touches are sent from the finger events instead.
+                if event.which == SDL_TOUCH_MOUSEID {
+                    return;
+                }
 
                 // Are milliseconds the correct units?
                 let timestamp = Duration::from_millis(event.timestamp as u64);
@@ -468,22 +1080,99 @@ fn process_event(callback: &mut Box<dyn FnMut(Event)>, event: &SDL_Event) {
                     SDL_BUTTON_X2 => PointerButton::Extra2,
                     _ => PointerButton::Unknown,
                 };
-                callback(Event::PointerUp {
+                let modifiers = update_modifiers(SDL_GetModState() as u32);
+                send_event(Event::PointerUp {
                     x: event.x as f64,
                     y: event.y as f64,
-                    source,
+                    source: PointerSource::Mouse,
+                    pointer_id: 0,
                     button,
+                    pressure: 0.0,
+                    tilt_x: 0.0,
+                    tilt_y: 0.0,
+                    contact_width: 0.0,
+                    contact_height: 0.0,
+                    window_id,
+                    modifiers,
                     timestamp,
                 });
                 if event.clicks == 2 {
-                    callback(Event::DoubleClickUp {
+                    send_event(Event::DoubleClickUp {
                         x: event.x as f64,
                         y: event.y as f64,
                         button,
+                        window_id,
                         timestamp,
                     });
                 }
             }
+            SDL_FINGERDOWN | SDL_FINGERMOTION | SDL_FINGERUP => {
+                let event = event.tfinger;
+
+                // Older SDL versions don't report the window a touch is in.
+                let window = if event.windowID != 0 {
+                    SDL_GetWindowFromID(event.windowID)
+                } else {
+                    SDL_GetMouseFocus()
+                };
+                let window_id = WindowId::new(window as *mut c_void);
+
+                // Finger positions are from 0.0 to 1.0 across the window.
+                let mut width = 0;
+                let mut height = 0;
+                SDL_GetWindowSize(window, &mut width, &mut height);
+                let x = event.x as f64 * width as f64;
+                let y = event.y as f64 * height as f64;
+
+                let pointer_id = event.fingerId as u64;
+                let pressure = event.pressure as f64;
+                let timestamp = Duration::from_millis(event.timestamp as u64);
+                match event.type_ {
+                    SDL_FINGERMOTION => send_event(Event::PointerMoved {
+                        x,
+                        y,
+                        source: PointerSource::Touch,
+                        pointer_id,
+                        pressure,
+                        tilt_x: 0.0,
+                        tilt_y: 0.0,
+                        contact_width: 0.0,
+                        contact_height: 0.0,
+                        window_id,
+                        timestamp,
+                    }),
+                    SDL_FINGERDOWN => send_event(Event::PointerDown {
+                        x,
+                        y,
+                        source: PointerSource::Touch,
+                        pointer_id,
+                        button: PointerButton::Primary,
+                        pressure,
+                        tilt_x: 0.0,
+                        tilt_y: 0.0,
+                        contact_width: 0.0,
+                        contact_height: 0.0,
+                        window_id,
+                        modifiers: update_modifiers(SDL_GetModState() as u32),
+                        timestamp,
+                    }),
+                    _ => send_event(Event::PointerUp {
+                        x,
+                        y,
+                        source: PointerSource::Touch,
+                        pointer_id,
+                        button: PointerButton::Primary,
+                        pressure,
+                        tilt_x: 0.0,
+                        tilt_y: 0.0,
+                        contact_width: 0.0,
+                        contact_height: 0.0,
+                        window_id,
+                        modifiers: update_modifiers(SDL_GetModState() as u32),
+                        timestamp,
+                    }),
+                }
+            }
             SDL_MOUSEWHEEL => {
                 let event = event.wheel;
                 let mut delta_x = event.x as f64;
@@ -494,27 +1183,60 @@ fn process_event(callback: &mut Box<dyn FnMut(Event)>, event: &SDL_Event) {
                     delta_y *= -1.0;
                 }
 
-                let window_id = WindowId::new(SDL_GetWindowFromID(event.windowID) as *mut c_void);
+                let window_id = window_id_from_sdl(event.windowID);
                 let timestamp = Duration::from_millis(event.timestamp as u64);
+                let modifiers = update_modifiers(SDL_GetModState() as u32);
 
-                callback(Event::Scroll {
+                send_event(Event::Scroll {
                     delta_x,
                     delta_y,
                     window_id,
+                    modifiers,
                     timestamp,
                 });
             }
             SDL_TEXTINPUT => {
+                let window_id = window_id_from_sdl(event.text.windowID);
                 let c_str = CStr::from_ptr(event.text.text.as_ptr()).to_str().unwrap();
                 for character in c_str.chars() {
                     // Send a character received for each key.
-                    callback(Event::CharacterReceived { character });
+                    send_event(Event::CharacterReceived {
+                        character,
+                        window_id,
+                    });
+                }
+            }
+            SDL_DROPBEGIN => DROPPED_PATHS.with(|paths| paths.borrow_mut().clear()),
+            SDL_DROPFILE | SDL_DROPTEXT => {
+                let drop_event = event.drop;
+                let text = CStr::from_ptr(drop_event.file)
+                    .to_string_lossy()
+                    .into_owned();
+                SDL_free(drop_event.file as *mut c_void);
+                if event.type_ == SDL_DROPFILE {
+                    DROPPED_PATHS.with(|paths| paths.borrow_mut().push(PathBuf::from(text)));
+                } else {
+                    send_event(Event::Dropped {
+                        data: DropData::Text(text),
+                        window_id: window_id_from_sdl(drop_event.windowID),
+                    });
+                }
+            }
+            SDL_DROPCOMPLETE => {
+                let paths = DROPPED_PATHS.with(|paths| paths.replace(Vec::new()));
+                if !paths.is_empty() {
+                    send_event(Event::Dropped {
+                        data: DropData::Paths(paths),
+                        window_id: window_id_from_sdl(event.drop.windowID),
+                    });
                 }
             }
             SDL_TEXTEDITING => {
+                let window_id = window_id_from_sdl(event.edit.windowID);
                 let c_str = CStr::from_ptr(event.text.text.as_ptr()).to_str().unwrap();
-                callback(Event::IMEComposition {
+                send_event(Event::IMEComposition {
                     composition: c_str.to_string(),
+                    window_id,
                 });
             }
             _ => {}
@@ -522,10 +1244,247 @@ fn process_event(callback: &mut Box<dyn FnMut(Event)>, event: &SDL_Event) {
     }
 }
 
+fn pointer_button_to_sdl_button(button: PointerButton) -> u8 {
+    (match button {
+        PointerButton::Primary => SDL_BUTTON_LEFT,
+        PointerButton::Auxillary => SDL_BUTTON_MIDDLE,
+        PointerButton::Secondary => SDL_BUTTON_RIGHT,
+        PointerButton::Extra1 => SDL_BUTTON_X1,
+        PointerButton::Extra2 => SDL_BUTTON_X2,
+        _ => 0,
+    }) as u8
+}
+
+fn sdl_bool(value: bool) -> SDL_bool {
+    if value {
+        SDL_TRUE
+    } else {
+        SDL_FALSE
+    }
+}
+
+fn window_flags(window_id: WindowId) -> u32 {
+    unsafe { SDL_GetWindowFlags(window_id.raw() as *mut SDL_Window) }
+}
+
+unsafe fn window_id_from_sdl(window_id: u32) -> WindowId {
+    WindowId::new(SDL_GetWindowFromID(window_id) as *mut c_void)
+}
+
+unsafe fn window_id_to_sdl(window_id: WindowId) -> u32 {
+    SDL_GetWindowID(window_id.raw() as *mut SDL_Window)
+}
+
+/// Creates the SDL event that `process_event` translates to `event`.
+/// Returns `None` for events that SDL has no equivalent for.
+fn event_to_sdl_event(event: &Event) -> Option<SDL_Event> {
+    unsafe {
+        let mut sdl_event: SDL_Event = std::mem::zeroed();
+        match *event {
+            Event::QuitRequested => sdl_event.type_ = SDL_QUIT,
+            Event::ClipboardChanged {
+                selection: ClipboardSelection::Clipboard,
+            } => sdl_event.type_ = SDL_CLIPBOARDUPDATE,
+            Event::KeyDown {
+                key,
+                logical_key,
+                window_id,
+                modifiers,
+                timestamp,
+                ..
+            }
+            | Event::KeyRepeat {
+                key,
+                logical_key,
+                window_id,
+                modifiers,
+                timestamp,
+                ..
+            }
+            | Event::KeyUp {
+                key,
+                logical_key,
+                window_id,
+                modifiers,
+                timestamp,
+                ..
+            } => {
+                let pressed = !matches!(event, Event::KeyUp { .. });
+                let keyboard_event = &mut sdl_event.key;
+                keyboard_event.type_ = if pressed { SDL_KEYDOWN } else { SDL_KEYUP };
+                keyboard_event.timestamp = timestamp.as_millis() as u32;
+                keyboard_event.windowID = window_id_to_sdl(window_id);
+                // SDL_PRESSED is 1 and SDL_RELEASED is 0
+                keyboard_event.state = pressed as u8;
+                keyboard_event.repeat = matches!(event, Event::KeyRepeat { .. }) as u8;
+                keyboard_event.keysym.scancode = key_to_scancode(key);
+                keyboard_event.keysym.sym = logical_key_to_keycode(logical_key);
+                keyboard_event.keysym.mod_ = modifiers_to_sdl_modifiers(modifiers) as _;
+            }
+            Event::CharacterReceived {
+                character,
+                window_id,
+            } => {
+                let text_event = &mut sdl_event.text;
+                text_event.type_ = SDL_TEXTINPUT;
+                text_event.windowID = window_id_to_sdl(window_id);
+                let mut buffer = [0; 4];
+                for (i, byte) in character.encode_utf8(&mut buffer).bytes().enumerate() {
+                    text_event.text[i] = byte as _;
+                }
+            }
+            // Touches are injected as finger events.
+            Event::PointerMoved {
+                x,
+                y,
+                source: PointerSource::Touch,
+                pointer_id,
+                pressure,
+                window_id,
+                timestamp,
+                ..
+            }
+            | Event::PointerDown {
+                x,
+                y,
+                source: PointerSource::Touch,
+                pointer_id,
+                pressure,
+                window_id,
+                timestamp,
+                ..
+            }
+            | Event::PointerUp {
+                x,
+                y,
+                source: PointerSource::Touch,
+                pointer_id,
+                pressure,
+                window_id,
+                timestamp,
+                ..
+            } => {
+                let finger_event = &mut sdl_event.tfinger;
+                finger_event.type_ = match *event {
+                    Event::PointerDown { .. } => SDL_FINGERDOWN,
+                    Event::PointerUp { .. } => SDL_FINGERUP,
+                    _ => SDL_FINGERMOTION,
+                };
+                finger_event.timestamp = timestamp.as_millis() as u32;
+                finger_event.windowID = window_id_to_sdl(window_id);
+                finger_event.fingerId = pointer_id as i64;
+                finger_event.pressure = pressure as f32;
+
+                // Finger positions are from 0.0 to 1.0 across the window.
+                let mut width = 0;
+                let mut height = 0;
+                SDL_GetWindowSize(window_id.raw() as *mut SDL_Window, &mut width, &mut height);
+                finger_event.x = (x / width.max(1) as f64) as f32;
+                finger_event.y = (y / height.max(1) as f64) as f32;
+            }
+            // SDL reports the pointer position and motion together,
+            // so an injected `PointerMoved` also produces a `MouseMotion` without movement.
+            Event::PointerMoved {
+                x,
+                y,
+                window_id,
+                timestamp,
+                ..
+            } => {
+                let motion_event = &mut sdl_event.motion;
+                motion_event.type_ = SDL_MOUSEMOTION;
+                motion_event.timestamp = timestamp.as_millis() as u32;
+                motion_event.windowID = window_id_to_sdl(window_id);
+                motion_event.x = x as i32;
+                motion_event.y = y as i32;
+            }
+            Event::PointerDown {
+                x,
+                y,
+                button,
+                window_id,
+                timestamp,
+                ..
+            }
+            | Event::PointerUp {
+                x,
+                y,
+                button,
+                window_id,
+                timestamp,
+                ..
+            } => {
+                let pressed = matches!(event, Event::PointerDown { .. });
+                let button_event = &mut sdl_event.button;
+                button_event.type_ = if pressed {
+                    SDL_MOUSEBUTTONDOWN
+                } else {
+                    SDL_MOUSEBUTTONUP
+                };
+                button_event.timestamp = timestamp.as_millis() as u32;
+                button_event.windowID = window_id_to_sdl(window_id);
+                button_event.button = pointer_button_to_sdl_button(button);
+                button_event.state = pressed as u8;
+                button_event.clicks = 1;
+                button_event.x = x as i32;
+                button_event.y = y as i32;
+            }
+            Event::Scroll {
+                delta_x,
+                delta_y,
+                window_id,
+                timestamp,
+                ..
+            } => {
+                let wheel_event = &mut sdl_event.wheel;
+                wheel_event.type_ = SDL_MOUSEWHEEL;
+                wheel_event.timestamp = timestamp.as_millis() as u32;
+                wheel_event.windowID = window_id_to_sdl(window_id);
+                wheel_event.x = delta_x.round() as i32;
+                wheel_event.y = delta_y.round() as i32;
+            }
+            Event::WindowMinimized { window_id }
+            | Event::WindowMaximized { window_id }
+            | Event::WindowRestored { window_id, .. }
+            | Event::WindowGainedFocus { window_id }
+            | Event::WindowLostFocus { window_id }
+            | Event::WindowCloseRequested { window_id }
+            | Event::WindowMoved { window_id, .. }
+            | Event::WindowResized { window_id, .. } => {
+                let window_event = &mut sdl_event.window;
+                window_event.type_ = SDL_WINDOWEVENT;
+                window_event.windowID = window_id_to_sdl(window_id);
+                window_event.event = match *event {
+                    Event::WindowMinimized { .. } => SDL_WINDOWEVENT_MINIMIZED,
+                    Event::WindowMaximized { .. } => SDL_WINDOWEVENT_MAXIMIZED,
+                    Event::WindowRestored { .. } => SDL_WINDOWEVENT_RESTORED,
+                    Event::WindowGainedFocus { .. } => SDL_WINDOWEVENT_FOCUS_GAINED,
+                    Event::WindowLostFocus { .. } => SDL_WINDOWEVENT_FOCUS_LOST,
+                    Event::WindowCloseRequested { .. } => SDL_WINDOWEVENT_CLOSE,
+                    Event::WindowMoved { x, y, .. } => {
+                        window_event.data1 = x as i32;
+                        window_event.data2 = y as i32;
+                        SDL_WINDOWEVENT_MOVED
+                    }
+                    Event::WindowResized { width, height, .. } => {
+                        window_event.data1 = width as i32;
+                        window_event.data2 = height as i32;
+                        SDL_WINDOWEVENT_SIZE_CHANGED
+                    }
+                    _ => unreachable!(),
+                };
+            }
+            _ => return None,
+        }
+        Some(sdl_event)
+    }
+}
+
 pub struct PlatformEventLoop {}
 
 impl PlatformEventLoopTrait for PlatformEventLoop {
-    fn run(&self, mut callback: Box<dyn FnMut(Event)>) {
+    fn run(&self, callback: Box<dyn FnMut(Event)>) {
+        event_receiver::set_callback(callback);
         unsafe {
             let mut event = std::mem::zeroed();
             loop {
@@ -534,22 +1493,34 @@ impl PlatformEventLoopTrait for PlatformEventLoop {
                 }
                 // Wait for a new event if we don't have any redraw requests
                 if redraw_manager::draw_requests_count() == 0 {
-                    SDL_WaitEvent(&mut event);
-                    process_event(&mut callback, &event);
+                    match control_flow::timeout() {
+                        None => {
+                            SDL_WaitEvent(&mut event);
+                            process_event(&event);
+                        }
+                        Some(timeout) => {
+                            let milliseconds = control_flow::timeout_milliseconds(timeout);
+                            if SDL_WaitEventTimeout(&mut event, milliseconds) != 0 {
+                                process_event(&event);
+                            }
+                        }
+                    }
                 }
 
                 // Process all events.
                 while SDL_PollEvent(&mut event) != 0 {
-                    process_event(&mut callback, &event);
+                    process_event(&event);
                 }
+                event_loop_proxy::send_proxy_events();
+                control_flow::resume_if_reached();
 
                 // When there are no events remaining, we're at the end of the event loop
-                callback(Event::EventsCleared);
+                send_event(Event::EventsCleared);
 
                 // Send a draw event for each window that needs to be drawn.
                 redraw_manager::begin_draw_flush();
                 while let Some(window_id) = redraw_manager::get_draw_request() {
-                    callback(Event::Draw { window_id });
+                    send_event(Event::Draw { window_id });
                 }
             }
         }
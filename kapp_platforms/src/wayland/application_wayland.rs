@@ -0,0 +1,1394 @@
+use super::decorations_wayland::{ClientDecorations, TITLEBAR_HEIGHT};
+use super::event_loop_wayland::{add_dispatcher, process_messages, ProxyKind};
+use super::external_wayland::*;
+use super::protocols_wayland::*;
+use crate::linux::cursors_linux::cursor_names;
+use crate::linux::wake_pipe_linux::WakePipe;
+use kapp_platform_common::*;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::ptr::{null, null_mut};
+use std::time::{Duration, Instant};
+
+thread_local!(pub(crate) static APPLICATION_DATA: RefCell<Option<ApplicationData>> = const { RefCell::new(None) });
+
+/// Borrows the application data.
+/// Events must not be sent while the data is borrowed because the user callback
+/// may call back into the application.
+pub(crate) fn with_data<R>(f: impl FnOnce(&mut ApplicationData) -> R) -> R {
+    APPLICATION_DATA.with(|d| f(d.borrow_mut().as_mut().expect("Wayland is not initialized")))
+}
+
+/// Globals advertised by the compositor that kapp uses.
+pub(crate) struct Globals {
+    pub compositor: *mut wl_proxy,
+    pub subcompositor: *mut wl_proxy,
+    pub shm: *mut wl_proxy,
+    pub wm_base: *mut wl_proxy,
+    pub seat: *mut wl_proxy,
+    pub decoration_manager: *mut wl_proxy,
+    pub pointer_constraints: *mut wl_proxy,
+    pub relative_pointer_manager: *mut wl_proxy,
+}
+
+/// The connection to the compositor, kept separate from the rest of the application data
+/// so requests can be sent while windows are borrowed.
+pub(crate) struct Connection {
+    pub wayland: WaylandClient,
+    pub display: *mut wl_display,
+    pub registry: *mut wl_proxy,
+    pub globals: Globals,
+}
+
+impl Connection {
+    pub unsafe fn request(&self, proxy: *mut wl_proxy, opcode: u32, arguments: &mut [wl_argument]) {
+        (self.wayland.wl_proxy_marshal_array)(proxy, opcode, arguments.as_mut_ptr());
+    }
+
+    /// Sends a request that creates a new object.
+    /// The new object's argument should be `wl_argument { n: 0 }`
+    pub unsafe fn create(
+        &self,
+        proxy: *mut wl_proxy,
+        opcode: u32,
+        arguments: &mut [wl_argument],
+        interface: *const wl_interface,
+    ) -> *mut wl_proxy {
+        (self.wayland.wl_proxy_marshal_array_constructor)(
+            proxy,
+            opcode,
+            arguments.as_mut_ptr(),
+            interface,
+        )
+    }
+
+    /// Sends a destructor request and destroys the proxy.
+    pub unsafe fn destroy(&self, proxy: *mut wl_proxy, opcode: u32) {
+        if !proxy.is_null() {
+            self.request(proxy, opcode, &mut []);
+            (self.wayland.wl_proxy_destroy)(proxy);
+        }
+    }
+
+    pub unsafe fn create_surface(&self) -> *mut wl_proxy {
+        self.create(
+            self.globals.compositor,
+            WL_COMPOSITOR_CREATE_SURFACE,
+            &mut [wl_argument { n: 0 }],
+            self.wayland.wl_surface_interface,
+        )
+    }
+
+    /// Creates a shared memory buffer with ARGB pixels drawn by `draw`.
+    /// Returns null if the buffer could not be created.
+    pub unsafe fn create_shm_buffer(
+        &self,
+        width: i32,
+        height: i32,
+        draw: impl FnOnce(&mut [u32]),
+    ) -> *mut wl_proxy {
+        if width <= 0 || height <= 0 || self.globals.shm.is_null() {
+            return null_mut();
+        }
+        let stride = width * 4;
+        let size = (stride * height) as usize;
+
+        let fd = memfd_create(b"kapp-buffer\0".as_ptr() as *const c_char, MFD_CLOEXEC);
+        if fd < 0 {
+            return null_mut();
+        }
+        if ftruncate(fd, size as i64) < 0 {
+            close(fd);
+            return null_mut();
+        }
+        let memory = mmap(null_mut(), size, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+        if memory == MAP_FAILED {
+            close(fd);
+            return null_mut();
+        }
+        draw(std::slice::from_raw_parts_mut(
+            memory as *mut u32,
+            (width * height) as usize,
+        ));
+        munmap(memory, size);
+
+        let pool = self.create(
+            self.globals.shm,
+            WL_SHM_CREATE_POOL,
+            &mut [
+                wl_argument { n: 0 },
+                wl_argument { h: fd },
+                wl_argument { i: size as i32 },
+            ],
+            self.wayland.wl_shm_pool_interface,
+        );
+        let buffer = self.create(
+            pool,
+            WL_SHM_POOL_CREATE_BUFFER,
+            &mut [
+                wl_argument { n: 0 },
+                wl_argument { i: 0 },
+                wl_argument { i: width },
+                wl_argument { i: height },
+                wl_argument { i: stride },
+                wl_argument {
+                    u: WL_SHM_FORMAT_ARGB8888,
+                },
+            ],
+            self.wayland.wl_buffer_interface,
+        );
+        // The buffer keeps the memory alive after the pool and file are released.
+        self.destroy(pool, WL_SHM_POOL_DESTROY);
+        close(fd);
+        buffer
+    }
+
+    pub unsafe fn commit(&self, surface: *mut wl_proxy) {
+        self.request(surface, WL_SURFACE_COMMIT, &mut []);
+    }
+
+    /// Buffer scales were added in version 3 of wl_surface.
+    pub unsafe fn set_buffer_scale(&self, surface: *mut wl_proxy, scale: i32) {
+        if (self.wayland.wl_proxy_get_version)(surface) >= 3 {
+            self.request(
+                surface,
+                WL_SURFACE_SET_BUFFER_SCALE,
+                &mut [wl_argument { i: scale }],
+            );
+        }
+    }
+
+    /// Sets the part of a surface that receives pointer and touch input,
+    /// as rectangles in surface coordinates. `None` lets the whole surface receive input.
+    pub unsafe fn set_input_region(
+        &self,
+        surface: *mut wl_proxy,
+        rectangles: Option<&[(i32, i32, i32, i32)]>,
+    ) {
+        let region = match rectangles {
+            Some(rectangles) => {
+                let region = self.create(
+                    self.globals.compositor,
+                    WL_COMPOSITOR_CREATE_REGION,
+                    &mut [wl_argument { n: 0 }],
+                    self.wayland.wl_region_interface,
+                );
+                for &(x, y, width, height) in rectangles {
+                    self.request(
+                        region,
+                        WL_REGION_ADD,
+                        &mut [
+                            wl_argument { i: x },
+                            wl_argument { i: y },
+                            wl_argument { i: width },
+                            wl_argument { i: height },
+                        ],
+                    );
+                }
+                region
+            }
+            None => null_mut(),
+        };
+        self.request(
+            surface,
+            WL_SURFACE_SET_INPUT_REGION,
+            &mut [wl_argument { o: region }],
+        );
+        // The surface keeps a copy of the region.
+        self.destroy(region, WL_REGION_DESTROY);
+    }
+
+    pub unsafe fn flush(&self) {
+        (self.wayland.wl_display_flush)(self.display);
+    }
+
+    /// Tells the compositor which part of the window is the window itself,
+    /// which includes client side decorations.
+    pub unsafe fn update_window_geometry(&self, window: &WindowData) {
+        let titlebar_height = window.titlebar_height();
+        self.request(
+            window.xdg_surface,
+            XDG_SURFACE_SET_WINDOW_GEOMETRY,
+            &mut [
+                wl_argument { i: 0 },
+                wl_argument {
+                    i: -titlebar_height,
+                },
+                wl_argument { i: window.width },
+                wl_argument {
+                    i: window.height + titlebar_height,
+                },
+            ],
+        );
+    }
+
+    /// Tells the compositor the window's size limits.
+    /// They apply to the window geometry, which includes client side decorations.
+    pub unsafe fn update_size_limits(&self, window: &WindowData) {
+        let titlebar_height = window.titlebar_height();
+        let logical = |(width, height): (u32, u32)| {
+            (
+                width as i32 / window.scale,
+                height as i32 / window.scale + titlebar_height,
+            )
+        };
+        let (minimum_size, maximum_size) = if window.resizable {
+            (
+                window.minimum_size.map(logical),
+                window.maximum_size.map(logical),
+            )
+        } else {
+            let size = (window.width, window.height + titlebar_height);
+            (Some(size), Some(size))
+        };
+
+        // A size of 0 means there's no limit.
+        let (min_width, min_height) = minimum_size.unwrap_or((0, 0));
+        self.request(
+            window.toplevel,
+            XDG_TOPLEVEL_SET_MIN_SIZE,
+            &mut [wl_argument { i: min_width }, wl_argument { i: min_height }],
+        );
+        let (max_width, max_height) = maximum_size.unwrap_or((0, 0));
+        self.request(
+            window.toplevel,
+            XDG_TOPLEVEL_SET_MAX_SIZE,
+            &mut [wl_argument { i: max_width }, wl_argument { i: max_height }],
+        );
+    }
+}
+
+pub(crate) struct Output {
+    pub registry_name: u32,
+    pub scale: i32,
+    /// The make and model of the monitor.
+    pub name: String,
+    /// The position in the compositor's global space.
+    pub position: (i32, i32),
+    /// The current mode's size in physical pixels.
+    pub size: (u32, u32),
+    pub transform: i32,
+    pub refresh_rate: Option<f64>,
+    /// The screen as of the last `done` event, or `None` before the output is done.
+    pub reported: Option<Screen>,
+}
+
+impl Output {
+    pub fn screen(&self) -> Screen {
+        // Odd transforms rotate the output by 90 or 270 degrees.
+        let size = if self.transform % 2 == 1 {
+            (self.size.1, self.size.0)
+        } else {
+            self.size
+        };
+        Screen {
+            id: ScreenId::new(self.registry_name as usize as *mut c_void),
+            name: self.name.clone(),
+            position: self.position,
+            size,
+            // Wayland doesn't tell clients which parts of an output panels cover.
+            work_area_position: self.position,
+            work_area_size: size,
+            scale: self.scale as f64,
+            refresh_rate: self.refresh_rate,
+            // Wayland clients can't change the output's mode.
+            video_modes: Vec::new(),
+        }
+    }
+}
+
+pub(crate) struct WindowData {
+    pub surface: *mut wl_proxy,
+    pub xdg_surface: *mut wl_proxy,
+    pub toplevel: *mut wl_proxy,
+    /// Server side decorations, if the compositor supports them.
+    pub decoration: *mut wl_proxy,
+    /// Client side decorations, used when the compositor does not draw decorations.
+    pub client_decorations: Option<ClientDecorations>,
+    /// False if the window shouldn't have decorations drawn by either side.
+    pub decorations: bool,
+    /// Size limits in physical pixels.
+    pub minimum_size: Option<(u32, u32)>,
+    pub maximum_size: Option<(u32, u32)>,
+    pub resizable: bool,
+    /// The size of the content area in logical pixels.
+    pub width: i32,
+    pub height: i32,
+    /// The size and states from the last toplevel configure.
+    /// They're applied when the configure is acknowledged.
+    pub pending_size: (i32, i32),
+    pub pending_states: Vec<u32>,
+    pub configured: bool,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    /// The screen asked for by the last fullscreen request, or `None` if the compositor chooses.
+    pub fullscreen_screen: Option<ScreenId>,
+    pub resizing: bool,
+    pub scale: i32,
+    /// The outputs the window is on, which determine its scale.
+    pub outputs: Vec<*mut wl_proxy>,
+}
+
+impl WindowData {
+    pub fn window_id(&self) -> WindowId {
+        WindowId::new(self.surface as *mut c_void)
+    }
+
+    /// The fullscreen to report while the window is fullscreen.
+    pub fn borderless_fullscreen(&self, outputs: &HashMap<*mut wl_proxy, Output>) -> Fullscreen {
+        let output_screen = || Some(outputs.get(self.outputs.first()?)?.screen().id);
+        Fullscreen::Borderless(self.fullscreen_screen.or_else(output_screen))
+    }
+
+    pub fn titlebar_height(&self) -> i32 {
+        if self.client_decorations.is_some() && !self.fullscreen {
+            TITLEBAR_HEIGHT
+        } else {
+            0
+        }
+    }
+}
+
+pub(crate) struct Keyboard {
+    pub context: *mut xkb_context,
+    pub keymap: *mut xkb_keymap,
+    pub state: *mut xkb_state,
+    /// Keys per second and the delay before repeating starts, as set by the compositor.
+    pub repeat_rate: i32,
+    pub repeat_delay: Duration,
+    pub repeating_key: Option<RepeatingKey>,
+    pub held_keys: HashSet<u32>,
+    pub focused_window: Option<*mut wl_proxy>,
+    /// The last modifiers sent, used to send `ModifiersChanged`.
+    pub modifiers: Modifiers,
+}
+
+/// Wayland clients repeat held keys themselves.
+pub(crate) struct RepeatingKey {
+    pub code: u32,
+    /// The timestamp of the key press and when it was received.
+    pub timestamp: Duration,
+    pub pressed_at: Instant,
+    pub next_repeat: Instant,
+}
+
+pub(crate) struct MouseClick {
+    pub surface: *mut wl_proxy,
+    pub button: u32,
+    pub time: u32,
+}
+
+pub(crate) struct Pointer {
+    /// The surface the pointer is over.
+    pub surface: Option<*mut wl_proxy>,
+    pub enter_serial: u32,
+    /// The serial of the last button press, needed to move windows.
+    pub button_serial: u32,
+    /// Position in logical pixels relative to the surface.
+    pub x: f64,
+    pub y: f64,
+    pub last_click: Option<MouseClick>,
+    /// The button of a double click that has not been released yet.
+    pub double_click_button: Option<u32>,
+    pub locked_pointer: *mut wl_proxy,
+    pub relative_pointer: *mut wl_proxy,
+}
+
+/// An image that can be attached to the cursor surface.
+#[derive(Clone, Copy)]
+pub(crate) struct CursorImage {
+    pub buffer: *mut wl_proxy,
+    pub width: i32,
+    pub height: i32,
+    pub hotspot: (i32, i32),
+}
+
+// Global singleton data shared by the application struct and the event loop.
+pub(crate) struct ApplicationData {
+    pub connection: Connection,
+    pub xkb: Option<Xkb>,
+    pub cursor_library: Option<WaylandCursor>,
+    pub outputs: HashMap<*mut wl_proxy, Output>,
+    pub windows: HashMap<*mut wl_proxy, WindowData>,
+    pub pointer: *mut wl_proxy,
+    pub keyboard: *mut wl_proxy,
+    pub keyboard_state: Keyboard,
+    pub pointer_state: Pointer,
+    pub cursor_theme: *mut wl_cursor_theme,
+    pub cursor_surface: *mut wl_proxy,
+    pub cursor: Cursor,
+    pub cursor_hidden: bool,
+    /// Images created with `create_cursor`, indexed by the custom cursor's id.
+    pub custom_cursors: Vec<CursorImage>,
+    pub actually_quit: bool, // Set when quit is called. Indicates the program should quit.
+    pub text_input_enabled: bool, // Should text input be sent in addition to KeyDown events?
+    /// Polled with the connection so proxies can wake the event loop.
+    pub wake_pipe: WakePipe,
+}
+
+impl ApplicationData {
+    unsafe fn new() -> Option<Self> {
+        let wayland = WaylandClient::open()?;
+        let display = (wayland.wl_display_connect)(null());
+        if display.is_null() {
+            return None;
+        }
+
+        let registry = (wayland.wl_proxy_marshal_array_constructor)(
+            display as *mut wl_proxy,
+            WL_DISPLAY_GET_REGISTRY,
+            [wl_argument { n: 0 }].as_mut_ptr(),
+            wayland.wl_registry_interface,
+        );
+        add_dispatcher(&wayland, registry, &ProxyKind::Registry);
+
+        let mut data = Self {
+            connection: Connection {
+                wayland,
+                display,
+                registry,
+                globals: Globals {
+                    compositor: null_mut(),
+                    subcompositor: null_mut(),
+                    shm: null_mut(),
+                    wm_base: null_mut(),
+                    seat: null_mut(),
+                    decoration_manager: null_mut(),
+                    pointer_constraints: null_mut(),
+                    relative_pointer_manager: null_mut(),
+                },
+            },
+            xkb: Xkb::open(),
+            cursor_library: WaylandCursor::open(),
+            outputs: HashMap::new(),
+            windows: HashMap::new(),
+            pointer: null_mut(),
+            keyboard: null_mut(),
+            keyboard_state: Keyboard {
+                context: null_mut(),
+                keymap: null_mut(),
+                state: null_mut(),
+                repeat_rate: 25,
+                repeat_delay: Duration::from_millis(600),
+                repeating_key: None,
+                held_keys: HashSet::new(),
+                focused_window: None,
+                modifiers: Modifiers::empty(),
+            },
+            pointer_state: Pointer {
+                surface: None,
+                enter_serial: 0,
+                button_serial: 0,
+                x: 0.,
+                y: 0.,
+                last_click: None,
+                double_click_button: None,
+                locked_pointer: null_mut(),
+                relative_pointer: null_mut(),
+            },
+            cursor_theme: null_mut(),
+            cursor_surface: null_mut(),
+            cursor: Cursor::Arrow,
+            cursor_hidden: false,
+            custom_cursors: Vec::new(),
+            actually_quit: false,
+            text_input_enabled: false,
+            wake_pipe: WakePipe::new(),
+        };
+
+        // The first roundtrip receives the globals, and the second receives
+        // the events sent by the globals that were bound.
+        // No windows exist yet so no user events are produced.
+        let mut events = Vec::new();
+        for _ in 0..2 {
+            (data.connection.wayland.wl_display_roundtrip)(display);
+            process_messages(&mut data, &mut events);
+        }
+
+        let globals = &data.connection.globals;
+        if globals.compositor.is_null() || globals.wm_base.is_null() {
+            // Without these no windows can be created.
+            return None;
+        }
+
+        if !globals.shm.is_null() {
+            data.cursor_surface = data.connection.create_surface();
+            if let Some(cursor_library) = &data.cursor_library {
+                let theme = std::env::var("XCURSOR_THEME")
+                    .ok()
+                    .and_then(|theme| CString::new(theme).ok());
+                let size = std::env::var("XCURSOR_SIZE")
+                    .ok()
+                    .and_then(|size| size.parse().ok())
+                    .unwrap_or(24);
+                data.cursor_theme = (cursor_library.wl_cursor_theme_load)(
+                    theme.as_ref().map_or(null(), |theme| theme.as_ptr()),
+                    size,
+                    globals.shm,
+                );
+            }
+        }
+        Some(data)
+    }
+
+    /// Finds the window for a surface, or for the surface of its decorations.
+    pub fn window_for_surface(&self, surface: *mut wl_proxy) -> Option<&WindowData> {
+        self.windows.get(&surface).or_else(|| {
+            self.windows.values().find(|w| {
+                w.client_decorations
+                    .as_ref()
+                    .is_some_and(|d| d.surface == surface)
+            })
+        })
+    }
+
+    /// Applies the current cursor to the pointer.
+    pub unsafe fn update_cursor(&self) {
+        let surface = match self.pointer_state.surface {
+            Some(surface) if !self.pointer.is_null() => surface,
+            _ => return,
+        };
+        let connection = &self.connection;
+        let serial = wl_argument {
+            u: self.pointer_state.enter_serial,
+        };
+
+        // Decorations always use the arrow cursor.
+        let over_content = self.windows.contains_key(&surface);
+        if self.cursor_hidden && over_content {
+            self.set_pointer_cursor(serial, null_mut(), 0, 0);
+            return;
+        }
+        let cursor = if over_content {
+            self.cursor
+        } else {
+            Cursor::Arrow
+        };
+
+        let image = match cursor {
+            Cursor::Custom(custom_cursor) => self
+                .custom_cursors
+                .get(custom_cursor.raw() as usize)
+                .filter(|image| !image.buffer.is_null())
+                .copied()
+                .or_else(|| self.theme_cursor_image(Cursor::Arrow)),
+            _ => self.theme_cursor_image(cursor),
+        };
+        // Without a cursor theme only hiding the cursor and custom cursors are possible.
+        let image = match image {
+            Some(image) => image,
+            None => return,
+        };
+
+        connection.request(
+            self.cursor_surface,
+            WL_SURFACE_ATTACH,
+            &mut [
+                wl_argument { o: image.buffer },
+                wl_argument { i: 0 },
+                wl_argument { i: 0 },
+            ],
+        );
+        connection.request(
+            self.cursor_surface,
+            WL_SURFACE_DAMAGE,
+            &mut [
+                wl_argument { i: 0 },
+                wl_argument { i: 0 },
+                wl_argument { i: image.width },
+                wl_argument { i: image.height },
+            ],
+        );
+        connection.commit(self.cursor_surface);
+        self.set_pointer_cursor(
+            serial,
+            self.cursor_surface,
+            image.hotspot.0,
+            image.hotspot.1,
+        );
+    }
+
+    /// Finds a cursor in the cursor theme.
+    unsafe fn theme_cursor_image(&self, cursor: Cursor) -> Option<CursorImage> {
+        let cursor_library = match &self.cursor_library {
+            Some(cursor_library) if !self.cursor_theme.is_null() => cursor_library,
+            _ => return None,
+        };
+        let wl_cursor = cursor_names(cursor)
+            .iter()
+            .map(|name| {
+                (cursor_library.wl_cursor_theme_get_cursor)(
+                    self.cursor_theme,
+                    name.as_ptr() as *const c_char,
+                )
+            })
+            .find(|wl_cursor| !wl_cursor.is_null())?;
+
+        let image = *(*wl_cursor).images;
+        Some(CursorImage {
+            buffer: (cursor_library.wl_cursor_image_get_buffer)(image),
+            width: (*image).width as i32,
+            height: (*image).height as i32,
+            hotspot: ((*image).hotspot_x as i32, (*image).hotspot_y as i32),
+        })
+    }
+
+    unsafe fn set_pointer_cursor(
+        &self,
+        serial: wl_argument,
+        surface: *mut wl_proxy,
+        hotspot_x: i32,
+        hotspot_y: i32,
+    ) {
+        self.connection.request(
+            self.pointer,
+            WL_POINTER_SET_CURSOR,
+            &mut [
+                serial,
+                wl_argument { o: surface },
+                wl_argument { i: hotspot_x },
+                wl_argument { i: hotspot_y },
+            ],
+        );
+        self.connection.flush();
+    }
+
+    /// Sets the window's content size in logical pixels and updates its decorations.
+    /// Returns a resize event if the size changed.
+    pub unsafe fn resize_window(
+        &mut self,
+        surface: *mut wl_proxy,
+        width: i32,
+        height: i32,
+    ) -> Option<Event> {
+        let connection = &self.connection;
+        let window = self.windows.get_mut(&surface)?;
+        if let Some(decorations) = &mut window.client_decorations {
+            decorations.update(connection, width, window.scale, window.fullscreen);
+        }
+        let resized = window.width != width || window.height != height;
+        window.width = width;
+        window.height = height;
+        connection.update_window_geometry(window);
+        if !window.resizable {
+            connection.update_size_limits(window);
+        }
+        if !resized {
+            return None;
+        }
+        redraw_manager::add_draw_request(window.window_id());
+        Some(Event::WindowResized {
+            width: (width * window.scale) as u32,
+            height: (height * window.scale) as u32,
+            window_id: window.window_id(),
+        })
+    }
+
+    /// Adds client side decorations to a window.
+    pub unsafe fn add_client_decorations(&mut self, surface: *mut wl_proxy) {
+        let connection = &self.connection;
+        if let Some(window) = self.windows.get_mut(&surface) {
+            let globals = &connection.globals;
+            if window.client_decorations.is_none()
+                && window.decorations
+                && !globals.shm.is_null()
+                && !globals.subcompositor.is_null()
+            {
+                let mut decorations = ClientDecorations::new(connection, surface);
+                decorations.update(connection, window.width, window.scale, window.fullscreen);
+                window.client_decorations = Some(decorations);
+                connection.update_window_geometry(window);
+                connection.update_size_limits(window);
+            }
+        }
+    }
+
+    pub unsafe fn remove_client_decorations(&mut self, surface: *mut wl_proxy) {
+        let connection = &self.connection;
+        if let Some(window) = self.windows.get_mut(&surface) {
+            if let Some(decorations) = window.client_decorations.take() {
+                decorations.destroy(connection);
+                connection.update_window_geometry(window);
+                connection.update_size_limits(window);
+            }
+        }
+    }
+}
+
+impl Drop for ApplicationData {
+    fn drop(&mut self) {
+        unsafe {
+            let connection = &self.connection;
+            for (_, window) in self.windows.drain() {
+                destroy_window(connection, window);
+            }
+            if let Some(xkb) = &self.xkb {
+                let keyboard = &self.keyboard_state;
+                if !keyboard.state.is_null() {
+                    (xkb.xkb_state_unref)(keyboard.state);
+                }
+                if !keyboard.keymap.is_null() {
+                    (xkb.xkb_keymap_unref)(keyboard.keymap);
+                }
+                if !keyboard.context.is_null() {
+                    (xkb.xkb_context_unref)(keyboard.context);
+                }
+            }
+            for image in self.custom_cursors.drain(..) {
+                connection.destroy(image.buffer, WL_BUFFER_DESTROY);
+            }
+            if let Some(cursor_library) = &self.cursor_library {
+                if !self.cursor_theme.is_null() {
+                    (cursor_library.wl_cursor_theme_destroy)(self.cursor_theme);
+                }
+            }
+            // Disconnecting releases everything else held by the compositor.
+            (connection.wayland.wl_display_disconnect)(connection.display);
+        }
+    }
+}
+
+unsafe fn destroy_window(connection: &Connection, window: WindowData) {
+    if let Some(decorations) = window.client_decorations {
+        decorations.destroy(connection);
+    }
+    connection.destroy(window.decoration, ZXDG_TOPLEVEL_DECORATION_V1_DESTROY);
+    connection.destroy(window.toplevel, XDG_TOPLEVEL_DESTROY);
+    connection.destroy(window.xdg_surface, XDG_SURFACE_DESTROY);
+    connection.destroy(window.surface, WL_SURFACE_DESTROY);
+}
+
+fn surface_from_id(window_id: WindowId) -> *mut wl_proxy {
+    unsafe { window_id.raw() as *mut wl_proxy }
+}
+
+pub struct PlatformApplication {}
+
+impl PlatformApplication {
+    /// Connects to the Wayland compositor.
+    /// Returns `None` if there is no compositor or it lacks the protocols kapp requires.
+    pub fn try_new() -> Option<Self> {
+        let data = unsafe { ApplicationData::new()? };
+        APPLICATION_DATA.with(|d| *d.borrow_mut() = Some(data));
+        Some(Self {})
+    }
+}
+
+impl PlatformApplicationTrait for PlatformApplication {
+    type EventLoop = PlatformEventLoop;
+    fn new() -> Self {
+        Self::try_new().expect("Could not connect to a Wayland compositor")
+    }
+
+    fn event_loop(&mut self) -> Self::EventLoop {
+        PlatformEventLoop {}
+    }
+
+    fn set_window_position(&mut self, _window_id: WindowId, _x: u32, _y: u32) {
+        // Wayland does not let clients position their windows.
+    }
+
+    fn set_window_size(&mut self, window_id: WindowId, width: u32, height: u32) {
+        let event = with_data(|data| unsafe {
+            let surface = surface_from_id(window_id);
+            let scale = data.windows.get(&surface)?.scale;
+            let event = data.resize_window(surface, width as i32 / scale, height as i32 / scale);
+            data.connection.flush();
+            event
+        });
+        // Wayland clients choose their own size, so the resize is reported immediately.
+        if let Some(event) = event {
+            event_receiver::send_event(event);
+        }
+    }
+
+    fn set_window_title(&mut self, window_id: WindowId, title: &str) {
+        with_data(|data| unsafe {
+            if let Some(window) = data.windows.get(&surface_from_id(window_id)) {
+                set_title(&data.connection, window.toplevel, title);
+                data.connection.flush();
+            }
+        })
+    }
+
+    fn set_window_icon(&mut self, _window_id: WindowId, _icon: &[Icon]) {
+        // Wayland has no stable protocol for window icons.
+        // Compositors find the icon in the desktop file matching the window's app id.
+    }
+
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        update_size_limits(window_id, |window| window.resizable = resizable);
+    }
+
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        update_size_limits(window_id, |window| window.minimum_size = size);
+    }
+
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        update_size_limits(window_id, |window| window.maximum_size = size);
+    }
+
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        with_data(|data| unsafe {
+            let surface = surface_from_id(window_id);
+            let window = match data.windows.get_mut(&surface) {
+                Some(window) => window,
+                None => return,
+            };
+            window.decorations = decorations;
+            if !window.decoration.is_null() {
+                // Client side mode tells the compositor not to draw decorations.
+                // The compositor's configure adds kapp's decorations if it can't draw them.
+                let mode = if decorations {
+                    ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE
+                } else {
+                    ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE
+                };
+                data.connection.request(
+                    window.decoration,
+                    ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE,
+                    &mut [wl_argument { u: mode }],
+                );
+            } else if decorations {
+                data.add_client_decorations(surface);
+            }
+            if !decorations {
+                data.remove_client_decorations(surface);
+            }
+            data.connection.flush();
+        })
+    }
+
+    fn set_window_always_on_top(&mut self, _window_id: WindowId, _always_on_top: bool) {
+        // Wayland has no protocol for keeping a window above others.
+    }
+
+    fn set_window_visible(&mut self, _window_id: WindowId, _visible: bool) {
+        // Hiding a surface unmaps it, after which the compositor must configure it again
+        // before anything is drawn. Unsupported until drawing can wait for that.
+    }
+
+    fn set_window_opacity(&mut self, _window_id: WindowId, _opacity: f32) {
+        // Wayland has no protocol for a window's opacity.
+        // Transparent windows can draw with alpha instead.
+    }
+
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        with_data(|data| unsafe {
+            let surface = surface_from_id(window_id);
+            let scale = match data.windows.get(&surface) {
+                Some(window) => window.scale,
+                None => return false,
+            };
+            // The region is in logical pixels.
+            let rectangles: Option<Vec<_>> = region.map(|region| {
+                region
+                    .iter()
+                    .map(|&(x, y, width, height)| {
+                        (
+                            x as i32 / scale,
+                            y as i32 / scale,
+                            (width as i32 + scale - 1) / scale,
+                            (height as i32 + scale - 1) / scale,
+                        )
+                    })
+                    .collect()
+            });
+            data.connection
+                .set_input_region(surface, rectangles.as_deref());
+            data.connection.commit(surface);
+            data.connection.flush();
+            true
+        })
+    }
+
+    fn minimize_window(&mut self, window_id: WindowId) {
+        toplevel_request(window_id, XDG_TOPLEVEL_SET_MINIMIZED, &mut []);
+    }
+
+    fn maximize_window(&mut self, window_id: WindowId) {
+        toplevel_request(window_id, XDG_TOPLEVEL_SET_MAXIMIZED, &mut []);
+    }
+
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
+        // Wayland clients can't change video modes,
+        // so exclusive fullscreen is borderless on the mode's output.
+        let screen_id = match fullscreen {
+            Fullscreen::Off => {
+                toplevel_request(window_id, XDG_TOPLEVEL_UNSET_FULLSCREEN, &mut []);
+                return;
+            }
+            Fullscreen::Borderless(screen_id) => screen_id,
+            Fullscreen::Exclusive(video_mode) => Some(video_mode.screen_id),
+        };
+        let event = with_data(|data| unsafe {
+            // A null output lets the compositor choose the output.
+            let output = data
+                .outputs
+                .iter()
+                .find(|(_, output)| Some(output.screen().id) == screen_id)
+                .map_or(null_mut(), |(output, _)| *output);
+            let window = data.windows.get_mut(&surface_from_id(window_id))?;
+            let old_screen = std::mem::replace(&mut window.fullscreen_screen, screen_id);
+            data.connection.request(
+                window.toplevel,
+                XDG_TOPLEVEL_SET_FULLSCREEN,
+                &mut [wl_argument { o: output }],
+            );
+            data.connection.flush();
+
+            // The compositor's configure only says the window is still fullscreen.
+            if window.fullscreen && old_screen != screen_id {
+                Some(Event::WindowFullscreened {
+                    fullscreen: window.borderless_fullscreen(&data.outputs),
+                    window_id,
+                })
+            } else {
+                None
+            }
+        });
+        if let Some(event) = event {
+            event_receiver::send_event(event);
+        }
+    }
+
+    fn restore_window(&mut self, window_id: WindowId) {
+        // There is no way to restore a minimized window on Wayland.
+        toplevel_request(window_id, XDG_TOPLEVEL_UNSET_FULLSCREEN, &mut []);
+        toplevel_request(window_id, XDG_TOPLEVEL_UNSET_MAXIMIZED, &mut []);
+    }
+
+    fn close_window(&mut self, window_id: WindowId) {
+        with_data(|data| unsafe {
+            let surface = surface_from_id(window_id);
+            if let Some(window) = data.windows.remove(&surface) {
+                destroy_window(&data.connection, window);
+                data.connection.flush();
+            }
+            if data.keyboard_state.focused_window == Some(surface) {
+                data.keyboard_state.focused_window = None;
+            }
+            if data.pointer_state.surface == Some(surface) {
+                data.pointer_state.surface = None;
+            }
+        })
+    }
+
+    fn get_window_size(&mut self, window_id: WindowId) -> (u32, u32) {
+        with_data(|data| {
+            data.windows
+                .get(&surface_from_id(window_id))
+                .map_or((0, 0), |window| {
+                    (
+                        (window.width * window.scale) as u32,
+                        (window.height * window.scale) as u32,
+                    )
+                })
+        })
+    }
+
+    fn get_window_scale(&mut self, window_id: WindowId) -> f64 {
+        with_data(|data| {
+            data.windows
+                .get(&surface_from_id(window_id))
+                .map_or(1.0, |window| window.scale as f64)
+        })
+    }
+
+    fn get_window_position(&mut self, _window_id: WindowId) -> (u32, u32) {
+        // Wayland clients can't know where their windows are.
+        (0, 0)
+    }
+
+    fn is_window_minimized(&mut self, _window_id: WindowId) -> bool {
+        // The compositor doesn't tell clients when a window is minimized.
+        false
+    }
+
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        with_data(|data| {
+            let window = data.windows.get(&surface_from_id(window_id));
+            window.is_some_and(|window| window.maximized)
+        })
+    }
+
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        with_data(|data| match data.windows.get(&surface_from_id(window_id)) {
+            Some(window) if window.fullscreen => window.borderless_fullscreen(&data.outputs),
+            _ => Fullscreen::Off,
+        })
+    }
+
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        with_data(|data| data.keyboard_state.focused_window == Some(surface_from_id(window_id)))
+    }
+
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        // Surfaces are shown once their first configure is acknowledged.
+        with_data(|data| {
+            let window = data.windows.get(&surface_from_id(window_id));
+            window.is_some_and(|window| window.configured)
+        })
+    }
+
+    fn screens(&mut self) -> Vec<Screen> {
+        with_data(|data| {
+            // Outputs have no primary, so they're listed in the order they were announced.
+            let mut outputs: Vec<&Output> = data.outputs.values().collect();
+            outputs.sort_by_key(|output| output.registry_name);
+            outputs
+                .iter()
+                .filter_map(|output| output.reported.clone())
+                .collect()
+        })
+    }
+
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        with_data(|data| {
+            // The compositor doesn't say where windows are, only which outputs they're on.
+            let window = data.windows.get(&surface_from_id(window_id))?;
+            let output = data.outputs.get(window.outputs.first()?)?;
+            Some(output.screen().id)
+        })
+    }
+
+    fn redraw_window(&mut self, window_id: WindowId) {
+        redraw_manager::add_draw_request(window_id);
+    }
+
+    fn lock_mouse_position(&mut self) {
+        with_data(|data| unsafe {
+            let connection = &data.connection;
+            let pointer_constraints = connection.globals.pointer_constraints;
+            if pointer_constraints.is_null() || !data.pointer_state.locked_pointer.is_null() {
+                return;
+            }
+            // The pointer can only be locked to the window it's over.
+            let surface = match data.pointer_state.surface {
+                Some(surface) if data.windows.contains_key(&surface) => surface,
+                _ => return,
+            };
+            data.pointer_state.locked_pointer = connection.create(
+                pointer_constraints,
+                ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER,
+                &mut [
+                    wl_argument { n: 0 },
+                    wl_argument { o: surface },
+                    wl_argument { o: data.pointer },
+                    wl_argument { o: null_mut() },
+                    wl_argument {
+                        u: ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT,
+                    },
+                ],
+                &ZWP_LOCKED_POINTER_V1_INTERFACE.0,
+            );
+            connection.flush();
+        })
+    }
+
+    fn unlock_mouse_position(&mut self) {
+        with_data(|data| unsafe {
+            data.connection.destroy(
+                data.pointer_state.locked_pointer,
+                ZWP_LOCKED_POINTER_V1_DESTROY,
+            );
+            data.pointer_state.locked_pointer = null_mut();
+            data.connection.flush();
+        })
+    }
+
+    fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
+        with_data(|data| unsafe {
+            let connection = &data.connection;
+            let globals = &connection.globals;
+
+            // TODO: The size is treated as logical pixels because the window's scale
+            // is not known until it's placed on an output.
+            let (width, height) = window_parameters.size.unwrap_or((500, 500));
+
+            let surface = connection.create_surface();
+            add_dispatcher(&connection.wayland, surface, &ProxyKind::Surface);
+
+            let xdg_surface = connection.create(
+                globals.wm_base,
+                XDG_WM_BASE_GET_XDG_SURFACE,
+                &mut [wl_argument { n: 0 }, wl_argument { o: surface }],
+                &XDG_SURFACE_INTERFACE.0,
+            );
+            add_dispatcher(&connection.wayland, xdg_surface, &ProxyKind::XdgSurface);
+
+            let toplevel = connection.create(
+                xdg_surface,
+                XDG_SURFACE_GET_TOPLEVEL,
+                &mut [wl_argument { n: 0 }],
+                &XDG_TOPLEVEL_INTERFACE.0,
+            );
+            add_dispatcher(&connection.wayland, toplevel, &ProxyKind::Toplevel);
+
+            set_title(connection, toplevel, &window_parameters.title);
+
+            // The app id lets the desktop associate the window with the program.
+            if let Some(app_id) = std::env::current_exe()
+                .ok()
+                .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+                .and_then(|name| CString::new(name).ok())
+            {
+                connection.request(
+                    toplevel,
+                    XDG_TOPLEVEL_SET_APP_ID,
+                    &mut [wl_argument { s: app_id.as_ptr() }],
+                );
+            }
+
+            // Ask the compositor to draw decorations.
+            // If it can't then decorations are drawn by kapp.
+            // Windows without a titlebar ask the compositor not to draw them.
+            let decorations = !window_parameters.without_titlebar;
+            let decoration = if globals.decoration_manager.is_null() {
+                null_mut()
+            } else {
+                let decoration = connection.create(
+                    globals.decoration_manager,
+                    ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION,
+                    &mut [wl_argument { n: 0 }, wl_argument { o: toplevel }],
+                    &ZXDG_TOPLEVEL_DECORATION_V1_INTERFACE.0,
+                );
+                add_dispatcher(&connection.wayland, decoration, &ProxyKind::Decoration);
+                let mode = if decorations {
+                    ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE
+                } else {
+                    ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE
+                };
+                connection.request(
+                    decoration,
+                    ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE,
+                    &mut [wl_argument { u: mode }],
+                );
+                decoration
+            };
+
+            data.windows.insert(
+                surface,
+                WindowData {
+                    surface,
+                    xdg_surface,
+                    toplevel,
+                    decoration,
+                    client_decorations: None,
+                    decorations,
+                    minimum_size: window_parameters.minimum_size,
+                    maximum_size: window_parameters.maximum_size,
+                    resizable: window_parameters.resizable,
+                    width: width as i32,
+                    height: height as i32,
+                    pending_size: (0, 0),
+                    pending_states: Vec::new(),
+                    configured: false,
+                    maximized: false,
+                    fullscreen: false,
+                    fullscreen_screen: None,
+                    resizing: false,
+                    scale: 1,
+                    outputs: Vec::new(),
+                },
+            );
+            if decoration.is_null() && decorations {
+                data.add_client_decorations(surface);
+            }
+            let connection = &data.connection;
+            connection.update_size_limits(&data.windows[&surface]);
+
+            // Committing without a buffer asks the compositor to configure the window.
+            // The window is drawn for the first time once it's configured.
+            connection.commit(surface);
+            connection.flush();
+
+            WindowId::new(surface as *mut c_void)
+        })
+    }
+
+    fn quit(&self) {
+        with_data(|data| data.actually_quit = true);
+    }
+
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        control_flow::set_control_flow(control_flow);
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        timers::add_timer(duration, repeat)
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        timers::remove_timer(timer_id);
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        with_data(|data| data.wake_pipe.proxy())
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        with_data(|data| unsafe {
+            data.cursor = cursor;
+            data.update_cursor();
+        })
+    }
+
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        with_data(|data| unsafe {
+            // Pixels are premultiplied ARGB.
+            let buffer = data
+                .connection
+                .create_shm_buffer(width as i32, height as i32, |pixels| {
+                    for (pixel, rgba) in pixels.iter_mut().zip(rgba.chunks_exact(4)) {
+                        let alpha = rgba[3] as u32;
+                        let premultiply = |channel: u8| channel as u32 * alpha / 255;
+                        *pixel = alpha << 24
+                            | premultiply(rgba[0]) << 16
+                            | premultiply(rgba[1]) << 8
+                            | premultiply(rgba[2]);
+                    }
+                });
+            // If the buffer couldn't be created the arrow is used instead.
+            data.custom_cursors.push(CursorImage {
+                buffer,
+                width: width as i32,
+                height: height as i32,
+                hotspot: (hotspot.0 as i32, hotspot.1 as i32),
+            });
+            CustomCursor::new((data.custom_cursors.len() - 1) as *mut c_void)
+        })
+    }
+
+    fn hide_cursor(&mut self) {
+        with_data(|data| unsafe {
+            data.cursor_hidden = true;
+            data.update_cursor();
+        })
+    }
+
+    fn show_cursor(&mut self) {
+        with_data(|data| unsafe {
+            data.cursor_hidden = false;
+            data.update_cursor();
+        })
+    }
+
+    fn start_text_input(&mut self) {
+        with_data(|data| data.text_input_enabled = true);
+    }
+
+    fn end_text_input(&mut self) {
+        with_data(|data| data.text_input_enabled = false);
+    }
+
+    fn set_text_input_rectangle(
+        &mut self,
+        _window_id: WindowId,
+        _x: f64,
+        _y: f64,
+        _width: f64,
+        _height: f64,
+    ) {
+        // Text is produced with xkbcommon instead of an input method,
+        // so there are no input method popups to position.
+    }
+
+    fn clipboard_mime_types(&mut self, _selection: ClipboardSelection) -> Vec<String> {
+        // Clipboard access isn't implemented on Wayland yet.
+        Vec::new()
+    }
+    fn get_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+    fn set_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+        _data: &[u8],
+    ) {
+    }
+
+    fn set_drop_accepted(&mut self, _accepted: bool) {
+        // Drag and drop isn't implemented on Wayland yet.
+    }
+
+    fn raw_window_handle(&self, window_id: WindowId) -> RawWindowHandle {
+        use raw_window_handle::unix::WaylandHandle;
+        with_data(|data| {
+            RawWindowHandle::Wayland(WaylandHandle {
+                surface: surface_from_id(window_id) as *mut c_void,
+                display: data.connection.display as *mut c_void,
+                ..WaylandHandle::empty()
+            })
+        })
+    }
+
+    fn inject_event(&mut self, event: Event) {
+        event_receiver::send_event(event);
+    }
+}
+
+unsafe fn set_title(connection: &Connection, toplevel: *mut wl_proxy, title: &str) {
+    // C strings end at the first NUL, so any NULs in the title are removed.
+    let c_string = CString::new(title.replace('\0', "")).unwrap();
+    connection.request(
+        toplevel,
+        XDG_TOPLEVEL_SET_TITLE,
+        &mut [wl_argument {
+            s: c_string.as_ptr(),
+        }],
+    );
+}
+
+/// Changes a window's size limits and sends them to the compositor.
+fn update_size_limits(window_id: WindowId, change: impl FnOnce(&mut WindowData)) {
+    with_data(|data| unsafe {
+        if let Some(window) = data.windows.get_mut(&surface_from_id(window_id)) {
+            change(window);
+            data.connection.update_size_limits(window);
+            data.connection.flush();
+        }
+    })
+}
+
+fn toplevel_request(window_id: WindowId, opcode: u32, arguments: &mut [wl_argument]) {
+    with_data(|data| unsafe {
+        if let Some(window) = data.windows.get(&surface_from_id(window_id)) {
+            data.connection.request(window.toplevel, opcode, arguments);
+            data.connection.flush();
+        }
+    })
+}
+
+// When the application is dropped, disconnect from the compositor.
+impl Drop for PlatformApplication {
+    fn drop(&mut self) {
+        // try_with because the thread local may already be destroyed.
+        let _ = APPLICATION_DATA.try_with(|d| d.borrow_mut().take());
+    }
+}
+
+pub struct PlatformEventLoop {}
+
+impl PlatformEventLoopTrait for PlatformEventLoop {
+    fn run(&self, callback: Box<dyn FnMut(kapp_platform_common::Event)>) {
+        super::event_loop_wayland::run(callback);
+    }
+}
@@ -0,0 +1,156 @@
+use super::application_wayland::Connection;
+use super::external_wayland::*;
+
+use std::ptr::null_mut;
+
+/// Height of the title bar in logical pixels.
+pub const TITLEBAR_HEIGHT: i32 = 24;
+
+const TITLEBAR_COLOR: u32 = 0xFF2E2E2E;
+const CLOSE_BUTTON_COLOR: u32 = 0xFFDDDDDD;
+
+/// A minimal title bar drawn by kapp for compositors that do not draw decorations,
+/// like GNOME's.
+/// The title bar is a subsurface placed above the window's surface.
+/// It can be dragged to move the window and has a close button on its right side.
+pub(crate) struct ClientDecorations {
+    pub surface: *mut wl_proxy,
+    subsurface: *mut wl_proxy,
+    buffer: *mut wl_proxy,
+    /// The size of the buffer in physical pixels.
+    buffer_size: (i32, i32),
+    hidden: bool,
+}
+
+impl ClientDecorations {
+    pub unsafe fn new(connection: &Connection, parent: *mut wl_proxy) -> Self {
+        let surface = connection.create_surface();
+        let subsurface = connection.create(
+            connection.globals.subcompositor,
+            WL_SUBCOMPOSITOR_GET_SUBSURFACE,
+            &mut [
+                wl_argument { n: 0 },
+                wl_argument { o: surface },
+                wl_argument { o: parent },
+            ],
+            connection.wayland.wl_subsurface_interface,
+        );
+        connection.request(
+            subsurface,
+            WL_SUBSURFACE_SET_POSITION,
+            &mut [
+                wl_argument { i: 0 },
+                wl_argument {
+                    i: -TITLEBAR_HEIGHT,
+                },
+            ],
+        );
+        // Let the title bar update without waiting for the window to be committed.
+        connection.request(subsurface, WL_SUBSURFACE_SET_DESYNC, &mut []);
+        Self {
+            surface,
+            subsurface,
+            buffer: null_mut(),
+            buffer_size: (0, 0),
+            hidden: false,
+        }
+    }
+
+    /// Redraws the title bar for the window's width in logical pixels.
+    /// Fullscreen windows do not have a title bar.
+    pub unsafe fn update(&mut self, connection: &Connection, width: i32, scale: i32, hidden: bool) {
+        let buffer_size = (width * scale, TITLEBAR_HEIGHT * scale);
+        if hidden {
+            if !self.hidden {
+                // Removing the buffer hides the surface.
+                self.attach(connection, null_mut(), 0, 0);
+                self.hidden = true;
+            }
+            return;
+        }
+        if !self.hidden && buffer_size == self.buffer_size && !self.buffer.is_null() {
+            return;
+        }
+        self.hidden = false;
+
+        let buffer = draw_titlebar(connection, buffer_size.0, buffer_size.1, scale);
+        if buffer.is_null() {
+            return;
+        }
+        connection.set_buffer_scale(self.surface, scale);
+        self.attach(connection, buffer, buffer_size.0, buffer_size.1);
+        connection.destroy(self.buffer, WL_BUFFER_DESTROY);
+        self.buffer = buffer;
+        self.buffer_size = buffer_size;
+    }
+
+    unsafe fn attach(
+        &self,
+        connection: &Connection,
+        buffer: *mut wl_proxy,
+        width: i32,
+        height: i32,
+    ) {
+        connection.request(
+            self.surface,
+            WL_SURFACE_ATTACH,
+            &mut [
+                wl_argument { o: buffer },
+                wl_argument { i: 0 },
+                wl_argument { i: 0 },
+            ],
+        );
+        connection.request(
+            self.surface,
+            WL_SURFACE_DAMAGE,
+            &mut [
+                wl_argument { i: 0 },
+                wl_argument { i: 0 },
+                wl_argument { i: width },
+                wl_argument { i: height },
+            ],
+        );
+        connection.commit(self.surface);
+    }
+
+    /// Is a position on the title bar, in logical pixels, over the close button?
+    pub fn close_button_contains(&self, width: i32, x: f64) -> bool {
+        x >= (width - TITLEBAR_HEIGHT) as f64
+    }
+
+    pub unsafe fn destroy(self, connection: &Connection) {
+        connection.destroy(self.subsurface, WL_SUBSURFACE_DESTROY);
+        connection.destroy(self.surface, WL_SURFACE_DESTROY);
+        connection.destroy(self.buffer, WL_BUFFER_DESTROY);
+    }
+}
+
+/// Draws the title bar into a new shared memory buffer.
+/// Returns null if the buffer could not be created.
+unsafe fn draw_titlebar(
+    connection: &Connection,
+    width: i32,
+    height: i32,
+    scale: i32,
+) -> *mut wl_proxy {
+    connection.create_shm_buffer(width, height, |pixels| {
+        for pixel in pixels.iter_mut() {
+            *pixel = TITLEBAR_COLOR;
+        }
+
+        // Draw an 'x' for the close button in the rightmost square.
+        let button_left = width - height;
+        let padding = height / 3;
+        let thickness = scale.max(1);
+        for y in padding..height - padding {
+            for x in padding..height - padding {
+                if (x - y).abs() < thickness || (x + y - (height - 1)).abs() < thickness {
+                    let index = y * width + button_left + x;
+                    if index >= 0 && button_left + x >= 0 {
+                        pixels[index as usize] = CLOSE_BUTTON_COLOR;
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,1356 @@
+use super::application_wayland::{with_data, ApplicationData, MouseClick, Output, RepeatingKey};
+use super::external_wayland::*;
+use super::protocols_wayland::*;
+use crate::linux::keys_linux::{evdev_code_to_key, keysym_to_character};
+use kapp_platform_common::*;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::CStr;
+use std::ptr::{null, null_mut};
+use std::time::{Duration, Instant};
+
+// Wayland does not report double clicks, so they're detected here.
+// 500 milliseconds is the default double click time on most desktops.
+const DOUBLE_CLICK_TIME: u32 = 500;
+
+/// The kind of object a proxy is, which determines how its events are read.
+pub(crate) enum ProxyKind {
+    Registry,
+    WmBase,
+    XdgSurface,
+    Toplevel,
+    Decoration,
+    Surface,
+    Seat,
+    Output,
+    Pointer,
+    Keyboard,
+    RelativePointer,
+}
+
+/// Events from the compositor, copied out of libwayland's dispatch.
+/// They're queued and processed later so that libwayland never calls into
+/// code that borrows the application data.
+pub(crate) enum Message {
+    Global {
+        name: u32,
+        interface: String,
+        version: u32,
+    },
+    GlobalRemove {
+        name: u32,
+    },
+    Ping {
+        serial: u32,
+    },
+    XdgSurfaceConfigure {
+        xdg_surface: *mut wl_proxy,
+        serial: u32,
+    },
+    ToplevelConfigure {
+        toplevel: *mut wl_proxy,
+        width: i32,
+        height: i32,
+        states: Vec<u32>,
+    },
+    ToplevelClose {
+        toplevel: *mut wl_proxy,
+    },
+    DecorationConfigure {
+        decoration: *mut wl_proxy,
+        mode: u32,
+    },
+    SurfaceEnter {
+        surface: *mut wl_proxy,
+        output: *mut wl_proxy,
+    },
+    SurfaceLeave {
+        surface: *mut wl_proxy,
+        output: *mut wl_proxy,
+    },
+    SeatCapabilities {
+        capabilities: u32,
+    },
+    OutputGeometry {
+        output: *mut wl_proxy,
+        x: i32,
+        y: i32,
+        make: String,
+        model: String,
+        transform: i32,
+    },
+    OutputMode {
+        output: *mut wl_proxy,
+        flags: u32,
+        width: i32,
+        height: i32,
+        refresh: i32,
+    },
+    OutputDone {
+        output: *mut wl_proxy,
+    },
+    OutputScale {
+        output: *mut wl_proxy,
+        scale: i32,
+    },
+    PointerEnter {
+        serial: u32,
+        surface: *mut wl_proxy,
+        x: f64,
+        y: f64,
+    },
+    PointerLeave {
+        surface: *mut wl_proxy,
+    },
+    PointerMotion {
+        time: u32,
+        x: f64,
+        y: f64,
+    },
+    PointerButton {
+        serial: u32,
+        time: u32,
+        button: u32,
+        pressed: bool,
+    },
+    PointerAxis {
+        time: u32,
+        axis: u32,
+        value: f64,
+    },
+    RelativeMotion {
+        timestamp: Duration,
+        delta_x: f64,
+        delta_y: f64,
+    },
+    Keymap {
+        format: u32,
+        fd: i32,
+        size: u32,
+    },
+    KeyboardEnter {
+        surface: *mut wl_proxy,
+    },
+    KeyboardLeave {
+        surface: *mut wl_proxy,
+    },
+    Key {
+        time: u32,
+        code: u32,
+        pressed: bool,
+    },
+    Modifiers {
+        depressed: u32,
+        latched: u32,
+        locked: u32,
+        group: u32,
+    },
+    RepeatInfo {
+        rate: i32,
+        delay: i32,
+    },
+}
+
+thread_local!(static MESSAGES: RefCell<VecDeque<Message>> = const { RefCell::new(VecDeque::new()) });
+
+fn next_message() -> Option<Message> {
+    MESSAGES.with(|m| m.borrow_mut().pop_front())
+}
+
+fn messages_pending() -> bool {
+    MESSAGES.with(|m| !m.borrow().is_empty())
+}
+
+/// Routes a proxy's events to `dispatch`.
+pub(crate) unsafe fn add_dispatcher(
+    wayland: &WaylandClient,
+    proxy: *mut wl_proxy,
+    kind: &'static ProxyKind,
+) {
+    (wayland.wl_proxy_add_dispatcher)(
+        proxy,
+        dispatch,
+        kind as *const ProxyKind as *const c_void,
+        null_mut(),
+    );
+}
+
+// Event opcodes are from the protocol descriptions listed in external_wayland.rs
+// and protocols_wayland.rs
+unsafe extern "C" fn dispatch(
+    kind: *const c_void,
+    proxy: *mut c_void,
+    opcode: u32,
+    _message: *const wl_message,
+    arguments: *mut wl_argument,
+) -> c_int {
+    let kind = &*(kind as *const ProxyKind);
+    let proxy = proxy as *mut wl_proxy;
+    let argument = |i: usize| *arguments.add(i);
+
+    let message = match (kind, opcode) {
+        (ProxyKind::Registry, 0) => Message::Global {
+            name: argument(0).u,
+            interface: CStr::from_ptr(argument(1).s).to_string_lossy().into_owned(),
+            version: argument(2).u,
+        },
+        (ProxyKind::Registry, 1) => Message::GlobalRemove {
+            name: argument(0).u,
+        },
+        (ProxyKind::WmBase, 0) => Message::Ping {
+            serial: argument(0).u,
+        },
+        (ProxyKind::XdgSurface, 0) => Message::XdgSurfaceConfigure {
+            xdg_surface: proxy,
+            serial: argument(0).u,
+        },
+        (ProxyKind::Toplevel, 0) => {
+            let array = &*argument(2).a;
+            let states =
+                std::slice::from_raw_parts(array.data as *const u32, array.size / 4).to_vec();
+            Message::ToplevelConfigure {
+                toplevel: proxy,
+                width: argument(0).i,
+                height: argument(1).i,
+                states,
+            }
+        }
+        (ProxyKind::Toplevel, 1) => Message::ToplevelClose { toplevel: proxy },
+        (ProxyKind::Decoration, 0) => Message::DecorationConfigure {
+            decoration: proxy,
+            mode: argument(0).u,
+        },
+        (ProxyKind::Surface, 0) => Message::SurfaceEnter {
+            surface: proxy,
+            output: argument(0).o,
+        },
+        (ProxyKind::Surface, 1) => Message::SurfaceLeave {
+            surface: proxy,
+            output: argument(0).o,
+        },
+        (ProxyKind::Seat, 0) => Message::SeatCapabilities {
+            capabilities: argument(0).u,
+        },
+        (ProxyKind::Output, 0) => Message::OutputGeometry {
+            output: proxy,
+            x: argument(0).i,
+            y: argument(1).i,
+            make: CStr::from_ptr(argument(5).s).to_string_lossy().into_owned(),
+            model: CStr::from_ptr(argument(6).s).to_string_lossy().into_owned(),
+            transform: argument(7).i,
+        },
+        (ProxyKind::Output, 1) => Message::OutputMode {
+            output: proxy,
+            flags: argument(0).u,
+            width: argument(1).i,
+            height: argument(2).i,
+            refresh: argument(3).i,
+        },
+        (ProxyKind::Output, 2) => Message::OutputDone { output: proxy },
+        (ProxyKind::Output, 3) => Message::OutputScale {
+            output: proxy,
+            scale: argument(0).i,
+        },
+        (ProxyKind::Pointer, 0) => Message::PointerEnter {
+            serial: argument(0).u,
+            surface: argument(1).o,
+            x: wl_fixed_to_f64(argument(2).f),
+            y: wl_fixed_to_f64(argument(3).f),
+        },
+        (ProxyKind::Pointer, 1) => Message::PointerLeave {
+            surface: argument(1).o,
+        },
+        (ProxyKind::Pointer, 2) => Message::PointerMotion {
+            time: argument(0).u,
+            x: wl_fixed_to_f64(argument(1).f),
+            y: wl_fixed_to_f64(argument(2).f),
+        },
+        (ProxyKind::Pointer, 3) => Message::PointerButton {
+            serial: argument(0).u,
+            time: argument(1).u,
+            button: argument(2).u,
+            pressed: argument(3).u == WL_POINTER_BUTTON_STATE_PRESSED,
+        },
+        (ProxyKind::Pointer, 4) => Message::PointerAxis {
+            time: argument(0).u,
+            axis: argument(1).u,
+            value: wl_fixed_to_f64(argument(2).f),
+        },
+        (ProxyKind::RelativePointer, 0) => {
+            let microseconds = ((argument(0).u as u64) << 32) | argument(1).u as u64;
+            Message::RelativeMotion {
+                timestamp: Duration::from_micros(microseconds),
+                delta_x: wl_fixed_to_f64(argument(2).f),
+                delta_y: wl_fixed_to_f64(argument(3).f),
+            }
+        }
+        (ProxyKind::Keyboard, 0) => Message::Keymap {
+            format: argument(0).u,
+            fd: argument(1).h,
+            size: argument(2).u,
+        },
+        (ProxyKind::Keyboard, 1) => Message::KeyboardEnter {
+            surface: argument(1).o,
+        },
+        (ProxyKind::Keyboard, 2) => Message::KeyboardLeave {
+            surface: argument(1).o,
+        },
+        (ProxyKind::Keyboard, 3) => Message::Key {
+            time: argument(1).u,
+            code: argument(2).u,
+            pressed: argument(3).u == WL_KEYBOARD_KEY_STATE_PRESSED,
+        },
+        (ProxyKind::Keyboard, 4) => Message::Modifiers {
+            depressed: argument(1).u,
+            latched: argument(2).u,
+            locked: argument(3).u,
+            group: argument(4).u,
+        },
+        (ProxyKind::Keyboard, 5) => Message::RepeatInfo {
+            rate: argument(0).i,
+            delay: argument(1).i,
+        },
+        _ => return 0,
+    };
+    MESSAGES.with(|m| m.borrow_mut().push_back(message));
+    0
+}
+
+pub fn run(callback: Box<dyn FnMut(Event)>) {
+    event_receiver::set_callback(callback);
+
+    let mut events = Vec::new();
+    loop {
+        // Wait for a new event if we don't have any redraw requests
+        let wait = redraw_manager::draw_requests_count() == 0;
+        with_data(|data| unsafe { read_messages(data, wait) });
+
+        // Process all messages.
+        // The borrow is released between messages so the user callback can use the application.
+        while let Some(message) = next_message() {
+            with_data(|data| unsafe { process_message(data, message, &mut events) });
+            send_events(&mut events);
+        }
+        with_data(|data| repeat_key(data, &mut events));
+        send_events(&mut events);
+        event_loop_proxy::send_proxy_events();
+        control_flow::resume_if_reached();
+        timers::send_due_timers();
+
+        // When there are no events remaining, we're at the end of the event loop
+        event_receiver::send_event(Event::EventsCleared);
+
+        if with_data(|data| data.actually_quit) {
+            event_receiver::send_event(Event::Quit);
+            break;
+        }
+
+        // Send a draw event for each window that needs to be drawn.
+        redraw_manager::begin_draw_flush();
+        while let Some(window_id) = redraw_manager::get_draw_request() {
+            event_receiver::send_event(Event::Draw { window_id });
+        }
+
+        // Send any requests made by the user's code to the compositor.
+        with_data(|data| unsafe { data.connection.flush() });
+    }
+}
+
+fn send_events(events: &mut Vec<Event>) {
+    for event in events.drain(..) {
+        event_receiver::send_event(event);
+    }
+}
+
+/// Reads messages from the compositor, waiting for them if `wait` is true.
+unsafe fn read_messages(data: &mut ApplicationData, wait: bool) {
+    let connection = &data.connection;
+    let wayland = &connection.wayland;
+    let display = connection.display;
+
+    while (wayland.wl_display_prepare_read)(display) != 0 {
+        (wayland.wl_display_dispatch_pending)(display);
+    }
+    connection.flush();
+
+    // Held keys are repeated by the client, so waiting stops at the next repeat.
+    let repeat_timeout = data
+        .keyboard_state
+        .repeating_key
+        .as_ref()
+        .map(|repeating_key| {
+            repeating_key
+                .next_repeat
+                .saturating_duration_since(Instant::now())
+        });
+    let timeout = if !wait || messages_pending() || event_loop_proxy::proxy_events_pending() {
+        0
+    } else {
+        match (repeat_timeout, control_flow::timeout()) {
+            (Some(a), Some(b)) => control_flow::timeout_milliseconds(a.min(b)),
+            (Some(timeout), None) | (None, Some(timeout)) => {
+                control_flow::timeout_milliseconds(timeout)
+            }
+            (None, None) => -1,
+        }
+    };
+
+    // Proxies write to the wake pipe to stop the wait.
+    let mut poll_fds = [
+        pollfd {
+            fd: (wayland.wl_display_get_fd)(display),
+            events: POLLIN,
+            revents: 0,
+        },
+        pollfd {
+            fd: data.wake_pipe.read_fd(),
+            events: POLLIN,
+            revents: 0,
+        },
+    ];
+    if poll(poll_fds.as_mut_ptr(), poll_fds.len() as u64, timeout) > 0
+        && poll_fds[0].revents & POLLIN != 0
+    {
+        (wayland.wl_display_read_events)(display);
+    } else {
+        (wayland.wl_display_cancel_read)(display);
+    }
+    data.wake_pipe.clear();
+    (wayland.wl_display_dispatch_pending)(display);
+
+    // The connection is unusable after a protocol error or if the compositor exits.
+    if (wayland.wl_display_get_error)(display) != 0 {
+        data.actually_quit = true;
+    }
+}
+
+/// Processes every queued message. Used during initialization.
+pub(crate) fn process_messages(data: &mut ApplicationData, events: &mut Vec<Event>) {
+    while let Some(message) = next_message() {
+        unsafe { process_message(data, message, events) };
+    }
+}
+
+fn button_to_pointer_button(button: u32) -> PointerButton {
+    match button {
+        BTN_LEFT => PointerButton::Primary,
+        BTN_MIDDLE => PointerButton::Auxillary,
+        BTN_RIGHT => PointerButton::Secondary,
+        BTN_SIDE => PointerButton::Extra1,
+        BTN_EXTRA => PointerButton::Extra2,
+        _ => PointerButton::Unknown,
+    }
+}
+
+unsafe fn process_message(data: &mut ApplicationData, message: Message, events: &mut Vec<Event>) {
+    match message {
+        Message::Global {
+            name,
+            interface,
+            version,
+        } => bind_global(data, name, &interface, version),
+        Message::GlobalRemove { name } => {
+            let output = data
+                .outputs
+                .iter()
+                .find(|(_, output)| output.registry_name == name)
+                .map(|(proxy, _)| *proxy);
+            if let Some(output) = output {
+                if let Some(screen) = data.outputs.remove(&output).and_then(|o| o.reported) {
+                    events.push(Event::ScreenRemoved {
+                        screen_id: screen.id,
+                    });
+                }
+                let surfaces: Vec<_> = data.windows.keys().copied().collect();
+                for surface in surfaces {
+                    let window = data.windows.get_mut(&surface).unwrap();
+                    window.outputs.retain(|o| *o != output);
+                    update_window_scale(data, surface, events);
+                }
+                (data.connection.wayland.wl_proxy_destroy)(output);
+            }
+        }
+        Message::Ping { serial } => {
+            data.connection.request(
+                data.connection.globals.wm_base,
+                XDG_WM_BASE_PONG,
+                &mut [wl_argument { u: serial }],
+            );
+        }
+        Message::ToplevelConfigure {
+            toplevel,
+            width,
+            height,
+            states,
+        } => {
+            if let Some(window) = data.windows.values_mut().find(|w| w.toplevel == toplevel) {
+                window.pending_size = (width, height);
+                window.pending_states = states;
+            }
+        }
+        Message::XdgSurfaceConfigure {
+            xdg_surface,
+            serial,
+        } => configure_window(data, xdg_surface, serial, events),
+        Message::ToplevelClose { toplevel } => {
+            if let Some(window) = data.windows.values().find(|w| w.toplevel == toplevel) {
+                events.push(Event::WindowCloseRequested {
+                    window_id: window.window_id(),
+                });
+            }
+        }
+        Message::DecorationConfigure { decoration, mode } => {
+            let surface = data
+                .windows
+                .values()
+                .find(|w| w.decoration == decoration)
+                .map(|w| w.surface);
+            if let Some(surface) = surface {
+                if mode == ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE {
+                    data.remove_client_decorations(surface);
+                } else {
+                    data.add_client_decorations(surface);
+                }
+            }
+        }
+        Message::SurfaceEnter { surface, output } => {
+            if let Some(window) = data.windows.get_mut(&surface) {
+                window.outputs.push(output);
+                update_window_scale(data, surface, events);
+            }
+        }
+        Message::SurfaceLeave { surface, output } => {
+            if let Some(window) = data.windows.get_mut(&surface) {
+                window.outputs.retain(|o| *o != output);
+                update_window_scale(data, surface, events);
+            }
+        }
+        Message::OutputGeometry {
+            output,
+            x,
+            y,
+            make,
+            model,
+            transform,
+        } => {
+            if let Some(o) = data.outputs.get_mut(&output) {
+                o.name = format!("{} {}", make, model);
+                o.position = (x, y);
+                o.transform = transform;
+            }
+        }
+        Message::OutputMode {
+            output,
+            flags,
+            width,
+            height,
+            refresh,
+        } => {
+            if let Some(o) = data.outputs.get_mut(&output) {
+                if flags & WL_OUTPUT_MODE_CURRENT != 0 {
+                    o.size = (width as u32, height as u32);
+                    // The refresh rate is in millihertz.
+                    o.refresh_rate = if refresh > 0 {
+                        Some(refresh as f64 / 1000.0)
+                    } else {
+                        None
+                    };
+                }
+            }
+        }
+        Message::OutputDone { output } => {
+            if let Some(o) = data.outputs.get_mut(&output) {
+                let screen = o.screen();
+                match &o.reported {
+                    None => events.push(Event::ScreenAdded {
+                        screen_id: screen.id,
+                    }),
+                    Some(reported) if *reported != screen => events.push(Event::ScreenChanged {
+                        screen_id: screen.id,
+                    }),
+                    _ => {}
+                }
+                o.reported = Some(screen);
+            }
+        }
+        Message::OutputScale { output, scale } => {
+            if let Some(o) = data.outputs.get_mut(&output) {
+                o.scale = scale;
+            }
+            let surfaces: Vec<_> = data
+                .windows
+                .values()
+                .filter(|w| w.outputs.contains(&output))
+                .map(|w| w.surface)
+                .collect();
+            for surface in surfaces {
+                update_window_scale(data, surface, events);
+            }
+        }
+        Message::SeatCapabilities { capabilities } => {
+            update_seat(data, capabilities);
+        }
+        Message::PointerEnter {
+            serial,
+            surface,
+            x,
+            y,
+        } => {
+            // The surface is null if it was destroyed before the event was read.
+            if surface.is_null() {
+                return;
+            }
+            let pointer = &mut data.pointer_state;
+            pointer.surface = Some(surface);
+            pointer.enter_serial = serial;
+            pointer.x = x;
+            pointer.y = y;
+            data.update_cursor();
+        }
+        Message::PointerLeave { surface } => {
+            if data.pointer_state.surface == Some(surface) {
+                data.pointer_state.surface = None;
+            }
+        }
+        Message::PointerMotion { time, x, y } => {
+            let pointer = &mut data.pointer_state;
+            let (last_x, last_y) = (pointer.x, pointer.y);
+            pointer.x = x;
+            pointer.y = y;
+            let surface = pointer.surface;
+            let has_relative_pointer = !pointer.relative_pointer.is_null();
+            let window = match surface.and_then(|s| data.windows.get(&s)) {
+                Some(window) => window,
+                None => return,
+            };
+            let scale = window.scale as f64;
+            let timestamp = Duration::from_millis(time as u64);
+
+            // Without relative pointer events motion is calculated from the position.
+            if !has_relative_pointer {
+                events.push(Event::MouseMotion {
+                    delta_x: (x - last_x) * scale,
+                    delta_y: (y - last_y) * scale,
+                    window_id: window.window_id(),
+                    timestamp,
+                });
+            }
+            events.push(Event::PointerMoved {
+                x: x * scale,
+                y: y * scale,
+                source: PointerSource::Mouse,
+                pointer_id: 0,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: window.window_id(),
+                timestamp,
+            });
+        }
+        Message::RelativeMotion {
+            timestamp,
+            delta_x,
+            delta_y,
+        } => {
+            let window = match data
+                .pointer_state
+                .surface
+                .and_then(|s| data.window_for_surface(s))
+            {
+                Some(window) => window,
+                None => return,
+            };
+            let scale = window.scale as f64;
+            events.push(Event::MouseMotion {
+                delta_x: delta_x * scale,
+                delta_y: delta_y * scale,
+                window_id: window.window_id(),
+                timestamp,
+            });
+        }
+        Message::PointerButton {
+            serial,
+            time,
+            button,
+            pressed,
+        } => pointer_button(data, serial, time, button, pressed, events),
+        Message::PointerAxis { time, axis, value } => {
+            let window = match data
+                .pointer_state
+                .surface
+                .and_then(|s| data.windows.get(&s))
+            {
+                Some(window) => window,
+                None => return,
+            };
+            // Axis values are in the same units as pointer motion, about 10 per wheel step.
+            // Positive values scroll down, which is the opposite of kapp's convention.
+            let (delta_x, delta_y) = if axis == WL_POINTER_AXIS_VERTICAL_SCROLL {
+                (0., -value / 10.)
+            } else {
+                (value / 10., 0.)
+            };
+            events.push(Event::Scroll {
+                delta_x,
+                delta_y,
+                window_id: window.window_id(),
+                modifiers: data.keyboard_state.modifiers,
+                timestamp: Duration::from_millis(time as u64),
+            });
+        }
+        Message::Keymap { format, fd, size } => {
+            if format == WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1 {
+                load_keymap(data, fd, size as usize);
+            }
+            close(fd);
+        }
+        Message::KeyboardEnter { surface } => {
+            if let Some(window) = data.windows.get(&surface) {
+                data.keyboard_state.focused_window = Some(surface);
+                events.push(Event::WindowGainedFocus {
+                    window_id: window.window_id(),
+                });
+            }
+        }
+        Message::KeyboardLeave { surface } => {
+            let keyboard = &mut data.keyboard_state;
+            // Key releases will not be received while the window is unfocused.
+            keyboard.held_keys.clear();
+            keyboard.repeating_key = None;
+            if keyboard.focused_window == Some(surface) {
+                keyboard.focused_window = None;
+            }
+            if let Some(window) = data.windows.get(&surface) {
+                events.push(Event::WindowLostFocus {
+                    window_id: window.window_id(),
+                });
+            }
+        }
+        Message::Key {
+            time,
+            code,
+            pressed,
+        } => key(data, time, code, pressed, events),
+        Message::Modifiers {
+            depressed,
+            latched,
+            locked,
+            group,
+        } => {
+            if let Some(xkb) = &data.xkb {
+                let state = data.keyboard_state.state;
+                if !state.is_null() {
+                    (xkb.xkb_state_update_mask)(state, depressed, latched, locked, 0, 0, group);
+                    let modifiers = state_modifiers(xkb, state);
+                    update_modifiers(data, modifiers, events);
+                }
+            }
+        }
+        Message::RepeatInfo { rate, delay } => {
+            let keyboard = &mut data.keyboard_state;
+            keyboard.repeat_rate = rate;
+            keyboard.repeat_delay = Duration::from_millis(delay.max(0) as u64);
+        }
+    }
+}
+
+unsafe fn bind_global(data: &mut ApplicationData, name: u32, interface: &str, version: u32) {
+    let wayland = &data.connection.wayland;
+    let registry = data.connection.registry;
+    let bind = |interface: *const wl_interface, max_version: u32| {
+        let version = version.min(max_version);
+        (wayland.wl_proxy_marshal_array_constructor_versioned)(
+            registry,
+            WL_REGISTRY_BIND,
+            [
+                wl_argument { u: name },
+                wl_argument {
+                    s: (*interface).name,
+                },
+                wl_argument { u: version },
+                wl_argument { n: 0 },
+            ]
+            .as_mut_ptr(),
+            interface,
+            version,
+        )
+    };
+
+    let globals = &mut data.connection.globals;
+    match interface {
+        "wl_compositor" => globals.compositor = bind(wayland.wl_compositor_interface, 4),
+        "wl_subcompositor" => globals.subcompositor = bind(wayland.wl_subcompositor_interface, 1),
+        "wl_shm" => globals.shm = bind(wayland.wl_shm_interface, 1),
+        "xdg_wm_base" => {
+            globals.wm_base = bind(&XDG_WM_BASE_INTERFACE.0, 1);
+            add_dispatcher(wayland, globals.wm_base, &ProxyKind::WmBase);
+        }
+        // Only the first seat is used.
+        "wl_seat" if globals.seat.is_null() => {
+            globals.seat = bind(wayland.wl_seat_interface, 5);
+            add_dispatcher(wayland, globals.seat, &ProxyKind::Seat);
+        }
+        "wl_output" => {
+            let output = bind(wayland.wl_output_interface, 2);
+            add_dispatcher(wayland, output, &ProxyKind::Output);
+            data.outputs.insert(
+                output,
+                Output {
+                    registry_name: name,
+                    scale: 1,
+                    name: String::new(),
+                    position: (0, 0),
+                    size: (0, 0),
+                    refresh_rate: None,
+                    transform: 0,
+                    reported: None,
+                },
+            );
+        }
+        "zxdg_decoration_manager_v1" => {
+            globals.decoration_manager = bind(&ZXDG_DECORATION_MANAGER_V1_INTERFACE.0, 1)
+        }
+        "zwp_pointer_constraints_v1" => {
+            globals.pointer_constraints = bind(&ZWP_POINTER_CONSTRAINTS_V1_INTERFACE.0, 1)
+        }
+        "zwp_relative_pointer_manager_v1" => {
+            globals.relative_pointer_manager = bind(&ZWP_RELATIVE_POINTER_MANAGER_V1_INTERFACE.0, 1)
+        }
+        _ => {}
+    }
+}
+
+/// Creates or releases the pointer and keyboard as the seat's capabilities change.
+unsafe fn update_seat(data: &mut ApplicationData, capabilities: u32) {
+    let connection = &data.connection;
+    let seat = connection.globals.seat;
+
+    let has_pointer = capabilities & WL_SEAT_CAPABILITY_POINTER != 0;
+    if has_pointer && data.pointer.is_null() {
+        data.pointer = connection.create(
+            seat,
+            WL_SEAT_GET_POINTER,
+            &mut [wl_argument { n: 0 }],
+            connection.wayland.wl_pointer_interface,
+        );
+        add_dispatcher(&connection.wayland, data.pointer, &ProxyKind::Pointer);
+
+        let relative_pointer_manager = connection.globals.relative_pointer_manager;
+        if !relative_pointer_manager.is_null() {
+            let relative_pointer = connection.create(
+                relative_pointer_manager,
+                ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER,
+                &mut [wl_argument { n: 0 }, wl_argument { o: data.pointer }],
+                &ZWP_RELATIVE_POINTER_V1_INTERFACE.0,
+            );
+            add_dispatcher(
+                &connection.wayland,
+                relative_pointer,
+                &ProxyKind::RelativePointer,
+            );
+            data.pointer_state.relative_pointer = relative_pointer;
+        }
+    } else if !has_pointer && !data.pointer.is_null() {
+        let pointer = &mut data.pointer_state;
+        connection.destroy(pointer.relative_pointer, ZWP_RELATIVE_POINTER_V1_DESTROY);
+        connection.destroy(pointer.locked_pointer, ZWP_LOCKED_POINTER_V1_DESTROY);
+        pointer.relative_pointer = null_mut();
+        pointer.locked_pointer = null_mut();
+        pointer.surface = None;
+        connection.destroy(data.pointer, WL_POINTER_RELEASE);
+        data.pointer = null_mut();
+    }
+
+    let has_keyboard = capabilities & WL_SEAT_CAPABILITY_KEYBOARD != 0;
+    if has_keyboard && data.keyboard.is_null() {
+        data.keyboard = connection.create(
+            seat,
+            WL_SEAT_GET_KEYBOARD,
+            &mut [wl_argument { n: 0 }],
+            connection.wayland.wl_keyboard_interface,
+        );
+        add_dispatcher(&connection.wayland, data.keyboard, &ProxyKind::Keyboard);
+    } else if !has_keyboard && !data.keyboard.is_null() {
+        connection.destroy(data.keyboard, WL_KEYBOARD_RELEASE);
+        data.keyboard = null_mut();
+        data.keyboard_state.held_keys.clear();
+        data.keyboard_state.repeating_key = None;
+    }
+}
+
+/// Applies the last toplevel configure once the compositor has sent all of it.
+unsafe fn configure_window(
+    data: &mut ApplicationData,
+    xdg_surface: *mut wl_proxy,
+    serial: u32,
+    events: &mut Vec<Event>,
+) {
+    let window = match data
+        .windows
+        .values_mut()
+        .find(|w| w.xdg_surface == xdg_surface)
+    {
+        Some(window) => window,
+        None => return,
+    };
+    data.connection.request(
+        xdg_surface,
+        XDG_SURFACE_ACK_CONFIGURE,
+        &mut [wl_argument { u: serial }],
+    );
+
+    let window_id = window.window_id();
+    let states = &window.pending_states;
+    let maximized = states.contains(&XDG_TOPLEVEL_STATE_MAXIMIZED);
+    let fullscreen = states.contains(&XDG_TOPLEVEL_STATE_FULLSCREEN);
+    let resizing = states.contains(&XDG_TOPLEVEL_STATE_RESIZING);
+
+    if fullscreen && !window.fullscreen {
+        events.push(Event::WindowFullscreened {
+            fullscreen: window.borderless_fullscreen(&data.outputs),
+            window_id,
+        });
+    } else if maximized && !window.maximized && !fullscreen {
+        events.push(Event::WindowMaximized { window_id });
+    } else if (window.maximized || window.fullscreen) && !maximized && !fullscreen {
+        events.push(Event::WindowRestored {
+            fullscreen: Fullscreen::Off,
+            window_id,
+        });
+    }
+    if resizing && !window.resizing {
+        events.push(Event::WindowStartResize { window_id });
+    } else if !resizing && window.resizing {
+        events.push(Event::WindowEndResize { window_id });
+    }
+    window.maximized = maximized;
+    window.fullscreen = fullscreen;
+    window.resizing = resizing;
+
+    // A size of zero lets the client choose, so the current size is kept.
+    // The configured size includes client side decorations.
+    let (width, height) = match window.pending_size {
+        (width, height) if width > 0 && height > 0 => {
+            (width, (height - window.titlebar_height()).max(1))
+        }
+        _ => (window.width, window.height),
+    };
+
+    if !window.configured {
+        // The window is drawn for the first time after its first configure.
+        window.configured = true;
+        redraw_manager::add_draw_request(window_id);
+    }
+
+    let surface = window.surface;
+    if let Some(event) = data.resize_window(surface, width, height) {
+        events.push(event);
+    }
+}
+
+/// A window's scale is the largest scale of the outputs it's on.
+unsafe fn update_window_scale(
+    data: &mut ApplicationData,
+    surface: *mut wl_proxy,
+    events: &mut Vec<Event>,
+) {
+    let outputs = &data.outputs;
+    let window = match data.windows.get_mut(&surface) {
+        Some(window) => window,
+        None => return,
+    };
+    let scale = window
+        .outputs
+        .iter()
+        .filter_map(|o| outputs.get(o))
+        .map(|o| o.scale)
+        .max();
+    // Keep the current scale if the window is not on any output.
+    let scale = match scale {
+        Some(scale) if scale != window.scale => scale,
+        _ => return,
+    };
+    window.scale = scale;
+    data.connection.set_buffer_scale(surface, scale);
+    if let Some(decorations) = &mut window.client_decorations {
+        decorations.update(&data.connection, window.width, scale, window.fullscreen);
+    }
+
+    let window_id = window.window_id();
+    redraw_manager::add_draw_request(window_id);
+    events.push(Event::WindowScaleChanged {
+        scale: scale as f64,
+        window_id,
+    });
+    events.push(Event::WindowResized {
+        width: (window.width * scale) as u32,
+        height: (window.height * scale) as u32,
+        window_id,
+    });
+}
+
+unsafe fn pointer_button(
+    data: &mut ApplicationData,
+    serial: u32,
+    time: u32,
+    button: u32,
+    pressed: bool,
+    events: &mut Vec<Event>,
+) {
+    let surface = match data.pointer_state.surface {
+        Some(surface) => surface,
+        None => return,
+    };
+    data.pointer_state.button_serial = serial;
+
+    // Presses on the title bar move or close the window.
+    if !data.windows.contains_key(&surface) {
+        let window = match data.window_for_surface(surface) {
+            Some(window) => window,
+            None => return,
+        };
+        if !pressed || button != BTN_LEFT {
+            return;
+        }
+        let decorations = window.client_decorations.as_ref().unwrap();
+        if decorations.close_button_contains(window.width, data.pointer_state.x) {
+            events.push(Event::WindowCloseRequested {
+                window_id: window.window_id(),
+            });
+        } else {
+            data.connection.request(
+                window.toplevel,
+                XDG_TOPLEVEL_MOVE,
+                &mut [
+                    wl_argument {
+                        o: data.connection.globals.seat,
+                    },
+                    wl_argument { u: serial },
+                ],
+            );
+        }
+        return;
+    }
+
+    let window_id = data.windows[&surface].window_id();
+    let scale = data.windows[&surface].scale as f64;
+    let modifiers = data.keyboard_state.modifiers;
+    let pointer = &mut data.pointer_state;
+    let x = pointer.x * scale;
+    let y = pointer.y * scale;
+    let timestamp = Duration::from_millis(time as u64);
+    let pointer_button = button_to_pointer_button(button);
+
+    if !pressed {
+        events.push(Event::PointerUp {
+            x,
+            y,
+            source: PointerSource::Mouse,
+            pointer_id: 0,
+            button: pointer_button,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id,
+            modifiers,
+            timestamp,
+        });
+        if pointer.double_click_button == Some(button) {
+            pointer.double_click_button = None;
+            events.push(Event::DoubleClickUp {
+                x,
+                y,
+                button: pointer_button,
+                window_id,
+                timestamp,
+            });
+        }
+        return;
+    }
+
+    events.push(Event::PointerDown {
+        x,
+        y,
+        source: PointerSource::Mouse,
+        pointer_id: 0,
+        button: pointer_button,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id,
+        modifiers,
+        timestamp,
+    });
+
+    let is_double_click = match &pointer.last_click {
+        Some(last_click) => {
+            last_click.surface == surface
+                && last_click.button == button
+                && time.wrapping_sub(last_click.time) < DOUBLE_CLICK_TIME
+        }
+        None => false,
+    };
+    if is_double_click {
+        // Reset so that a third click does not produce another double click.
+        pointer.last_click = None;
+        pointer.double_click_button = Some(button);
+        events.push(Event::DoubleClickDown {
+            x,
+            y,
+            button: pointer_button,
+            window_id,
+            timestamp,
+        });
+        events.push(Event::DoubleClick {
+            x,
+            y,
+            button: pointer_button,
+            window_id,
+            timestamp,
+        });
+    } else {
+        pointer.last_click = Some(MouseClick {
+            surface,
+            button,
+            time,
+        });
+    }
+}
+
+unsafe fn load_keymap(data: &mut ApplicationData, fd: c_int, size: usize) {
+    let xkb = match &data.xkb {
+        Some(xkb) => xkb,
+        None => return,
+    };
+    let keyboard = &mut data.keyboard_state;
+    if keyboard.context.is_null() {
+        keyboard.context = (xkb.xkb_context_new)(0);
+        if keyboard.context.is_null() {
+            return;
+        }
+    }
+
+    let memory = mmap(null_mut(), size, PROT_READ, MAP_PRIVATE, fd, 0);
+    if memory == MAP_FAILED {
+        return;
+    }
+    let keymap = (xkb.xkb_keymap_new_from_string)(
+        keyboard.context,
+        memory as *const c_char,
+        XKB_KEYMAP_FORMAT_TEXT_V1,
+        0,
+    );
+    munmap(memory, size);
+    if keymap.is_null() {
+        return;
+    }
+
+    if !keyboard.state.is_null() {
+        (xkb.xkb_state_unref)(keyboard.state);
+    }
+    if !keyboard.keymap.is_null() {
+        (xkb.xkb_keymap_unref)(keyboard.keymap);
+    }
+    keyboard.keymap = keymap;
+    keyboard.state = (xkb.xkb_state_new)(keymap);
+}
+
+unsafe fn key(
+    data: &mut ApplicationData,
+    time: u32,
+    code: u32,
+    pressed: bool,
+    events: &mut Vec<Event>,
+) {
+    // Key events are only sent to the focused window.
+    let window_id = match focused_window_id(data) {
+        Some(window_id) => window_id,
+        None => return,
+    };
+    let key = evdev_code_to_key(code);
+    let logical_key = logical_key(data, code, key);
+    let timestamp = Duration::from_millis(time as u64);
+
+    // The compositor sends the new modifiers after the key event, so they're predicted here.
+    let modifiers = if pressed && data.keyboard_state.held_keys.contains(&code) {
+        data.keyboard_state.modifiers
+    } else {
+        data.keyboard_state.modifiers.after_key(key, pressed)
+    };
+    update_modifiers(data, modifiers, events);
+    let keyboard = &mut data.keyboard_state;
+
+    if !pressed {
+        keyboard.held_keys.remove(&code);
+        if keyboard.repeating_key.as_ref().map(|r| r.code) == Some(code) {
+            keyboard.repeating_key = None;
+        }
+        events.push(Event::KeyUp {
+            key,
+            scancode: code,
+            logical_key,
+            window_id,
+            modifiers,
+            timestamp,
+        });
+        return;
+    }
+
+    if keyboard.held_keys.insert(code) {
+        events.push(Event::KeyDown {
+            key,
+            scancode: code,
+            logical_key,
+            window_id,
+            modifiers,
+            timestamp,
+        });
+    } else {
+        events.push(Event::KeyRepeat {
+            key,
+            scancode: code,
+            logical_key,
+            window_id,
+            modifiers,
+            timestamp,
+        });
+    }
+
+    // Modifier keys do not repeat.
+    let repeats = match &data.xkb {
+        Some(xkb) if !keyboard.keymap.is_null() => {
+            (xkb.xkb_keymap_key_repeats)(keyboard.keymap, code + 8) != 0
+        }
+        _ => false,
+    };
+    keyboard.repeating_key = if repeats && keyboard.repeat_rate > 0 {
+        let now = Instant::now();
+        Some(RepeatingKey {
+            code,
+            timestamp,
+            pressed_at: now,
+            next_repeat: now + keyboard.repeat_delay,
+        })
+    } else {
+        None
+    };
+
+    character_received(data, code, events);
+}
+
+fn focused_window_id(data: &ApplicationData) -> Option<WindowId> {
+    let surface = data.keyboard_state.focused_window?;
+    data.windows.get(&surface).map(|window| window.window_id())
+}
+
+/// Returns the key's meaning in the current layout, ignoring modifiers.
+unsafe fn logical_key(data: &ApplicationData, code: u32, key: Key) -> LogicalKey {
+    let keyboard = &data.keyboard_state;
+    let character = match &data.xkb {
+        Some(xkb) if !keyboard.state.is_null() => {
+            let layout = (xkb.xkb_state_key_get_layout)(keyboard.state, code + 8);
+            let mut keysyms = null();
+            let count = (xkb.xkb_keymap_key_get_syms_by_level)(
+                keyboard.keymap,
+                code + 8,
+                layout,
+                0,
+                &mut keysyms,
+            );
+            if count > 0 {
+                keysym_to_character(*keysyms)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+    LogicalKey::from_character(character, key)
+}
+
+unsafe fn state_modifiers(xkb: &Xkb, state: *mut xkb_state) -> Modifiers {
+    let active = |name: &[u8]| {
+        (xkb.xkb_state_mod_name_is_active)(
+            state,
+            name.as_ptr() as *const c_char,
+            XKB_STATE_MODS_EFFECTIVE,
+        ) == 1
+    };
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, active(b"Shift\0"));
+    modifiers.set(Modifiers::CAPS_LOCK, active(b"Lock\0"));
+    modifiers.set(Modifiers::CONTROL, active(b"Control\0"));
+    modifiers.set(Modifiers::ALT, active(b"Mod1\0"));
+    modifiers.set(Modifiers::NUM_LOCK, active(b"Mod2\0"));
+    modifiers.set(Modifiers::META, active(b"Mod4\0"));
+    modifiers
+}
+
+/// Sends `ModifiersChanged` if the modifiers are different from the last sent.
+fn update_modifiers(data: &mut ApplicationData, modifiers: Modifiers, events: &mut Vec<Event>) {
+    if data.keyboard_state.modifiers != modifiers {
+        data.keyboard_state.modifiers = modifiers;
+        events.push(Event::ModifiersChanged { modifiers });
+    }
+}
+
+/// Sends the text produced by a key press if text input is enabled.
+unsafe fn character_received(data: &ApplicationData, code: u32, events: &mut Vec<Event>) {
+    let state = data.keyboard_state.state;
+    let xkb = match &data.xkb {
+        Some(xkb) if data.text_input_enabled && !state.is_null() => xkb,
+        _ => return,
+    };
+    let window_id = match focused_window_id(data) {
+        Some(window_id) => window_id,
+        None => return,
+    };
+    // xkbcommon keycodes are evdev codes offset by 8, like X keycodes.
+    let character = std::char::from_u32((xkb.xkb_state_key_get_utf32)(state, code + 8));
+    if let Some(character) = character {
+        // Control characters are reported with KeyDown instead.
+        if character != '\0' && !character.is_control() {
+            events.push(Event::CharacterReceived {
+                character,
+                window_id,
+            });
+        }
+    }
+}
+
+/// Sends repeats for the held key that are due.
+fn repeat_key(data: &mut ApplicationData, events: &mut Vec<Event>) {
+    let now = Instant::now();
+    let keyboard = &mut data.keyboard_state;
+    let interval = Duration::from_secs(1) / keyboard.repeat_rate.max(1) as u32;
+    let repeating_key = match &mut keyboard.repeating_key {
+        Some(repeating_key) if repeating_key.next_repeat <= now => repeating_key,
+        _ => return,
+    };
+    let code = repeating_key.code;
+    let timestamp = repeating_key.timestamp + (now - repeating_key.pressed_at);
+    // If the program falls behind, repeats are skipped rather than sent all at once.
+    repeating_key.next_repeat = (repeating_key.next_repeat + interval).max(now);
+
+    let window_id = match focused_window_id(data) {
+        Some(window_id) => window_id,
+        None => return,
+    };
+    let key = evdev_code_to_key(code);
+    events.push(Event::KeyRepeat {
+        key,
+        scancode: code,
+        logical_key: unsafe { logical_key(data, code, key) },
+        window_id,
+        modifiers: data.keyboard_state.modifiers,
+        timestamp,
+    });
+    unsafe { character_received(data, code, events) };
+}
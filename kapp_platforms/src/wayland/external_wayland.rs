@@ -0,0 +1,274 @@
+//! Types and functions from libwayland-client, libwayland-cursor, and libxkbcommon.
+//! Hand transcribed from wayland-client-core.h, wayland-util.h, wayland-cursor.h,
+//! and xkbcommon.h
+//! Libraries are loaded at runtime with `dynamic_library!`
+//!
+//! Requests are sent with the `wl_proxy_marshal_array` family of functions,
+//! so the request opcodes for each interface are listed here.
+//! They're from wayland.xml: https://gitlab.freedesktop.org/wayland/wayland/-/blob/main/protocol/wayland.xml
+pub use std::ffi::c_void;
+pub use std::os::raw::{c_char, c_int, c_short, c_uint};
+
+pub enum wl_proxy {}
+pub enum wl_display {}
+pub enum wl_cursor_theme {}
+pub enum xkb_context {}
+pub enum xkb_keymap {}
+pub enum xkb_state {}
+
+pub type wl_fixed_t = i32;
+
+#[repr(C)]
+pub struct wl_message {
+    pub name: *const c_char,
+    pub signature: *const c_char,
+    pub types: *const *const wl_interface,
+}
+
+#[repr(C)]
+pub struct wl_interface {
+    pub name: *const c_char,
+    pub version: c_int,
+    pub method_count: c_int,
+    pub methods: *const wl_message,
+    pub event_count: c_int,
+    pub events: *const wl_message,
+}
+
+#[repr(C)]
+pub struct wl_array {
+    pub size: usize,
+    pub alloc: usize,
+    pub data: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union wl_argument {
+    pub i: i32,
+    pub u: u32,
+    pub f: wl_fixed_t,
+    pub s: *const c_char,
+    pub o: *mut wl_proxy,
+    pub n: u32,
+    pub a: *mut wl_array,
+    pub h: i32,
+}
+
+pub type wl_dispatcher_func_t = unsafe extern "C" fn(
+    *const c_void,
+    *mut c_void,
+    u32,
+    *const wl_message,
+    *mut wl_argument,
+) -> c_int;
+
+#[repr(C)]
+pub struct wl_cursor_image {
+    pub width: u32,
+    pub height: u32,
+    pub hotspot_x: u32,
+    pub hotspot_y: u32,
+    pub delay: u32,
+}
+
+#[repr(C)]
+pub struct wl_cursor {
+    pub image_count: c_uint,
+    pub images: *mut *mut wl_cursor_image,
+    pub name: *mut c_char,
+}
+
+pub fn wl_fixed_to_f64(f: wl_fixed_t) -> f64 {
+    f as f64 / 256.
+}
+
+// wl_display
+pub const WL_DISPLAY_GET_REGISTRY: u32 = 1;
+
+// wl_registry
+pub const WL_REGISTRY_BIND: u32 = 0;
+
+// wl_compositor
+pub const WL_COMPOSITOR_CREATE_SURFACE: u32 = 0;
+pub const WL_COMPOSITOR_CREATE_REGION: u32 = 1;
+
+// wl_surface
+pub const WL_SURFACE_DESTROY: u32 = 0;
+pub const WL_SURFACE_ATTACH: u32 = 1;
+pub const WL_SURFACE_DAMAGE: u32 = 2;
+pub const WL_SURFACE_SET_INPUT_REGION: u32 = 5;
+pub const WL_SURFACE_COMMIT: u32 = 6;
+pub const WL_SURFACE_SET_BUFFER_SCALE: u32 = 8;
+
+// wl_region
+pub const WL_REGION_DESTROY: u32 = 0;
+pub const WL_REGION_ADD: u32 = 1;
+
+// wl_subcompositor
+pub const WL_SUBCOMPOSITOR_GET_SUBSURFACE: u32 = 1;
+
+// wl_subsurface
+pub const WL_SUBSURFACE_DESTROY: u32 = 0;
+pub const WL_SUBSURFACE_SET_POSITION: u32 = 1;
+pub const WL_SUBSURFACE_SET_DESYNC: u32 = 5;
+
+// wl_shm
+pub const WL_SHM_CREATE_POOL: u32 = 0;
+pub const WL_SHM_FORMAT_ARGB8888: u32 = 0;
+
+// wl_shm_pool
+pub const WL_SHM_POOL_CREATE_BUFFER: u32 = 0;
+pub const WL_SHM_POOL_DESTROY: u32 = 1;
+
+// wl_buffer
+pub const WL_BUFFER_DESTROY: u32 = 0;
+
+// wl_seat
+pub const WL_SEAT_GET_POINTER: u32 = 0;
+pub const WL_SEAT_GET_KEYBOARD: u32 = 1;
+pub const WL_SEAT_CAPABILITY_POINTER: u32 = 1;
+pub const WL_SEAT_CAPABILITY_KEYBOARD: u32 = 2;
+
+// wl_output
+pub const WL_OUTPUT_MODE_CURRENT: u32 = 1;
+
+// wl_pointer
+pub const WL_POINTER_SET_CURSOR: u32 = 0;
+pub const WL_POINTER_RELEASE: u32 = 1;
+pub const WL_POINTER_BUTTON_STATE_PRESSED: u32 = 1;
+pub const WL_POINTER_AXIS_VERTICAL_SCROLL: u32 = 0;
+
+// wl_keyboard
+pub const WL_KEYBOARD_RELEASE: u32 = 0;
+pub const WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1: u32 = 1;
+pub const WL_KEYBOARD_KEY_STATE_PRESSED: u32 = 1;
+
+// Linux button codes from input-event-codes.h
+pub const BTN_LEFT: u32 = 0x110;
+pub const BTN_RIGHT: u32 = 0x111;
+pub const BTN_MIDDLE: u32 = 0x112;
+pub const BTN_SIDE: u32 = 0x113;
+pub const BTN_EXTRA: u32 = 0x114;
+
+pub const XKB_KEYMAP_FORMAT_TEXT_V1: c_int = 1;
+pub const XKB_STATE_MODS_EFFECTIVE: c_int = 1 << 3;
+
+dynamic_library! {
+    pub struct WaylandClient("libwayland-client.so.0", "libwayland-client.so") {
+        pub wl_display_connect: unsafe extern "C" fn(*const c_char) -> *mut wl_display,
+        pub wl_display_disconnect: unsafe extern "C" fn(*mut wl_display),
+        pub wl_display_get_fd: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_display_roundtrip: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_display_dispatch_pending: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_display_prepare_read: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_display_read_events: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_display_cancel_read: unsafe extern "C" fn(*mut wl_display),
+        pub wl_display_flush: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_display_get_error: unsafe extern "C" fn(*mut wl_display) -> c_int,
+        pub wl_proxy_marshal_array: unsafe extern "C" fn(*mut wl_proxy, u32, *mut wl_argument),
+        pub wl_proxy_marshal_array_constructor: unsafe extern "C" fn(
+            *mut wl_proxy,
+            u32,
+            *mut wl_argument,
+            *const wl_interface,
+        ) -> *mut wl_proxy,
+        pub wl_proxy_marshal_array_constructor_versioned: unsafe extern "C" fn(
+            *mut wl_proxy,
+            u32,
+            *mut wl_argument,
+            *const wl_interface,
+            u32,
+        ) -> *mut wl_proxy,
+        pub wl_proxy_add_dispatcher: unsafe extern "C" fn(
+            *mut wl_proxy,
+            wl_dispatcher_func_t,
+            *const c_void,
+            *mut c_void,
+        ) -> c_int,
+        pub wl_proxy_destroy: unsafe extern "C" fn(*mut wl_proxy),
+        pub wl_proxy_get_version: unsafe extern "C" fn(*mut wl_proxy) -> u32,
+        static wl_registry_interface: wl_interface,
+        static wl_compositor_interface: wl_interface,
+        static wl_subcompositor_interface: wl_interface,
+        static wl_surface_interface: wl_interface,
+        static wl_region_interface: wl_interface,
+        static wl_subsurface_interface: wl_interface,
+        static wl_shm_interface: wl_interface,
+        static wl_shm_pool_interface: wl_interface,
+        static wl_buffer_interface: wl_interface,
+        static wl_seat_interface: wl_interface,
+        static wl_pointer_interface: wl_interface,
+        static wl_keyboard_interface: wl_interface,
+        static wl_output_interface: wl_interface,
+    }
+}
+
+dynamic_library! {
+    pub struct WaylandCursor("libwayland-cursor.so.0", "libwayland-cursor.so") {
+        pub wl_cursor_theme_load: unsafe extern "C" fn(*const c_char, c_int, *mut wl_proxy) -> *mut wl_cursor_theme,
+        pub wl_cursor_theme_destroy: unsafe extern "C" fn(*mut wl_cursor_theme),
+        pub wl_cursor_theme_get_cursor: unsafe extern "C" fn(*mut wl_cursor_theme, *const c_char) -> *mut wl_cursor,
+        pub wl_cursor_image_get_buffer: unsafe extern "C" fn(*mut wl_cursor_image) -> *mut wl_proxy,
+    }
+}
+
+dynamic_library! {
+    pub struct Xkb("libxkbcommon.so.0", "libxkbcommon.so") {
+        pub xkb_context_new: unsafe extern "C" fn(c_int) -> *mut xkb_context,
+        pub xkb_context_unref: unsafe extern "C" fn(*mut xkb_context),
+        pub xkb_keymap_new_from_string: unsafe extern "C" fn(
+            *mut xkb_context,
+            *const c_char,
+            c_int,
+            c_int,
+        ) -> *mut xkb_keymap,
+        pub xkb_keymap_unref: unsafe extern "C" fn(*mut xkb_keymap),
+        pub xkb_keymap_key_repeats: unsafe extern "C" fn(*mut xkb_keymap, u32) -> c_int,
+        pub xkb_keymap_key_get_syms_by_level: unsafe extern "C" fn(
+            *mut xkb_keymap,
+            u32,
+            u32,
+            u32,
+            *mut *const u32,
+        ) -> c_int,
+        pub xkb_state_new: unsafe extern "C" fn(*mut xkb_keymap) -> *mut xkb_state,
+        pub xkb_state_unref: unsafe extern "C" fn(*mut xkb_state),
+        pub xkb_state_update_mask: unsafe extern "C" fn(*mut xkb_state, u32, u32, u32, u32, u32, u32) -> c_int,
+        pub xkb_state_key_get_utf32: unsafe extern "C" fn(*mut xkb_state, u32) -> u32,
+        pub xkb_state_key_get_layout: unsafe extern "C" fn(*mut xkb_state, u32) -> u32,
+        pub xkb_state_mod_name_is_active: unsafe extern "C" fn(*mut xkb_state, *const c_char, c_int) -> c_int,
+    }
+}
+
+// From libc, which is always linked.
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+
+pub const POLLIN: c_short = 0x1;
+pub const PROT_READ: c_int = 0x1;
+pub const PROT_WRITE: c_int = 0x2;
+pub const MAP_SHARED: c_int = 0x1;
+pub const MAP_PRIVATE: c_int = 0x2;
+pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+pub const MFD_CLOEXEC: c_uint = 0x1;
+
+extern "C" {
+    pub fn poll(fds: *mut pollfd, nfds: u64, timeout: c_int) -> c_int;
+    pub fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: i64,
+    ) -> *mut c_void;
+    pub fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    pub fn close(fd: c_int) -> c_int;
+    pub fn ftruncate(fd: c_int, length: i64) -> c_int;
+    pub fn memfd_create(name: *const c_char, flags: c_uint) -> c_int;
+}
@@ -0,0 +1,13 @@
+mod application_wayland;
+mod decorations_wayland;
+mod event_loop_wayland;
+#[allow(
+    non_upper_case_globals,
+    non_snake_case,
+    non_camel_case_types,
+    dead_code
+)]
+mod external_wayland;
+mod protocols_wayland;
+
+pub use application_wayland::{PlatformApplication, PlatformEventLoop};
@@ -0,0 +1,180 @@
+//! Interface descriptions for the protocols that libwayland-client does not include.
+//! These would normally be generated by wayland-scanner.
+//! Only the interface versions listed here are bound.
+//!
+//! xdg-shell: https://gitlab.freedesktop.org/wayland/wayland-protocols/-/blob/main/stable/xdg-shell/xdg-shell.xml
+//! xdg-decoration: https://gitlab.freedesktop.org/wayland/wayland-protocols/-/blob/main/unstable/xdg-decoration/xdg-decoration-unstable-v1.xml
+//! pointer-constraints: https://gitlab.freedesktop.org/wayland/wayland-protocols/-/blob/main/unstable/pointer-constraints/pointer-constraints-unstable-v1.xml
+//! relative-pointer: https://gitlab.freedesktop.org/wayland/wayland-protocols/-/blob/main/unstable/relative-pointer/relative-pointer-unstable-v1.xml
+use super::external_wayland::*;
+
+/// Lets interface descriptions, which contain pointers, be stored in statics.
+#[repr(transparent)]
+pub struct Static<T>(pub T);
+unsafe impl<T> Sync for Static<T> {}
+
+// libwayland only uses argument types to check the interfaces of objects in events,
+// and it skips the check when the type is null.
+// Every message shares this list because none of them have more than eight arguments.
+static NULL_TYPES: Static<[*const wl_interface; 8]> = Static([std::ptr::null(); 8]);
+
+macro_rules! messages {
+    ($($name:literal $signature:literal),* $(,)?) => {
+        &[$(wl_message {
+            name: concat!($name, "\0").as_ptr() as *const c_char,
+            signature: concat!($signature, "\0").as_ptr() as *const c_char,
+            types: &NULL_TYPES.0 as *const _ as *const *const wl_interface,
+        }),*]
+    };
+}
+
+macro_rules! interface {
+    ($static_name:ident, $name:literal, $version:literal, requests: $requests:expr, events: $events:expr $(,)?) => {
+        pub static $static_name: Static<wl_interface> = Static(wl_interface {
+            name: concat!($name, "\0").as_ptr() as *const c_char,
+            version: $version,
+            method_count: $requests.len() as c_int,
+            methods: $requests.as_ptr(),
+            event_count: $events.len() as c_int,
+            events: $events.as_ptr(),
+        });
+    };
+}
+
+const NO_MESSAGES: &[wl_message] = &[];
+
+interface!(
+    XDG_WM_BASE_INTERFACE,
+    "xdg_wm_base",
+    1,
+    requests: messages!(
+        "destroy" "",
+        "create_positioner" "n",
+        "get_xdg_surface" "no",
+        "pong" "u",
+    ),
+    events: messages!("ping" "u"),
+);
+pub const XDG_WM_BASE_GET_XDG_SURFACE: u32 = 2;
+pub const XDG_WM_BASE_PONG: u32 = 3;
+
+interface!(
+    XDG_SURFACE_INTERFACE,
+    "xdg_surface",
+    1,
+    requests: messages!(
+        "destroy" "",
+        "get_toplevel" "n",
+        "get_popup" "n?oo",
+        "set_window_geometry" "iiii",
+        "ack_configure" "u",
+    ),
+    events: messages!("configure" "u"),
+);
+pub const XDG_SURFACE_DESTROY: u32 = 0;
+pub const XDG_SURFACE_GET_TOPLEVEL: u32 = 1;
+pub const XDG_SURFACE_SET_WINDOW_GEOMETRY: u32 = 3;
+pub const XDG_SURFACE_ACK_CONFIGURE: u32 = 4;
+
+interface!(
+    XDG_TOPLEVEL_INTERFACE,
+    "xdg_toplevel",
+    1,
+    requests: messages!(
+        "destroy" "",
+        "set_parent" "?o",
+        "set_title" "s",
+        "set_app_id" "s",
+        "show_window_menu" "ouii",
+        "move" "ou",
+        "resize" "ouu",
+        "set_max_size" "ii",
+        "set_min_size" "ii",
+        "set_maximized" "",
+        "unset_maximized" "",
+        "set_fullscreen" "?o",
+        "unset_fullscreen" "",
+        "set_minimized" "",
+    ),
+    events: messages!("configure" "iia", "close" ""),
+);
+pub const XDG_TOPLEVEL_DESTROY: u32 = 0;
+pub const XDG_TOPLEVEL_SET_TITLE: u32 = 2;
+pub const XDG_TOPLEVEL_SET_APP_ID: u32 = 3;
+pub const XDG_TOPLEVEL_MOVE: u32 = 5;
+pub const XDG_TOPLEVEL_SET_MAX_SIZE: u32 = 7;
+pub const XDG_TOPLEVEL_SET_MIN_SIZE: u32 = 8;
+pub const XDG_TOPLEVEL_SET_MAXIMIZED: u32 = 9;
+pub const XDG_TOPLEVEL_UNSET_MAXIMIZED: u32 = 10;
+pub const XDG_TOPLEVEL_SET_FULLSCREEN: u32 = 11;
+pub const XDG_TOPLEVEL_UNSET_FULLSCREEN: u32 = 12;
+pub const XDG_TOPLEVEL_SET_MINIMIZED: u32 = 13;
+pub const XDG_TOPLEVEL_STATE_MAXIMIZED: u32 = 1;
+pub const XDG_TOPLEVEL_STATE_FULLSCREEN: u32 = 2;
+pub const XDG_TOPLEVEL_STATE_RESIZING: u32 = 3;
+
+interface!(
+    ZXDG_DECORATION_MANAGER_V1_INTERFACE,
+    "zxdg_decoration_manager_v1",
+    1,
+    requests: messages!("destroy" "", "get_toplevel_decoration" "no"),
+    events: NO_MESSAGES,
+);
+pub const ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION: u32 = 1;
+
+interface!(
+    ZXDG_TOPLEVEL_DECORATION_V1_INTERFACE,
+    "zxdg_toplevel_decoration_v1",
+    1,
+    requests: messages!("destroy" "", "set_mode" "u", "unset_mode" ""),
+    events: messages!("configure" "u"),
+);
+pub const ZXDG_TOPLEVEL_DECORATION_V1_DESTROY: u32 = 0;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE: u32 = 1;
+pub const ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE: u32 = 2;
+
+interface!(
+    ZWP_POINTER_CONSTRAINTS_V1_INTERFACE,
+    "zwp_pointer_constraints_v1",
+    1,
+    requests: messages!(
+        "destroy" "",
+        "lock_pointer" "noo?ou",
+        "confine_pointer" "noo?ou",
+    ),
+    events: NO_MESSAGES,
+);
+pub const ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER: u32 = 1;
+pub const ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT: u32 = 2;
+
+interface!(
+    ZWP_LOCKED_POINTER_V1_INTERFACE,
+    "zwp_locked_pointer_v1",
+    1,
+    requests: messages!(
+        "destroy" "",
+        "set_cursor_position_hint" "ff",
+        "set_region" "?o",
+    ),
+    events: messages!("locked" "", "unlocked" ""),
+);
+pub const ZWP_LOCKED_POINTER_V1_DESTROY: u32 = 0;
+
+interface!(
+    ZWP_RELATIVE_POINTER_MANAGER_V1_INTERFACE,
+    "zwp_relative_pointer_manager_v1",
+    1,
+    requests: messages!("destroy" "", "get_relative_pointer" "no"),
+    events: NO_MESSAGES,
+);
+pub const ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER: u32 = 1;
+
+interface!(
+    ZWP_RELATIVE_POINTER_V1_INTERFACE,
+    "zwp_relative_pointer_v1",
+    1,
+    requests: messages!("destroy" ""),
+    events: messages!("relative_motion" "uuffff"),
+);
+pub const ZWP_RELATIVE_POINTER_V1_DESTROY: u32 = 0;
@@ -1,8 +1,10 @@
 use kapp_platform_common::*;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{Clamped, JsCast};
 use web_sys::HtmlElement;
 
 pub static mut CURRENT_CURSOR: Option<String> = None;
+/// CSS cursor values for images created with `create_cursor`, indexed by the custom cursor's id.
+static mut CUSTOM_CURSORS: Vec<String> = Vec::new();
 
 pub struct PlatformApplication {}
 
@@ -21,6 +23,67 @@ impl PlatformApplicationTrait for PlatformApplication {
     fn set_window_position(&mut self, _window_id: WindowId, _x: u32, _y: u32) {}
     fn set_window_size(&mut self, _window_id: WindowId, _width: u32, _height: u32) {}
     fn set_window_title(&mut self, _window_id: WindowId, _title: &str) {}
+    fn set_window_icon(&mut self, _window_id: WindowId, icon: &[Icon]) {
+        // The page's favicon is replaced. Without an icon the browser's default is used.
+        let document = web_sys::window().unwrap().document().unwrap();
+        let links = document.query_selector_all("link[rel~='icon']").unwrap();
+        for i in 0..links.length() {
+            if let Some(link) = links.item(i) {
+                link.unchecked_into::<web_sys::Element>().remove();
+            }
+        }
+
+        // The browser picks the most suitable size.
+        let head = document.head().unwrap();
+        for size in icon {
+            let link = document.create_element("link").unwrap();
+            link.set_attribute("rel", "icon").unwrap();
+            link.set_attribute("sizes", &format!("{}x{}", size.width, size.height))
+                .unwrap();
+            link.set_attribute("href", &image_data_url(&size.data, size.width, size.height))
+                .unwrap();
+            head.append_child(&link).unwrap();
+        }
+    }
+    fn set_window_resizable(&mut self, _window_id: WindowId, _resizable: bool) {}
+    fn set_window_minimum_size(&mut self, _window_id: WindowId, _size: Option<(u32, u32)>) {}
+    fn set_window_maximum_size(&mut self, _window_id: WindowId, _size: Option<(u32, u32)>) {}
+    fn set_window_decorations(&mut self, _window_id: WindowId, _decorations: bool) {}
+    fn set_window_always_on_top(&mut self, _window_id: WindowId, _always_on_top: bool) {}
+    fn set_window_visible(&mut self, _window_id: WindowId, _visible: bool) {}
+    fn set_window_opacity(&mut self, _window_id: WindowId, opacity: f32) {
+        let style = web_sys::window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .get_element_by_id("canvas")
+            .unwrap()
+            .unchecked_into::<HtmlElement>()
+            .style();
+        style.set_property("opacity", &opacity.to_string()).ok();
+    }
+    fn set_window_input_region(
+        &mut self,
+        _window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        // Pointer input can only pass through the whole canvas.
+        let pointer_events = match region {
+            Some(region) if region.is_empty() => "none",
+            _ => "auto",
+        };
+        let style = web_sys::window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .get_element_by_id("canvas")
+            .unwrap()
+            .unchecked_into::<HtmlElement>()
+            .style();
+        style.set_property("pointer-events", pointer_events).ok();
+        // Other regions can't be used, so the whole canvas receives input.
+        region.is_none_or(|region| region.is_empty())
+    }
     fn minimize_window(&mut self, _window_id: WindowId) {}
     fn maximize_window(&mut self, _window_id: WindowId) {}
     fn get_window_size(&mut self, _window_id: WindowId) -> (u32, u32) {
@@ -38,8 +101,64 @@ impl PlatformApplicationTrait for PlatformApplication {
     fn get_window_scale(&mut self, _window_id: WindowId) -> f64 {
         web_sys::window().unwrap().device_pixel_ratio()
     }
-    fn fullscreen_window(&mut self, _window_id: WindowId) {
-        super::event_loop_web::request_fullscreen()
+    fn get_window_position(&mut self, _window_id: WindowId) -> (u32, u32) {
+        (0, 0)
+    }
+    fn is_window_minimized(&mut self, _window_id: WindowId) -> bool {
+        false
+    }
+    fn is_window_maximized(&mut self, _window_id: WindowId) -> bool {
+        false
+    }
+    fn get_window_fullscreen(&mut self, _window_id: WindowId) -> Fullscreen {
+        let document = web_sys::window().unwrap().document().unwrap();
+        if document.fullscreen_element().is_some() {
+            Fullscreen::Borderless(Some(ScreenId::new(std::ptr::null_mut())))
+        } else {
+            Fullscreen::Off
+        }
+    }
+    fn window_has_focus(&mut self, _window_id: WindowId) -> bool {
+        let document = web_sys::window().unwrap().document().unwrap();
+        document.has_focus().unwrap_or(false)
+    }
+    fn is_window_visible(&mut self, _window_id: WindowId) -> bool {
+        // Pages are hidden when their tab isn't shown.
+        let document = web_sys::window().unwrap().document().unwrap();
+        !document.hidden()
+    }
+    fn screens(&mut self) -> Vec<Screen> {
+        // Browsers only expose the screen the page is on.
+        let window = web_sys::window().unwrap();
+        let screen = window.screen().unwrap();
+        let scale = window.device_pixel_ratio();
+        let physical = |css_pixels: Result<i32, _>| (css_pixels.unwrap_or(0) as f64 * scale) as u32;
+        let size = (physical(screen.width()), physical(screen.height()));
+        vec![Screen {
+            id: ScreenId::new(std::ptr::null_mut()),
+            name: String::new(),
+            position: (0, 0),
+            size,
+            // Where the work area starts isn't exposed, only its size.
+            work_area_position: (0, 0),
+            work_area_size: (
+                physical(screen.avail_width()),
+                physical(screen.avail_height()),
+            ),
+            scale,
+            refresh_rate: None,
+            video_modes: Vec::new(),
+        }]
+    }
+    fn window_screen(&mut self, _window_id: WindowId) -> Option<ScreenId> {
+        Some(ScreenId::new(std::ptr::null_mut()))
+    }
+    fn set_window_fullscreen(&mut self, _window_id: WindowId, fullscreen: Fullscreen) {
+        // Browsers don't let pages change video modes, so all fullscreens are borderless.
+        match fullscreen {
+            Fullscreen::Off => super::event_loop_web::exit_fullscreen(),
+            _ => super::event_loop_web::request_fullscreen(),
+        }
     }
     fn restore_window(&mut self, _window_id: WindowId) {
         todo!()
@@ -64,12 +183,36 @@ impl PlatformApplicationTrait for PlatformApplication {
         document.exit_pointer_lock();
     }
 
-    fn new_window(&mut self, _window_parameters: &WindowParameters) -> WindowId {
-        WindowId::new(0 as *mut std::ffi::c_void)
+    fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
+        let window_id = WindowId::new(0 as *mut std::ffi::c_void);
+        if !window_parameters.icon.is_empty() {
+            self.set_window_icon(window_id, &window_parameters.icon);
+        }
+        window_id
     }
 
     fn quit(&self) {}
 
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        // `ControlFlow::WaitUntil` can't be supported because `Instant` panics on Web.
+        control_flow::set_control_flow(control_flow);
+        if control_flow == ControlFlow::Poll {
+            super::event_loop_web::schedule_poll();
+        }
+    }
+
+    fn set_timer(&mut self, duration: std::time::Duration, repeat: bool) -> TimerId {
+        super::event_loop_web::set_timer(duration, repeat)
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        super::event_loop_web::cancel_timer(timer_id);
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        super::event_loop_web::proxy()
+    }
+
     fn set_cursor(&mut self, cursor: Cursor) {
         let style = web_sys::window()
             .unwrap()
@@ -85,12 +228,45 @@ impl PlatformApplicationTrait for PlatformApplication {
             Cursor::PointingHand => "pointer",
             Cursor::OpenHand => "grab",
             Cursor::ClosedHand => "grabbing",
+            Cursor::ResizeNS => "ns-resize",
+            Cursor::ResizeEW => "ew-resize",
+            Cursor::ResizeNESW => "nesw-resize",
+            Cursor::ResizeNWSE => "nwse-resize",
+            Cursor::Crosshair => "crosshair",
+            Cursor::NotAllowed => "not-allowed",
+            Cursor::Wait => "wait",
+            Cursor::Progress => "progress",
+            Cursor::Move => "move",
+            Cursor::Help => "help",
+            Cursor::ZoomIn => "zoom-in",
+            Cursor::ZoomOut => "zoom-out",
+            Cursor::Custom(custom_cursor) => unsafe {
+                CUSTOM_CURSORS
+                    .get(custom_cursor.raw() as usize)
+                    .map_or("default", |cursor| cursor.as_str())
+            },
         };
         unsafe {
             CURRENT_CURSOR = Some(cursor_name.into());
         }
         style.set_property("cursor", cursor_name).ok();
     }
+
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        // Browsers ignore cursor images that are too large, often above 128x128,
+        // in which case the cursor falls back to `auto`.
+        let url = image_data_url(rgba, width, height);
+        unsafe {
+            CUSTOM_CURSORS.push(format!("url({}) {} {}, auto", url, hotspot.0, hotspot.1));
+            CustomCursor::new((CUSTOM_CURSORS.len() - 1) as *mut std::ffi::c_void)
+        }
+    }
     fn hide_cursor(&mut self) {
         let style = web_sys::window()
             .unwrap()
@@ -118,6 +294,32 @@ impl PlatformApplicationTrait for PlatformApplication {
             .ok();
     }
 
+    fn clipboard_mime_types(&mut self, _selection: ClipboardSelection) -> Vec<String> {
+        // Clipboard access isn't implemented on web yet.
+        Vec::new()
+    }
+    fn get_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+    fn set_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+        _data: &[u8],
+    ) {
+    }
+
+    fn set_drop_accepted(&mut self, accepted: bool) {
+        // Safe because web's main loop is single threaded.
+        unsafe {
+            super::event_loop_web::DROP_ACCEPTED = accepted;
+        }
+    }
+
     fn raw_window_handle(&self, _window_id: WindowId) -> RawWindowHandle {
         RawWindowHandle::Web(raw_window_handle::web::WebHandle::empty())
     }
@@ -136,6 +338,10 @@ impl PlatformApplicationTrait for PlatformApplication {
     ) {
         // Perhaps a hidden text input field could be moved to make IME input appear in the right place.
     }
+
+    fn inject_event(&mut self, event: Event) {
+        event_receiver::send_event(event);
+    }
 }
 
 // When the application is dropped, quit the program.
@@ -152,3 +358,23 @@ impl PlatformEventLoopTrait for PlatformEventLoop {
         super::event_loop_web::run(callback);
     }
 }
+
+/// Draws RGBA pixels to a canvas and returns them as a PNG data URL.
+fn image_data_url(rgba: &[u8], width: u32, height: u32) -> String {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas = document
+        .create_element("canvas")
+        .unwrap()
+        .unchecked_into::<web_sys::HtmlCanvasElement>();
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")
+        .unwrap()
+        .unwrap()
+        .unchecked_into::<web_sys::CanvasRenderingContext2d>();
+    let image_data =
+        web_sys::ImageData::new_with_u8_clamped_array_and_sh(Clamped(rgba), width, height).unwrap();
+    context.put_image_data(&image_data, 0., 0.).unwrap();
+    canvas.to_data_url().unwrap()
+}
@@ -1,6 +1,7 @@
 use super::keys_web;
 use kapp_platform_common::*;
 
+use std::path::PathBuf;
 use std::time::Duration;
 
 use wasm_bindgen::prelude::*;
@@ -18,15 +19,25 @@ fn window() -> web_sys::Window {
     web_sys::window().expect("no global `window` exists")
 }
 
-static mut CALLBACK: Option<Box<dyn FnMut(Event)>> = None;
 static mut REQUEST_ANIMATION_FRAME_CLOSURE: Option<Closure<dyn FnMut()>> = None;
 static mut REQUEST_FULLSCREEN_CLOSURE: Option<Closure<dyn FnMut()>> = None;
+static mut POLL_CLOSURE: Option<Closure<dyn FnMut()>> = None;
+static mut POLL_SCHEDULED: bool = false;
+static mut TIMER_CLOSURE: Option<Closure<dyn FnMut(JsValue)>> = None;
+/// Kept open so proxies can wake the event loop.
+static mut PROXY_CHANNEL: Option<web_sys::BroadcastChannel> = None;
+/// The browser's handle for each running timer, and if it repeats.
+static mut TIMERS: Vec<(TimerId, i32, bool)> = Vec::new();
 static mut CANVAS_HEIGHT: u32 = 0;
+static mut MODIFIERS: Modifiers = Modifiers::empty();
+pub static mut DROP_ACCEPTED: bool = true;
+
+/// Proxies post to a `BroadcastChannel` with this name, because workers have no `window`.
+/// Other pages from the same origin share it, which only wakes them to find no events.
+const PROXY_CHANNEL_NAME: &str = "kapp_event_loop_proxy";
 
 fn send_event(event: Event) {
-    unsafe {
-        (CALLBACK.as_mut().unwrap())(event);
-    }
+    event_receiver::send_event(event);
 }
 
 pub fn run<T>(callback: T)
@@ -44,7 +55,7 @@ where
     // An alternative approach is documented here: https://rustwasm.github.io/docs/wasm-bindgen/examples/request-animation-frame.html
     // It may be better, but for now I found the following simpler to understand and implement.
     unsafe {
-        CALLBACK = Some(Box::new(Box::new(callback)));
+        event_receiver::set_callback(Box::new(callback));
         {
             let canvas = canvas.clone();
             REQUEST_ANIMATION_FRAME_CLOSURE = Some(Closure::wrap(Box::new(move || {
@@ -85,6 +96,13 @@ where
                 x,
                 y,
                 source: get_pointer_type(&event),
+                pointer_id: get_pointer_id(&event),
+                pressure: event.pressure() as f64,
+                tilt_x: event.tilt_x() as f64,
+                tilt_y: event.tilt_y() as f64,
+                contact_width: event.width() as f64,
+                contact_height: event.height() as f64,
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
                 timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
             });
         }) as Box<dyn FnMut(web_sys::PointerEvent)>);
@@ -95,10 +113,12 @@ where
         let pointer_down = Closure::wrap(Box::new(move |event: web_sys::PointerEvent| {
             let (x, y) = get_pointer_position(&event);
 
+            let modifiers = mouse_modifiers(&event);
             send_event(Event::PointerDown {
                 x,
                 y,
                 source: get_pointer_type(&event),
+                pointer_id: get_pointer_id(&event),
                 button: match event.button() {
                     0 => PointerButton::Primary,
                     1 => PointerButton::Auxillary,
@@ -107,6 +127,13 @@ where
                     4 => PointerButton::Extra2,
                     _ => PointerButton::Unknown,
                 },
+                pressure: event.pressure() as f64,
+                tilt_x: event.tilt_x() as f64,
+                tilt_y: event.tilt_y() as f64,
+                contact_width: event.width() as f64,
+                contact_height: event.height() as f64,
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                modifiers,
                 timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
             });
         }) as Box<dyn FnMut(web_sys::PointerEvent)>);
@@ -128,6 +155,7 @@ where
                         4 => PointerButton::Extra2,
                         _ => PointerButton::Unknown,
                     },
+                    window_id: WindowId::new(0 as *mut std::ffi::c_void),
                     timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
                 });
             }
@@ -141,6 +169,7 @@ where
             send_event(Event::MouseMotion {
                 delta_x,
                 delta_y,
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
                 timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
             });
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
@@ -163,12 +192,14 @@ where
                 x,
                 y,
                 button,
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
                 timestamp,
             });
             send_event(Event::DoubleClick {
                 x,
                 y,
                 button,
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
                 timestamp,
             });
         }) as Box<dyn FnMut(web_sys::MouseEvent)>);
@@ -179,10 +210,12 @@ where
         let pointer_up = Closure::wrap(Box::new(move |event: web_sys::PointerEvent| {
             let (x, y) = get_pointer_position(&event);
 
+            let modifiers = mouse_modifiers(&event);
             send_event(Event::PointerUp {
                 x,
                 y,
                 source: get_pointer_type(&event),
+                pointer_id: get_pointer_id(&event),
                 button: match event.button() {
                     0 => PointerButton::Primary,
                     1 => PointerButton::Auxillary,
@@ -191,6 +224,13 @@ where
                     4 => PointerButton::Extra2,
                     _ => PointerButton::Unknown,
                 },
+                pressure: event.pressure() as f64,
+                tilt_x: event.tilt_x() as f64,
+                tilt_y: event.tilt_y() as f64,
+                contact_width: event.width() as f64,
+                contact_height: event.height() as f64,
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                modifiers,
                 timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
             });
         }) as Box<dyn FnMut(web_sys::PointerEvent)>);
@@ -199,14 +239,25 @@ where
 
         // Key down event
         let keydown = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            let modifiers = keyboard_modifiers(&event);
+            let key = keys_web::virtual_keycode_to_key(&event.code());
+            let logical_key = keys_web::key_value_to_logical_key(&event.key(), key);
             let key_event = if event.repeat() {
                 Event::KeyRepeat {
-                    key: keys_web::virtual_keycode_to_key(&event.code()),
+                    key,
+                    scancode: 0,
+                    logical_key,
+                    window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                    modifiers,
                     timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
                 }
             } else {
                 Event::KeyDown {
-                    key: keys_web::virtual_keycode_to_key(&event.code()),
+                    key,
+                    scancode: 0,
+                    logical_key,
+                    window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                    modifiers,
                     timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
                 }
             };
@@ -222,7 +273,10 @@ where
             // A more robust solution may watch a text field for changes instead.
             if !event.is_composing() && event.key().chars().count() == 1 {
                 for character in event.key().chars() {
-                    send_event(Event::CharacterReceived { character })
+                    send_event(Event::CharacterReceived {
+                        character,
+                        window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                    })
                 }
             }
 
@@ -236,8 +290,14 @@ where
 
         // Key up event
         let keyup = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+            let modifiers = keyboard_modifiers(&event);
+            let key = keys_web::virtual_keycode_to_key(&event.code());
             send_event(Event::KeyUp {
-                key: keys_web::virtual_keycode_to_key(&event.code()),
+                key,
+                scancode: 0,
+                logical_key: keys_web::key_value_to_logical_key(&event.key(), key),
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                modifiers,
                 timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
             });
             event
@@ -265,13 +325,16 @@ where
                     // Should this library even make such adjustments?
                     // Is there a way to find an actual scale factor instead of a guess?
                     delta: -event.delta_y() * 0.02,
+                    window_id: WindowId::new(0 as *mut std::ffi::c_void),
                     timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
                 });
             } else {
+                let modifiers = mouse_modifiers(&event);
                 send_event(Event::Scroll {
                     delta_x: -event.delta_x(),
                     delta_y: -event.delta_y(),
                     window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                    modifiers,
                     timestamp: Duration::from_secs_f64(event.time_stamp() * 1000.0),
                 });
             }
@@ -282,6 +345,63 @@ where
         }) as Box<dyn FnMut(web_sys::WheelEvent)>);
         canvas.set_onwheel(Some(wheel.as_ref().unchecked_ref()));
         wheel.forget();
+
+        // Drag and drop events
+        // The browser only allows a drop if the default is prevented while dragging.
+        let drag_enter = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+            DROP_ACCEPTED = true;
+            send_event(Event::DragEntered {
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
+            });
+            if DROP_ACCEPTED {
+                event.prevent_default();
+            }
+        }) as Box<dyn FnMut(web_sys::DragEvent)>);
+        canvas.set_ondragenter(Some(drag_enter.as_ref().unchecked_ref()));
+        drag_enter.forget();
+
+        let drag_over = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+            send_event(Event::DragMoved {
+                x: event.client_x().into(),
+                y: event.client_y().into(),
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
+            });
+            if DROP_ACCEPTED {
+                event.prevent_default();
+            }
+        }) as Box<dyn FnMut(web_sys::DragEvent)>);
+        canvas.set_ondragover(Some(drag_over.as_ref().unchecked_ref()));
+        drag_over.forget();
+
+        let drag_leave = Closure::wrap(Box::new(move |_event: web_sys::DragEvent| {
+            send_event(Event::DragLeft {
+                window_id: WindowId::new(0 as *mut std::ffi::c_void),
+            });
+        }) as Box<dyn FnMut(web_sys::DragEvent)>);
+        canvas.set_ondragleave(Some(drag_leave.as_ref().unchecked_ref()));
+        drag_leave.forget();
+
+        let drop = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+            // Otherwise the browser opens dropped files.
+            event.prevent_default();
+            if let Some(data) = event.data_transfer().and_then(|d| get_drop_data(&d)) {
+                send_event(Event::Dropped {
+                    data,
+                    window_id: WindowId::new(0 as *mut std::ffi::c_void),
+                });
+            }
+        }) as Box<dyn FnMut(web_sys::DragEvent)>);
+        canvas.set_ondrop(Some(drop.as_ref().unchecked_ref()));
+        drop.forget();
+
+        // Proxies post a message to wake the event loop.
+        let message = Closure::wrap(Box::new(move |_event: web_sys::MessageEvent| {
+            event_loop_proxy::send_proxy_events();
+        }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+        let proxy_channel = web_sys::BroadcastChannel::new(PROXY_CHANNEL_NAME).unwrap();
+        proxy_channel.set_onmessage(Some(message.as_ref().unchecked_ref()));
+        PROXY_CHANNEL = Some(proxy_channel);
+        message.forget();
         // Finally, start the draw loop.
         request_frame();
     }
@@ -296,6 +416,33 @@ fn get_pointer_type(event: &web_sys::PointerEvent) -> PointerSource {
     }
 }
 
+// Browsers give the mouse an id too, but kapp always uses 0 for the mouse.
+fn get_pointer_id(event: &web_sys::PointerEvent) -> u64 {
+    if event.pointer_type() == "mouse" {
+        0
+    } else {
+        event.pointer_id() as u64
+    }
+}
+
+// Browsers don't reveal where files come from, so only their names are known.
+fn get_drop_data(data_transfer: &web_sys::DataTransfer) -> Option<DropData> {
+    let files = data_transfer.files()?;
+    if files.length() > 0 {
+        let paths = (0..files.length())
+            .filter_map(|i| files.get(i))
+            .map(|file| PathBuf::from(file.name()))
+            .collect();
+        return Some(DropData::Paths(paths));
+    }
+    let text = data_transfer.get_data("text/plain").ok()?;
+    if text.is_empty() {
+        None
+    } else {
+        Some(DropData::Text(text))
+    }
+}
+
 fn get_pointer_position(event: &web_sys::PointerEvent) -> (f64, f64) {
     // 0,0 is the upper left of the canvas on web, so no transformations need to be performed.
     (event.client_x().into(), event.client_y().into())
@@ -313,6 +460,91 @@ pub fn request_frame() {
     }
 }
 
+/// The browser runs the event loop, so polling is simulated with a timeout
+/// that sends `EventsCleared` after the browser has processed other events.
+pub fn schedule_poll() {
+    unsafe {
+        if POLL_SCHEDULED {
+            return;
+        }
+        POLL_SCHEDULED = true;
+        let closure = POLL_CLOSURE.get_or_insert_with(|| {
+            Closure::wrap(Box::new(move || {
+                POLL_SCHEDULED = false;
+                if control_flow::control_flow() == ControlFlow::Poll {
+                    send_event(Event::EventsCleared);
+                    schedule_poll();
+                }
+            }) as Box<dyn FnMut()>)
+        });
+        window()
+            .set_timeout_with_callback(closure.as_ref().unchecked_ref())
+            .expect("should register `setTimeout` OK");
+    }
+}
+
+/// Starts a browser timer that passes the timer's id to a shared closure.
+pub fn set_timer(duration: Duration, repeat: bool) -> TimerId {
+    unsafe {
+        let timer_id = timers::new_timer_id();
+        let closure = TIMER_CLOSURE.get_or_insert_with(|| {
+            Closure::wrap(Box::new(move |raw_id: JsValue| {
+                let timer_id = TimerId::from_raw(raw_id.as_f64().unwrap() as u64);
+                if let Some(index) = TIMERS.iter().position(|(id, _, _)| *id == timer_id) {
+                    if !TIMERS[index].2 {
+                        TIMERS.remove(index);
+                    }
+                    send_event(Event::Timer { timer_id });
+                }
+            }) as Box<dyn FnMut(JsValue)>)
+        });
+        let timeout = duration.as_millis().min(i32::MAX as u128) as i32;
+        let raw_id = JsValue::from_f64(timer_id.raw() as f64);
+        let handle = if repeat {
+            window().set_interval_with_callback_and_timeout_and_arguments_1(
+                closure.as_ref().unchecked_ref(),
+                timeout,
+                &raw_id,
+            )
+        } else {
+            window().set_timeout_with_callback_and_timeout_and_arguments_1(
+                closure.as_ref().unchecked_ref(),
+                timeout,
+                &raw_id,
+            )
+        }
+        .expect("should register timer OK");
+        TIMERS.push((timer_id, handle, repeat));
+        timer_id
+    }
+}
+
+pub fn cancel_timer(timer_id: TimerId) {
+    unsafe {
+        if let Some(index) = TIMERS.iter().position(|(id, _, _)| *id == timer_id) {
+            let (_, handle, repeat) = TIMERS.remove(index);
+            if repeat {
+                window().clear_interval_with_handle(handle);
+            } else {
+                window().clear_timeout_with_handle(handle);
+            }
+        }
+    }
+}
+
+/// Other threads don't have a window to post to,
+/// so their events wait until the main thread is woken.
+pub fn proxy() -> EventLoopProxy {
+    EventLoopProxy::new(|| {
+        // A channel is opened for each message because proxies may be on any thread.
+        // Closing it doesn't cancel the message.
+        if let Ok(channel) = web_sys::BroadcastChannel::new(PROXY_CHANNEL_NAME) {
+            let _ = channel.post_message(&JsValue::NULL);
+            channel.close();
+        }
+    })
+}
+
 pub fn request_fullscreen() {
     let document = web_sys::window().unwrap().document().unwrap();
     let canvas = document
@@ -323,3 +555,59 @@ pub fn request_fullscreen() {
 
     canvas.request_fullscreen().unwrap();
 }
+
+pub fn exit_fullscreen() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    }
+}
+
+fn mouse_modifiers(event: &web_sys::MouseEvent) -> Modifiers {
+    update_modifiers(
+        event.shift_key(),
+        event.ctrl_key(),
+        event.alt_key(),
+        event.meta_key(),
+        event.get_modifier_state("CapsLock"),
+        event.get_modifier_state("NumLock"),
+    )
+}
+
+fn keyboard_modifiers(event: &web_sys::KeyboardEvent) -> Modifiers {
+    update_modifiers(
+        event.shift_key(),
+        event.ctrl_key(),
+        event.alt_key(),
+        event.meta_key(),
+        event.get_modifier_state("CapsLock"),
+        event.get_modifier_state("NumLock"),
+    )
+}
+
+/// Sends a `ModifiersChanged` event if the modifiers differ from the last event's.
+fn update_modifiers(
+    shift: bool,
+    control: bool,
+    alt: bool,
+    meta: bool,
+    caps_lock: bool,
+    num_lock: bool,
+) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, shift);
+    modifiers.set(Modifiers::CONTROL, control);
+    modifiers.set(Modifiers::ALT, alt);
+    modifiers.set(Modifiers::META, meta);
+    modifiers.set(Modifiers::CAPS_LOCK, caps_lock);
+    modifiers.set(Modifiers::NUM_LOCK, num_lock);
+
+    // Safe because web's main loop is single threaded.
+    unsafe {
+        if modifiers != MODIFIERS {
+            MODIFIERS = modifiers;
+            send_event(Event::ModifiersChanged { modifiers });
+        }
+    }
+    modifiers
+}
@@ -1,4 +1,4 @@
-use kapp_platform_common::{Key, Key::*};
+use kapp_platform_common::{Key, Key::*, LogicalKey};
 
 // Hand transcribed from here:
 // https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key/Key_Values
@@ -164,3 +164,13 @@ pub fn virtual_keycode_to_key(key_in: &str) -> Key {
         _ => Unknown,
     }
 }
+
+/// A `KeyboardEvent`'s `key` is either the character the key produces or the key's name.
+/// Browsers apply shift to the character, which is undone for letters by `LogicalKey`.
+pub fn key_value_to_logical_key(value: &str, key: Key) -> LogicalKey {
+    let mut characters = value.chars();
+    match (characters.next(), characters.next()) {
+        (Some(character), None) => LogicalKey::from_character(Some(character), key),
+        _ => LogicalKey::Key(key),
+    }
+}
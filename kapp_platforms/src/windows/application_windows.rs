@@ -1,17 +1,22 @@
+use super::event_loop_windows::get_window_data;
 use super::external_windows::*;
 use super::utils_windows::*;
 use std::convert::TryInto;
 use std::ptr::{null, null_mut};
+use std::time::Duration;
 
 use kapp_platform_common::*;
 
 // These should be made into something safe.
 pub static mut CURRENT_CURSOR: HCURSOR = null_mut();
 pub static mut WINDOWS_TO_REDRAW: Vec<WindowId> = Vec::new();
+// The screens when they last changed, to find which changed on the next display change.
+pub static mut SCREENS: Vec<Screen> = Vec::new();
 
 pub struct PlatformApplication {
     window_class_name: Vec<u16>,
     h_instance: HINSTANCE,
+    custom_cursors: Vec<HCURSOR>,
 }
 
 pub(crate) struct WindowData {
@@ -19,6 +24,88 @@ pub(crate) struct WindowData {
     pub minimum_height: u32,
     pub maximum_width: u32,
     pub maximum_height: u32,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub fullscreen: Fullscreen,
+    pub opacity: f32,
+    /// The parts of the client area that receive mouse input, or `None` for all of it.
+    pub input_region: Option<Vec<(u32, u32, u32, u32)>>,
+}
+
+impl WindowData {
+    /// The style of the window when it isn't fullscreen.
+    fn window_style(&self) -> DWORD {
+        let mut window_style = if self.decorations {
+            WS_OVERLAPPEDWINDOW
+        } else {
+            WS_POPUP
+        };
+        if !self.resizable {
+            window_style &= !(WS_THICKFRAME | WS_MAXIMIZEBOX);
+        }
+        window_style
+    }
+
+    /// The extended style flags that depend on the window's opacity and input region.
+    /// An empty input region lets all input pass through to whatever is behind the window.
+    fn layered_style(&self) -> DWORD {
+        let mut extended_style = 0;
+        if self.opacity < 1.0 {
+            extended_style |= WS_EX_LAYERED;
+        }
+        if self
+            .input_region
+            .as_ref()
+            .map_or(false, |input_region| input_region.is_empty())
+        {
+            extended_style |= WS_EX_LAYERED | WS_EX_TRANSPARENT;
+        }
+        extended_style
+    }
+}
+
+/// Applies a change to the window's style, keeping flags like `WS_VISIBLE` and `WS_MAXIMIZE`.
+/// Fullscreen windows keep their borderless style until they're restored.
+unsafe fn update_window_style(hwnd: HWND, change: impl FnOnce(&mut WindowData)) {
+    let window_data = match get_window_data(hwnd) {
+        Some(window_data) => window_data,
+        None => return,
+    };
+    change(&mut *window_data);
+    if (*window_data).fullscreen != Fullscreen::Off {
+        return;
+    }
+    let window_style = GetWindowLongPtrW(hwnd, GWL_STYLE) as DWORD;
+    let window_style =
+        window_style & !(WS_OVERLAPPEDWINDOW | WS_POPUP) | (*window_data).window_style();
+    SetWindowLongPtrW(hwnd, GWL_STYLE, window_style as _);
+    // The frame is only redrawn with the new style after this.
+    SetWindowPos(
+        hwnd,
+        null_mut(),
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+    );
+}
+
+/// Applies a change to the window's opacity or input region.
+unsafe fn update_layered_style(hwnd: HWND, change: impl FnOnce(&mut WindowData)) {
+    let window_data = match get_window_data(hwnd) {
+        Some(window_data) => window_data,
+        None => return,
+    };
+    change(&mut *window_data);
+    let layered_style = (*window_data).layered_style();
+    let extended_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as DWORD;
+    let extended_style = extended_style & !(WS_EX_LAYERED | WS_EX_TRANSPARENT) | layered_style;
+    SetWindowLongPtrW(hwnd, GWL_EXSTYLE, extended_style as _);
+    // Layered windows aren't drawn until their attributes are set.
+    if layered_style & WS_EX_LAYERED != 0 {
+        SetLayeredWindowAttributes(hwnd, 0, ((*window_data).opacity * 255.0) as BYTE, LWA_ALPHA);
+    }
 }
 
 impl PlatformApplicationTrait for PlatformApplication {
@@ -45,11 +132,13 @@ impl PlatformApplicationTrait for PlatformApplication {
             };
 
             CURRENT_CURSOR = LoadCursorW(null_mut(), IDC_ARROW);
+            SCREENS = screens();
             RegisterClassW(&window_class);
 
             Self {
                 window_class_name,
                 h_instance,
+                custom_cursors: Vec::new(),
             }
         }
     }
@@ -105,6 +194,94 @@ impl PlatformApplicationTrait for PlatformApplication {
             SetWindowTextW(window_id.raw() as HWND, title.as_ptr());
         }
     }
+    fn set_window_icon(&mut self, window_id: WindowId, icon: &[Icon]) {
+        unsafe {
+            set_window_icon(window_id.raw() as HWND, icon);
+        }
+    }
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        unsafe {
+            update_window_style(window_id.raw() as HWND, |window_data| {
+                window_data.resizable = resizable
+            });
+        }
+    }
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        unsafe {
+            if let Some(window_data) = get_window_data(window_id.raw() as HWND) {
+                let (width, height) = size.unwrap_or((
+                    GetSystemMetrics(SM_CXMINTRACK) as u32,
+                    GetSystemMetrics(SM_CYMINTRACK) as u32,
+                ));
+                (*window_data).minimum_width = width;
+                (*window_data).minimum_height = height;
+            }
+        }
+    }
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        unsafe {
+            if let Some(window_data) = get_window_data(window_id.raw() as HWND) {
+                let (width, height) = size.unwrap_or((
+                    GetSystemMetrics(SM_CXMAXTRACK) as u32,
+                    GetSystemMetrics(SM_CYMAXTRACK) as u32,
+                ));
+                (*window_data).maximum_width = width;
+                (*window_data).maximum_height = height;
+            }
+        }
+    }
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        unsafe {
+            update_window_style(window_id.raw() as HWND, |window_data| {
+                window_data.decorations = decorations
+            });
+        }
+    }
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool) {
+        let insert_after = if always_on_top {
+            HWND_TOPMOST
+        } else {
+            HWND_NOTOPMOST
+        };
+        unsafe {
+            SetWindowPos(
+                window_id.raw() as HWND,
+                insert_after,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            );
+        }
+    }
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool) {
+        unsafe {
+            ShowWindow(
+                window_id.raw() as HWND,
+                if visible { SW_SHOW } else { SW_HIDE },
+            );
+        }
+    }
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32) {
+        unsafe {
+            update_layered_style(window_id.raw() as HWND, |window_data| {
+                window_data.opacity = opacity
+            });
+        }
+    }
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        unsafe {
+            update_layered_style(window_id.raw() as HWND, |window_data| {
+                window_data.input_region = region.map(|region| region.to_vec())
+            });
+        }
+        true
+    }
     fn minimize_window(&mut self, window_id: WindowId) {
         unsafe {
             ShowWindow(window_id.raw() as HWND, SW_MINIMIZE);
@@ -115,34 +292,74 @@ impl PlatformApplicationTrait for PlatformApplication {
             ShowWindow(window_id.raw() as HWND, SW_MAXIMIZE);
         }
     }
-    fn fullscreen_window(&mut self, window_id: WindowId) {
-        // This implementation is windowless fullscreen, not true fullscreen.
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
         unsafe {
             let hwnd = window_id.raw() as HWND;
-            let screen_width = GetSystemMetrics(SM_CXSCREEN);
-            let screen_height = GetSystemMetrics(SM_CYSCREEN);
-            SetWindowLongPtrW(hwnd, GWL_STYLE, (WS_VISIBLE | WS_POPUP).try_into().unwrap());
-            let mut rect = RECT {
-                left: 0,
-                top: 0,
-                right: 0,
-                bottom: 0,
+            let window_data = match get_window_data(hwnd) {
+                Some(window_data) => window_data,
+                None => return,
             };
-            GetWindowRect(window_id.raw() as HWND, &mut rect);
+            let monitor = match fullscreen {
+                Fullscreen::Off => {
+                    if (*window_data).fullscreen != Fullscreen::Off {
+                        self.restore_window(window_id);
+                    }
+                    return;
+                }
+                Fullscreen::Borderless(Some(screen_id)) => screen_id.raw() as HMONITOR,
+                Fullscreen::Borderless(None) => MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST),
+                Fullscreen::Exclusive(video_mode) => video_mode.screen_id.raw() as HMONITOR,
+            };
+            let fullscreen = match fullscreen {
+                Fullscreen::Borderless(None) => {
+                    Fullscreen::Borderless(Some(ScreenId::new(monitor as *mut std::ffi::c_void)))
+                }
+                fullscreen => fullscreen,
+            };
+
+            let old_fullscreen = std::mem::replace(&mut (*window_data).fullscreen, fullscreen);
+            if let Fullscreen::Exclusive(video_mode) = old_fullscreen {
+                restore_display_mode(video_mode.screen_id.raw() as HMONITOR);
+            }
+            if let Fullscreen::Exclusive(video_mode) = fullscreen {
+                set_display_mode(monitor, &video_mode);
+            }
+
+            // The monitor's rectangle is read after its mode changes.
+            // This is a borderless window covering the monitor.
+            let rect = match monitor_info(monitor) {
+                Some(info) => info.rcMonitor,
+                None => return,
+            };
+            SetWindowLongPtrW(hwnd, GWL_STYLE, (WS_VISIBLE | WS_POPUP).try_into().unwrap());
             MoveWindow(
-                window_id.raw() as HWND,
-                0,
-                0,
-                screen_width as i32,
-                screen_height as i32,
+                hwnd,
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
                 FALSE,
             );
+
+            event_receiver::send_event(Event::WindowFullscreened {
+                fullscreen,
+                window_id,
+            });
         }
     }
     fn restore_window(&mut self, window_id: WindowId) {
         unsafe {
             let hwnd = window_id.raw() as HWND;
-            let window_style = WS_OVERLAPPEDWINDOW | WS_VISIBLE | CS_OWNDC;
+            let mut window_style = WS_OVERLAPPEDWINDOW;
+            if let Some(window_data) = get_window_data(hwnd) {
+                let old_fullscreen =
+                    std::mem::replace(&mut (*window_data).fullscreen, Fullscreen::Off);
+                if let Fullscreen::Exclusive(video_mode) = old_fullscreen {
+                    restore_display_mode(video_mode.screen_id.raw() as HMONITOR);
+                }
+                window_style = (*window_data).window_style();
+            }
+            let window_style = window_style | WS_VISIBLE | CS_OWNDC;
             SetWindowLongPtrW(hwnd, GWL_STYLE, window_style.try_into().unwrap());
             ShowWindow(window_id.raw() as HWND, SW_RESTORE);
         }
@@ -178,6 +395,52 @@ impl PlatformApplicationTrait for PlatformApplication {
         dpi as f64 / USER_DEFAULT_SCREEN_DPI as f64
     }
 
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32) {
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        unsafe {
+            GetWindowRect(window_id.raw() as HWND, &mut rect);
+        }
+        (rect.left.max(0) as u32, rect.top.max(0) as u32)
+    }
+
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool {
+        unsafe { IsIconic(window_id.raw() as HWND) != FALSE }
+    }
+
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        unsafe { IsZoomed(window_id.raw() as HWND) != FALSE }
+    }
+
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        unsafe {
+            get_window_data(window_id.raw() as HWND)
+                .map_or(Fullscreen::Off, |window_data| (*window_data).fullscreen)
+        }
+    }
+
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        unsafe { GetFocus() == window_id.raw() as HWND }
+    }
+
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        unsafe { IsWindowVisible(window_id.raw() as HWND) != FALSE }
+    }
+
+    fn screens(&mut self) -> Vec<Screen> {
+        unsafe { screens() }
+    }
+
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        let monitor =
+            unsafe { MonitorFromWindow(window_id.raw() as HWND, MONITOR_DEFAULTTONEAREST) };
+        Some(ScreenId::new(monitor as *mut std::ffi::c_void))
+    }
+
     fn lock_mouse_position(&mut self) {
         unsafe {
             let mut position = POINT { x: 0, y: 0 };
@@ -201,7 +464,26 @@ impl PlatformApplicationTrait for PlatformApplication {
     fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
         unsafe {
             let extended_style = WS_EX_APPWINDOW;
-            let window_style = WS_OVERLAPPEDWINDOW | WS_VISIBLE | CS_OWNDC;
+            let (minimum_width, minimum_height) = window_parameters.minimum_size.unwrap_or((
+                GetSystemMetrics(SM_CXMINTRACK) as u32,
+                GetSystemMetrics(SM_CYMINTRACK) as u32,
+            ));
+            let (maximum_width, maximum_height) = window_parameters.maximum_size.unwrap_or((
+                GetSystemMetrics(SM_CXMAXTRACK) as u32,
+                GetSystemMetrics(SM_CYMAXTRACK) as u32,
+            ));
+            let window_data = Box::new(WindowData {
+                minimum_width,
+                minimum_height,
+                maximum_width,
+                maximum_height,
+                resizable: window_parameters.resizable,
+                decorations: !window_parameters.without_titlebar,
+                fullscreen: Fullscreen::Off,
+                opacity: 1.0,
+                input_region: None,
+            });
+            let window_style = window_data.window_style() | WS_VISIBLE | CS_OWNDC;
             let title = win32_string(&window_parameters.title);
 
             let (x, y) = if let Some(position) = window_parameters.position {
@@ -228,21 +510,6 @@ impl PlatformApplicationTrait for PlatformApplication {
                         (rect.right - rect.left, rect.bottom - rect.top)
                     });
 
-            let (minimum_width, minimum_height) = window_parameters.minimum_size.unwrap_or((
-                GetSystemMetrics(SM_CXMINTRACK) as u32,
-                GetSystemMetrics(SM_CYMINTRACK) as u32,
-            ));
-            let (maximum_width, maximum_height) = window_parameters.maximum_size.unwrap_or((
-                GetSystemMetrics(SM_CXMAXTRACK) as u32,
-                GetSystemMetrics(SM_CYMAXTRACK) as u32,
-            ));
-            let window_data = Box::new(WindowData {
-                minimum_width,
-                minimum_height,
-                maximum_width,
-                maximum_height,
-            });
-
             let data = Box::leak(window_data) as *mut WindowData as *mut std::ffi::c_void;
             let window_handle = CreateWindowExW(
                 extended_style,
@@ -258,6 +525,22 @@ impl PlatformApplicationTrait for PlatformApplication {
                 self.h_instance,
                 data,
             );
+            if !window_parameters.icon.is_empty() {
+                set_window_icon(window_handle, &window_parameters.icon);
+            }
+            if window_parameters.transparent {
+                // The compositor blends the window using the alpha of what's drawn to it.
+                // The blur region is empty so nothing is blurred.
+                let region = CreateRectRgn(0, 0, -1, -1);
+                let blur_behind = DWM_BLURBEHIND {
+                    dwFlags: DWM_BB_ENABLE | DWM_BB_BLURREGION,
+                    fEnable: TRUE,
+                    hRgnBlur: region,
+                    fTransitionOnMaximized: FALSE,
+                };
+                DwmEnableBlurBehindWindow(window_handle, &blur_behind);
+                DeleteObject(region as HGDIOBJ);
+            }
 
             let window_id = WindowId::new(window_handle as *mut std::ffi::c_void);
             // When a window is created immediately request that it should redraw
@@ -273,6 +556,27 @@ impl PlatformApplicationTrait for PlatformApplication {
         }
     }
 
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        control_flow::set_control_flow(control_flow);
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        timers::add_timer(duration, repeat)
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        timers::remove_timer(timer_id);
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        let thread_id = unsafe { GetCurrentThreadId() };
+        EventLoopProxy::new(move || unsafe {
+            // The message only wakes the event loop, which sends the proxy's events.
+            // Modal loops like resizing drop it, so events wait until the modal loop ends.
+            PostThreadMessageW(thread_id, WM_APP, 0, 0);
+        })
+    }
+
     fn set_cursor(&mut self, cursor: Cursor) {
         unsafe {
             // List of cursors here:
@@ -280,16 +584,30 @@ impl PlatformApplicationTrait for PlatformApplication {
             let cursor = match cursor {
                 Cursor::Arrow => LoadCursorW(null_mut(), IDC_ARROW),
                 Cursor::IBeam => LoadCursorW(null_mut(), IDC_IBEAM),
-
-                // There's no default for this on Windows
-                Cursor::PointingHand => LoadCursorW(null_mut(), IDC_ARROW),
-                Cursor::OpenHand => LoadCursorW(null_mut(), IDC_HAND),
-
-                // There's no default for this on Windows
-                Cursor::ClosedHand => LoadCursorW(null_mut(), IDC_HAND),
+                Cursor::PointingHand => LoadCursorW(null_mut(), IDC_HAND),
+
+                // There's no default for these on Windows
+                Cursor::OpenHand | Cursor::ClosedHand => LoadCursorW(null_mut(), IDC_HAND),
+
+                Cursor::ResizeNS => LoadCursorW(null_mut(), IDC_SIZENS),
+                Cursor::ResizeEW => LoadCursorW(null_mut(), IDC_SIZEWE),
+                Cursor::ResizeNESW => LoadCursorW(null_mut(), IDC_SIZENESW),
+                Cursor::ResizeNWSE => LoadCursorW(null_mut(), IDC_SIZENWSE),
+                Cursor::Crosshair => LoadCursorW(null_mut(), IDC_CROSS),
+                Cursor::NotAllowed => LoadCursorW(null_mut(), IDC_NO),
+                Cursor::Wait => LoadCursorW(null_mut(), IDC_WAIT),
+                Cursor::Progress => LoadCursorW(null_mut(), IDC_APPSTARTING),
+                Cursor::Move => LoadCursorW(null_mut(), IDC_SIZEALL),
+                Cursor::Help => LoadCursorW(null_mut(), IDC_HELP),
+
+                // There's no default for these on Windows
+                Cursor::ZoomIn | Cursor::ZoomOut => LoadCursorW(null_mut(), IDC_ARROW),
+
+                Cursor::Custom(custom_cursor) => custom_cursor.raw() as HCURSOR,
             };
 
-            SetCursor(super::application_windows::CURRENT_CURSOR);
+            CURRENT_CURSOR = cursor;
+            SetCursor(cursor);
 
             // This is a workaround.
             // The cursor doesn't immediately update because the WM_SETCURSOR event isn't
@@ -297,8 +615,25 @@ impl PlatformApplicationTrait for PlatformApplication {
             let mut position = POINT { x: 0, y: 0 };
             GetCursorPos(&mut position);
             SetCursorPos(position.x, position.y);
+        }
+    }
 
-            CURRENT_CURSOR = cursor;
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        unsafe {
+            let cursor = create_icon(rgba, width, height, Some(hotspot));
+            if cursor.is_null() {
+                // If the cursor couldn't be created the arrow is used instead.
+                CustomCursor::new(LoadCursorW(null_mut(), IDC_ARROW) as *mut std::ffi::c_void)
+            } else {
+                self.custom_cursors.push(cursor);
+                CustomCursor::new(cursor as *mut std::ffi::c_void)
+            }
         }
     }
     fn hide_cursor(&mut self) {
@@ -312,6 +647,29 @@ impl PlatformApplicationTrait for PlatformApplication {
         }
     }
 
+    fn clipboard_mime_types(&mut self, _selection: ClipboardSelection) -> Vec<String> {
+        // Clipboard access isn't implemented on Windows yet.
+        Vec::new()
+    }
+    fn get_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+    fn set_clipboard_data(
+        &mut self,
+        _selection: ClipboardSelection,
+        _mime_type: &str,
+        _data: &[u8],
+    ) {
+    }
+
+    fn set_drop_accepted(&mut self, _accepted: bool) {
+        // Drag and drop isn't implemented on Windows yet.
+    }
+
     fn raw_window_handle(&self, window_id: WindowId) -> RawWindowHandle {
         raw_window_handle::RawWindowHandle::Windows(raw_window_handle::windows::WindowsHandle {
             hwnd: unsafe { window_id.raw() },
@@ -338,11 +696,20 @@ impl PlatformApplicationTrait for PlatformApplication {
     ) {
         todo!()
     }
+
+    fn inject_event(&mut self, event: Event) {
+        event_receiver::send_event(event);
+    }
 }
 
 // When the application is dropped, quit the program.
 impl Drop for PlatformApplication {
     fn drop(&mut self) {
+        for cursor in self.custom_cursors.drain(..) {
+            unsafe {
+                DestroyCursor(cursor);
+            }
+        }
         self.quit();
     }
 }
@@ -1,10 +1,10 @@
 use super::application_windows::WindowData;
-use super::{external_windows::*, keys_windows::virtual_keycode_to_key};
+use super::{external_windows::*, keys_windows::*};
 use kapp_platform_common::*;
 
-use std::ptr::null_mut;
+use std::ptr::{null, null_mut};
 use std::convert::TryInto;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 pub static mut DBLCLICK_L: AtomicBool = AtomicBool::new(false);
 pub static mut DBLCLICK_M: AtomicBool = AtomicBool::new(false);
@@ -12,6 +12,9 @@ pub static mut DBLCLICK_R: AtomicBool = AtomicBool::new(false);
 pub static mut DBLCLICK_X1: AtomicBool = AtomicBool::new(false);
 pub static mut DBLCLICK_X2: AtomicBool = AtomicBool::new(false);
 
+// The last modifiers sent, used to send `ModifiersChanged`.
+static MODIFIERS: AtomicU8 = AtomicU8::new(0);
+
 pub unsafe extern "system" fn window_callback(
     hwnd: HWND,
     u_msg: UINT,
@@ -34,21 +37,26 @@ pub unsafe extern "system" fn window_callback(
             // Return 0 to reject the close because the user application must approve the close.
             return 0;
         }
-        WM_KEYDOWN | WM_SYSKEYDOWN => produce_event(process_key_down(w_param, l_param)),
-        WM_KEYUP | WM_SYSKEYUP => produce_event(process_key_up(w_param, l_param)),
+        WM_KEYDOWN | WM_SYSKEYDOWN => produce_event(process_key_down(hwnd, w_param, l_param)),
+        WM_KEYUP | WM_SYSKEYUP => produce_event(process_key_up(hwnd, w_param, l_param)),
         WM_CHAR => {
             let character = String::from_utf16(&[w_param as u16])
                 .unwrap()
                 .chars()
                 .next()
                 .unwrap();
-            produce_event(Event::CharacterReceived { character });
+            produce_event(Event::CharacterReceived {
+                character,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+            });
         }
         WM_IME_STARTCOMPOSITION => {
             return 0;
         }
         WM_IME_ENDCOMPOSITION => {
-            produce_event(Event::IMEEndComposition);
+            produce_event(Event::IMEEndComposition {
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+            });
             return 0;
         }
         WM_IME_COMPOSITION => {
@@ -63,7 +71,10 @@ pub unsafe extern "system" fn window_callback(
                     ImmGetCompositionStringW(himc, GCS_COMPSTR, buffer.as_mut_ptr().cast(), size_bytes as u32);
                     buffer.set_len(size_bytes as usize / std::mem::size_of::<u16>());
                     let composition = String::from_utf16(&buffer).unwrap();
-                    produce_event(Event::IMEComposition { composition });
+                    produce_event(Event::IMEComposition {
+                        composition,
+                        window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                    });
                 }
                 ImmReleaseContext(hwnd, himc);
                 return 0;
@@ -107,7 +118,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Primary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             *DBLCLICK_L.get_mut() = false;
@@ -119,7 +138,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Auxillary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             *DBLCLICK_M.get_mut() = false;
@@ -131,7 +158,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Secondary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             *DBLCLICK_R.get_mut() = false;
@@ -154,7 +189,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
         }
@@ -166,7 +209,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Primary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
 
@@ -175,6 +226,7 @@ pub unsafe extern "system" fn window_callback(
                     x: x as f64,
                     y: y as f64,
                     button: PointerButton::Primary,
+                    window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                     timestamp: get_message_time(),
                 });
             }
@@ -187,7 +239,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Auxillary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
 
@@ -196,6 +256,7 @@ pub unsafe extern "system" fn window_callback(
                     x: x as f64,
                     y: y as f64,
                     button: PointerButton::Auxillary,
+                    window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                     timestamp: get_message_time(),
                 });
             }
@@ -208,7 +269,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Secondary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
 
@@ -217,6 +286,7 @@ pub unsafe extern "system" fn window_callback(
                     x: x as f64,
                     y: y as f64,
                     button: PointerButton::Secondary,
+                    window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                     timestamp: get_message_time(),
                 });
             }
@@ -233,7 +303,15 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
 
@@ -246,6 +324,7 @@ pub unsafe extern "system" fn window_callback(
                     x: x as f64,
                     y: y as f64,
                     button,
+                    window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                     timestamp: get_message_time(),
                 });
             }
@@ -260,13 +339,22 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Primary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             produce_event(Event::DoubleClickDown {
                 x: x as f64,
                 y: y as f64,
                 button: PointerButton::Primary,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                 timestamp: get_message_time(),
             });
             *DBLCLICK_L.get_mut() = true;
@@ -280,13 +368,22 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Auxillary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             produce_event(Event::DoubleClickDown {
                 x: x as f64,
                 y: y as f64,
                 button: PointerButton::Auxillary,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                 timestamp: get_message_time(),
             });
             *DBLCLICK_M.get_mut() = true;
@@ -300,13 +397,22 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button: PointerButton::Secondary,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             produce_event(Event::DoubleClickDown {
                 x: x as f64,
                 y: y as f64,
                 button: PointerButton::Secondary,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                 timestamp: get_message_time(),
             });
             *DBLCLICK_R.get_mut() = true;
@@ -332,13 +438,22 @@ pub unsafe extern "system" fn window_callback(
                 x: x as f64,
                 y: y as f64,
                 source: PointerSource::Mouse,
+                pointer_id: 0,
                 button,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+                modifiers: update_modifiers(),
                 timestamp: get_message_time(),
             });
             produce_event(Event::DoubleClickDown {
                 x: x as f64,
                 y: y as f64,
                 button,
+                window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
                 timestamp: get_message_time(),
             });
         }
@@ -352,6 +467,9 @@ pub unsafe extern "system" fn window_callback(
                 window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
             });
         }
+        // Every top level window receives these, but only the first reports the changes.
+        WM_DISPLAYCHANGE => update_screens(),
+        WM_SETTINGCHANGE if w_param == SPI_SETWORKAREA as WPARAM => update_screens(),
         WM_GETMINMAXINFO => {
             // This is the first message sent to a new window.
             // But it does not seem like it's safe to assume that.
@@ -366,9 +484,38 @@ pub unsafe extern "system" fn window_callback(
                 (*min_max_info).ptMaxTrackSize.y = (*window_data).maximum_height as i32;
             }
         }
+        WM_NCHITTEST => {
+            // Mouse input outside the input region goes to the window beneath.
+            // Windows only passes it on to windows created by the same thread.
+            if let Some(window_data) = get_window_data(hwnd) {
+                if let Some(input_region) = &(*window_data).input_region {
+                    let hit = DefWindowProcW(hwnd, u_msg, w_param, l_param);
+                    let mut point = POINT {
+                        x: GET_X_LPARAM(l_param),
+                        y: GET_Y_LPARAM(l_param),
+                    };
+                    ScreenToClient(hwnd, &mut point);
+                    let inside = input_region.iter().any(|&(x, y, width, height)| {
+                        point.x >= x as i32
+                            && point.y >= y as i32
+                            && point.x < (x + width) as i32
+                            && point.y < (y + height) as i32
+                    });
+                    return if hit == HTCLIENT && !inside {
+                        HTTRANSPARENT
+                    } else {
+                        hit
+                    };
+                }
+            }
+        }
         WM_NCDESTROY => {
             // Deallocate data associated with this window.
-            let _ = Box::from_raw(GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut WindowData);
+            let window_data =
+                Box::from_raw(GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut WindowData);
+            if let Fullscreen::Exclusive(video_mode) = window_data.fullscreen {
+                super::utils_windows::restore_display_mode(video_mode.screen_id.raw() as HMONITOR);
+            }
         }
         _ => {}
     }
@@ -376,7 +523,7 @@ pub unsafe extern "system" fn window_callback(
     DefWindowProcW(hwnd, u_msg, w_param, l_param)
 }
 
-fn get_window_data(hwnd: HWND) -> Option<*mut WindowData> {
+pub(crate) fn get_window_data(hwnd: HWND) -> Option<*mut WindowData> {
     let data = unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut WindowData };
     if data == std::ptr::null_mut() {
         None
@@ -413,7 +560,11 @@ fn resize_event(hwnd: HWND, l_param: LPARAM, w_param: WPARAM) {
             neither the SIZE_MINIMIZED nor SIZE_MAXIMIZED value applies" */
             // While resizing the OS directly calls window_callback and does not call the typical event loop.
             // To redraw the window smoothly Event::Draw is passed in here.
+            // Borderless fullscreen windows are restored windows the size of the screen.
+            let fullscreen = get_window_data(hwnd)
+                .map_or(Fullscreen::Off, |window_data| unsafe { (*window_data).fullscreen });
             produce_event(Event::WindowRestored {
+                fullscreen,
                 window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
             });
             kapp_platform_common::redraw_manager::draw(WindowId::new(
@@ -424,12 +575,20 @@ fn resize_event(hwnd: HWND, l_param: LPARAM, w_param: WPARAM) {
     }
 }
 
+unsafe fn update_screens() {
+    let screens = super::utils_windows::screens();
+    let old_screens = std::mem::replace(&mut super::application_windows::SCREENS, screens);
+    for event in screen_changes(&old_screens, &super::application_windows::SCREENS) {
+        produce_event(event);
+    }
+}
+
 fn produce_event(event: Event) {
     event_receiver::send_event(event);
 }
 
 // https://docs.microsoft.com/en-us/windows/win32/inputdev/wm-mousemove
-fn process_mouse_move_event(_hwnd: HWND, l_param: LPARAM) -> Event {
+fn process_mouse_move_event(hwnd: HWND, l_param: LPARAM) -> Event {
     let x = GET_X_LPARAM(l_param);
     let y = GET_Y_LPARAM(l_param);
 
@@ -437,6 +596,13 @@ fn process_mouse_move_event(_hwnd: HWND, l_param: LPARAM) -> Event {
         x: x as f64,
         y: y as f64,
         source: PointerSource::Mouse,
+        pointer_id: 0,
+        pressure: 0.0,
+        tilt_x: 0.0,
+        tilt_y: 0.0,
+        contact_width: 0.0,
+        contact_height: 0.0,
+        window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
         timestamp: get_message_time(),
     }
 }
@@ -447,36 +613,84 @@ fn get_width_height(l_param: LPARAM) -> (u32, u32) {
     (width, height)
 }
 
-fn process_key_down(w_param: WPARAM, l_param: LPARAM) -> Event {
-    let (_scancode, key, repeat) = process_key_event(w_param, l_param);
+fn process_key_down(hwnd: HWND, w_param: WPARAM, l_param: LPARAM) -> Event {
+    let (scancode, key, logical_key, repeat) = process_key_event(w_param, l_param);
+    let modifiers = update_modifiers();
 
     if repeat {
         Event::KeyRepeat {
             key,
+            scancode,
+            logical_key,
+            window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+            modifiers,
             timestamp: get_message_time(),
         }
     } else {
         Event::KeyDown {
             key,
+            scancode,
+            logical_key,
+            window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+            modifiers,
             timestamp: get_message_time(),
         }
     }
 }
 
-fn process_key_up(w_param: WPARAM, l_param: LPARAM) -> Event {
-    let (_scancode, key, _repeat) = process_key_event(w_param, l_param);
+fn process_key_up(hwnd: HWND, w_param: WPARAM, l_param: LPARAM) -> Event {
+    let (scancode, key, logical_key, _repeat) = process_key_event(w_param, l_param);
     Event::KeyUp {
         key,
+        scancode,
+        logical_key,
+        window_id: WindowId::new(hwnd as *mut std::ffi::c_void),
+        modifiers: update_modifiers(),
         timestamp: get_message_time(),
     }
 }
 
-fn process_key_event(w_param: WPARAM, l_param: LPARAM) -> (UINT, Key, bool) {
-    let scancode = ((l_param >> 16) & 16) as UINT; // bits 16-23 represent the scancode
-    let _extended = (l_param >> 24) & 1 != 0; // bit 24 represents if its an extended key
+/// Gets the modifiers as of the message being processed.
+/// https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getkeystate
+fn get_modifiers() -> Modifiers {
+    // The high bit is set if the key is down and the low bit is set if the key is toggled on.
+    let down = |key| unsafe { GetKeyState(key) } as u16 & 0x8000 != 0;
+    let toggled = |key| unsafe { GetKeyState(key) } & 1 != 0;
+
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, down(VK_SHIFT));
+    modifiers.set(Modifiers::CONTROL, down(VK_CONTROL));
+    modifiers.set(Modifiers::ALT, down(VK_MENU));
+    modifiers.set(Modifiers::META, down(VK_LWIN) || down(VK_RWIN));
+    modifiers.set(Modifiers::CAPS_LOCK, toggled(VK_CAPITAL));
+    modifiers.set(Modifiers::NUM_LOCK, toggled(VK_NUMLOCK));
+    modifiers
+}
+
+/// Gets the current modifiers and sends `ModifiersChanged` if they're different from the last sent.
+fn update_modifiers() -> Modifiers {
+    let modifiers = get_modifiers();
+    if MODIFIERS.swap(modifiers.bits(), Ordering::Relaxed) != modifiers.bits() {
+        produce_event(Event::ModifiersChanged { modifiers });
+    }
+    modifiers
+}
+
+fn process_key_event(w_param: WPARAM, l_param: LPARAM) -> (UINT, Key, LogicalKey, bool) {
+    let mut scancode = ((l_param >> 16) & 0xFF) as UINT; // bits 16-23 represent the scancode
+    let extended = (l_param >> 24) & 1 != 0; // bit 24 represents if its an extended key
+    if extended {
+        scancode |= 0xE000;
+    }
     let repeat = (l_param >> 30) & 1 == 1;
-    let key = virtual_keycode_to_key(w_param as _);
-    (scancode, key, repeat)
+
+    // Keys without a scancode, like some media keys, use their virtual key instead.
+    let key = match scancode_to_key(scancode) {
+        Key::Unknown => virtual_keycode_to_key(w_param as _),
+        key => key,
+    };
+    let logical_key = virtual_keycode_to_logical_key(w_param as _);
+    (scancode, key, logical_key, repeat)
 }
 
 pub fn run(callback: Box<dyn FnMut(kapp_platform_common::Event)>) {
@@ -486,9 +700,12 @@ pub fn run(callback: Box<dyn FnMut(kapp_platform_common::Event)>) {
         let mut message: MSG = std::mem::zeroed();
 
         while message.message != WM_QUIT {
-            // Block and wait for messages unless there is a redraw request.
+            // Block and wait for messages unless there is a redraw request,
+            // the control flow limits how long to wait, or a proxy has sent events.
             // GetMessageW will return 0 if WM_QUIT is encountered
             while redraw_manager::draw_requests_count() == 0
+                && control_flow::timeout().is_none()
+                && !event_loop_proxy::proxy_events_pending()
                 && GetMessageW(&mut message, null_mut(), 0, 0) > 0
             {
                 TranslateMessage(&message as *const MSG);
@@ -499,12 +716,32 @@ pub fn run(callback: Box<dyn FnMut(kapp_platform_common::Event)>) {
                 break;
             }
 
-            // We only reach here if there are redraw requests.
+            // Wait until a message arrives or the control flow's deadline passes.
+            if redraw_manager::draw_requests_count() == 0
+                && !event_loop_proxy::proxy_events_pending()
+            {
+                if let Some(timeout) = control_flow::timeout() {
+                    MsgWaitForMultipleObjects(
+                        0,
+                        null(),
+                        FALSE,
+                        control_flow::timeout_milliseconds(timeout) as DWORD,
+                        QS_ALLINPUT,
+                    );
+                }
+            }
+
             // Iterate through all messages without blocking.
             while PeekMessageW(&mut message, null_mut(), 0, 0, PM_REMOVE) > 0 {
                 TranslateMessage(&message as *const MSG);
                 DispatchMessageW(&message as *const MSG);
             }
+            event_loop_proxy::send_proxy_events();
+            control_flow::resume_if_reached();
+            timers::send_due_timers();
+
+            // When there are no messages remaining, we're at the end of the event loop
+            produce_event(Event::EventsCleared);
 
             redraw_manager::begin_draw_flush();
             while let Some(window_id) = redraw_manager::get_draw_request() {
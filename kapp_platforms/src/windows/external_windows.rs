@@ -107,6 +107,8 @@ pub type LONG_PTR = isize;
 pub const FALSE: BOOL = 0;
 pub const TRUE: BOOL = 1;
 pub type DWORD = c_ulong;
+pub type BYTE = u8;
+pub type COLORREF = DWORD;
 pub type BOOL = c_int;
 pub type WORD = c_ushort;
 pub type LPVOID = *mut std::ffi::c_void;
@@ -133,6 +135,10 @@ DECLARE_HANDLE! {HWND, HWND__}
 DECLARE_HANDLE! {HICON, HICON__}
 DECLARE_HANDLE! {HMENU, HMENU__}
 DECLARE_HANDLE! {HBRUSH, HBRUSH__}
+DECLARE_HANDLE! {HBITMAP, HBITMAP__}
+DECLARE_HANDLE! {HMONITOR, HMONITOR__}
+DECLARE_HANDLE! {HDC, HDC__}
+DECLARE_HANDLE! {HRGN, HRGN__}
 
 DECLARE_HANDLE! {HIMC, HIMC__}
 
@@ -184,6 +190,11 @@ extern "system" {
     pub fn GetModuleHandleW(lpModuleName: LPCWSTR) -> HMODULE;
 }
 
+// Copied from https://github.com/retep998/winapi-rs/blob/0.3/src/um/processthreadsapi.rs
+extern "system" {
+    pub fn GetCurrentThreadId() -> DWORD;
+}
+
 // Copied from https://github.com/retep998/winapi-rs/blob/0.3/src/um/winuser.rs
 FN! {stdcall WNDPROC(
     HWND,
@@ -226,14 +237,36 @@ extern "system" {
         lpParam: LPVOID,
     ) -> HWND;
     pub fn ShowWindow(hWnd: HWND, nCmdShow: c_int) -> BOOL;
+    pub fn SetWindowPos(
+        hWnd: HWND,
+        hWndInsertAfter: HWND,
+        X: c_int,
+        Y: c_int,
+        cx: c_int,
+        cy: c_int,
+        uFlags: UINT,
+    ) -> BOOL;
+    pub fn SetLayeredWindowAttributes(
+        hwnd: HWND,
+        crKey: COLORREF,
+        bAlpha: BYTE,
+        dwFlags: DWORD,
+    ) -> BOOL;
+    pub fn ScreenToClient(hWnd: HWND, lpPoint: LPPOINT) -> BOOL;
     pub fn DefWindowProcW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> LRESULT;
     pub fn PostQuitMessage(nExitCode: c_int);
     pub fn DispatchMessageW(lpmsg: *const MSG) -> LRESULT;
     pub fn GetCursorPos(lpPoint: LPPOINT) -> BOOL;
     pub fn GetMessageTime() -> LONG;
+    pub fn GetKeyState(nVirtKey: c_int) -> c_short;
+    pub fn MapVirtualKeyW(uCode: UINT, uMapType: UINT) -> UINT;
     pub fn GetSystemMetrics(nIndex: c_int) -> c_int;
     pub fn GetWindowRect(hWnd: HWND, lpRect: LPRECT) -> BOOL;
     pub fn GetClientRect(hWnd: HWND, lpRect: LPRECT) -> BOOL;
+    pub fn IsIconic(hWnd: HWND) -> BOOL;
+    pub fn IsZoomed(hWnd: HWND) -> BOOL;
+    pub fn IsWindowVisible(hWnd: HWND) -> BOOL;
+    pub fn GetFocus() -> HWND;
     pub fn LoadCursorW(hInstance: HINSTANCE, lpCursorName: LPCWSTR) -> HCURSOR;
     pub fn PeekMessageW(
         lpMsg: LPMSG,
@@ -242,7 +275,15 @@ extern "system" {
         wMsgFilterMax: UINT,
         wRemoveMsg: UINT,
     ) -> BOOL;
+    pub fn MsgWaitForMultipleObjects(
+        nCount: DWORD,
+        pHandles: *const HANDLE,
+        fWaitAll: BOOL,
+        dwMilliseconds: DWORD,
+        dwWakeMask: DWORD,
+    ) -> DWORD;
     pub fn GetMessageW(lpMsg: LPMSG, hWnd: HWND, wMsgFilterMin: UINT, wMsgFilterMax: UINT) -> BOOL;
+    pub fn PostThreadMessageW(idThread: DWORD, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> BOOL;
     pub fn RegisterClassW(lpWndClass: *const WNDCLASSW) -> ATOM;
     pub fn SetCursor(hCursor: HCURSOR) -> HCURSOR;
     pub fn SetCursorPos(X: c_int, Y: c_int) -> BOOL;
@@ -260,8 +301,104 @@ extern "system" {
     pub fn GetDpiForWindow(hwnd: HWND) -> UINT;
 
     pub fn ClipCursor(lpRect: *const RECT);
+    pub fn CreateIconIndirect(piconinfo: *const ICONINFO) -> HICON;
+    pub fn DestroyCursor(hCursor: HCURSOR) -> BOOL;
+    pub fn DestroyIcon(hIcon: HICON) -> BOOL;
+    pub fn SendMessageW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> LRESULT;
+    pub fn EnumDisplayMonitors(
+        hdc: HDC,
+        lprcClip: *const RECT,
+        lpfnEnum: MONITORENUMPROC,
+        dwData: LPARAM,
+    ) -> BOOL;
+    pub fn GetMonitorInfoW(hMonitor: HMONITOR, lpmi: *mut MONITORINFOEXW) -> BOOL;
+    pub fn MonitorFromWindow(hwnd: HWND, dwFlags: DWORD) -> HMONITOR;
+    pub fn EnumDisplaySettingsW(
+        lpszDeviceName: LPCWSTR,
+        iModeNum: DWORD,
+        lpDevMode: *mut DEVMODEW,
+    ) -> BOOL;
+    pub fn ChangeDisplaySettingsExW(
+        lpszDeviceName: LPCWSTR,
+        lpDevMode: *mut DEVMODEW,
+        hwnd: HWND,
+        dwflags: DWORD,
+        lParam: LPVOID,
+    ) -> LONG;
+}
+
+FN! {stdcall MONITORENUMPROC(
+    HMONITOR,
+    HDC,
+    LPRECT,
+    LPARAM,
+) -> BOOL}
+
+pub const CCHDEVICENAME: usize = 32;
+pub const MONITORINFOF_PRIMARY: DWORD = 1;
+pub const MONITOR_DEFAULTTONEAREST: DWORD = 2;
+pub const ENUM_CURRENT_SETTINGS: DWORD = 0xFFFFFFFF;
+pub const CDS_FULLSCREEN: DWORD = 0x00000004;
+pub const DISP_CHANGE_SUCCESSFUL: LONG = 0;
+
+STRUCT! {struct MONITORINFOEXW {
+    cbSize: DWORD,
+    rcMonitor: RECT,
+    rcWork: RECT,
+    dwFlags: DWORD,
+    szDevice: [WCHAR; CCHDEVICENAME],
+}}
+
+// The union after dmFields is declared as its display variant.
+STRUCT! {struct DEVMODEW {
+    dmDeviceName: [WCHAR; 32],
+    dmSpecVersion: WORD,
+    dmDriverVersion: WORD,
+    dmSize: WORD,
+    dmDriverExtra: WORD,
+    dmFields: DWORD,
+    dmPosition: POINT,
+    dmDisplayOrientation: DWORD,
+    dmDisplayFixedOutput: DWORD,
+    dmColor: c_short,
+    dmDuplex: c_short,
+    dmYResolution: c_short,
+    dmTTOption: c_short,
+    dmCollate: c_short,
+    dmFormName: [WCHAR; 32],
+    dmLogPixels: WORD,
+    dmBitsPerPel: DWORD,
+    dmPelsWidth: DWORD,
+    dmPelsHeight: DWORD,
+    dmDisplayFlags: DWORD,
+    dmDisplayFrequency: DWORD,
+    dmICMMethod: DWORD,
+    dmICMIntent: DWORD,
+    dmMediaType: DWORD,
+    dmDitherType: DWORD,
+    dmReserved1: DWORD,
+    dmReserved2: DWORD,
+    dmPanningWidth: DWORD,
+    dmPanningHeight: DWORD,
+}}
+
+// Copied from https://github.com/retep998/winapi-rs/blob/0.3/src/um/wingdi.rs
+#[link(name = "gdi32")]
+extern "system" {
+    pub fn CreateBitmap(
+        nWidth: c_int,
+        nHeight: c_int,
+        nPlanes: UINT,
+        nBitCount: UINT,
+        lpBits: *const std::ffi::c_void,
+    ) -> HBITMAP;
+    pub fn DeleteObject(ho: HGDIOBJ) -> BOOL;
+    pub fn CreateRectRgn(x1: c_int, y1: c_int, x2: c_int, y2: c_int) -> HRGN;
 }
 
+pub type HGDIOBJ = *mut std::ffi::c_void;
+pub type HANDLE = *mut std::ffi::c_void;
+
 #[link(name = "Imm32")]
 extern "system" {
     pub fn ImmGetContext(Arg1: HWND) -> HIMC;
@@ -283,13 +420,37 @@ pub use self::GetWindowLongW as GetWindowLongPtrW;
 #[link(name = "Shcore")]
 extern "system" {
     pub fn SetProcessDpiAwareness(value: PROCESS_DPI_AWARENESS) -> HRESULT;
+    pub fn GetDpiForMonitor(
+        hmonitor: HMONITOR,
+        dpiType: MONITOR_DPI_TYPE,
+        dpiX: *mut UINT,
+        dpiY: *mut UINT,
+    ) -> HRESULT;
 }
 
 pub const USER_DEFAULT_SCREEN_DPI: c_long = 96;
 pub const PROCESS_PER_MONITOR_DPI_AWARE: PROCESS_DPI_AWARENESS = 2;
 type PROCESS_DPI_AWARENESS = u32;
+pub const MDT_EFFECTIVE_DPI: MONITOR_DPI_TYPE = 0;
+type MONITOR_DPI_TYPE = u32;
 type HRESULT = c_long;
 
+// Copied from https://github.com/retep998/winapi-rs/blob/0.3/src/um/dwmapi.rs
+#[link(name = "dwmapi")]
+extern "system" {
+    pub fn DwmEnableBlurBehindWindow(hWnd: HWND, pBlurBehind: *const DWM_BLURBEHIND) -> HRESULT;
+}
+
+STRUCT! {struct DWM_BLURBEHIND {
+    dwFlags: DWORD,
+    fEnable: BOOL,
+    hRgnBlur: HRGN,
+    fTransitionOnMaximized: BOOL,
+}}
+
+pub const DWM_BB_ENABLE: DWORD = 0x00000001;
+pub const DWM_BB_BLURREGION: DWORD = 0x00000002;
+
 STRUCT! {struct MSG {
     hwnd: HWND,
     message: UINT,
@@ -312,6 +473,14 @@ STRUCT! {struct WNDCLASSW {
     lpszClassName: LPCWSTR,
 }}
 
+STRUCT! {struct ICONINFO {
+    fIcon: BOOL,
+    xHotspot: DWORD,
+    yHotspot: DWORD,
+    hbmMask: HBITMAP,
+    hbmColor: HBITMAP,
+}}
+
 STRUCT! {struct MINMAXINFO {
     ptReserved: POINT,
     ptMaxSize: POINT,
@@ -336,19 +505,34 @@ pub const WS_OVERLAPPEDWINDOW: DWORD =
     WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU | WS_THICKFRAME | WS_MINIMIZEBOX | WS_MAXIMIZEBOX;
 
 pub const WS_EX_APPWINDOW: DWORD = 0x00040000;
+pub const WS_EX_LAYERED: DWORD = 0x00080000;
+pub const WS_EX_TRANSPARENT: DWORD = 0x00000020;
+pub const LWA_ALPHA: DWORD = 0x00000002;
 pub const CS_OWNDC: UINT = 0x0020;
 pub const CS_DBLCLKS: UINT = 0x0008;
 
 #[allow(overflowing_literals)]
 pub const CW_USEDEFAULT: c_int = 0x80000000;
 pub const GWL_STYLE: c_int = -16;
+pub const GWL_EXSTYLE: c_int = -20;
 pub const GWLP_USERDATA: c_int = -21;
 
 pub const IDC_ARROW: LPCWSTR = 32512 as LPCWSTR;
 pub const IDC_IBEAM: LPCWSTR = 32513 as LPCWSTR;
+pub const IDC_WAIT: LPCWSTR = 32514 as LPCWSTR;
+pub const IDC_CROSS: LPCWSTR = 32515 as LPCWSTR;
+pub const IDC_SIZENWSE: LPCWSTR = 32642 as LPCWSTR;
+pub const IDC_SIZENESW: LPCWSTR = 32643 as LPCWSTR;
+pub const IDC_SIZEWE: LPCWSTR = 32644 as LPCWSTR;
+pub const IDC_SIZENS: LPCWSTR = 32645 as LPCWSTR;
+pub const IDC_SIZEALL: LPCWSTR = 32646 as LPCWSTR;
+pub const IDC_NO: LPCWSTR = 32648 as LPCWSTR;
 pub const IDC_HAND: LPCWSTR = 32649 as LPCWSTR;
+pub const IDC_APPSTARTING: LPCWSTR = 32650 as LPCWSTR;
+pub const IDC_HELP: LPCWSTR = 32651 as LPCWSTR;
 
 pub const PM_REMOVE: UINT = 0x0001;
+pub const QS_ALLINPUT: DWORD = 0x04FF;
 
 pub const SM_CXSCREEN: c_int = 0;
 pub const SM_CYSCREEN: c_int = 1;
@@ -356,14 +540,30 @@ pub const SM_CXMINTRACK: c_int = 34;
 pub const SM_CYMINTRACK: c_int = 35;
 pub const SM_CXMAXTRACK: c_int = 59;
 pub const SM_CYMAXTRACK: c_int = 60;
+pub const SM_CXICON: c_int = 11;
+pub const SM_CXSMICON: c_int = 49;
+
+pub const ICON_SMALL: WPARAM = 0;
+pub const ICON_BIG: WPARAM = 1;
 
 pub const SIZE_RESTORED: WPARAM = 0;
 pub const SIZE_MINIMIZED: WPARAM = 1;
 pub const SIZE_MAXIMIZED: WPARAM = 2;
 
+pub const SW_HIDE: c_int = 0;
 pub const SW_MAXIMIZE: c_int = 3;
+pub const SW_SHOW: c_int = 5;
 pub const SW_MINIMIZE: c_int = 6;
 pub const SW_RESTORE: c_int = 9;
+
+pub const HWND_TOPMOST: HWND = -1 as isize as HWND;
+pub const HWND_NOTOPMOST: HWND = -2 as isize as HWND;
+pub const SWP_NOSIZE: UINT = 0x0001;
+pub const SWP_NOMOVE: UINT = 0x0002;
+pub const SWP_NOZORDER: UINT = 0x0004;
+pub const SWP_NOACTIVATE: UINT = 0x0010;
+pub const SWP_FRAMECHANGED: UINT = 0x0020;
+pub const MAPVK_VK_TO_CHAR: UINT = 2;
 pub const VK_CANCEL: c_int = 0x03;
 pub const VK_BACK: c_int = 0x08;
 pub const VK_TAB: c_int = 0x09;
@@ -500,6 +700,7 @@ pub const WM_PAINT: UINT = 0x000F;
 pub const WM_CLOSE: UINT = 0x0010;
 pub const WM_QUIT: UINT = 0x0012;
 pub const WM_SETCURSOR: UINT = 0x0020;
+pub const WM_SETICON: UINT = 0x0080;
 
 pub const WM_ENTERSIZEMOVE: UINT = 0x0231;
 pub const WM_EXITSIZEMOVE: UINT = 0x0232;
@@ -527,10 +728,17 @@ pub const WM_MBUTTONDBLCLK: UINT = 0x0209;
 pub const WM_RBUTTONDBLCLK: UINT = 0x0206;
 pub const WM_XBUTTONDBLCLK: UINT = 0x020D;
 pub const WM_DPICHANGED: UINT = 0x02E0;
+pub const WM_DISPLAYCHANGE: UINT = 0x007E;
+pub const WM_SETTINGCHANGE: UINT = 0x001A;
+pub const SPI_SETWORKAREA: UINT = 0x002F;
 pub const WM_NCDESTROY: UINT = 0x0082;
+pub const WM_NCHITTEST: UINT = 0x0084;
+pub const HTTRANSPARENT: LRESULT = -1;
+pub const HTCLIENT: LRESULT = 1;
 pub const WM_GETMINMAXINFO: UINT = 0x0024;
 //pub const WM_NCCREATE: UINT = 0x0081;
 pub const WM_CREATE: UINT = 0x0001;
+pub const WM_APP: UINT = 0x8000;
 
 pub const XBUTTON1: WORD = 0x0001;
 pub const XBUTTON2: WORD = 0x0002;
@@ -1,7 +1,223 @@
+use super::external_windows::*;
+use kapp_platform_common::{closest_icon, Icon, Screen, ScreenId, VideoMode};
 use std::ffi::OsStr;
 use std::iter::once;
 use std::os::windows::prelude::*;
+use std::ptr::null_mut;
 
 pub fn win32_string(value: &str) -> Vec<u16> {
     OsStr::new(value).encode_wide().chain(once(0)).collect()
 }
+
+/// Creates an icon from RGBA pixels, or a cursor if a hotspot is given.
+/// Returns null if it couldn't be created.
+pub unsafe fn create_icon(
+    rgba: &[u8],
+    width: u32,
+    height: u32,
+    cursor_hotspot: Option<(u32, u32)>,
+) -> HICON {
+    // The color bitmap's pixels are BGRA, and its alpha is used instead of the mask.
+    // Rows of the mask must be a multiple of 2 bytes.
+    let mut bgra = rgba.to_vec();
+    for pixel in bgra.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let mask = vec![0u8; ((width as usize + 15) / 16) * 2 * height as usize];
+    let (x_hotspot, y_hotspot) = cursor_hotspot.unwrap_or((0, 0));
+    let icon_info = ICONINFO {
+        fIcon: cursor_hotspot.is_none() as BOOL,
+        xHotspot: x_hotspot,
+        yHotspot: y_hotspot,
+        hbmMask: CreateBitmap(width as i32, height as i32, 1, 1, mask.as_ptr() as _),
+        hbmColor: CreateBitmap(width as i32, height as i32, 1, 32, bgra.as_ptr() as _),
+    };
+    let icon = CreateIconIndirect(&icon_info);
+
+    // The icon has its own copy of the bitmaps.
+    DeleteObject(icon_info.hbmMask as HGDIOBJ);
+    DeleteObject(icon_info.hbmColor as HGDIOBJ);
+    icon
+}
+
+/// Sets the window's title bar and taskbar icons from the closest sizes.
+pub unsafe fn set_window_icon(window: HWND, icon: &[Icon]) {
+    for (kind, size) in &[(ICON_SMALL, SM_CXSMICON), (ICON_BIG, SM_CXICON)] {
+        let new_icon = closest_icon(icon, GetSystemMetrics(*size) as u32)
+            .map_or(null_mut(), |icon| {
+                create_icon(&icon.data, icon.width, icon.height, None)
+            });
+        // The previous icon is returned so it can be destroyed.
+        let previous_icon = SendMessageW(window, WM_SETICON, *kind, new_icon as LPARAM) as HICON;
+        if !previous_icon.is_null() {
+            DestroyIcon(previous_icon);
+        }
+    }
+}
+
+/// Returns the monitors with the primary monitor first.
+pub unsafe fn screens() -> Vec<Screen> {
+    unsafe extern "system" fn add_monitor(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        let monitors = &mut *(data as *mut Vec<HMONITOR>);
+        monitors.push(monitor);
+        TRUE
+    }
+
+    let mut monitors: Vec<HMONITOR> = Vec::new();
+    EnumDisplayMonitors(
+        null_mut(),
+        std::ptr::null(),
+        Some(add_monitor),
+        &mut monitors as *mut Vec<HMONITOR> as LPARAM,
+    );
+
+    let mut screens = Vec::new();
+    for monitor in monitors {
+        let info = match monitor_info(monitor) {
+            Some(info) => info,
+            None => continue,
+        };
+        let device_name_length = info
+            .szDevice
+            .iter()
+            .position(|c| *c == 0)
+            .unwrap_or(CCHDEVICENAME);
+        let name = String::from_utf16_lossy(&info.szDevice[..device_name_length]);
+
+        let mut dpi_x = 0;
+        let mut dpi_y = 0;
+        let scale = if GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) == 0 {
+            dpi_x as f64 / USER_DEFAULT_SCREEN_DPI as f64
+        } else {
+            1.0
+        };
+
+        let mut mode: DEVMODEW = std::mem::zeroed();
+        mode.dmSize = std::mem::size_of::<DEVMODEW>() as WORD;
+        // Frequencies of 0 and 1 mean the hardware's default.
+        let refresh_rate =
+            if EnumDisplaySettingsW(info.szDevice.as_ptr(), ENUM_CURRENT_SETTINGS, &mut mode)
+                != FALSE
+                && mode.dmDisplayFrequency > 1
+            {
+                Some(mode.dmDisplayFrequency as f64)
+            } else {
+                None
+            };
+
+        let id = ScreenId::new(monitor as *mut std::ffi::c_void);
+        let mut video_modes = Vec::new();
+        for mode in display_modes(&info.szDevice) {
+            let video_mode = VideoMode {
+                screen_id: id,
+                size: (mode.dmPelsWidth, mode.dmPelsHeight),
+                refresh_rate: mode.dmDisplayFrequency as f64,
+                bit_depth: mode.dmBitsPerPel,
+            };
+            // Modes that only differ in scaling or orientation are listed more than once.
+            if !video_modes.contains(&video_mode) {
+                video_modes.push(video_mode);
+            }
+        }
+
+        let rect = info.rcMonitor;
+        let work = info.rcWork;
+        let screen = Screen {
+            id,
+            name,
+            position: (rect.left, rect.top),
+            size: (
+                (rect.right - rect.left) as u32,
+                (rect.bottom - rect.top) as u32,
+            ),
+            work_area_position: (work.left, work.top),
+            work_area_size: (
+                (work.right - work.left) as u32,
+                (work.bottom - work.top) as u32,
+            ),
+            scale,
+            refresh_rate,
+            video_modes,
+        };
+        if info.dwFlags & MONITORINFOF_PRIMARY != 0 {
+            screens.insert(0, screen);
+        } else {
+            screens.push(screen);
+        }
+    }
+    screens
+}
+
+pub unsafe fn monitor_info(monitor: HMONITOR) -> Option<MONITORINFOEXW> {
+    let mut info: MONITORINFOEXW = std::mem::zeroed();
+    info.cbSize = std::mem::size_of::<MONITORINFOEXW>() as DWORD;
+    if GetMonitorInfoW(monitor, &mut info) == FALSE {
+        None
+    } else {
+        Some(info)
+    }
+}
+
+/// Returns every mode the display device supports.
+pub unsafe fn display_modes(device_name: &[WCHAR]) -> Vec<DEVMODEW> {
+    let mut modes = Vec::new();
+    loop {
+        let mut mode: DEVMODEW = std::mem::zeroed();
+        mode.dmSize = std::mem::size_of::<DEVMODEW>() as WORD;
+        if EnumDisplaySettingsW(device_name.as_ptr(), modes.len() as DWORD, &mut mode) == FALSE {
+            return modes;
+        }
+        modes.push(mode);
+    }
+}
+
+/// Switches the monitor to the closest display mode to `video_mode` until it's restored
+/// with `restore_display_mode`. Returns false if the mode couldn't be changed.
+pub unsafe fn set_display_mode(monitor: HMONITOR, video_mode: &VideoMode) -> bool {
+    let info = match monitor_info(monitor) {
+        Some(info) => info,
+        None => return false,
+    };
+    let refresh_difference =
+        |mode: &DEVMODEW| (mode.dmDisplayFrequency as f64 - video_mode.refresh_rate).abs();
+    let mode = display_modes(&info.szDevice)
+        .into_iter()
+        .filter(|mode| (mode.dmPelsWidth, mode.dmPelsHeight) == video_mode.size)
+        .filter(|mode| mode.dmBitsPerPel == video_mode.bit_depth)
+        .min_by(|a, b| {
+            refresh_difference(a)
+                .partial_cmp(&refresh_difference(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    match mode {
+        // CDS_FULLSCREEN makes the change temporary, so the registry's mode returns when the program exits.
+        Some(mut mode) => {
+            ChangeDisplaySettingsExW(
+                info.szDevice.as_ptr(),
+                &mut mode,
+                null_mut(),
+                CDS_FULLSCREEN,
+                null_mut(),
+            ) == DISP_CHANGE_SUCCESSFUL
+        }
+        None => false,
+    }
+}
+
+/// Returns the monitor to the display mode stored in the registry.
+pub unsafe fn restore_display_mode(monitor: HMONITOR) {
+    if let Some(info) = monitor_info(monitor) {
+        ChangeDisplaySettingsExW(
+            info.szDevice.as_ptr(),
+            null_mut(),
+            null_mut(),
+            0,
+            null_mut(),
+        );
+    }
+}
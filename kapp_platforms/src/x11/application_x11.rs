@@ -0,0 +1,1229 @@
+use super::clipboard_x11::{self, Clipboard};
+use super::drag_drop_x11::{self, Drag};
+use super::external_x11::*;
+use super::screens_x11::{self, Screens};
+use crate::linux::cursors_linux::cursor_names;
+use crate::linux::wake_pipe_linux::WakePipe;
+use kapp_platform_common::*;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString};
+use std::ptr::{null, null_mut};
+use std::time::Duration;
+
+thread_local!(pub(crate) static APPLICATION_DATA: RefCell<Option<ApplicationData>> = const { RefCell::new(None) });
+
+/// Borrows the application data.
+/// Events must not be sent while the data is borrowed because the user callback
+/// may call back into the application.
+pub(crate) fn with_data<R>(f: impl FnOnce(&mut ApplicationData) -> R) -> R {
+    APPLICATION_DATA.with(|d| f(d.borrow_mut().as_mut().expect("X11 is not initialized")))
+}
+
+pub(crate) struct Atoms {
+    pub wm_protocols: Atom,
+    pub wm_delete_window: Atom,
+    pub net_wm_name: Atom,
+    pub net_wm_icon: Atom,
+    pub net_workarea: Atom,
+    pub utf8_string: Atom,
+    pub net_wm_state: Atom,
+    pub net_wm_state_maximized_vert: Atom,
+    pub net_wm_state_maximized_horz: Atom,
+    pub net_wm_state_fullscreen: Atom,
+    pub net_wm_state_hidden: Atom,
+    pub net_wm_state_above: Atom,
+    pub net_wm_window_opacity: Atom,
+    pub motif_wm_hints: Atom,
+    pub clipboard: Atom,
+    pub targets: Atom,
+    pub incr: Atom,
+    pub text: Atom,
+    /// The property other programs write clipboard data to.
+    pub kapp_selection: Atom,
+    pub xdnd_aware: Atom,
+    pub xdnd_enter: Atom,
+    pub xdnd_position: Atom,
+    pub xdnd_status: Atom,
+    pub xdnd_leave: Atom,
+    pub xdnd_drop: Atom,
+    pub xdnd_finished: Atom,
+    pub xdnd_selection: Atom,
+    pub xdnd_type_list: Atom,
+    pub xdnd_action_copy: Atom,
+    pub text_uri_list: Atom,
+    pub text_plain: Atom,
+    pub text_plain_utf8: Atom,
+}
+
+impl Atoms {
+    unsafe fn new(xlib: &Xlib, display: *mut Display) -> Self {
+        let atom = |name: &[u8]| (xlib.XInternAtom)(display, name.as_ptr() as *const c_char, False);
+        Self {
+            wm_protocols: atom(b"WM_PROTOCOLS\0"),
+            wm_delete_window: atom(b"WM_DELETE_WINDOW\0"),
+            net_wm_name: atom(b"_NET_WM_NAME\0"),
+            net_wm_icon: atom(b"_NET_WM_ICON\0"),
+            net_workarea: atom(b"_NET_WORKAREA\0"),
+            utf8_string: atom(b"UTF8_STRING\0"),
+            net_wm_state: atom(b"_NET_WM_STATE\0"),
+            net_wm_state_maximized_vert: atom(b"_NET_WM_STATE_MAXIMIZED_VERT\0"),
+            net_wm_state_maximized_horz: atom(b"_NET_WM_STATE_MAXIMIZED_HORZ\0"),
+            net_wm_state_fullscreen: atom(b"_NET_WM_STATE_FULLSCREEN\0"),
+            net_wm_state_hidden: atom(b"_NET_WM_STATE_HIDDEN\0"),
+            net_wm_state_above: atom(b"_NET_WM_STATE_ABOVE\0"),
+            net_wm_window_opacity: atom(b"_NET_WM_WINDOW_OPACITY\0"),
+            motif_wm_hints: atom(b"_MOTIF_WM_HINTS\0"),
+            clipboard: atom(b"CLIPBOARD\0"),
+            targets: atom(b"TARGETS\0"),
+            incr: atom(b"INCR\0"),
+            text: atom(b"TEXT\0"),
+            kapp_selection: atom(b"KAPP_SELECTION\0"),
+            xdnd_aware: atom(b"XdndAware\0"),
+            xdnd_enter: atom(b"XdndEnter\0"),
+            xdnd_position: atom(b"XdndPosition\0"),
+            xdnd_status: atom(b"XdndStatus\0"),
+            xdnd_leave: atom(b"XdndLeave\0"),
+            xdnd_drop: atom(b"XdndDrop\0"),
+            xdnd_finished: atom(b"XdndFinished\0"),
+            xdnd_selection: atom(b"XdndSelection\0"),
+            xdnd_type_list: atom(b"XdndTypeList\0"),
+            xdnd_action_copy: atom(b"XdndActionCopy\0"),
+            text_uri_list: atom(b"text/uri-list\0"),
+            text_plain: atom(b"text/plain\0"),
+            text_plain_utf8: atom(b"text/plain;charset=utf-8\0"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) struct WindowState {
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
+pub(crate) struct WindowData {
+    pub input_context: XIC,
+    // The last position and size reported to the user.
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub state: WindowState,
+    /// The fullscreen reported while the window manager has the window fullscreen.
+    pub fullscreen: Fullscreen,
+    pub minimum_size: Option<(u32, u32)>,
+    pub maximum_size: Option<(u32, u32)>,
+    pub resizable: bool,
+}
+
+pub(crate) struct MouseClick {
+    pub window: Window,
+    pub button: c_uint,
+    pub time: Time,
+}
+
+// Global singleton data shared by the application struct and the event loop.
+pub(crate) struct ApplicationData {
+    pub xlib: Xlib,
+    pub display: *mut Display,
+    pub screen: c_int,
+    pub root: Window,
+    pub atoms: Atoms,
+    pub input_method: XIM,
+    pub clipboard: Clipboard,
+    pub screens: Screens,
+    /// The drag currently over one of the windows.
+    pub drag: Option<Drag>,
+    pub drop_accepted: bool,
+    pub windows: HashMap<Window, WindowData>,
+    pub actually_quit: bool, // Set when quit is called. Indicates the program should quit.
+    pub text_input_enabled: bool, // Should text input be sent in addition to KeyDown events?
+    pub held_keys: HashSet<c_uint>, // Used to distinguish key repeats from key presses.
+    pub modifiers: Modifiers, // The last modifiers sent, used to send `ModifiersChanged`.
+    pub focused_window: Option<Window>,
+    pub pointer_window: Option<Window>,
+    pub last_root_pointer_position: Option<(c_int, c_int)>,
+    /// The window and position the pointer is locked to.
+    pub mouse_lock: Option<(Window, c_int, c_int)>,
+    pub last_click: Option<MouseClick>,
+    /// The button of a double click that has not been released yet.
+    pub double_click_button: Option<c_uint>,
+    pub scale: f64,
+    pub cursor: XCursor,
+    pub cursor_hidden: bool,
+    xcursor: Option<Xcursor>,
+    /// The Shape extension, if the X server supports it.
+    xext: Option<Xext>,
+    /// System cursors, which are loaded the first time they're used.
+    system_cursors: HashMap<Cursor, XCursor>,
+    custom_cursors: Vec<XCursor>,
+    invisible_cursor: XCursor,
+    /// Polled with the connection so proxies can wake the event loop.
+    pub wake_pipe: WakePipe,
+}
+
+impl ApplicationData {
+    unsafe fn new() -> Self {
+        let xlib = Xlib::open().expect("Could not load libX11");
+        let display = (xlib.XOpenDisplay)(null());
+        assert!(!display.is_null(), "Could not connect to the X server");
+
+        let screen = (xlib.XDefaultScreen)(display);
+        let root = (xlib.XRootWindow)(display, screen);
+        let atoms = Atoms::new(&xlib, display);
+
+        // Without this the X server sends a fake KeyRelease before each repeated KeyPress.
+        (xlib.XkbSetDetectableAutoRepeat)(display, True, null_mut());
+
+        // The input method needs the locale to be set from the environment to produce UTF-8 text.
+        setlocale(LC_CTYPE, b"\0".as_ptr() as *const c_char);
+        (xlib.XSetLocaleModifiers)(b"\0".as_ptr() as *const c_char);
+        let input_method = (xlib.XOpenIM)(display, null_mut(), null_mut(), null_mut());
+
+        // A 1x1 cursor with an empty mask is used to hide the cursor.
+        let blank_data = [0 as c_char; 1];
+        let blank = (xlib.XCreateBitmapFromData)(display, root, blank_data.as_ptr(), 1, 1);
+        let mut black: XColor = std::mem::zeroed();
+        let invisible_cursor =
+            (xlib.XCreatePixmapCursor)(display, blank, blank, &mut black, &mut black, 0, 0);
+        (xlib.XFreePixmap)(display, blank);
+
+        let clipboard = Clipboard::new(&xlib, display, root);
+        let screens = Screens::new(&xlib, display, root);
+
+        let scale = dpi_scale(&xlib, display);
+
+        let xext = Xext::open().filter(|xext| {
+            let mut event_base = 0;
+            let mut error_base = 0;
+            (xext.XShapeQueryExtension)(display, &mut event_base, &mut error_base) == True
+        });
+
+        let mut data = Self {
+            xlib,
+            display,
+            screen,
+            root,
+            atoms,
+            input_method,
+            clipboard,
+            screens,
+            drag: None,
+            drop_accepted: true,
+            windows: HashMap::new(),
+            actually_quit: false,
+            text_input_enabled: false,
+            held_keys: HashSet::new(),
+            modifiers: Modifiers::empty(),
+            focused_window: None,
+            pointer_window: None,
+            last_root_pointer_position: None,
+            mouse_lock: None,
+            last_click: None,
+            double_click_button: None,
+            scale,
+            cursor: 0,
+            cursor_hidden: false,
+            xcursor: Xcursor::open(),
+            xext,
+            system_cursors: HashMap::new(),
+            custom_cursors: Vec::new(),
+            invisible_cursor,
+            wake_pipe: WakePipe::new(),
+        };
+        data.cursor = data.load_cursor(Cursor::Arrow);
+        data.screens.last = screens_x11::screens(&data);
+        data
+    }
+
+    /// Returns the X cursor for a cursor, loading it if it hasn't been used before.
+    /// Cursors are loaded from the cursor theme if Xcursor is available,
+    /// otherwise the closest cursor in the X cursor font is used.
+    pub unsafe fn load_cursor(&mut self, cursor: Cursor) -> XCursor {
+        if let Cursor::Custom(custom_cursor) = cursor {
+            return custom_cursor.raw() as XCursor;
+        }
+        if let Some(x_cursor) = self.system_cursors.get(&cursor) {
+            return *x_cursor;
+        }
+
+        let display = self.display;
+        let themed = self.xcursor.as_ref().and_then(|xcursor| {
+            cursor_names(cursor)
+                .iter()
+                .map(|name| {
+                    (xcursor.XcursorLibraryLoadCursor)(display, name.as_ptr() as *const c_char)
+                })
+                .find(|x_cursor| *x_cursor != 0)
+        });
+        let x_cursor = themed.unwrap_or_else(|| {
+            let shape = match cursor {
+                Cursor::Arrow | Cursor::Custom(_) => XC_left_ptr,
+                Cursor::IBeam => XC_xterm,
+                Cursor::PointingHand => XC_hand2,
+                Cursor::OpenHand => XC_hand1,
+                Cursor::ClosedHand | Cursor::Move => XC_fleur,
+                Cursor::ResizeNS => XC_sb_v_double_arrow,
+                Cursor::ResizeEW => XC_sb_h_double_arrow,
+                // The cursor font has no diagonal arrows, but themes usually draw
+                // the corner cursors as diagonal resize cursors.
+                Cursor::ResizeNESW => XC_bottom_left_corner,
+                Cursor::ResizeNWSE => XC_bottom_right_corner,
+                Cursor::Crosshair => XC_crosshair,
+                Cursor::NotAllowed => XC_X_cursor,
+                // The cursor font has no progress or zoom cursors.
+                Cursor::Wait | Cursor::Progress => XC_watch,
+                Cursor::Help => XC_question_arrow,
+                Cursor::ZoomIn | Cursor::ZoomOut => XC_plus,
+            };
+            (self.xlib.XCreateFontCursor)(display, shape)
+        });
+        self.system_cursors.insert(cursor, x_cursor);
+        x_cursor
+    }
+
+    /// Creates a cursor from RGBA pixels.
+    /// Without Xcursor images can't be used as cursors, so the arrow is returned instead.
+    pub unsafe fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> XCursor {
+        let xcursor = match &self.xcursor {
+            Some(xcursor) => xcursor,
+            None => return self.load_cursor(Cursor::Arrow),
+        };
+        let image = (xcursor.XcursorImageCreate)(width as c_int, height as c_int);
+        if image.is_null() {
+            return self.load_cursor(Cursor::Arrow);
+        }
+        (*image).xhot = hotspot.0;
+        (*image).yhot = hotspot.1;
+        let pixels = std::slice::from_raw_parts_mut((*image).pixels, rgba.len() / 4);
+        for (pixel, rgba) in pixels.iter_mut().zip(rgba.chunks_exact(4)) {
+            let alpha = rgba[3] as c_uint;
+            let premultiply = |channel: u8| channel as c_uint * alpha / 255;
+            *pixel = alpha << 24
+                | premultiply(rgba[0]) << 16
+                | premultiply(rgba[1]) << 8
+                | premultiply(rgba[2]);
+        }
+        let x_cursor = (xcursor.XcursorImageLoadCursor)(self.display, image);
+        (xcursor.XcursorImageDestroy)(image);
+        if x_cursor == 0 {
+            return self.load_cursor(Cursor::Arrow);
+        }
+        self.custom_cursors.push(x_cursor);
+        x_cursor
+    }
+
+    /// Applies the current cursor to every window.
+    pub unsafe fn update_cursor(&self) {
+        let cursor = if self.cursor_hidden {
+            self.invisible_cursor
+        } else {
+            self.cursor
+        };
+        for window in self.windows.keys() {
+            (self.xlib.XDefineCursor)(self.display, *window, cursor);
+        }
+        (self.xlib.XFlush)(self.display);
+    }
+
+    /// Asks the window manager to add or remove window states.
+    /// https://specifications.freedesktop.org/wm-spec/wm-spec-latest.html#idm45805407959456
+    pub unsafe fn change_window_state(&self, window: Window, add: bool, first: Atom, second: Atom) {
+        let mut event: XEvent = std::mem::zeroed();
+        event.client_message = XClientMessageEvent {
+            type_: ClientMessage,
+            serial: 0,
+            send_event: True,
+            display: self.display,
+            window,
+            message_type: self.atoms.net_wm_state,
+            format: 32,
+            // The fourth value indicates the request comes from a normal application.
+            data: [add as c_long, first as c_long, second as c_long, 1, 0],
+        };
+        (self.xlib.XSendEvent)(
+            self.display,
+            self.root,
+            False,
+            SubstructureRedirectMask | SubstructureNotifyMask,
+            &mut event,
+        );
+        (self.xlib.XFlush)(self.display);
+    }
+
+    /// Reads the window's `_NET_WM_STATE` property.
+    pub unsafe fn read_window_state(&self, window: Window) -> WindowState {
+        let mut actual_type = 0;
+        let mut actual_format = 0;
+        let mut item_count = 0;
+        let mut bytes_after = 0;
+        let mut property = null_mut();
+        let mut state = WindowState::default();
+
+        (self.xlib.XGetWindowProperty)(
+            self.display,
+            window,
+            self.atoms.net_wm_state,
+            0,
+            1024,
+            False,
+            XA_ATOM,
+            &mut actual_type,
+            &mut actual_format,
+            &mut item_count,
+            &mut bytes_after,
+            &mut property,
+        );
+        if property.is_null() {
+            return state;
+        }
+
+        // 32 bit properties are returned as an array of longs.
+        let atoms = std::slice::from_raw_parts(property as *const Atom, item_count as usize);
+        for atom in atoms {
+            if *atom == self.atoms.net_wm_state_hidden {
+                state.minimized = true;
+            } else if *atom == self.atoms.net_wm_state_fullscreen {
+                state.fullscreen = true;
+            } else if *atom == self.atoms.net_wm_state_maximized_vert
+                || *atom == self.atoms.net_wm_state_maximized_horz
+            {
+                state.maximized = true;
+            }
+        }
+        (self.xlib.XFree)(property as *mut c_void);
+        state
+    }
+
+    /// Returns the window's content position relative to the root window.
+    pub unsafe fn window_position(&self, window: Window) -> (c_int, c_int) {
+        let mut x = 0;
+        let mut y = 0;
+        let mut child = 0;
+        (self.xlib.XTranslateCoordinates)(
+            self.display,
+            window,
+            self.root,
+            0,
+            0,
+            &mut x,
+            &mut y,
+            &mut child,
+        );
+        (x, y)
+    }
+}
+
+impl Drop for ApplicationData {
+    fn drop(&mut self) {
+        unsafe {
+            screens_x11::restore_video_modes(self);
+            for (window, window_data) in self.windows.drain() {
+                if !window_data.input_context.is_null() {
+                    (self.xlib.XDestroyIC)(window_data.input_context);
+                }
+                (self.xlib.XDestroyWindow)(self.display, window);
+            }
+            (self.xlib.XDestroyWindow)(self.display, self.clipboard.window);
+            if !self.input_method.is_null() {
+                (self.xlib.XCloseIM)(self.input_method);
+            }
+            let cursors = self
+                .system_cursors
+                .values()
+                .chain(&self.custom_cursors)
+                .chain(std::iter::once(&self.invisible_cursor));
+            for cursor in cursors {
+                (self.xlib.XFreeCursor)(self.display, *cursor);
+            }
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+const LC_CTYPE: c_int = 0;
+extern "C" {
+    fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char;
+}
+
+/// X11 has no per-monitor scale factor.
+/// Desktop environments communicate the user's preferred scale through the `Xft.dpi` resource.
+unsafe fn dpi_scale(xlib: &Xlib, display: *mut Display) -> f64 {
+    let resources = (xlib.XResourceManagerString)(display);
+    if resources.is_null() {
+        return 1.0;
+    }
+    CStr::from_ptr(resources)
+        .to_string_lossy()
+        .lines()
+        .find_map(|line| {
+            let value = line.strip_prefix("Xft.dpi:")?;
+            value.trim().parse::<f64>().ok()
+        })
+        .map_or(1.0, |dpi| dpi / 96.)
+}
+
+fn window_from_id(window_id: WindowId) -> Window {
+    unsafe { window_id.raw() as Window }
+}
+
+pub struct PlatformApplication {}
+
+impl PlatformApplicationTrait for PlatformApplication {
+    type EventLoop = PlatformEventLoop;
+    fn new() -> Self {
+        let data = unsafe { ApplicationData::new() };
+        APPLICATION_DATA.with(|d| *d.borrow_mut() = Some(data));
+        Self {}
+    }
+
+    fn event_loop(&mut self) -> Self::EventLoop {
+        PlatformEventLoop {}
+    }
+
+    fn set_window_position(&mut self, window_id: WindowId, x: u32, y: u32) {
+        with_data(|data| unsafe {
+            (data.xlib.XMoveWindow)(
+                data.display,
+                window_from_id(window_id),
+                x as c_int,
+                y as c_int,
+            );
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn set_window_size(&mut self, window_id: WindowId, width: u32, height: u32) {
+        with_data(|data| unsafe {
+            (data.xlib.XResizeWindow)(data.display, window_from_id(window_id), width, height);
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn set_window_title(&mut self, window_id: WindowId, title: &str) {
+        with_data(|data| unsafe { set_title(data, window_from_id(window_id), title) })
+    }
+
+    fn set_window_icon(&mut self, window_id: WindowId, icon: &[Icon]) {
+        with_data(|data| unsafe { set_icon(data, window_from_id(window_id), icon) })
+    }
+
+    fn set_window_resizable(&mut self, window_id: WindowId, resizable: bool) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            if let Some(window_data) = data.windows.get_mut(&window) {
+                window_data.resizable = resizable;
+                set_size_hints(data, window, None);
+            }
+        })
+    }
+
+    fn set_window_minimum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            if let Some(window_data) = data.windows.get_mut(&window) {
+                window_data.minimum_size = size;
+                set_size_hints(data, window, None);
+            }
+        })
+    }
+
+    fn set_window_maximum_size(&mut self, window_id: WindowId, size: Option<(u32, u32)>) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            if let Some(window_data) = data.windows.get_mut(&window) {
+                window_data.maximum_size = size;
+                set_size_hints(data, window, None);
+            }
+        })
+    }
+
+    fn set_window_decorations(&mut self, window_id: WindowId, decorations: bool) {
+        with_data(|data| unsafe {
+            set_decorations(data, window_from_id(window_id), decorations);
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn set_window_always_on_top(&mut self, window_id: WindowId, always_on_top: bool) {
+        with_data(|data| unsafe {
+            let state = data.atoms.net_wm_state_above;
+            data.change_window_state(window_from_id(window_id), always_on_top, state, 0);
+        })
+    }
+
+    fn set_window_visible(&mut self, window_id: WindowId, visible: bool) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            if visible {
+                (data.xlib.XMapWindow)(data.display, window);
+            } else {
+                // Withdrawing also tells the window manager to stop managing the window.
+                (data.xlib.XWithdrawWindow)(data.display, window, data.screen);
+            }
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn set_window_opacity(&mut self, window_id: WindowId, opacity: f32) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            let property = data.atoms.net_wm_window_opacity;
+            if opacity >= 1.0 {
+                (data.xlib.XDeleteProperty)(data.display, window, property);
+            } else {
+                // Compositors read the opacity as a fraction of the largest 32 bit value.
+                let opacity = (opacity as f64 * u32::MAX as f64) as c_ulong;
+                (data.xlib.XChangeProperty)(
+                    data.display,
+                    window,
+                    property,
+                    XA_CARDINAL,
+                    32,
+                    PropModeReplace,
+                    &opacity as *const c_ulong as *const c_uchar,
+                    1,
+                );
+            }
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn set_window_input_region(
+        &mut self,
+        window_id: WindowId,
+        region: Option<&[(u32, u32, u32, u32)]>,
+    ) -> bool {
+        with_data(|data| unsafe {
+            // Without the shape extension the whole window receives input.
+            let xext = match &data.xext {
+                Some(xext) => xext,
+                None => return region.is_none(),
+            };
+            let window = window_from_id(window_id);
+            match region {
+                Some(region) => {
+                    let mut rectangles: Vec<XRectangle> = region
+                        .iter()
+                        .map(|&(x, y, width, height)| XRectangle {
+                            x: x.min(c_short::MAX as u32) as c_short,
+                            y: y.min(c_short::MAX as u32) as c_short,
+                            width: width.min(c_ushort::MAX as u32) as c_ushort,
+                            height: height.min(c_ushort::MAX as u32) as c_ushort,
+                        })
+                        .collect();
+                    (xext.XShapeCombineRectangles)(
+                        data.display,
+                        window,
+                        ShapeInput,
+                        0,
+                        0,
+                        rectangles.as_mut_ptr(),
+                        rectangles.len() as c_int,
+                        ShapeSet,
+                        Unsorted,
+                    );
+                }
+                // Without a mask the whole window receives input again.
+                None => {
+                    (xext.XShapeCombineMask)(data.display, window, ShapeInput, 0, 0, 0, ShapeSet)
+                }
+            }
+            (data.xlib.XFlush)(data.display);
+            true
+        })
+    }
+
+    fn minimize_window(&mut self, window_id: WindowId) {
+        with_data(|data| unsafe {
+            (data.xlib.XIconifyWindow)(data.display, window_from_id(window_id), data.screen);
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn maximize_window(&mut self, window_id: WindowId) {
+        with_data(|data| unsafe {
+            data.change_window_state(
+                window_from_id(window_id),
+                true,
+                data.atoms.net_wm_state_maximized_vert,
+                data.atoms.net_wm_state_maximized_horz,
+            );
+        })
+    }
+
+    fn set_window_fullscreen(&mut self, window_id: WindowId, fullscreen: Fullscreen) {
+        let current_screen = self.window_screen(window_id);
+        let fullscreen = match fullscreen {
+            Fullscreen::Borderless(None) => Fullscreen::Borderless(current_screen),
+            fullscreen => fullscreen,
+        };
+        let event = with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            let window_data = data.windows.get_mut(&window)?;
+            let old_fullscreen = std::mem::replace(&mut window_data.fullscreen, fullscreen);
+            let was_fullscreen = window_data.state.fullscreen;
+            if let Fullscreen::Exclusive(_) = old_fullscreen {
+                screens_x11::restore_video_modes(data);
+            }
+
+            let screen_id = match fullscreen {
+                Fullscreen::Off => {
+                    let state = data.atoms.net_wm_state_fullscreen;
+                    data.change_window_state(window, false, state, 0);
+                    return None;
+                }
+                Fullscreen::Borderless(screen_id) => screen_id,
+                Fullscreen::Exclusive(video_mode) => {
+                    screens_x11::set_video_mode(data, &video_mode);
+                    Some(video_mode.screen_id)
+                }
+            };
+
+            // Window managers make a window fullscreen on the monitor it's on,
+            // so it leaves fullscreen to move to another one.
+            let moving = screen_id != current_screen;
+            if moving {
+                if was_fullscreen {
+                    let state = data.atoms.net_wm_state_fullscreen;
+                    data.change_window_state(window, false, state, 0);
+                }
+                let screen = data.screens.last.iter().find(|s| Some(s.id) == screen_id);
+                if let Some(screen) = screen {
+                    let (x, y) = screen.position;
+                    (data.xlib.XMoveWindow)(data.display, window, x, y);
+                }
+            }
+            let state = data.atoms.net_wm_state_fullscreen;
+            data.change_window_state(window, true, state, 0);
+
+            // The window manager's state doesn't change, so it won't send an event.
+            if was_fullscreen && !moving && old_fullscreen != fullscreen {
+                Some(Event::WindowFullscreened {
+                    fullscreen,
+                    window_id,
+                })
+            } else {
+                None
+            }
+        });
+        if let Some(event) = event {
+            event_receiver::send_event(event);
+        }
+    }
+
+    fn restore_window(&mut self, window_id: WindowId) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            (data.xlib.XMapRaised)(data.display, window);
+            data.change_window_state(window, false, data.atoms.net_wm_state_fullscreen, 0);
+            data.change_window_state(
+                window,
+                false,
+                data.atoms.net_wm_state_maximized_vert,
+                data.atoms.net_wm_state_maximized_horz,
+            );
+        })
+    }
+
+    fn close_window(&mut self, window_id: WindowId) {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            if let Some(window_data) = data.windows.remove(&window) {
+                if let Fullscreen::Exclusive(_) = window_data.fullscreen {
+                    screens_x11::restore_video_modes(data);
+                }
+                if !window_data.input_context.is_null() {
+                    (data.xlib.XDestroyIC)(window_data.input_context);
+                }
+                (data.xlib.XDestroyWindow)(data.display, window);
+                (data.xlib.XFlush)(data.display);
+            }
+            if data.focused_window == Some(window) {
+                data.focused_window = None;
+            }
+            if data.pointer_window == Some(window) {
+                data.pointer_window = None;
+            }
+            if data.drag.as_ref().map(|drag| drag.window) == Some(window) {
+                data.drag = None;
+            }
+        })
+    }
+
+    fn get_window_size(&mut self, window_id: WindowId) -> (u32, u32) {
+        with_data(|data| unsafe {
+            let mut attributes: XWindowAttributes = std::mem::zeroed();
+            (data.xlib.XGetWindowAttributes)(
+                data.display,
+                window_from_id(window_id),
+                &mut attributes,
+            );
+            (attributes.width as u32, attributes.height as u32)
+        })
+    }
+
+    fn get_window_scale(&mut self, _window_id: WindowId) -> f64 {
+        with_data(|data| data.scale)
+    }
+
+    fn get_window_position(&mut self, window_id: WindowId) -> (u32, u32) {
+        with_data(|data| match data.windows.get(&window_from_id(window_id)) {
+            Some(window_data) => (window_data.x.max(0) as u32, window_data.y.max(0) as u32),
+            None => (0, 0),
+        })
+    }
+
+    fn is_window_minimized(&mut self, window_id: WindowId) -> bool {
+        with_data(|data| {
+            let window_data = data.windows.get(&window_from_id(window_id));
+            window_data.is_some_and(|w| w.state.minimized)
+        })
+    }
+
+    fn is_window_maximized(&mut self, window_id: WindowId) -> bool {
+        with_data(|data| {
+            let window_data = data.windows.get(&window_from_id(window_id));
+            window_data.is_some_and(|w| w.state.maximized)
+        })
+    }
+
+    fn get_window_fullscreen(&mut self, window_id: WindowId) -> Fullscreen {
+        with_data(|data| match data.windows.get(&window_from_id(window_id)) {
+            // Until the window manager applies a fullscreen request the window isn't fullscreen.
+            Some(window_data) if window_data.state.fullscreen => window_data.fullscreen,
+            _ => Fullscreen::Off,
+        })
+    }
+
+    fn window_has_focus(&mut self, window_id: WindowId) -> bool {
+        with_data(|data| data.focused_window == Some(window_from_id(window_id)))
+    }
+
+    fn is_window_visible(&mut self, window_id: WindowId) -> bool {
+        with_data(|data| unsafe {
+            let window = window_from_id(window_id);
+            let window_data = match data.windows.get(&window) {
+                Some(window_data) => window_data,
+                None => return false,
+            };
+            // Minimized windows are unmapped by the window manager.
+            let mut attributes: XWindowAttributes = std::mem::zeroed();
+            (data.xlib.XGetWindowAttributes)(data.display, window, &mut attributes);
+            attributes.map_state == IsViewable || window_data.state.minimized
+        })
+    }
+
+    fn screens(&mut self) -> Vec<Screen> {
+        with_data(|data| unsafe { screens_x11::screens(data) })
+    }
+
+    fn window_screen(&mut self, window_id: WindowId) -> Option<ScreenId> {
+        with_data(|data| {
+            let window_data = data.windows.get(&window_from_id(window_id))?;
+            let center_x = window_data.x + window_data.width / 2;
+            let center_y = window_data.y + window_data.height / 2;
+            data.screens
+                .last
+                .iter()
+                .find(|screen| screen.contains(center_x, center_y))
+                .or_else(|| data.screens.last.first())
+                .map(|screen| screen.id)
+        })
+    }
+
+    fn redraw_window(&mut self, window_id: WindowId) {
+        redraw_manager::add_draw_request(window_id);
+    }
+
+    fn lock_mouse_position(&mut self) {
+        with_data(|data| unsafe {
+            // The pointer is locked to the focused window and warped back each time it moves.
+            let window = match data.focused_window.or(data.pointer_window) {
+                Some(window) => window,
+                None => return,
+            };
+            let (window_x, window_y) = data.window_position(window);
+            let (x, y) = data
+                .last_root_pointer_position
+                .map_or((0, 0), |(x, y)| (x - window_x, y - window_y));
+
+            let grab_result = (data.xlib.XGrabPointer)(
+                data.display,
+                window,
+                True,
+                (ButtonPressMask | ButtonReleaseMask | PointerMotionMask) as c_uint,
+                GrabModeAsync,
+                GrabModeAsync,
+                window,
+                0,
+                CurrentTime,
+            );
+            if grab_result == GrabSuccess {
+                data.mouse_lock = Some((window, x, y));
+            }
+            (data.xlib.XFlush)(data.display);
+        })
+    }
+
+    fn unlock_mouse_position(&mut self) {
+        with_data(|data| unsafe {
+            if data.mouse_lock.take().is_some() {
+                (data.xlib.XUngrabPointer)(data.display, CurrentTime);
+                (data.xlib.XFlush)(data.display);
+            }
+        })
+    }
+
+    fn new_window(&mut self, window_parameters: &WindowParameters) -> WindowId {
+        with_data(|data| unsafe {
+            let (x, y) = window_parameters.position.unwrap_or((0, 0));
+            let (width, height) = window_parameters.size.unwrap_or((500, 500));
+
+            // Transparent windows need a visual with an alpha channel,
+            // which compositors blend with what's behind the window.
+            let mut visual_info: XVisualInfo = std::mem::zeroed();
+            let transparent = window_parameters.transparent
+                && (data.xlib.XMatchVisualInfo)(
+                    data.display,
+                    data.screen,
+                    32,
+                    TrueColor,
+                    &mut visual_info,
+                ) != 0;
+            let (visual, depth) = if transparent {
+                (visual_info.visual, visual_info.depth)
+            } else {
+                (
+                    (data.xlib.XDefaultVisual)(data.display, data.screen),
+                    (data.xlib.XDefaultDepth)(data.display, data.screen),
+                )
+            };
+
+            let mut attributes: XSetWindowAttributes = std::mem::zeroed();
+            attributes.background_pixel = if transparent {
+                0
+            } else {
+                (data.xlib.XBlackPixel)(data.display, data.screen)
+            };
+            attributes.colormap =
+                (data.xlib.XCreateColormap)(data.display, data.root, visual, AllocNone);
+            attributes.event_mask = KeyPressMask
+                | KeyReleaseMask
+                | ButtonPressMask
+                | ButtonReleaseMask
+                | PointerMotionMask
+                | EnterWindowMask
+                | LeaveWindowMask
+                | ExposureMask
+                | StructureNotifyMask
+                | FocusChangeMask
+                | PropertyChangeMask;
+
+            let window = (data.xlib.XCreateWindow)(
+                data.display,
+                data.root,
+                x as c_int,
+                y as c_int,
+                width,
+                height,
+                0,
+                depth,
+                InputOutput,
+                visual,
+                CWBackPixel | CWBorderPixel | CWColormap | CWEventMask,
+                &mut attributes,
+            );
+
+            // Ask the window manager to send a message instead of destroying the window when closed.
+            let mut protocols = [data.atoms.wm_delete_window];
+            (data.xlib.XSetWMProtocols)(data.display, window, protocols.as_mut_ptr(), 1);
+
+            set_title(data, window, &window_parameters.title);
+            set_icon(data, window, &window_parameters.icon);
+            drag_drop_x11::set_drop_target(data, window);
+
+            let input_context = if data.input_method.is_null() {
+                null_mut()
+            } else {
+                (data.xlib.XCreateIC)(
+                    data.input_method,
+                    XNInputStyle.as_ptr(),
+                    XIMPreeditNothing | XIMStatusNothing,
+                    XNClientWindow.as_ptr(),
+                    window,
+                    XNFocusWindow.as_ptr(),
+                    window,
+                    null_mut::<c_void>(),
+                )
+            };
+
+            data.windows.insert(
+                window,
+                WindowData {
+                    input_context,
+                    x: x as i32,
+                    y: y as i32,
+                    width: width as i32,
+                    height: height as i32,
+                    state: WindowState::default(),
+                    fullscreen: Fullscreen::Off,
+                    minimum_size: window_parameters.minimum_size,
+                    maximum_size: window_parameters.maximum_size,
+                    resizable: window_parameters.resizable,
+                },
+            );
+            let position = window_parameters.position.map(|_| (x as c_int, y as c_int));
+            set_size_hints(data, window, position);
+            if window_parameters.without_titlebar {
+                set_decorations(data, window, false);
+            }
+            data.update_cursor();
+
+            (data.xlib.XMapWindow)(data.display, window);
+            (data.xlib.XFlush)(data.display);
+
+            let window_id = WindowId::new(window as *mut c_void);
+            // When a window is created immediately request that it should redraw
+            redraw_manager::add_draw_request(window_id);
+            window_id
+        })
+    }
+
+    fn quit(&self) {
+        with_data(|data| data.actually_quit = true);
+    }
+
+    fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        control_flow::set_control_flow(control_flow);
+    }
+
+    fn set_timer(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        timers::add_timer(duration, repeat)
+    }
+
+    fn cancel_timer(&mut self, timer_id: TimerId) {
+        timers::remove_timer(timer_id);
+    }
+
+    fn proxy(&mut self) -> EventLoopProxy {
+        with_data(|data| data.wake_pipe.proxy())
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        with_data(|data| unsafe {
+            data.cursor = data.load_cursor(cursor);
+            data.update_cursor();
+        })
+    }
+
+    fn create_cursor(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        with_data(|data| unsafe {
+            let x_cursor = data.create_cursor(rgba, width, height, hotspot);
+            CustomCursor::new(x_cursor as *mut c_void)
+        })
+    }
+
+    fn hide_cursor(&mut self) {
+        with_data(|data| unsafe {
+            data.cursor_hidden = true;
+            data.update_cursor();
+        })
+    }
+
+    fn show_cursor(&mut self) {
+        with_data(|data| unsafe {
+            data.cursor_hidden = false;
+            data.update_cursor();
+        })
+    }
+
+    fn start_text_input(&mut self) {
+        with_data(|data| data.text_input_enabled = true);
+    }
+
+    fn end_text_input(&mut self) {
+        with_data(|data| data.text_input_enabled = false);
+    }
+
+    fn set_text_input_rectangle(
+        &mut self,
+        _window_id: WindowId,
+        _x: f64,
+        _y: f64,
+        _width: f64,
+        _height: f64,
+    ) {
+        // The input context is created without a preedit area,
+        // so the input method positions its own popups.
+    }
+
+    fn clipboard_mime_types(&mut self, selection: ClipboardSelection) -> Vec<String> {
+        with_data(|data| unsafe { clipboard_x11::mime_types(data, selection) })
+    }
+    fn get_clipboard_data(
+        &mut self,
+        selection: ClipboardSelection,
+        mime_type: &str,
+    ) -> Option<Vec<u8>> {
+        with_data(|data| unsafe { clipboard_x11::get_data(data, selection, mime_type) })
+    }
+    fn set_clipboard_data(&mut self, selection: ClipboardSelection, mime_type: &str, data: &[u8]) {
+        with_data(|application_data| unsafe {
+            clipboard_x11::set_data(application_data, selection, mime_type, data)
+        })
+    }
+
+    fn set_drop_accepted(&mut self, accepted: bool) {
+        with_data(|data| unsafe {
+            data.drop_accepted = accepted;
+            drag_drop_x11::send_status(data);
+        })
+    }
+
+    fn raw_window_handle(&self, window_id: WindowId) -> RawWindowHandle {
+        use raw_window_handle::unix::XlibHandle;
+        with_data(|data| {
+            RawWindowHandle::Xlib(XlibHandle {
+                window: window_from_id(window_id),
+                display: data.display as *mut c_void,
+                ..XlibHandle::empty()
+            })
+        })
+    }
+
+    fn inject_event(&mut self, event: Event) {
+        event_receiver::send_event(event);
+    }
+}
+
+/// The window manager reads the size limits and requested position from the size hints.
+/// Windows that aren't resizable are limited to their current size.
+unsafe fn set_size_hints(data: &ApplicationData, window: Window, position: Option<(c_int, c_int)>) {
+    let window_data = match data.windows.get(&window) {
+        Some(window_data) => window_data,
+        None => return,
+    };
+    let mut size_hints: XSizeHints = std::mem::zeroed();
+    if let Some((x, y)) = position {
+        size_hints.flags |= USPosition | PPosition;
+        size_hints.x = x;
+        size_hints.y = y;
+    }
+    let (minimum_size, maximum_size) = if window_data.resizable {
+        (window_data.minimum_size, window_data.maximum_size)
+    } else {
+        let size = (window_data.width as u32, window_data.height as u32);
+        (Some(size), Some(size))
+    };
+    if let Some((min_width, min_height)) = minimum_size {
+        size_hints.flags |= PMinSize;
+        size_hints.min_width = min_width as c_int;
+        size_hints.min_height = min_height as c_int;
+    }
+    if let Some((max_width, max_height)) = maximum_size {
+        size_hints.flags |= PMaxSize;
+        size_hints.max_width = max_width as c_int;
+        size_hints.max_height = max_height as c_int;
+    }
+    (data.xlib.XSetWMNormalHints)(data.display, window, &mut size_hints);
+    (data.xlib.XFlush)(data.display);
+}
+
+unsafe fn set_decorations(data: &ApplicationData, window: Window, decorations: bool) {
+    // Window managers read decorations from the Motif hints, which are 5 longs:
+    // flags, functions, decorations, input mode, and status.
+    // The flags say only the decorations are set.
+    const MWM_HINTS_DECORATIONS: c_ulong = 1 << 1;
+    let hints: [c_ulong; 5] = [MWM_HINTS_DECORATIONS, 0, decorations as c_ulong, 0, 0];
+    (data.xlib.XChangeProperty)(
+        data.display,
+        window,
+        data.atoms.motif_wm_hints,
+        data.atoms.motif_wm_hints,
+        32,
+        PropModeReplace,
+        hints.as_ptr() as *const c_uchar,
+        hints.len() as c_int,
+    );
+}
+
+unsafe fn set_title(data: &ApplicationData, window: Window, title: &str) {
+    // C strings end at the first NUL, so any NULs in the title are removed.
+    let c_string = CString::new(title.replace('\0', "")).unwrap();
+    // WM_NAME is for older window managers that don't read _NET_WM_NAME.
+    (data.xlib.XStoreName)(data.display, window, c_string.as_ptr());
+    (data.xlib.XChangeProperty)(
+        data.display,
+        window,
+        data.atoms.net_wm_name,
+        data.atoms.utf8_string,
+        8,
+        PropModeReplace,
+        c_string.as_ptr() as *const c_uchar,
+        title.len() as c_int,
+    );
+    (data.xlib.XFlush)(data.display);
+}
+
+/// Sets `_NET_WM_ICON`, which holds each size's width and height followed by its ARGB pixels.
+unsafe fn set_icon(data: &ApplicationData, window: Window, icon: &[Icon]) {
+    if icon.is_empty() {
+        (data.xlib.XDeleteProperty)(data.display, window, data.atoms.net_wm_icon);
+    } else {
+        // 32 bit properties are passed as an array of longs.
+        let mut values: Vec<c_ulong> = Vec::new();
+        for size in icon {
+            values.push(size.width as c_ulong);
+            values.push(size.height as c_ulong);
+            values.extend(size.data.chunks_exact(4).map(|rgba| {
+                (rgba[3] as c_ulong) << 24
+                    | (rgba[0] as c_ulong) << 16
+                    | (rgba[1] as c_ulong) << 8
+                    | rgba[2] as c_ulong
+            }));
+        }
+        (data.xlib.XChangeProperty)(
+            data.display,
+            window,
+            data.atoms.net_wm_icon,
+            XA_CARDINAL,
+            32,
+            PropModeReplace,
+            values.as_ptr() as *const c_uchar,
+            values.len() as c_int,
+        );
+    }
+    (data.xlib.XFlush)(data.display);
+}
+
+// When the application is dropped, close the connection to the X server.
+impl Drop for PlatformApplication {
+    fn drop(&mut self) {
+        // try_with because the thread local may already be destroyed.
+        let _ = APPLICATION_DATA.try_with(|d| d.borrow_mut().take());
+    }
+}
+
+pub struct PlatformEventLoop {}
+
+impl PlatformEventLoopTrait for PlatformEventLoop {
+    fn run(&self, callback: Box<dyn FnMut(kapp_platform_common::Event)>) {
+        super::event_loop_x11::run(callback);
+    }
+}
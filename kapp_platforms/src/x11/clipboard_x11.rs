@@ -0,0 +1,517 @@
+use super::application_x11::ApplicationData;
+use super::external_x11::*;
+use kapp_platform_common::*;
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::ffi::{CStr, CString};
+use std::ptr::null_mut;
+use std::time::{Duration, Instant};
+
+/// How long to wait for another program to respond to a clipboard request.
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An outgoing transfer that's too large to send at once.
+/// It's sent in chunks, each time the requestor deletes the previous chunk.
+/// https://tronche.com/gui/x/icccm/sec-2.html#s-2.7.2
+struct Transfer {
+    requestor: Window,
+    property: Atom,
+    type_: Atom,
+    contents: Vec<u8>,
+    offset: usize,
+}
+
+/// X11 selections are owned by a window, so a hidden window
+/// is used to request and serve clipboard data.
+pub(crate) struct Clipboard {
+    pub window: Window,
+    /// The MIME type and data of each selection this program owns.
+    owned: HashMap<ClipboardSelection, (String, Vec<u8>)>,
+    transfers: Vec<Transfer>,
+    /// XFixes and its first event type, if the extension is available.
+    xfixes: Option<(XFixes, c_int)>,
+}
+
+impl Clipboard {
+    pub unsafe fn new(xlib: &Xlib, display: *mut Display, root: Window) -> Self {
+        let window = (xlib.XCreateSimpleWindow)(display, root, 0, 0, 1, 1, 0, 0, 0);
+        (xlib.XSelectInput)(display, window, PropertyChangeMask);
+
+        // XFixes reports when any program takes ownership of a selection.
+        let xfixes = XFixes::open().and_then(|xfixes| {
+            let mut event_base = 0;
+            let mut error_base = 0;
+            if (xfixes.XFixesQueryExtension)(display, &mut event_base, &mut error_base) == False {
+                return None;
+            }
+            // The version must be queried before any other request is made.
+            let mut major = 1;
+            let mut minor = 0;
+            (xfixes.XFixesQueryVersion)(display, &mut major, &mut minor);
+            Some((xfixes, event_base))
+        });
+        if let Some((xfixes, _)) = &xfixes {
+            let clipboard =
+                (xlib.XInternAtom)(display, b"CLIPBOARD\0".as_ptr() as *const c_char, False);
+            for selection in &[clipboard, XA_PRIMARY] {
+                (xfixes.XFixesSelectSelectionInput)(
+                    display,
+                    window,
+                    *selection,
+                    XFixesSetSelectionOwnerNotifyMask,
+                );
+            }
+        }
+
+        Self {
+            window,
+            owned: HashMap::new(),
+            transfers: Vec::new(),
+            xfixes,
+        }
+    }
+}
+
+fn selection_atom(data: &ApplicationData, selection: ClipboardSelection) -> Atom {
+    match selection {
+        ClipboardSelection::Clipboard => data.atoms.clipboard,
+        ClipboardSelection::Primary => XA_PRIMARY,
+    }
+}
+
+fn selection_from_atom(data: &ApplicationData, atom: Atom) -> Option<ClipboardSelection> {
+    if atom == data.atoms.clipboard {
+        Some(ClipboardSelection::Clipboard)
+    } else if atom == XA_PRIMARY {
+        Some(ClipboardSelection::Primary)
+    } else {
+        None
+    }
+}
+
+unsafe fn intern_atom(data: &ApplicationData, name: &str) -> Atom {
+    let name = CString::new(name).unwrap();
+    (data.xlib.XInternAtom)(data.display, name.as_ptr(), False)
+}
+
+/// Text may be requested as Latin-1 by older programs.
+fn utf8_to_latin1(text: &[u8]) -> Vec<u8> {
+    String::from_utf8_lossy(text)
+        .chars()
+        .map(|c| if (c as u32) < 256 { c as u8 } else { b'?' })
+        .collect()
+}
+
+fn latin1_to_utf8(text: &[u8]) -> Vec<u8> {
+    text.iter()
+        .map(|byte| *byte as char)
+        .collect::<String>()
+        .into_bytes()
+}
+
+pub(crate) unsafe fn mime_types(
+    data: &mut ApplicationData,
+    selection: ClipboardSelection,
+) -> Vec<String> {
+    if let Some((mime_type, _)) = data.clipboard.owned.get(&selection) {
+        return vec![mime_type.clone()];
+    }
+
+    let targets = match convert_selection(
+        data,
+        selection_atom(data, selection),
+        data.atoms.targets,
+        CurrentTime,
+    ) {
+        Some(targets) => targets,
+        None => return Vec::new(),
+    };
+    let mut mime_types = Vec::new();
+    for target in targets.chunks_exact(std::mem::size_of::<Atom>()) {
+        let target = Atom::from_ne_bytes(target.try_into().unwrap());
+        let mime_type = if target == data.atoms.utf8_string || target == XA_STRING {
+            TEXT_MIME_TYPE.to_string()
+        } else {
+            let name = (data.xlib.XGetAtomName)(data.display, target);
+            if name.is_null() {
+                continue;
+            }
+            let mime_type = CStr::from_ptr(name).to_string_lossy().into_owned();
+            (data.xlib.XFree)(name as *mut c_void);
+            // Other targets are X11 specific, like `TARGETS` and `TIMESTAMP`.
+            if !mime_type.contains('/') {
+                continue;
+            }
+            mime_type
+        };
+        if !mime_types.contains(&mime_type) {
+            mime_types.push(mime_type);
+        }
+    }
+    mime_types
+}
+
+pub(crate) unsafe fn get_data(
+    data: &mut ApplicationData,
+    selection: ClipboardSelection,
+    mime_type: &str,
+) -> Option<Vec<u8>> {
+    // Skip the round trip through the X server if this program owns the selection.
+    if let Some((owned_mime_type, contents)) = data.clipboard.owned.get(&selection) {
+        return if owned_mime_type == mime_type {
+            Some(contents.clone())
+        } else {
+            None
+        };
+    }
+
+    let selection = selection_atom(data, selection);
+    if mime_type == TEXT_MIME_TYPE {
+        // Most programs offer text as `UTF8_STRING` rather than a MIME type.
+        convert_selection(data, selection, data.atoms.utf8_string, CurrentTime)
+            .or_else(|| {
+                convert_selection(
+                    data,
+                    selection,
+                    intern_atom(data, TEXT_MIME_TYPE),
+                    CurrentTime,
+                )
+            })
+            .or_else(|| {
+                convert_selection(data, selection, XA_STRING, CurrentTime)
+                    .map(|t| latin1_to_utf8(&t))
+            })
+    } else {
+        convert_selection(data, selection, intern_atom(data, mime_type), CurrentTime)
+    }
+}
+
+pub(crate) unsafe fn set_data(
+    data: &mut ApplicationData,
+    selection: ClipboardSelection,
+    mime_type: &str,
+    contents: &[u8],
+) {
+    data.clipboard
+        .owned
+        .insert(selection, (mime_type.to_string(), contents.to_vec()));
+    (data.xlib.XSetSelectionOwner)(
+        data.display,
+        selection_atom(data, selection),
+        data.clipboard.window,
+        CurrentTime,
+    );
+    (data.xlib.XFlush)(data.display);
+}
+
+/// Asks the selection's owner to convert it to `target` and waits for the result.
+pub(crate) unsafe fn convert_selection(
+    data: &ApplicationData,
+    selection: Atom,
+    target: Atom,
+    time: Time,
+) -> Option<Vec<u8>> {
+    let window = data.clipboard.window;
+    let property = data.atoms.kapp_selection;
+    (data.xlib.XConvertSelection)(data.display, selection, target, property, window, time);
+    (data.xlib.XFlush)(data.display);
+
+    // A property of `None` means the owner couldn't convert the selection.
+    let notify = wait_for_event(data, SelectionNotify)?.selection;
+    if notify.property == 0 {
+        return None;
+    }
+
+    // Discard property notifications from before the transfer started.
+    let mut event: XEvent = std::mem::zeroed();
+    while (data.xlib.XCheckTypedWindowEvent)(data.display, window, PropertyNotify, &mut event)
+        == True
+    {}
+
+    let (type_, contents) = read_property(data, window, property, true)?;
+    if type_ != data.atoms.incr {
+        return Some(contents);
+    }
+
+    // Deleting the `INCR` property asks the owner to send the first chunk.
+    // Each chunk is another property change, and an empty chunk ends the transfer.
+    let mut contents = Vec::new();
+    loop {
+        let property_event = wait_for_event(data, PropertyNotify)?.property;
+        if property_event.atom != property || property_event.state != PropertyNewValue {
+            continue;
+        }
+        let (_, chunk) = read_property(data, window, property, true)?;
+        if chunk.is_empty() {
+            return Some(contents);
+        }
+        contents.extend_from_slice(&chunk);
+    }
+}
+
+/// Waits for an event of `event_type` sent to the clipboard's window.
+/// Other events are left in the queue.
+unsafe fn wait_for_event(data: &ApplicationData, event_type: c_int) -> Option<XEvent> {
+    let start = Instant::now();
+    let mut event: XEvent = std::mem::zeroed();
+    while (data.xlib.XCheckTypedWindowEvent)(
+        data.display,
+        data.clipboard.window,
+        event_type,
+        &mut event,
+    ) == False
+    {
+        if start.elapsed() > TIMEOUT {
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    Some(event)
+}
+
+/// Reads a property, returning its type and contents.
+pub(crate) unsafe fn read_property(
+    data: &ApplicationData,
+    window: Window,
+    property: Atom,
+    delete: bool,
+) -> Option<(Atom, Vec<u8>)> {
+    let mut actual_type = 0;
+    let mut actual_format = 0;
+    let mut item_count = 0;
+    let mut bytes_after = 0;
+    let mut value = null_mut();
+
+    // The length is in 32 bit units.
+    (data.xlib.XGetWindowProperty)(
+        data.display,
+        window,
+        property,
+        0,
+        c_long::MAX / 4,
+        delete as Bool,
+        AnyPropertyType,
+        &mut actual_type,
+        &mut actual_format,
+        &mut item_count,
+        &mut bytes_after,
+        &mut value,
+    );
+    if value.is_null() {
+        return None;
+    }
+
+    // 32 bit properties are returned as an array of longs.
+    let item_size = match actual_format {
+        32 => std::mem::size_of::<c_long>(),
+        16 => 2,
+        _ => 1,
+    };
+    let contents = std::slice::from_raw_parts(value, item_count as usize * item_size).to_vec();
+    (data.xlib.XFree)(value as *mut c_void);
+    Some((actual_type, contents))
+}
+
+/// Converts the owned data to `target`, returning the property type and contents.
+unsafe fn convert_owned(
+    data: &ApplicationData,
+    mime_type: &str,
+    contents: &[u8],
+    target: Atom,
+) -> Option<(Atom, Vec<u8>)> {
+    if target == intern_atom(data, mime_type) {
+        return Some((target, contents.to_vec()));
+    }
+    if mime_type != TEXT_MIME_TYPE {
+        return None;
+    }
+    if target == data.atoms.utf8_string || target == data.atoms.text {
+        Some((data.atoms.utf8_string, contents.to_vec()))
+    } else if target == XA_STRING {
+        Some((XA_STRING, utf8_to_latin1(contents)))
+    } else {
+        None
+    }
+}
+
+/// Answers another program's request for a selection this program owns.
+unsafe fn handle_selection_request(data: &mut ApplicationData, request: XSelectionRequestEvent) {
+    // Obsolete programs may not specify a property, in which case the target is used.
+    let property = if request.property == 0 {
+        request.target
+    } else {
+        request.property
+    };
+    let owned = selection_from_atom(data, request.selection)
+        .and_then(|selection| data.clipboard.owned.get(&selection));
+
+    let converted = match owned {
+        Some((mime_type, _)) if request.target == data.atoms.targets => {
+            let mut targets = vec![data.atoms.targets, intern_atom(data, mime_type)];
+            if mime_type == TEXT_MIME_TYPE {
+                targets.extend_from_slice(&[data.atoms.utf8_string, data.atoms.text, XA_STRING]);
+            }
+            (data.xlib.XChangeProperty)(
+                data.display,
+                request.requestor,
+                property,
+                XA_ATOM,
+                32,
+                PropModeReplace,
+                targets.as_ptr() as *const c_uchar,
+                targets.len() as c_int,
+            );
+            true
+        }
+        Some((mime_type, contents)) => {
+            match convert_owned(data, mime_type, contents, request.target) {
+                Some((type_, contents)) => {
+                    // Stay well below the largest request the server accepts.
+                    let chunk_size = (data.xlib.XMaxRequestSize)(data.display) as usize;
+                    if contents.len() > chunk_size {
+                        // The requestor's property changes are needed to send the next chunks.
+                        (data.xlib.XSelectInput)(
+                            data.display,
+                            request.requestor,
+                            PropertyChangeMask,
+                        );
+                        let size = contents.len() as c_long;
+                        (data.xlib.XChangeProperty)(
+                            data.display,
+                            request.requestor,
+                            property,
+                            data.atoms.incr,
+                            32,
+                            PropModeReplace,
+                            &size as *const c_long as *const c_uchar,
+                            1,
+                        );
+                        data.clipboard.transfers.push(Transfer {
+                            requestor: request.requestor,
+                            property,
+                            type_,
+                            contents,
+                            offset: 0,
+                        });
+                    } else {
+                        (data.xlib.XChangeProperty)(
+                            data.display,
+                            request.requestor,
+                            property,
+                            type_,
+                            8,
+                            PropModeReplace,
+                            contents.as_ptr(),
+                            contents.len() as c_int,
+                        );
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+        None => false,
+    };
+
+    let mut event: XEvent = std::mem::zeroed();
+    event.selection = XSelectionEvent {
+        type_: SelectionNotify,
+        serial: 0,
+        send_event: True,
+        display: data.display,
+        requestor: request.requestor,
+        selection: request.selection,
+        target: request.target,
+        property: if converted { property } else { 0 },
+        time: request.time,
+    };
+    (data.xlib.XSendEvent)(
+        data.display,
+        request.requestor,
+        False,
+        NoEventMask,
+        &mut event,
+    );
+    (data.xlib.XFlush)(data.display);
+}
+
+/// Sends the next chunk of a transfer after the requestor deleted the previous one.
+unsafe fn continue_transfer(data: &mut ApplicationData, index: usize) {
+    let chunk_size = (data.xlib.XMaxRequestSize)(data.display) as usize;
+    let transfer = &mut data.clipboard.transfers[index];
+    let end = (transfer.offset + chunk_size).min(transfer.contents.len());
+    let chunk = &transfer.contents[transfer.offset..end];
+    (data.xlib.XChangeProperty)(
+        data.display,
+        transfer.requestor,
+        transfer.property,
+        transfer.type_,
+        8,
+        PropModeReplace,
+        chunk.as_ptr(),
+        chunk.len() as c_int,
+    );
+
+    // The empty chunk that ends the transfer has been sent.
+    if chunk.is_empty() {
+        let transfer = data.clipboard.transfers.remove(index);
+        (data.xlib.XSelectInput)(data.display, transfer.requestor, NoEventMask);
+    } else {
+        transfer.offset = end;
+    }
+    (data.xlib.XFlush)(data.display);
+}
+
+/// Handles events for the clipboard's window and outgoing transfers.
+/// Returns `true` if the event was handled.
+// Xlib event type names are matched as-is.
+#[allow(non_upper_case_globals)]
+pub(crate) unsafe fn process_event(
+    data: &mut ApplicationData,
+    event: &XEvent,
+    events: &mut Vec<Event>,
+) -> bool {
+    if let Some((_, event_base)) = &data.clipboard.xfixes {
+        if event.type_ == event_base + XFixesSelectionNotify {
+            if let Some(selection) = selection_from_atom(data, event.xfixes_selection.selection) {
+                events.push(Event::ClipboardChanged { selection });
+            }
+            return true;
+        }
+    }
+
+    match event.type_ {
+        SelectionRequest if event.selection_request.owner == data.clipboard.window => {
+            handle_selection_request(data, event.selection_request);
+            true
+        }
+        SelectionClear if event.selection_clear.window == data.clipboard.window => {
+            // Another program owns the selection now.
+            if let Some(selection) = selection_from_atom(data, event.selection_clear.selection) {
+                data.clipboard.owned.remove(&selection);
+            }
+            true
+        }
+        PropertyNotify => {
+            let property_event = event.property;
+            if property_event.window == data.clipboard.window {
+                return true;
+            }
+            let index = data.clipboard.transfers.iter().position(|transfer| {
+                transfer.requestor == property_event.window
+                    && transfer.property == property_event.atom
+            });
+            match index {
+                Some(index) => {
+                    if property_event.state == PropertyDelete {
+                        continue_transfer(data, index);
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+        _ => event.any.window == data.clipboard.window,
+    }
+}
@@ -0,0 +1,230 @@
+//! Drops are received with the XDND protocol.
+//! https://www.freedesktop.org/wiki/Specifications/XDND/
+use super::application_x11::ApplicationData;
+use super::clipboard_x11::{convert_selection, read_property};
+use super::external_x11::*;
+use kapp_platform_common::*;
+
+use std::convert::TryInto;
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
+
+const XDND_VERSION: c_long = 5;
+
+pub(crate) struct Drag {
+    /// The window of the program the drag comes from.
+    source: Window,
+    pub window: Window,
+    /// The type the data will be requested as, or `None` if no supported type is offered.
+    target: Option<Atom>,
+}
+
+/// Tells drag sources the window accepts drops.
+pub(crate) unsafe fn set_drop_target(data: &ApplicationData, window: Window) {
+    (data.xlib.XChangeProperty)(
+        data.display,
+        window,
+        data.atoms.xdnd_aware,
+        XA_ATOM,
+        32,
+        PropModeReplace,
+        &XDND_VERSION as *const c_long as *const c_uchar,
+        1,
+    );
+}
+
+/// Picks the type to request the drop as, preferring files over text.
+fn choose_target(data: &ApplicationData, types: &[Atom]) -> Option<Atom> {
+    [
+        data.atoms.text_uri_list,
+        data.atoms.utf8_string,
+        data.atoms.text_plain_utf8,
+        data.atoms.text_plain,
+    ]
+    .iter()
+    .find(|target| types.contains(target))
+    .copied()
+}
+
+unsafe fn send_message(
+    data: &ApplicationData,
+    window: Window,
+    message_type: Atom,
+    values: [c_long; 5],
+) {
+    let mut event: XEvent = std::mem::zeroed();
+    event.client_message = XClientMessageEvent {
+        type_: ClientMessage,
+        serial: 0,
+        send_event: True,
+        display: data.display,
+        window,
+        message_type,
+        format: 32,
+        data: values,
+    };
+    (data.xlib.XSendEvent)(data.display, window, False, NoEventMask, &mut event);
+    (data.xlib.XFlush)(data.display);
+}
+
+/// Tells the drag's source if the drop will be accepted.
+pub(crate) unsafe fn send_status(data: &ApplicationData) {
+    if let Some(drag) = &data.drag {
+        let accepted = data.drop_accepted && drag.target.is_some();
+        let action = if accepted {
+            data.atoms.xdnd_action_copy
+        } else {
+            0
+        };
+        // The empty rectangle asks for a new position each time the pointer moves.
+        send_message(
+            data,
+            drag.source,
+            data.atoms.xdnd_status,
+            [
+                drag.window as c_long,
+                accepted as c_long,
+                0,
+                0,
+                action as c_long,
+            ],
+        );
+    }
+}
+
+/// Handles XDND messages sent to a window.
+pub(crate) unsafe fn process_client_message(
+    data: &mut ApplicationData,
+    window: Window,
+    message: &XClientMessageEvent,
+    events: &mut Vec<Event>,
+) {
+    let window_id = WindowId::new(window as *mut c_void);
+    let message_type = message.message_type;
+    if message_type == data.atoms.xdnd_enter {
+        let source = message.data[0] as Window;
+        // The first three types are in the message. If there are more they're in a property.
+        let types: Vec<Atom> = if message.data[1] & 1 != 0 {
+            read_property(data, source, data.atoms.xdnd_type_list, false)
+                .map(|(_, types)| {
+                    types
+                        .chunks_exact(std::mem::size_of::<Atom>())
+                        .map(|atom| Atom::from_ne_bytes(atom.try_into().unwrap()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            message.data[2..5]
+                .iter()
+                .map(|atom| *atom as Atom)
+                .collect()
+        };
+        data.drag = Some(Drag {
+            source,
+            window,
+            target: choose_target(data, &types),
+        });
+        data.drop_accepted = true;
+        events.push(Event::DragEntered { window_id });
+    } else if message_type == data.atoms.xdnd_position {
+        // The position is relative to the root window.
+        let root_x = (message.data[2] >> 16) as c_int;
+        let root_y = (message.data[2] & 0xFFFF) as c_int;
+        let (window_x, window_y) = data.window_position(window);
+        events.push(Event::DragMoved {
+            x: (root_x - window_x) as f64,
+            y: (root_y - window_y) as f64,
+            window_id,
+        });
+        send_status(data);
+    } else if message_type == data.atoms.xdnd_leave {
+        data.drag = None;
+        events.push(Event::DragLeft { window_id });
+    } else if message_type == data.atoms.xdnd_drop {
+        let drag = match data.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        let time = message.data[2] as Time;
+        let dropped = match drag.target {
+            Some(target) if data.drop_accepted => {
+                convert_selection(data, data.atoms.xdnd_selection, target, time)
+                    .and_then(|contents| drop_data(data, target, contents))
+            }
+            _ => None,
+        };
+
+        let action = if dropped.is_some() {
+            data.atoms.xdnd_action_copy
+        } else {
+            0
+        };
+        send_message(
+            data,
+            drag.source,
+            data.atoms.xdnd_finished,
+            [
+                window as c_long,
+                dropped.is_some() as c_long,
+                action as c_long,
+                0,
+                0,
+            ],
+        );
+        events.push(match dropped {
+            Some(data) => Event::Dropped { data, window_id },
+            None => Event::DragLeft { window_id },
+        });
+    }
+}
+
+fn drop_data(data: &ApplicationData, target: Atom, contents: Vec<u8>) -> Option<DropData> {
+    if target == data.atoms.text_uri_list {
+        let paths = parse_uri_list(&contents);
+        if paths.is_empty() {
+            None
+        } else {
+            Some(DropData::Paths(paths))
+        }
+    } else {
+        String::from_utf8(contents).ok().map(DropData::Text)
+    }
+}
+
+/// Returns the local file paths in a `text/uri-list`.
+fn parse_uri_list(uri_list: &[u8]) -> Vec<PathBuf> {
+    String::from_utf8_lossy(uri_list)
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|uri| {
+            let path = uri.trim().strip_prefix("file://")?;
+            // Skip the host name, which is usually empty or `localhost`.
+            let path = &path[path.find('/')?..];
+            Some(PathBuf::from(OsString::from_vec(percent_decode(path))))
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
@@ -0,0 +1,589 @@
+use super::application_x11::{with_data, ApplicationData, MouseClick, WindowState};
+use super::clipboard_x11;
+use super::drag_drop_x11;
+use super::external_x11::*;
+use super::screens_x11;
+use crate::linux::keys_linux::{evdev_code_to_key, keysym_to_character};
+use kapp_platform_common::*;
+
+use std::ptr::null_mut;
+use std::time::Duration;
+
+// The X server does not report double clicks, so they're detected here.
+// 500 milliseconds is the default double click time on most desktops.
+const DOUBLE_CLICK_TIME: Time = 500;
+
+pub fn run(callback: Box<dyn FnMut(Event)>) {
+    event_receiver::set_callback(callback);
+
+    let mut events = Vec::new();
+    loop {
+        // Wait for a new event if we don't have any redraw requests
+        let wait = redraw_manager::draw_requests_count() == 0;
+        with_data(|data| unsafe {
+            // The events are read below.
+            if wait {
+                wait_for_events(data, control_flow::timeout());
+            }
+            data.wake_pipe.clear();
+        });
+
+        // Process all events.
+        // The borrow is released between events so the user callback can use the application.
+        while let Some(mut event) = with_data(|data| unsafe { next_event(data) }) {
+            with_data(|data| unsafe { process_event(data, &mut event, &mut events) });
+            send_events(&mut events);
+        }
+        event_loop_proxy::send_proxy_events();
+        control_flow::resume_if_reached();
+        timers::send_due_timers();
+
+        // When there are no events remaining, we're at the end of the event loop
+        event_receiver::send_event(Event::EventsCleared);
+
+        if with_data(|data| data.actually_quit) {
+            event_receiver::send_event(Event::Quit);
+            break;
+        }
+
+        // Send a draw event for each window that needs to be drawn.
+        redraw_manager::begin_draw_flush();
+        while let Some(window_id) = redraw_manager::get_draw_request() {
+            event_receiver::send_event(Event::Draw { window_id });
+        }
+
+        // Send any requests made by the user's code to the X server.
+        with_data(|data| unsafe {
+            (data.xlib.XFlush)(data.display);
+        });
+    }
+}
+
+fn send_events(events: &mut Vec<Event>) {
+    for event in events.drain(..) {
+        event_receiver::send_event(event);
+    }
+}
+
+/// Waits until the X server sends events, a proxy wakes the event loop, or the timeout passes.
+/// Without a timeout it waits indefinitely.
+unsafe fn wait_for_events(data: &ApplicationData, timeout: Option<Duration>) {
+    // Xlib may have already read events from the connection.
+    if (data.xlib.XPending)(data.display) > 0 || event_loop_proxy::proxy_events_pending() {
+        return;
+    }
+    let mut poll_fds = [
+        pollfd {
+            fd: (data.xlib.XConnectionNumber)(data.display),
+            events: POLLIN,
+            revents: 0,
+        },
+        pollfd {
+            fd: data.wake_pipe.read_fd(),
+            events: POLLIN,
+            revents: 0,
+        },
+    ];
+    let timeout = timeout.map_or(-1, control_flow::timeout_milliseconds);
+    poll(poll_fds.as_mut_ptr(), poll_fds.len() as u64, timeout);
+}
+
+unsafe fn next_event(data: &mut ApplicationData) -> Option<XEvent> {
+    if (data.xlib.XPending)(data.display) > 0 {
+        let mut event: XEvent = std::mem::zeroed();
+        (data.xlib.XNextEvent)(data.display, &mut event);
+        Some(event)
+    } else {
+        None
+    }
+}
+
+fn window_id(window: Window) -> WindowId {
+    WindowId::new(window as *mut c_void)
+}
+
+/// Input events report the modifiers from before the event.
+fn state_to_modifiers(state: c_uint) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, state & ShiftMask != 0);
+    modifiers.set(Modifiers::CAPS_LOCK, state & LockMask != 0);
+    modifiers.set(Modifiers::CONTROL, state & ControlMask != 0);
+    modifiers.set(Modifiers::ALT, state & Mod1Mask != 0);
+    modifiers.set(Modifiers::NUM_LOCK, state & Mod2Mask != 0);
+    modifiers.set(Modifiers::META, state & Mod4Mask != 0);
+    modifiers
+}
+
+/// Sends `ModifiersChanged` if the modifiers are different from the last sent.
+fn update_modifiers(data: &mut ApplicationData, modifiers: Modifiers, events: &mut Vec<Event>) {
+    if data.modifiers != modifiers {
+        data.modifiers = modifiers;
+        events.push(Event::ModifiersChanged { modifiers });
+    }
+}
+
+/// X keycodes are evdev codes offset by 8.
+/// The evdev code is used as the scancode.
+fn keycode_to_scancode(keycode: c_uint) -> u32 {
+    keycode.wrapping_sub(8)
+}
+
+/// Returns the key's meaning in the current layout, ignoring modifiers.
+unsafe fn logical_key(data: &ApplicationData, key_event: &mut XKeyEvent, key: Key) -> LogicalKey {
+    let keysym = (data.xlib.XLookupKeysym)(key_event, 0);
+    LogicalKey::from_character(keysym_to_character(keysym as u32), key)
+}
+
+fn button_to_pointer_button(button: c_uint) -> PointerButton {
+    match button {
+        1 => PointerButton::Primary,
+        2 => PointerButton::Auxillary,
+        3 => PointerButton::Secondary,
+        8 => PointerButton::Extra1,
+        9 => PointerButton::Extra2,
+        _ => PointerButton::Unknown,
+    }
+}
+
+// Xlib event type names are matched as-is.
+#[allow(non_upper_case_globals)]
+unsafe fn process_event(data: &mut ApplicationData, event: &mut XEvent, events: &mut Vec<Event>) {
+    // Give the input method a chance to consume the event.
+    if (data.xlib.XFilterEvent)(event, 0) == True {
+        return;
+    }
+
+    if screens_x11::process_event(data, event, events) {
+        return;
+    }
+
+    if clipboard_x11::process_event(data, event, events) {
+        return;
+    }
+
+    // Ignore events for windows that have been closed.
+    let window = event.any.window;
+    if !data.windows.contains_key(&window) {
+        return;
+    }
+
+    match event.type_ {
+        ClientMessage => {
+            let client_message = event.client_message;
+            if client_message.message_type == data.atoms.wm_protocols
+                && client_message.data[0] as Atom == data.atoms.wm_delete_window
+            {
+                events.push(Event::WindowCloseRequested {
+                    window_id: window_id(window),
+                });
+            } else {
+                drag_drop_x11::process_client_message(data, window, &client_message, events);
+            }
+        }
+        Expose if event.expose.count == 0 => {
+            // Only request a redraw for the last expose event in a series.
+            redraw_manager::add_draw_request(window_id(window));
+        }
+        ConfigureNotify => {
+            let configure = event.configure;
+            // Positions in configure events are relative to the window manager's frame,
+            // so the position is looked up relative to the root window instead.
+            let (x, y) = data.window_position(window);
+            let window_data = data.windows.get_mut(&window).unwrap();
+            if configure.width != window_data.width || configure.height != window_data.height {
+                window_data.width = configure.width;
+                window_data.height = configure.height;
+                events.push(Event::WindowResized {
+                    width: configure.width as u32,
+                    height: configure.height as u32,
+                    window_id: window_id(window),
+                });
+            }
+            if x != window_data.x || y != window_data.y {
+                window_data.x = x;
+                window_data.y = y;
+                events.push(Event::WindowMoved {
+                    x: x.max(0) as u32,
+                    y: y.max(0) as u32,
+                    window_id: window_id(window),
+                });
+            }
+        }
+        PropertyNotify if event.property.atom == data.atoms.net_wm_state => {
+            let new_state = data.read_window_state(window);
+            let window_data = data.windows.get_mut(&window).unwrap();
+            let old_state = window_data.state;
+            window_data.state = new_state;
+
+            // The window manager can change fullscreen without the program asking.
+            let fullscreen = if !new_state.fullscreen {
+                if !new_state.minimized {
+                    let old_fullscreen =
+                        std::mem::replace(&mut window_data.fullscreen, Fullscreen::Off);
+                    if let Fullscreen::Exclusive(_) = old_fullscreen {
+                        screens_x11::restore_video_modes(data);
+                    }
+                }
+                Fullscreen::Off
+            } else if window_data.fullscreen == Fullscreen::Off {
+                let center_x = window_data.x + window_data.width / 2;
+                let center_y = window_data.y + window_data.height / 2;
+                let screen = data
+                    .screens
+                    .last
+                    .iter()
+                    .find(|screen| screen.contains(center_x, center_y));
+                let fullscreen = Fullscreen::Borderless(screen.map(|screen| screen.id));
+                data.windows.get_mut(&window).unwrap().fullscreen = fullscreen;
+                fullscreen
+            } else {
+                window_data.fullscreen
+            };
+
+            let window_id = window_id(window);
+            if let Some(event) = window_state_event(old_state, new_state, fullscreen, window_id) {
+                events.push(event);
+            }
+        }
+        FocusIn => {
+            // Focus events caused by the pointer are not real focus changes.
+            if event.focus_change.detail == NotifyPointer {
+                return;
+            }
+            let input_context = data.windows[&window].input_context;
+            if !input_context.is_null() {
+                (data.xlib.XSetICFocus)(input_context);
+            }
+            data.focused_window = Some(window);
+            events.push(Event::WindowGainedFocus {
+                window_id: window_id(window),
+            });
+        }
+        FocusOut => {
+            if event.focus_change.detail == NotifyPointer {
+                return;
+            }
+            let input_context = data.windows[&window].input_context;
+            if !input_context.is_null() {
+                (data.xlib.XUnsetICFocus)(input_context);
+            }
+            if data.focused_window == Some(window) {
+                data.focused_window = None;
+            }
+            // Key releases will not be received while the window is unfocused.
+            data.held_keys.clear();
+            events.push(Event::WindowLostFocus {
+                window_id: window_id(window),
+            });
+        }
+        KeyPress => {
+            let key_event = &mut event.key;
+            let scancode = keycode_to_scancode(key_event.keycode);
+            let key = evdev_code_to_key(scancode);
+            let logical_key = logical_key(data, key_event, key);
+            let timestamp = Duration::from_millis(key_event.time);
+
+            if data.held_keys.insert(key_event.keycode) {
+                let modifiers = state_to_modifiers(key_event.state).after_key(key, true);
+                update_modifiers(data, modifiers, events);
+                events.push(Event::KeyDown {
+                    key,
+                    scancode,
+                    logical_key,
+                    window_id: window_id(window),
+                    modifiers,
+                    timestamp,
+                });
+            } else {
+                events.push(Event::KeyRepeat {
+                    key,
+                    scancode,
+                    logical_key,
+                    window_id: window_id(window),
+                    modifiers: data.modifiers,
+                    timestamp,
+                });
+            }
+
+            if data.text_input_enabled {
+                let input_context = data.windows[&window].input_context;
+                for character in lookup_string(data, input_context, key_event).chars() {
+                    // Control characters are reported with KeyDown instead.
+                    if !character.is_control() {
+                        events.push(Event::CharacterReceived {
+                            character,
+                            window_id: window_id(window),
+                        });
+                    }
+                }
+            }
+        }
+        KeyRelease => {
+            let key_event = &mut event.key;
+            let scancode = keycode_to_scancode(key_event.keycode);
+            let key = evdev_code_to_key(scancode);
+            let logical_key = logical_key(data, key_event, key);
+            data.held_keys.remove(&key_event.keycode);
+
+            let modifiers = state_to_modifiers(key_event.state).after_key(key, false);
+            update_modifiers(data, modifiers, events);
+            events.push(Event::KeyUp {
+                key,
+                scancode,
+                logical_key,
+                window_id: window_id(window),
+                modifiers,
+                timestamp: Duration::from_millis(key_event.time),
+            });
+        }
+        ButtonPress => {
+            let button_event = event.button;
+            let timestamp = Duration::from_millis(button_event.time);
+            let modifiers = state_to_modifiers(button_event.state);
+            update_modifiers(data, modifiers, events);
+
+            // Scroll wheels are reported as buttons 4 through 7.
+            let scroll = match button_event.button {
+                4 => Some((0., 1.)),
+                5 => Some((0., -1.)),
+                6 => Some((-1., 0.)),
+                7 => Some((1., 0.)),
+                _ => None,
+            };
+            if let Some((delta_x, delta_y)) = scroll {
+                events.push(Event::Scroll {
+                    delta_x,
+                    delta_y,
+                    window_id: window_id(window),
+                    modifiers,
+                    timestamp,
+                });
+                return;
+            }
+
+            let x = button_event.x as f64;
+            let y = button_event.y as f64;
+            let button = button_to_pointer_button(button_event.button);
+            events.push(Event::PointerDown {
+                x,
+                y,
+                source: PointerSource::Mouse,
+                pointer_id: 0,
+                button,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: window_id(window),
+                modifiers,
+                timestamp,
+            });
+
+            let is_double_click = match &data.last_click {
+                Some(last_click) => {
+                    last_click.window == window
+                        && last_click.button == button_event.button
+                        && button_event.time.wrapping_sub(last_click.time) < DOUBLE_CLICK_TIME
+                }
+                None => false,
+            };
+            if is_double_click {
+                // Reset so that a third click does not produce another double click.
+                data.last_click = None;
+                data.double_click_button = Some(button_event.button);
+                events.push(Event::DoubleClickDown {
+                    x,
+                    y,
+                    button,
+                    window_id: window_id(window),
+                    timestamp,
+                });
+                events.push(Event::DoubleClick {
+                    x,
+                    y,
+                    button,
+                    window_id: window_id(window),
+                    timestamp,
+                });
+            } else {
+                data.last_click = Some(MouseClick {
+                    window,
+                    button: button_event.button,
+                    time: button_event.time,
+                });
+            }
+        }
+        ButtonRelease => {
+            let button_event = event.button;
+            if (4..=7).contains(&button_event.button) {
+                return;
+            }
+
+            let timestamp = Duration::from_millis(button_event.time);
+            let modifiers = state_to_modifiers(button_event.state);
+            update_modifiers(data, modifiers, events);
+
+            let x = button_event.x as f64;
+            let y = button_event.y as f64;
+            let button = button_to_pointer_button(button_event.button);
+            events.push(Event::PointerUp {
+                x,
+                y,
+                source: PointerSource::Mouse,
+                pointer_id: 0,
+                button,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: window_id(window),
+                modifiers,
+                timestamp,
+            });
+
+            if data.double_click_button == Some(button_event.button) {
+                data.double_click_button = None;
+                events.push(Event::DoubleClickUp {
+                    x,
+                    y,
+                    button,
+                    window_id: window_id(window),
+                    timestamp,
+                });
+            }
+        }
+        MotionNotify => {
+            let motion_event = event.motion;
+            let timestamp = Duration::from_millis(motion_event.time);
+
+            if let Some((lock_window, lock_x, lock_y)) = data.mouse_lock {
+                // Moving the pointer back to the lock position produces another motion event
+                // that is ignored here.
+                let delta_x = motion_event.x - lock_x;
+                let delta_y = motion_event.y - lock_y;
+                if window == lock_window && (delta_x != 0 || delta_y != 0) {
+                    events.push(Event::MouseMotion {
+                        delta_x: delta_x as f64,
+                        delta_y: delta_y as f64,
+                        window_id: window_id(window),
+                        timestamp,
+                    });
+                    (data.xlib.XWarpPointer)(
+                        data.display,
+                        0,
+                        lock_window,
+                        0,
+                        0,
+                        0,
+                        0,
+                        lock_x,
+                        lock_y,
+                    );
+                }
+                return;
+            }
+
+            // Root coordinates are used so that motion is continuous between windows.
+            if let Some((last_x, last_y)) = data.last_root_pointer_position {
+                events.push(Event::MouseMotion {
+                    delta_x: (motion_event.x_root - last_x) as f64,
+                    delta_y: (motion_event.y_root - last_y) as f64,
+                    window_id: window_id(window),
+                    timestamp,
+                });
+            }
+            data.last_root_pointer_position = Some((motion_event.x_root, motion_event.y_root));
+
+            events.push(Event::PointerMoved {
+                x: motion_event.x as f64,
+                y: motion_event.y as f64,
+                source: PointerSource::Mouse,
+                pointer_id: 0,
+                pressure: 0.0,
+                tilt_x: 0.0,
+                tilt_y: 0.0,
+                contact_width: 0.0,
+                contact_height: 0.0,
+                window_id: window_id(window),
+                timestamp,
+            });
+        }
+        EnterNotify => {
+            data.pointer_window = Some(window);
+        }
+        LeaveNotify if data.pointer_window == Some(window) => {
+            data.pointer_window = None;
+        }
+        _ => {}
+    }
+}
+
+/// Produces the event for a change in the window manager's state for the window.
+/// `fullscreen` is the window's fullscreen in the new state.
+fn window_state_event(
+    old: WindowState,
+    new: WindowState,
+    fullscreen: Fullscreen,
+    window_id: WindowId,
+) -> Option<Event> {
+    if new == old {
+        None
+    } else if new.minimized && !old.minimized {
+        Some(Event::WindowMinimized { window_id })
+    } else if new.fullscreen && !old.fullscreen && !new.minimized {
+        Some(Event::WindowFullscreened {
+            fullscreen,
+            window_id,
+        })
+    } else if new.maximized && !old.maximized && !new.minimized && !new.fullscreen {
+        Some(Event::WindowMaximized { window_id })
+    } else if old != WindowState::default() && !new.minimized {
+        Some(Event::WindowRestored {
+            fullscreen,
+            window_id,
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the text produced by a key press.
+unsafe fn lookup_string(
+    data: &ApplicationData,
+    input_context: XIC,
+    key_event: &mut XKeyEvent,
+) -> String {
+    let mut buffer = [0u8; 64];
+    let mut keysym = 0;
+    if input_context.is_null() {
+        // Without an input method only Latin-1 text is available.
+        let length = (data.xlib.XLookupString)(
+            key_event,
+            buffer.as_mut_ptr() as *mut c_char,
+            buffer.len() as c_int,
+            &mut keysym,
+            null_mut(),
+        );
+        buffer[..length.max(0) as usize]
+            .iter()
+            .map(|byte| *byte as char)
+            .collect()
+    } else {
+        let mut status = 0;
+        let length = (data.xlib.Xutf8LookupString)(
+            input_context,
+            key_event,
+            buffer.as_mut_ptr() as *mut c_char,
+            buffer.len() as c_int,
+            &mut keysym,
+            &mut status,
+        );
+        if status == XLookupChars || status == XLookupBoth {
+            String::from_utf8_lossy(&buffer[..length.max(0) as usize]).into_owned()
+        } else {
+            String::new()
+        }
+    }
+}
@@ -0,0 +1,811 @@
+//! Xlib types, constants, and functions.
+//! Hand transcribed from Xlib.h, X.h, and Xutil.h
+//! Functions are loaded at runtime with `dynamic_library!`
+pub use std::ffi::c_void;
+pub use std::os::raw::{c_char, c_int, c_long, c_short, c_uchar, c_uint, c_ulong, c_ushort};
+
+pub enum Display {}
+pub enum Visual {}
+pub enum XIMRec {}
+pub enum XICRec {}
+
+#[allow(clippy::upper_case_acronyms)]
+pub type XID = c_ulong;
+pub type Window = XID;
+pub type Atom = XID;
+pub type Pixmap = XID;
+/// `Cursor` in Xlib, renamed to avoid conflicting with `kapp_platform_common::Cursor`
+pub type XCursor = XID;
+pub type Colormap = XID;
+pub type Drawable = XID;
+pub type KeySym = XID;
+pub type Time = c_ulong;
+pub type Bool = c_int;
+pub type Status = c_int;
+#[allow(clippy::upper_case_acronyms)]
+pub type XIM = *mut XIMRec;
+#[allow(clippy::upper_case_acronyms)]
+pub type XIC = *mut XICRec;
+pub type GC = *mut c_void;
+
+pub const False: Bool = 0;
+pub const True: Bool = 1;
+pub const CurrentTime: Time = 0;
+
+// Event types
+pub const KeyPress: c_int = 2;
+pub const KeyRelease: c_int = 3;
+pub const ButtonPress: c_int = 4;
+pub const ButtonRelease: c_int = 5;
+pub const MotionNotify: c_int = 6;
+pub const EnterNotify: c_int = 7;
+pub const LeaveNotify: c_int = 8;
+pub const FocusIn: c_int = 9;
+pub const FocusOut: c_int = 10;
+pub const Expose: c_int = 12;
+pub const UnmapNotify: c_int = 18;
+pub const MapNotify: c_int = 19;
+pub const ConfigureNotify: c_int = 22;
+pub const PropertyNotify: c_int = 28;
+pub const SelectionClear: c_int = 29;
+pub const SelectionRequest: c_int = 30;
+pub const SelectionNotify: c_int = 31;
+pub const ClientMessage: c_int = 33;
+
+// Event masks
+pub const NoEventMask: c_long = 0;
+pub const KeyPressMask: c_long = 1 << 0;
+pub const KeyReleaseMask: c_long = 1 << 1;
+pub const ButtonPressMask: c_long = 1 << 2;
+pub const ButtonReleaseMask: c_long = 1 << 3;
+pub const EnterWindowMask: c_long = 1 << 4;
+pub const LeaveWindowMask: c_long = 1 << 5;
+pub const PointerMotionMask: c_long = 1 << 6;
+pub const ExposureMask: c_long = 1 << 15;
+pub const StructureNotifyMask: c_long = 1 << 17;
+pub const SubstructureNotifyMask: c_long = 1 << 19;
+pub const SubstructureRedirectMask: c_long = 1 << 20;
+pub const FocusChangeMask: c_long = 1 << 21;
+pub const PropertyChangeMask: c_long = 1 << 22;
+
+// Window attribute masks
+pub const CWBackPixel: c_ulong = 1 << 1;
+pub const CWBorderPixel: c_ulong = 1 << 3;
+pub const CWEventMask: c_ulong = 1 << 11;
+pub const CWColormap: c_ulong = 1 << 13;
+
+pub const InputOutput: c_uint = 1;
+pub const AllocNone: c_int = 0;
+pub const IsViewable: c_int = 2;
+pub const TrueColor: c_int = 4;
+
+// Focus event details
+pub const NotifyPointer: c_int = 5;
+
+// Property modes
+pub const PropModeReplace: c_int = 0;
+
+// Property notification states
+pub const PropertyNewValue: c_int = 0;
+pub const PropertyDelete: c_int = 1;
+
+pub const AnyPropertyType: Atom = 0;
+pub const XA_PRIMARY: Atom = 1;
+pub const XA_ATOM: Atom = 4;
+pub const XA_CARDINAL: Atom = 6;
+pub const XA_STRING: Atom = 31;
+
+// Size hint flags
+pub const USPosition: c_long = 1 << 0;
+pub const PPosition: c_long = 1 << 2;
+pub const PMinSize: c_long = 1 << 4;
+pub const PMaxSize: c_long = 1 << 5;
+
+// Pointer grabbing
+pub const GrabModeAsync: c_int = 1;
+pub const GrabSuccess: c_int = 0;
+
+// Modifier masks as reported in the `state` field of input events.
+pub const ShiftMask: c_uint = 1 << 0;
+pub const LockMask: c_uint = 1 << 1;
+pub const ControlMask: c_uint = 1 << 2;
+pub const Mod1Mask: c_uint = 1 << 3;
+pub const Mod2Mask: c_uint = 1 << 4;
+pub const Mod4Mask: c_uint = 1 << 6;
+
+// XIM
+pub const XIMPreeditNothing: c_ulong = 0x0008;
+pub const XIMStatusNothing: c_ulong = 0x0400;
+pub const XLookupChars: Status = 2;
+pub const XLookupBoth: Status = 4;
+pub const XNInputStyle: &[u8] = b"inputStyle\0";
+pub const XNClientWindow: &[u8] = b"clientWindow\0";
+pub const XNFocusWindow: &[u8] = b"focusWindow\0";
+
+// Font cursor glyphs from cursorfont.h
+pub const XC_X_cursor: c_uint = 0;
+pub const XC_bottom_left_corner: c_uint = 12;
+pub const XC_bottom_right_corner: c_uint = 14;
+pub const XC_crosshair: c_uint = 34;
+pub const XC_fleur: c_uint = 52;
+pub const XC_hand1: c_uint = 58;
+pub const XC_hand2: c_uint = 60;
+pub const XC_left_ptr: c_uint = 68;
+pub const XC_plus: c_uint = 90;
+pub const XC_question_arrow: c_uint = 92;
+pub const XC_sb_h_double_arrow: c_uint = 108;
+pub const XC_sb_v_double_arrow: c_uint = 116;
+pub const XC_watch: c_uint = 150;
+pub const XC_xterm: c_uint = 152;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XAnyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XKeyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub keycode: c_uint,
+    pub same_screen: Bool,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XButtonEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub button: c_uint,
+    pub same_screen: Bool,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XMotionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub root: Window,
+    pub subwindow: Window,
+    pub time: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub x_root: c_int,
+    pub y_root: c_int,
+    pub state: c_uint,
+    pub is_hint: c_char,
+    pub same_screen: Bool,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XFocusChangeEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub mode: c_int,
+    pub detail: c_int,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XExposeEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub count: c_int,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XConfigureEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub event: Window,
+    pub window: Window,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub border_width: c_int,
+    pub above: Window,
+    pub override_redirect: Bool,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XPropertyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub atom: Atom,
+    pub time: Time,
+    pub state: c_int,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XSelectionClearEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub selection: Atom,
+    pub time: Time,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XSelectionRequestEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub owner: Window,
+    pub requestor: Window,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+    pub time: Time,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XSelectionEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub requestor: Window,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+    pub time: Time,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XClientMessageEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub message_type: Atom,
+    pub format: c_int,
+    pub data: [c_long; 5],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union XEvent {
+    pub type_: c_int,
+    pub any: XAnyEvent,
+    pub key: XKeyEvent,
+    pub button: XButtonEvent,
+    pub motion: XMotionEvent,
+    pub focus_change: XFocusChangeEvent,
+    pub expose: XExposeEvent,
+    pub configure: XConfigureEvent,
+    pub property: XPropertyEvent,
+    pub selection_clear: XSelectionClearEvent,
+    pub selection_request: XSelectionRequestEvent,
+    pub selection: XSelectionEvent,
+    pub client_message: XClientMessageEvent,
+    pub xfixes_selection: XFixesSelectionNotifyEvent,
+    pub pad: [c_long; 24],
+}
+
+#[repr(C)]
+pub struct XSetWindowAttributes {
+    pub background_pixmap: Pixmap,
+    pub background_pixel: c_ulong,
+    pub border_pixmap: Pixmap,
+    pub border_pixel: c_ulong,
+    pub bit_gravity: c_int,
+    pub win_gravity: c_int,
+    pub backing_store: c_int,
+    pub backing_planes: c_ulong,
+    pub backing_pixel: c_ulong,
+    pub save_under: Bool,
+    pub event_mask: c_long,
+    pub do_not_propagate_mask: c_long,
+    pub override_redirect: Bool,
+    pub colormap: Colormap,
+    pub cursor: XCursor,
+}
+
+#[repr(C)]
+pub struct XWindowAttributes {
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub border_width: c_int,
+    pub depth: c_int,
+    pub visual: *mut Visual,
+    pub root: Window,
+    pub class: c_int,
+    pub bit_gravity: c_int,
+    pub win_gravity: c_int,
+    pub backing_store: c_int,
+    pub backing_planes: c_ulong,
+    pub backing_pixel: c_ulong,
+    pub save_under: Bool,
+    pub colormap: Colormap,
+    pub map_installed: Bool,
+    pub map_state: c_int,
+    pub all_event_masks: c_long,
+    pub your_event_mask: c_long,
+    pub do_not_propagate_mask: c_long,
+    pub override_redirect: Bool,
+    pub screen: *mut c_void,
+}
+
+#[repr(C)]
+pub struct XVisualInfo {
+    pub visual: *mut Visual,
+    pub visualid: XID,
+    pub screen: c_int,
+    pub depth: c_int,
+    pub class: c_int,
+    pub red_mask: c_ulong,
+    pub green_mask: c_ulong,
+    pub blue_mask: c_ulong,
+    pub colormap_size: c_int,
+    pub bits_per_rgb: c_int,
+}
+
+#[repr(C)]
+pub struct XRectangle {
+    pub x: c_short,
+    pub y: c_short,
+    pub width: c_ushort,
+    pub height: c_ushort,
+}
+
+#[repr(C)]
+pub struct AspectRatio {
+    pub x: c_int,
+    pub y: c_int,
+}
+
+#[repr(C)]
+pub struct XSizeHints {
+    pub flags: c_long,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_int,
+    pub height: c_int,
+    pub min_width: c_int,
+    pub min_height: c_int,
+    pub max_width: c_int,
+    pub max_height: c_int,
+    pub width_inc: c_int,
+    pub height_inc: c_int,
+    pub min_aspect: AspectRatio,
+    pub max_aspect: AspectRatio,
+    pub base_width: c_int,
+    pub base_height: c_int,
+    pub win_gravity: c_int,
+}
+
+#[repr(C)]
+pub struct XColor {
+    pub pixel: c_ulong,
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+    pub flags: c_char,
+    pub pad: c_char,
+}
+
+dynamic_library! {
+    pub struct Xlib("libX11.so.6", "libX11.so") {
+        pub XOpenDisplay: unsafe extern "C" fn(*const c_char) -> *mut Display,
+        pub XCloseDisplay: unsafe extern "C" fn(*mut Display) -> c_int,
+        pub XConnectionNumber: unsafe extern "C" fn(*mut Display) -> c_int,
+        pub XDefaultScreen: unsafe extern "C" fn(*mut Display) -> c_int,
+        pub XRootWindow: unsafe extern "C" fn(*mut Display, c_int) -> Window,
+        pub XDisplayWidth: unsafe extern "C" fn(*mut Display, c_int) -> c_int,
+        pub XDisplayHeight: unsafe extern "C" fn(*mut Display, c_int) -> c_int,
+        pub XDefaultVisual: unsafe extern "C" fn(*mut Display, c_int) -> *mut Visual,
+        pub XDefaultDepth: unsafe extern "C" fn(*mut Display, c_int) -> c_int,
+        pub XBlackPixel: unsafe extern "C" fn(*mut Display, c_int) -> c_ulong,
+        pub XCreateColormap: unsafe extern "C" fn(*mut Display, Window, *mut Visual, c_int) -> Colormap,
+        pub XMatchVisualInfo: unsafe extern "C" fn(*mut Display, c_int, c_int, c_int, *mut XVisualInfo) -> Status,
+        pub XCreateWindow: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            c_int,
+            c_int,
+            c_uint,
+            c_uint,
+            c_uint,
+            c_int,
+            c_uint,
+            *mut Visual,
+            c_ulong,
+            *mut XSetWindowAttributes,
+        ) -> Window,
+        pub XCreateSimpleWindow: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            c_int,
+            c_int,
+            c_uint,
+            c_uint,
+            c_uint,
+            c_ulong,
+            c_ulong,
+        ) -> Window,
+        pub XDestroyWindow: unsafe extern "C" fn(*mut Display, Window) -> c_int,
+        pub XMapWindow: unsafe extern "C" fn(*mut Display, Window) -> c_int,
+        pub XMapRaised: unsafe extern "C" fn(*mut Display, Window) -> c_int,
+        pub XWithdrawWindow: unsafe extern "C" fn(*mut Display, Window, c_int) -> Status,
+        pub XMoveWindow: unsafe extern "C" fn(*mut Display, Window, c_int, c_int) -> c_int,
+        pub XResizeWindow: unsafe extern "C" fn(*mut Display, Window, c_uint, c_uint) -> c_int,
+        pub XIconifyWindow: unsafe extern "C" fn(*mut Display, Window, c_int) -> Status,
+        pub XStoreName: unsafe extern "C" fn(*mut Display, Window, *const c_char) -> c_int,
+        pub XSetWMProtocols: unsafe extern "C" fn(*mut Display, Window, *mut Atom, c_int) -> Status,
+        pub XSetWMNormalHints: unsafe extern "C" fn(*mut Display, Window, *mut XSizeHints),
+        pub XGetWindowAttributes: unsafe extern "C" fn(*mut Display, Window, *mut XWindowAttributes) -> Status,
+        pub XTranslateCoordinates: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            Window,
+            c_int,
+            c_int,
+            *mut c_int,
+            *mut c_int,
+            *mut Window,
+        ) -> Bool,
+        pub XInternAtom: unsafe extern "C" fn(*mut Display, *const c_char, Bool) -> Atom,
+        pub XChangeProperty: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            Atom,
+            Atom,
+            c_int,
+            c_int,
+            *const c_uchar,
+            c_int,
+        ) -> c_int,
+        pub XGetWindowProperty: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            Atom,
+            c_long,
+            c_long,
+            Bool,
+            Atom,
+            *mut Atom,
+            *mut c_int,
+            *mut c_ulong,
+            *mut c_ulong,
+            *mut *mut c_uchar,
+        ) -> c_int,
+        pub XDeleteProperty: unsafe extern "C" fn(*mut Display, Window, Atom) -> c_int,
+        pub XGetAtomName: unsafe extern "C" fn(*mut Display, Atom) -> *mut c_char,
+        pub XSetSelectionOwner: unsafe extern "C" fn(*mut Display, Atom, Window, Time) -> c_int,
+        pub XConvertSelection: unsafe extern "C" fn(*mut Display, Atom, Atom, Atom, Window, Time) -> c_int,
+        pub XMaxRequestSize: unsafe extern "C" fn(*mut Display) -> c_long,
+        pub XSendEvent: unsafe extern "C" fn(*mut Display, Window, Bool, c_long, *mut XEvent) -> Status,
+        pub XSelectInput: unsafe extern "C" fn(*mut Display, Window, c_long) -> c_int,
+        pub XPending: unsafe extern "C" fn(*mut Display) -> c_int,
+        pub XNextEvent: unsafe extern "C" fn(*mut Display, *mut XEvent) -> c_int,
+        pub XCheckTypedWindowEvent: unsafe extern "C" fn(*mut Display, Window, c_int, *mut XEvent) -> Bool,
+        pub XFlush: unsafe extern "C" fn(*mut Display) -> c_int,
+        pub XSync: unsafe extern "C" fn(*mut Display, Bool) -> c_int,
+        pub XFree: unsafe extern "C" fn(*mut c_void) -> c_int,
+        pub XFilterEvent: unsafe extern "C" fn(*mut XEvent, Window) -> Bool,
+        pub XLookupString: unsafe extern "C" fn(*mut XKeyEvent, *mut c_char, c_int, *mut KeySym, *mut c_void) -> c_int,
+        pub XLookupKeysym: unsafe extern "C" fn(*mut XKeyEvent, c_int) -> KeySym,
+        pub XkbSetDetectableAutoRepeat: unsafe extern "C" fn(*mut Display, Bool, *mut Bool) -> Bool,
+        pub XSetLocaleModifiers: unsafe extern "C" fn(*const c_char) -> *mut c_char,
+        pub XOpenIM: unsafe extern "C" fn(*mut Display, *mut c_void, *mut c_char, *mut c_char) -> XIM,
+        pub XCloseIM: unsafe extern "C" fn(XIM) -> Status,
+        pub XCreateIC: unsafe extern "C" fn(XIM, ...) -> XIC,
+        pub XDestroyIC: unsafe extern "C" fn(XIC),
+        pub XSetICFocus: unsafe extern "C" fn(XIC),
+        pub XUnsetICFocus: unsafe extern "C" fn(XIC),
+        pub Xutf8LookupString: unsafe extern "C" fn(
+            XIC,
+            *mut XKeyEvent,
+            *mut c_char,
+            c_int,
+            *mut KeySym,
+            *mut Status,
+        ) -> c_int,
+        pub XResourceManagerString: unsafe extern "C" fn(*mut Display) -> *mut c_char,
+        pub XCreateFontCursor: unsafe extern "C" fn(*mut Display, c_uint) -> XCursor,
+        pub XCreateBitmapFromData: unsafe extern "C" fn(*mut Display, Drawable, *const c_char, c_uint, c_uint) -> Pixmap,
+        pub XCreatePixmapCursor: unsafe extern "C" fn(
+            *mut Display,
+            Pixmap,
+            Pixmap,
+            *mut XColor,
+            *mut XColor,
+            c_uint,
+            c_uint,
+        ) -> XCursor,
+        pub XFreePixmap: unsafe extern "C" fn(*mut Display, Pixmap) -> c_int,
+        pub XDefineCursor: unsafe extern "C" fn(*mut Display, Window, XCursor) -> c_int,
+        pub XFreeCursor: unsafe extern "C" fn(*mut Display, XCursor) -> c_int,
+        pub XGrabPointer: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            Bool,
+            c_uint,
+            c_int,
+            c_int,
+            Window,
+            XCursor,
+            Time,
+        ) -> c_int,
+        pub XUngrabPointer: unsafe extern "C" fn(*mut Display, Time) -> c_int,
+        pub XWarpPointer: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            Window,
+            c_int,
+            c_int,
+            c_uint,
+            c_uint,
+            c_int,
+            c_int,
+        ) -> c_int,
+    }
+}
+
+// XFixes, used to be notified when the clipboard changes.
+// Hand transcribed from Xfixes.h
+pub const XFixesSelectionNotify: c_int = 0;
+pub const XFixesSetSelectionOwnerNotifyMask: c_ulong = 1 << 0;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XFixesSelectionNotifyEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub window: Window,
+    pub subtype: c_int,
+    pub owner: Window,
+    pub selection: Atom,
+    pub timestamp: Time,
+    pub selection_timestamp: Time,
+}
+
+dynamic_library! {
+    pub struct XFixes("libXfixes.so.3", "libXfixes.so") {
+        pub XFixesQueryExtension: unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int) -> Bool,
+        pub XFixesQueryVersion: unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int) -> Status,
+        pub XFixesSelectSelectionInput: unsafe extern "C" fn(*mut Display, Window, Atom, c_ulong),
+    }
+}
+
+// Xcursor, used to load themed cursors and to create cursors from images.
+// Hand transcribed from Xcursor.h
+#[repr(C)]
+pub struct XcursorImage {
+    pub version: c_uint,
+    pub size: c_uint,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub xhot: c_uint,
+    pub yhot: c_uint,
+    pub delay: c_uint,
+    /// Premultiplied ARGB pixels.
+    pub pixels: *mut c_uint,
+}
+
+dynamic_library! {
+    pub struct Xcursor("libXcursor.so.1", "libXcursor.so") {
+        pub XcursorLibraryLoadCursor: unsafe extern "C" fn(*mut Display, *const c_char) -> XCursor,
+        pub XcursorImageCreate: unsafe extern "C" fn(c_int, c_int) -> *mut XcursorImage,
+        pub XcursorImageDestroy: unsafe extern "C" fn(*mut XcursorImage),
+        pub XcursorImageLoadCursor: unsafe extern "C" fn(*mut Display, *const XcursorImage) -> XCursor,
+    }
+}
+
+// XRandR, used to list monitors and to be notified when they change.
+// Hand transcribed from Xrandr.h and randr.h
+pub type RROutput = XID;
+pub type RRCrtc = XID;
+pub type RRMode = XID;
+pub type Rotation = c_ushort;
+
+pub const RRScreenChangeNotify: c_int = 0;
+pub const RRNotify: c_int = 1;
+pub const RRScreenChangeNotifyMask: c_int = 1 << 0;
+pub const RRCrtcChangeNotifyMask: c_int = 1 << 1;
+pub const RROutputChangeNotifyMask: c_int = 1 << 2;
+pub const RR_Connected: c_ushort = 0;
+pub const RR_Interlace: c_ulong = 0x00000010;
+pub const RR_DoubleScan: c_ulong = 0x00000020;
+pub const RRSetConfigSuccess: c_int = 0;
+
+#[repr(C)]
+pub struct XRRModeInfo {
+    pub id: RRMode,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub dotClock: c_ulong,
+    pub hSyncStart: c_uint,
+    pub hSyncEnd: c_uint,
+    pub hTotal: c_uint,
+    pub hSkew: c_uint,
+    pub vSyncStart: c_uint,
+    pub vSyncEnd: c_uint,
+    pub vTotal: c_uint,
+    pub name: *mut c_char,
+    pub nameLength: c_uint,
+    pub modeFlags: c_ulong,
+}
+
+#[repr(C)]
+pub struct XRRScreenResources {
+    pub timestamp: Time,
+    pub configTimestamp: Time,
+    pub ncrtc: c_int,
+    pub crtcs: *mut RRCrtc,
+    pub noutput: c_int,
+    pub outputs: *mut RROutput,
+    pub nmode: c_int,
+    pub modes: *mut XRRModeInfo,
+}
+
+#[repr(C)]
+pub struct XRROutputInfo {
+    pub timestamp: Time,
+    pub crtc: RRCrtc,
+    pub name: *mut c_char,
+    pub nameLen: c_int,
+    pub mm_width: c_ulong,
+    pub mm_height: c_ulong,
+    pub connection: c_ushort,
+    pub subpixel_order: c_ushort,
+    pub ncrtc: c_int,
+    pub crtcs: *mut RRCrtc,
+    pub nclone: c_int,
+    pub clones: *mut RROutput,
+    pub nmode: c_int,
+    pub npreferred: c_int,
+    pub modes: *mut RRMode,
+}
+
+#[repr(C)]
+pub struct XRRCrtcInfo {
+    pub timestamp: Time,
+    pub x: c_int,
+    pub y: c_int,
+    pub width: c_uint,
+    pub height: c_uint,
+    pub mode: RRMode,
+    pub rotation: Rotation,
+    pub noutput: c_int,
+    pub outputs: *mut RROutput,
+    pub rotations: c_ushort,
+    pub npossible: c_int,
+    pub possible: *mut RROutput,
+}
+
+dynamic_library! {
+    pub struct Xrandr("libXrandr.so.2", "libXrandr.so") {
+        pub XRRQueryExtension: unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int) -> Bool,
+        pub XRRSelectInput: unsafe extern "C" fn(*mut Display, Window, c_int),
+        pub XRRUpdateConfiguration: unsafe extern "C" fn(*mut XEvent) -> c_int,
+        pub XRRGetScreenResourcesCurrent:
+            unsafe extern "C" fn(*mut Display, Window) -> *mut XRRScreenResources,
+        pub XRRFreeScreenResources: unsafe extern "C" fn(*mut XRRScreenResources),
+        pub XRRGetOutputInfo: unsafe extern "C" fn(
+            *mut Display,
+            *mut XRRScreenResources,
+            RROutput,
+        ) -> *mut XRROutputInfo,
+        pub XRRFreeOutputInfo: unsafe extern "C" fn(*mut XRROutputInfo),
+        pub XRRGetCrtcInfo: unsafe extern "C" fn(
+            *mut Display,
+            *mut XRRScreenResources,
+            RRCrtc,
+        ) -> *mut XRRCrtcInfo,
+        pub XRRFreeCrtcInfo: unsafe extern "C" fn(*mut XRRCrtcInfo),
+        pub XRRGetOutputPrimary: unsafe extern "C" fn(*mut Display, Window) -> RROutput,
+        pub XRRSetCrtcConfig: unsafe extern "C" fn(
+            *mut Display,
+            *mut XRRScreenResources,
+            RRCrtc,
+            Time,
+            c_int,
+            c_int,
+            RRMode,
+            Rotation,
+            *mut RROutput,
+            c_int,
+        ) -> Status,
+    }
+}
+
+// The X Shape extension, used to set which parts of a window receive input.
+// Hand transcribed from shape.h
+pub const ShapeSet: c_int = 0;
+pub const ShapeInput: c_int = 2;
+pub const Unsorted: c_int = 0;
+
+dynamic_library! {
+    pub struct Xext("libXext.so.6", "libXext.so") {
+        pub XShapeQueryExtension: unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int) -> Bool,
+        pub XShapeCombineRectangles: unsafe extern "C" fn(
+            *mut Display,
+            Window,
+            c_int,
+            c_int,
+            c_int,
+            *mut XRectangle,
+            c_int,
+            c_int,
+            c_int,
+        ),
+        pub XShapeCombineMask: unsafe extern "C" fn(*mut Display, Window, c_int, c_int, c_int, Pixmap, c_int),
+    }
+}
+
+// From libc, which is always linked.
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+
+pub const POLLIN: c_short = 0x1;
+
+extern "C" {
+    pub fn poll(fds: *mut pollfd, nfds: u64, timeout: c_int) -> c_int;
+}
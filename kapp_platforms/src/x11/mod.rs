@@ -0,0 +1,15 @@
+mod application_x11;
+mod clipboard_x11;
+mod drag_drop_x11;
+mod event_loop_x11;
+mod screens_x11;
+
+#[allow(
+    non_upper_case_globals,
+    non_snake_case,
+    non_camel_case_types,
+    dead_code
+)]
+mod external_x11;
+
+pub use application_x11::{PlatformApplication, PlatformEventLoop};
@@ -0,0 +1,353 @@
+use super::application_x11::ApplicationData;
+use super::external_x11::*;
+use kapp_platform_common::*;
+
+use std::ptr::null_mut;
+
+/// Monitors are listed with XRandR when it's available.
+/// Otherwise the whole X screen is reported as a single screen.
+pub(crate) struct Screens {
+    /// XRandR and its first event type, if the extension is available.
+    xrandr: Option<(Xrandr, c_int)>,
+    /// The screens when they last changed, to find which changed on the next notification.
+    pub last: Vec<Screen>,
+    /// The CRTCs changed for exclusive fullscreen and the modes to return them to.
+    original_modes: Vec<(RRCrtc, RRMode)>,
+}
+
+impl Screens {
+    pub unsafe fn new(xlib: &Xlib, display: *mut Display, root: Window) -> Self {
+        let xrandr = Xrandr::open().and_then(|xrandr| {
+            let mut event_base = 0;
+            let mut error_base = 0;
+            if (xrandr.XRRQueryExtension)(display, &mut event_base, &mut error_base) == False {
+                return None;
+            }
+            Some((xrandr, event_base))
+        });
+        if let Some((xrandr, _)) = &xrandr {
+            (xrandr.XRRSelectInput)(
+                display,
+                root,
+                RRScreenChangeNotifyMask | RRCrtcChangeNotifyMask | RROutputChangeNotifyMask,
+            );
+        }
+
+        // Panels update `_NET_WORKAREA` on the root window when they change the work area.
+        (xlib.XSelectInput)(display, root, PropertyChangeMask);
+
+        Self {
+            xrandr,
+            last: Vec::new(),
+            original_modes: Vec::new(),
+        }
+    }
+}
+
+/// Returns the screens with the primary screen first.
+pub(crate) unsafe fn screens(data: &ApplicationData) -> Vec<Screen> {
+    let mut screens = match &data.screens.xrandr {
+        Some((xrandr, _)) => xrandr_screens(data, xrandr),
+        None => Vec::new(),
+    };
+
+    if screens.is_empty() {
+        let width = (data.xlib.XDisplayWidth)(data.display, data.screen) as u32;
+        let height = (data.xlib.XDisplayHeight)(data.display, data.screen) as u32;
+        screens.push(Screen {
+            id: ScreenId::new(null_mut()),
+            name: String::new(),
+            position: (0, 0),
+            size: (width, height),
+            work_area_position: (0, 0),
+            work_area_size: (width, height),
+            scale: data.scale,
+            refresh_rate: None,
+            // Without XRandR video modes can't be changed.
+            video_modes: Vec::new(),
+        });
+    }
+
+    // `_NET_WORKAREA` is a single rectangle across all monitors,
+    // so each screen's work area is the part of it on that screen.
+    if let Some((x, y, width, height)) = work_area(data) {
+        for screen in &mut screens {
+            let left = x.max(screen.position.0);
+            let top = y.max(screen.position.1);
+            let right = (x + width as i32).min(screen.position.0 + screen.size.0 as i32);
+            let bottom = (y + height as i32).min(screen.position.1 + screen.size.1 as i32);
+            if right > left && bottom > top {
+                screen.work_area_position = (left, top);
+                screen.work_area_size = ((right - left) as u32, (bottom - top) as u32);
+            }
+        }
+    }
+    screens
+}
+
+unsafe fn xrandr_screens(data: &ApplicationData, xrandr: &Xrandr) -> Vec<Screen> {
+    let resources = (xrandr.XRRGetScreenResourcesCurrent)(data.display, data.root);
+    if resources.is_null() {
+        return Vec::new();
+    }
+    let primary = (xrandr.XRRGetOutputPrimary)(data.display, data.root);
+    let outputs = slice((*resources).outputs, (*resources).noutput);
+    let modes = slice((*resources).modes, (*resources).nmode);
+    // XRandR modes don't have a depth, they all use the X screen's.
+    let bit_depth = (data.xlib.XDefaultDepth)(data.display, data.screen) as u32;
+
+    let mut screens = Vec::new();
+    for output in outputs {
+        let output_info = (xrandr.XRRGetOutputInfo)(data.display, resources, *output);
+        if output_info.is_null() {
+            continue;
+        }
+        // Outputs without a CRTC are connected but turned off.
+        if (*output_info).connection == RR_Connected && (*output_info).crtc != 0 {
+            let crtc_info = (xrandr.XRRGetCrtcInfo)(data.display, resources, (*output_info).crtc);
+            if !crtc_info.is_null() {
+                let name = slice((*output_info).name as *const u8, (*output_info).nameLen);
+                let position = ((*crtc_info).x, (*crtc_info).y);
+                let size = ((*crtc_info).width, (*crtc_info).height);
+                let id = ScreenId::new(*output as *mut c_void);
+
+                let output_modes = slice((*output_info).modes, (*output_info).nmode);
+                let mut video_modes = Vec::new();
+                for mode in modes.iter().filter(|mode| output_modes.contains(&mode.id)) {
+                    // Modes that only differ in timings appear more than once.
+                    let video_mode = VideoMode {
+                        screen_id: id,
+                        size: (mode.width, mode.height),
+                        refresh_rate: refresh_rate(mode).unwrap_or(0.0),
+                        bit_depth,
+                    };
+                    if !video_modes.contains(&video_mode) {
+                        video_modes.push(video_mode);
+                    }
+                }
+
+                let screen = Screen {
+                    id,
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    position,
+                    size,
+                    work_area_position: position,
+                    work_area_size: size,
+                    scale: data.scale,
+                    refresh_rate: modes
+                        .iter()
+                        .find(|mode| mode.id == (*crtc_info).mode)
+                        .and_then(refresh_rate),
+                    video_modes,
+                };
+                if *output == primary {
+                    screens.insert(0, screen);
+                } else {
+                    screens.push(screen);
+                }
+                (xrandr.XRRFreeCrtcInfo)(crtc_info);
+            }
+        }
+        (xrandr.XRRFreeOutputInfo)(output_info);
+    }
+    (xrandr.XRRFreeScreenResources)(resources);
+    screens
+}
+
+/// Switches the video mode's screen to the closest matching XRandR mode.
+/// The screen's previous mode is restored by `restore_video_modes`.
+pub(crate) unsafe fn set_video_mode(data: &mut ApplicationData, video_mode: &VideoMode) {
+    let xrandr = match &data.screens.xrandr {
+        Some((xrandr, _)) => xrandr,
+        None => return,
+    };
+    let resources = (xrandr.XRRGetScreenResourcesCurrent)(data.display, data.root);
+    if resources.is_null() {
+        return;
+    }
+    let output = video_mode.screen_id.raw() as RROutput;
+    let output_info = (xrandr.XRRGetOutputInfo)(data.display, resources, output);
+    if output_info.is_null() {
+        (xrandr.XRRFreeScreenResources)(resources);
+        return;
+    }
+
+    let crtc = (*output_info).crtc;
+    let output_modes = slice((*output_info).modes, (*output_info).nmode);
+    let refresh_difference =
+        |mode: &XRRModeInfo| (refresh_rate(mode).unwrap_or(0.0) - video_mode.refresh_rate).abs();
+    let mode = slice((*resources).modes, (*resources).nmode)
+        .iter()
+        .filter(|mode| output_modes.contains(&mode.id))
+        .filter(|mode| (mode.width, mode.height) == video_mode.size)
+        .min_by(|a, b| {
+            refresh_difference(a)
+                .partial_cmp(&refresh_difference(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+    let mut original_mode = None;
+    if let Some(mode) = mode {
+        if crtc != 0 {
+            original_mode = set_crtc_mode(data, xrandr, resources, crtc, mode.id);
+        }
+    }
+    (xrandr.XRRFreeOutputInfo)(output_info);
+    (xrandr.XRRFreeScreenResources)(resources);
+
+    // Only the first change is kept so the mode from before fullscreen is restored.
+    if let Some(original_mode) = original_mode {
+        let original_modes = &mut data.screens.original_modes;
+        if !original_modes.iter().any(|(c, _)| *c == crtc) {
+            original_modes.push((crtc, original_mode));
+        }
+    }
+}
+
+/// Returns screens changed by `set_video_mode` to their previous modes.
+pub(crate) unsafe fn restore_video_modes(data: &mut ApplicationData) {
+    let original_modes = std::mem::take(&mut data.screens.original_modes);
+    let xrandr = match &data.screens.xrandr {
+        Some((xrandr, _)) if !original_modes.is_empty() => xrandr,
+        _ => return,
+    };
+    let resources = (xrandr.XRRGetScreenResourcesCurrent)(data.display, data.root);
+    if resources.is_null() {
+        return;
+    }
+    for (crtc, mode) in original_modes {
+        set_crtc_mode(data, xrandr, resources, crtc, mode);
+    }
+    (xrandr.XRRFreeScreenResources)(resources);
+}
+
+/// Changes a CRTC's mode while keeping its position, rotation, and outputs.
+/// Returns the mode it had before, or `None` if it couldn't be changed.
+unsafe fn set_crtc_mode(
+    data: &ApplicationData,
+    xrandr: &Xrandr,
+    resources: *mut XRRScreenResources,
+    crtc: RRCrtc,
+    mode: RRMode,
+) -> Option<RRMode> {
+    let crtc_info = (xrandr.XRRGetCrtcInfo)(data.display, resources, crtc);
+    if crtc_info.is_null() {
+        return None;
+    }
+    let status = (xrandr.XRRSetCrtcConfig)(
+        data.display,
+        resources,
+        crtc,
+        CurrentTime,
+        (*crtc_info).x,
+        (*crtc_info).y,
+        mode,
+        (*crtc_info).rotation,
+        (*crtc_info).outputs,
+        (*crtc_info).noutput,
+    );
+    let original_mode = (*crtc_info).mode;
+    (xrandr.XRRFreeCrtcInfo)(crtc_info);
+    if status == RRSetConfigSuccess {
+        Some(original_mode)
+    } else {
+        None
+    }
+}
+
+/// XRandR arrays are null when they're empty.
+unsafe fn slice<'a, T>(pointer: *const T, length: c_int) -> &'a [T] {
+    if pointer.is_null() || length <= 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(pointer, length as usize)
+    }
+}
+
+fn refresh_rate(mode: &XRRModeInfo) -> Option<f64> {
+    let mut vertical_total = mode.vTotal as f64;
+    if mode.modeFlags & RR_DoubleScan != 0 {
+        vertical_total *= 2.0;
+    }
+    if mode.modeFlags & RR_Interlace != 0 {
+        vertical_total /= 2.0;
+    }
+    if mode.hTotal == 0 || vertical_total == 0.0 {
+        return None;
+    }
+    Some(mode.dotClock as f64 / (mode.hTotal as f64 * vertical_total))
+}
+
+/// Reads the first rectangle of the root window's `_NET_WORKAREA` property.
+unsafe fn work_area(data: &ApplicationData) -> Option<(i32, i32, u32, u32)> {
+    let mut actual_type = 0;
+    let mut actual_format = 0;
+    let mut item_count = 0;
+    let mut bytes_after = 0;
+    let mut property = null_mut();
+    (data.xlib.XGetWindowProperty)(
+        data.display,
+        data.root,
+        data.atoms.net_workarea,
+        0,
+        4,
+        False,
+        XA_CARDINAL,
+        &mut actual_type,
+        &mut actual_format,
+        &mut item_count,
+        &mut bytes_after,
+        &mut property,
+    );
+    if property.is_null() {
+        return None;
+    }
+
+    // 32 bit properties are returned as an array of longs.
+    let values = slice(property as *const c_long, item_count as c_int);
+    let work_area = if values.len() == 4 {
+        Some((
+            values[0] as i32,
+            values[1] as i32,
+            values[2] as u32,
+            values[3] as u32,
+        ))
+    } else {
+        None
+    };
+    (data.xlib.XFree)(property as *mut c_void);
+    work_area
+}
+
+/// Handles XRandR notifications and work area changes.
+/// Returns `true` if the event was handled.
+// Xlib event type names are matched as-is.
+#[allow(non_upper_case_globals)]
+pub(crate) unsafe fn process_event(
+    data: &mut ApplicationData,
+    event: &mut XEvent,
+    events: &mut Vec<Event>,
+) -> bool {
+    let changed = match &data.screens.xrandr {
+        Some((xrandr, event_base))
+            if event.type_ == event_base + RRScreenChangeNotify
+                || event.type_ == event_base + RRNotify =>
+        {
+            (xrandr.XRRUpdateConfiguration)(event);
+            true
+        }
+        _ => {
+            event.type_ == PropertyNotify
+                && event.property.window == data.root
+                && event.property.atom == data.atoms.net_workarea
+        }
+    };
+    if !changed {
+        return false;
+    }
+
+    let screens = screens(data);
+    events.extend(screen_changes(&data.screens.last, &screens));
+    data.screens.last = screens;
+    true
+}
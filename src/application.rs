@@ -1,19 +1,22 @@
+use crate::clipboard::Clipboard;
 use crate::platform::*;
 use crate::platform::{PlatformApplicationTrait, PlatformEventLoopTrait};
-use crate::state_tracker::StateTracker;
+use crate::state_tracker::{StateTracker, Touch, WindowState};
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
 
 /// A handle used to do things like quit,
 /// request a new frame, or create windows.
 #[derive(Clone)]
 pub struct Application {
     pub(crate) platform_application: Rc<RefCell<PlatformApplication>>,
-    state_tracker: Rc<RefCell<StateTracker>>,
+    pub(crate) state_tracker: Rc<RefCell<StateTracker>>,
 }
 
 /// Create an Application and EventLoop.
 pub fn initialize() -> (Application, EventLoop) {
+    window_id::reset_window_indices();
     let platform_application = Rc::new(RefCell::new(PlatformApplication::new()));
     let platform_event_loop = platform_application.borrow_mut().event_loop();
     let state_tracker = Rc::new(RefCell::new(StateTracker::new()));
@@ -42,6 +45,43 @@ impl Application {
         self.platform_application.borrow().quit();
     }
 
+    /// Sets how the event loop waits when there are no events to process.
+    /// `ControlFlow::Wait` is the default.
+    /// `ControlFlow::WaitUntil` is unsupported on Web, where `std::time::Instant` is unavailable.
+    pub fn set_control_flow(&self, control_flow: ControlFlow) {
+        self.platform_application
+            .borrow_mut()
+            .set_control_flow(control_flow);
+    }
+
+    /// Starts a timer that sends `Event::Timer` after the duration.
+    /// A repeating timer is sent again after every duration until it's cancelled,
+    /// but no more than once a millisecond.
+    /// With the `headless` feature timers are due when `advance_time` moves the clock past them.
+    pub fn set_timer(&self, duration: Duration, repeat: bool) -> TimerId {
+        let duration = if repeat {
+            duration.max(Duration::from_millis(1))
+        } else {
+            duration
+        };
+        self.platform_application
+            .borrow_mut()
+            .set_timer(duration, repeat)
+    }
+
+    /// Stops a timer. No more `Event::Timer` are sent for it.
+    pub fn cancel_timer(&self, timer_id: TimerId) {
+        self.platform_application
+            .borrow_mut()
+            .cancel_timer(timer_id);
+    }
+
+    /// Returns a handle that other threads can use to wake the event loop
+    /// or send it `Event::User`.
+    pub fn proxy(&self) -> EventLoopProxy {
+        self.platform_application.borrow_mut().proxy()
+    }
+
     /// Prevents the mouse from moving until a call to `unlock_mouse_position`
     pub fn lock_mouse_position(&self) {
         self.platform_application.borrow_mut().lock_mouse_position();
@@ -59,6 +99,30 @@ impl Application {
         self.platform_application.borrow_mut().set_cursor(cursor);
     }
 
+    /// Creates a cursor from 8 bit RGBA pixels, stored row by row from the top left.
+    /// The hotspot is the pixel within the image that's positioned at the pointer.
+    /// Set the cursor with `Cursor::Custom`.
+    pub fn create_cursor(
+        &self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+    ) -> CustomCursor {
+        assert_eq!(
+            rgba.len(),
+            width as usize * height as usize * 4,
+            "Cursor data must be 4 bytes per pixel"
+        );
+        assert!(
+            hotspot.0 < width && hotspot.1 < height,
+            "The hotspot must be within the cursor"
+        );
+        self.platform_application
+            .borrow_mut()
+            .create_cursor(rgba, width, height, hotspot)
+    }
+
     pub fn set_cursor_visible(&self, visible: bool) {
         if visible {
             self.platform_application.borrow_mut().show_cursor();
@@ -81,6 +145,38 @@ impl Application {
         self.platform_application.borrow_mut().end_text_input()
     }
 
+    /// Returns the connected screens with the primary screen first.
+    /// Web only reports the screen the page is on.
+    pub fn screens(&self) -> Vec<Screen> {
+        self.platform_application.borrow_mut().screens()
+    }
+
+    /// Returns a handle to the clipboard used by copy and paste.
+    pub fn clipboard(&self) -> Clipboard {
+        Clipboard::new(
+            ClipboardSelection::Clipboard,
+            self.platform_application.clone(),
+        )
+    }
+
+    /// Returns a handle to the primary selection, which holds the most recently selected text.
+    /// Only X11 and headless have a primary selection.
+    pub fn primary_selection(&self) -> Clipboard {
+        Clipboard::new(
+            ClipboardSelection::Primary,
+            self.platform_application.clone(),
+        )
+    }
+
+    /// Accepts or rejects the drop of what's currently dragged over a window.
+    /// Call in response to `Event::DragEntered` or `Event::DragMoved`.
+    /// Drops are accepted by default.
+    pub fn set_drop_accepted(&self, accepted: bool) {
+        self.platform_application
+            .borrow_mut()
+            .set_drop_accepted(accepted);
+    }
+
     /// Returns if the key is currently pressed
     pub fn key(&self, key: Key) -> bool {
         self.state_tracker.borrow().key(key)
@@ -92,12 +188,17 @@ impl Application {
     }
 
     /// Returns true if all the keys specified been pressed since the last draw.
-    /// Right now this doesn't work perfectly for keyboard shortcuts because
-    /// the different modifier keys are split out into their left and right versions.
+    /// Modifier keys are split out into their left and right versions,
+    /// so use `modifiers` to check the modifiers of keyboard shortcuts.
     pub fn keys_down(&self, keys: &[Key]) -> bool {
         self.state_tracker.borrow().keys_down(keys)
     }
 
+    /// Returns the modifier keys held and lock keys enabled.
+    pub fn modifiers(&self) -> Modifiers {
+        self.state_tracker.borrow().modifiers()
+    }
+
     /// Returns true if the pointer button is pressed
     pub fn pointer_button(&self, button: PointerButton) -> bool {
         self.state_tracker.borrow().pointer_button(button)
@@ -114,6 +215,64 @@ impl Application {
     pub fn pointer_position(&self) -> (f64, f64) {
         self.state_tracker.borrow().pointer_position()
     }
+
+    /// Returns if the key is currently pressed and was pressed in the window
+    pub fn key_in_window(&self, window_id: WindowId, key: Key) -> bool {
+        self.state_tracker.borrow().key_in_window(window_id, key)
+    }
+
+    /// Returns true if the key has been pressed in the window since the last draw
+    pub fn key_down_in_window(&self, window_id: WindowId, key: Key) -> bool {
+        self.state_tracker
+            .borrow()
+            .key_down_in_window(window_id, key)
+    }
+
+    /// Returns true if the pointer button is pressed and was pressed in the window
+    pub fn pointer_button_in_window(&self, window_id: WindowId, button: PointerButton) -> bool {
+        self.state_tracker
+            .borrow()
+            .pointer_button_in_window(window_id, button)
+    }
+
+    /// Returns true if the pointer button has been pressed in the window since the last draw
+    pub fn pointer_button_down_in_window(
+        &self,
+        window_id: WindowId,
+        button: PointerButton,
+    ) -> bool {
+        self.state_tracker
+            .borrow()
+            .pointer_button_down_in_window(window_id, button)
+    }
+
+    /// Returns the last pointer position in the window,
+    /// or `None` if the pointer hasn't moved over it.
+    pub fn pointer_position_in_window(&self, window_id: WindowId) -> Option<(f64, f64)> {
+        self.state_tracker
+            .borrow()
+            .pointer_position_in_window(window_id)
+    }
+
+    /// Returns the touches currently pressed down, oldest first.
+    pub fn touches(&self) -> Vec<Touch> {
+        self.state_tracker.borrow().touches().to_vec()
+    }
+
+    /// Returns the window's state as of the last event handled,
+    /// or `None` if the window wasn't created by this application.
+    /// During a `Draw` event this is the state the window is being drawn with.
+    pub fn window_state(&self, window_id: WindowId) -> Option<WindowState> {
+        self.state_tracker.borrow().window_state(window_id)
+    }
+
+    /// Sends an event to the program as if it came from the operating system.
+    /// The event is tracked by the `StateTracker` like any other event.
+    /// Events injected while an event is being handled are sent after it.
+    /// See [`crate::InputSimulator`] for simulating common sequences of input.
+    pub fn inject_event(&self, event: Event) {
+        self.platform_application.borrow_mut().inject_event(event);
+    }
 }
 
 /// Call the 'run' or 'run_async' function on an EventLoop instance to start your program.
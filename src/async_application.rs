@@ -1,12 +1,28 @@
 use crate::Application;
 use crate::Event;
 use crate::EventLoop;
+use crate::{TimerId, WindowId};
+use futures_core::Stream;
 use std::future::Future;
 
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::pin::Pin;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::task::{Context, Poll};
+use std::time::Duration;
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+type Queue = RefCell<VecDeque<Event>>;
+
+thread_local! {
+    /// Tasks started with `spawn_local` that haven't been polled yet.
+    static SPAWNED: RefCell<Vec<Task>> = const { RefCell::new(Vec::new()) };
+    /// The application `sleep` starts timers with, set while an `AsyncProgram` exists.
+    static APPLICATION: RefCell<Option<Application>> = const { RefCell::new(None) };
+    /// Timers started by `sleep`, and if they've finished.
+    static SLEEPS: RefCell<Vec<(TimerId, bool)>> = const { RefCell::new(Vec::new()) };
+}
 
 pub fn run_async<F>(run: impl Fn(Application, Events) -> F)
 where
@@ -16,6 +32,70 @@ where
     event_loop.run_async(application, run);
 }
 
+/// Runs a future on the main thread alongside the program started by `run_async`.
+/// It's first polled once the current task yields, and then whenever the program is.
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) {
+    SPAWNED.with(|s| s.borrow_mut().push(Box::pin(future)));
+}
+
+/// Completes once the duration has passed, measured from when it's first awaited.
+/// It uses `Application::set_timer`, so it must be awaited within `run_async`.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        duration,
+        timer_id: None,
+    }
+}
+
+/// Returned by `sleep`.
+pub struct Sleep {
+    duration: Duration,
+    timer_id: Option<TimerId>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _ctx: &mut Context) -> Poll<Self::Output> {
+        match self.timer_id {
+            None => {
+                let duration = self.duration;
+                let timer_id = APPLICATION.with(|a| {
+                    a.borrow()
+                        .as_ref()
+                        .expect("`sleep` must be awaited within `run_async`")
+                        .set_timer(duration, false)
+                });
+                SLEEPS.with(|s| s.borrow_mut().push((timer_id, false)));
+                self.timer_id = Some(timer_id);
+                Poll::Pending
+            }
+            Some(timer_id) => {
+                if SLEEPS.with(|s| s.borrow().contains(&(timer_id, true))) {
+                    SLEEPS.with(|s| s.borrow_mut().retain(|(id, _)| *id != timer_id));
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        // Stop the timer if the sleep is dropped before it finishes.
+        if let Some(timer_id) = self.timer_id {
+            let _ = SLEEPS.try_with(|s| s.borrow_mut().retain(|(id, _)| *id != timer_id));
+            let _ = APPLICATION.try_with(|a| {
+                if let Some(application) = a.borrow().as_ref() {
+                    application.cancel_timer(timer_id);
+                }
+            });
+        }
+    }
+}
+
 pub struct EventFuture<'a> {
     events: &'a Events,
 }
@@ -24,7 +104,7 @@ impl<'a> Future for EventFuture<'a> {
     type Output = Event;
 
     fn poll(self: Pin<&mut Self>, _ctx: &mut Context) -> Poll<Self::Output> {
-        if let Some(event) = self.events.queue.borrow_mut().pop() {
+        if let Some(event) = self.events.queue.borrow_mut().pop_front() {
             Poll::Ready(event)
         } else {
             Poll::Pending
@@ -32,9 +112,35 @@ impl<'a> Future for EventFuture<'a> {
     }
 }
 
+/// Returned by `Events::next_matching`.
+pub struct MatchingEventFuture<'a, F> {
+    events: &'a Events,
+    predicate: F,
+}
+
+impl<'a, F: FnMut(&Event) -> bool + Unpin> Future for MatchingEventFuture<'a, F> {
+    type Output = Event;
+
+    fn poll(mut self: Pin<&mut Self>, _ctx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+        while let Some(event) = this.events.queue.borrow_mut().pop_front() {
+            if (this.predicate)(&event) {
+                return Poll::Ready(event);
+            }
+        }
+        Poll::Pending
+    }
+}
+
 impl EventLoop {
     /// Events are sent to the program immediately as they're ready.
-    /// However if the main program is blocked then events are queued.
+    /// However if the main program is blocked then events are queued,
+    /// and the program receives them in order once it awaits `Events::next` again.
+    /// Futures the program awaits wake the event loop when they're ready,
+    /// even if they complete on another thread.
+    /// Tasks started with `spawn_local` run alongside the program.
+    ///
+    /// On web this returns immediately and the program continues as the browser sends events.
     /// ```no_run
     /// use kapp::*;
     /// fn main() {
@@ -64,10 +170,12 @@ impl EventLoop {
         F: 'static + Future<Output = ()>,
     {
         let events = Events::new();
-        let mut program =
-            AsyncProgram::new(events.clone(), run_function(application.clone(), events));
+        let mut program = AsyncProgram::new(
+            application.clone(),
+            events.clone(),
+            run_function(application, events),
+        );
 
-        // A proper context and waker need to be setup here.
         self.run(move |event| {
             program.update(event);
         });
@@ -76,64 +184,138 @@ impl EventLoop {
     }
 }
 
+/// Runs the program and the tasks it spawns.
+/// Every task is polled when an event arrives or a future wakes the program.
 pub struct AsyncProgram {
-    events: Events,
-    program: Pin<Box<dyn Future<Output = ()>>>,
+    application: Application,
+    /// The queues of every `Events` the program has.
+    queues: Queues,
+    /// The program and spawned tasks that haven't finished.
+    tasks: Vec<Task>,
 }
 
 impl AsyncProgram {
-    pub fn new(events: Events, future: impl Future<Output = ()> + 'static) -> Self {
-        let mut pin = Box::pin(future);
-
-        let waker = waker::create();
-        let mut context = Context::from_waker(&waker);
+    pub fn new(
+        application: Application,
+        events: Events,
+        future: impl Future<Output = ()> + 'static,
+    ) -> Self {
+        APPLICATION.with(|a| *a.borrow_mut() = Some(application.clone()));
+        // Only the queues are kept, so events aren't queued for this `Events`.
+        let mut program = Self {
+            application,
+            queues: events.queues.clone(),
+            tasks: vec![Box::pin(future)],
+        };
 
         // Poll the program once to give it a chance to setup things.
-        if Poll::Ready(()) == pin.as_mut().poll(&mut context) {
-            // Program immediately exited on first run.
-        }
+        program.poll();
+        program
+    }
 
-        Self {
-            events,
-            program: pin,
+    pub fn update(&mut self, event: Event) {
+        // Wakes from the program's futures only resume the program.
+        if let Event::User { data } = &event {
+            if data.take_as::<waker::ProgramWake>().is_some() {
+                self.poll();
+                return;
+            }
+        }
+        // Timers started by `sleep` aren't sent to the program.
+        if let Event::Timer { timer_id } = event {
+            let finished = SLEEPS.with(|s| {
+                let mut sleeps = s.borrow_mut();
+                let sleep = sleeps.iter_mut().find(|(id, _)| *id == timer_id);
+                sleep.map(|(_, finished)| *finished = true)
+            });
+            if finished.is_some() {
+                self.poll();
+                return;
+            }
         }
+
+        self.queues.send(event);
+
+        // If the program is awaiting something else this will immediately return 'pending'
+        // and the event waits in the queue.
+        // For an example if the program is blocked waiting for something to load
+        // a 'Draw' is delayed until it finishes, which can cause window resizing to flicker.
+        self.poll();
     }
 
-    pub fn update(&mut self, event: Event) {
-        self.events.queue.borrow_mut().push(event);
-
-        // This waker does nothing presently,
-        // This means that completed futures won't actually wake up the main loop.
-        // However the main loop has a chance to continue immediately on the next event.
-        // In the future an artificial event should be triggered to ensure the main loop
-        // continues immediately.
-        // That artificial event may need to be implemented per platform.
-        let waker = waker::create();
+    fn poll(&mut self) {
+        // A new proxy is used each time so the event loop knows when nothing can wake it.
+        let waker = waker::create(self.application.proxy());
         let mut context = Context::from_waker(&waker);
 
-        // The user main loop is polled here.
-        // If it's awaiting something else this will immediately return 'pending'.
-        // For an example if the main loop is blocked waiting for something to load
-        // and a 'Draw' is requested it will not immediately occur which can cause
-        // window resizing to flicker.
-        if Poll::Ready(()) == self.program.as_mut().poll(&mut context) {
-            // The main application loop has exited.
-            // Do something here!
+        // Tasks spawned while polling are polled before returning,
+        // so a new task starts without waiting for the next event.
+        let mut tasks = std::mem::take(&mut self.tasks);
+        loop {
+            tasks.retain_mut(|task| task.as_mut().poll(&mut context).is_pending());
+            self.tasks.append(&mut tasks);
+            tasks = SPAWNED.with(|s| std::mem::take(&mut *s.borrow_mut()));
+            if tasks.is_empty() {
+                break;
+            }
         }
     }
 }
 
+impl Drop for AsyncProgram {
+    fn drop(&mut self) {
+        let _ = APPLICATION.try_with(|a| a.borrow_mut().take());
+    }
+}
+
+/// Weak references to the queue of each `Events`, removed once it's dropped.
+#[derive(Clone, Default)]
+struct Queues(Rc<RefCell<Vec<Weak<Queue>>>>);
+
+impl Queues {
+    fn add(&self) -> Rc<Queue> {
+        let queue = Rc::new(RefCell::new(VecDeque::new()));
+        self.0.borrow_mut().push(Rc::downgrade(&queue));
+        queue
+    }
+
+    fn send(&self, event: Event) {
+        self.0.borrow_mut().retain(|queue| match queue.upgrade() {
+            Some(queue) => {
+                queue.borrow_mut().push_back(event.clone());
+                true
+            }
+            None => false,
+        });
+    }
+}
+
 /// Passed to an asynchronous function to get the next event.
 ///
-#[derive(Clone)]
+/// Each clone has its own queue and receives every event sent after it's created,
+/// so tasks that wait for events at the same time should each use their own clone.
+/// Waits on the same `Events` share its queue, and an event one of them takes
+/// or skips isn't seen by the others.
 pub struct Events {
-    queue: Rc<RefCell<Vec<crate::Event>>>,
+    queue: Rc<Queue>,
+    queues: Queues,
 }
 
 impl Events {
     pub fn new() -> Self {
+        let queues = Queues::default();
+        Events {
+            queue: queues.add(),
+            queues,
+        }
+    }
+}
+
+impl Clone for Events {
+    fn clone(&self) -> Self {
         Events {
-            queue: Rc::new(RefCell::new(Vec::new())),
+            queue: self.queues.add(),
+            queues: self.queues.clone(),
         }
     }
 }
@@ -142,22 +324,62 @@ impl Events {
     pub fn next(&self) -> self::EventFuture {
         self::EventFuture { events: self }
     }
+
+    /// Waits for the next event the predicate returns true for.
+    /// Events before it are skipped, so they're not seen by other waits on this `Events`.
+    pub fn next_matching<F: FnMut(&Event) -> bool + Unpin>(
+        &self,
+        predicate: F,
+    ) -> MatchingEventFuture<'_, F> {
+        MatchingEventFuture {
+            events: self,
+            predicate,
+        }
+    }
+
+    /// Waits for the next `Draw` for the window.
+    /// Events before it are skipped, as with `next_matching`.
+    pub async fn next_draw(&self, window_id: WindowId) {
+        self.next_matching(
+            |event| matches!(event, Event::Draw { window_id: id } if *id == window_id),
+        )
+        .await;
+    }
 }
 
-mod waker {
-    use std::task::{RawWaker, RawWakerVTable, Waker};
+impl Stream for Events {
+    type Item = Event;
 
-    pub fn create() -> Waker {
-        unsafe { Waker::from_raw(RAW_WAKER) }
+    fn poll_next(self: Pin<&mut Self>, _ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        match self.queue.borrow_mut().pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => Poll::Pending,
+        }
     }
+}
 
-    const RAW_WAKER: RawWaker = RawWaker::new(std::ptr::null(), &VTABLE);
-    const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+mod waker {
+    use crate::EventLoopProxy;
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+
+    /// Sent through the event loop to poll the program again.
+    pub struct ProgramWake;
+
+    struct ProxyWaker(EventLoopProxy);
+
+    impl Wake for ProxyWaker {
+        fn wake(self: Arc<Self>) {
+            self.wake_by_ref();
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.send_user_event(Box::new(ProgramWake));
+        }
+    }
 
-    unsafe fn clone(_: *const ()) -> RawWaker {
-        RAW_WAKER
+    /// Creates a waker that can be used from any thread.
+    pub fn create(proxy: EventLoopProxy) -> Waker {
+        Arc::new(ProxyWaker(proxy)).into()
     }
-    unsafe fn wake(_: *const ()) {}
-    unsafe fn wake_by_ref(_: *const ()) {}
-    unsafe fn drop(_: *const ()) {}
 }
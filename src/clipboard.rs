@@ -0,0 +1,77 @@
+use crate::platform::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A handle used to read and write one of the system's clipboards.
+/// Returned by [`crate::Application::clipboard`] and [`crate::Application::primary_selection`].
+///
+/// Contents can be stored as any MIME type,
+/// but other programs usually only understand text and common image formats.
+/// Supported on X11 and headless. SDL only supports text on the regular clipboard.
+#[derive(Clone)]
+pub struct Clipboard {
+    selection: ClipboardSelection,
+    platform_application: Rc<RefCell<PlatformApplication>>,
+}
+
+impl Clipboard {
+    pub(crate) fn new(
+        selection: ClipboardSelection,
+        platform_application: Rc<RefCell<PlatformApplication>>,
+    ) -> Self {
+        Self {
+            selection,
+            platform_application,
+        }
+    }
+
+    pub fn selection(&self) -> ClipboardSelection {
+        self.selection
+    }
+
+    /// Returns the MIME types the contents are available as.
+    pub fn mime_types(&self) -> Vec<String> {
+        self.platform_application
+            .borrow_mut()
+            .clipboard_mime_types(self.selection)
+    }
+
+    /// Returns the contents as text, or `None` if there's no text.
+    pub fn get_text(&self) -> Option<String> {
+        String::from_utf8(self.get_data(TEXT_MIME_TYPE)?).ok()
+    }
+
+    pub fn set_text(&self, text: &str) {
+        self.set_data(TEXT_MIME_TYPE, text.as_bytes());
+    }
+
+    /// Returns the contents as an image, or `None` if there's no image.
+    /// Only images stored as uncompressed BMP files can be read.
+    pub fn get_image(&self) -> Option<ClipboardImage> {
+        ClipboardImage::from_bmp(&self.get_data(IMAGE_MIME_TYPE)?)
+    }
+
+    /// Stores the image as a BMP file.
+    pub fn set_image(&self, image: &ClipboardImage) {
+        assert_eq!(
+            image.data.len(),
+            image.width as usize * image.height as usize * 4,
+            "Image data must be 4 bytes per pixel"
+        );
+        self.set_data(IMAGE_MIME_TYPE, &image.to_bmp());
+    }
+
+    /// Returns the contents as the MIME type, or `None` if they aren't available as that type.
+    pub fn get_data(&self, mime_type: &str) -> Option<Vec<u8>> {
+        self.platform_application
+            .borrow_mut()
+            .get_clipboard_data(self.selection, mime_type)
+    }
+
+    /// Replaces the contents with data of the MIME type.
+    pub fn set_data(&self, mime_type: &str, data: &[u8]) {
+        self.platform_application
+            .borrow_mut()
+            .set_clipboard_data(self.selection, mime_type, data);
+    }
+}
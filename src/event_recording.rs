@@ -0,0 +1,255 @@
+//! Recording and replay of the events a program receives.
+//!
+//! Recordings are text files with one event per line:
+//! the time since recording started in microseconds, then the event as JSON.
+//! ```text
+//! 1520 {"KeyDown":{"key":"A","scancode":30,"logical_key":{"Character":"a"},"window_id":0,...}}
+//! 1544 {"CharacterReceived":{"character":"a","window_id":0}}
+//! 16702 {"Draw":{"window_id":0}}
+//! ```
+//! Events use the `serde` format, so windows are stored as the order they were created in.
+use crate::platform::*;
+use crate::Application;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Writes each event it's given to a recording.
+/// ```no_run
+/// use kapp::*;
+///
+/// let (app, event_loop) = initialize();
+/// let _window = app.new_window().build().unwrap();
+/// let mut recorder = EventRecorder::create("events.txt").unwrap();
+///
+/// event_loop.run(move |event| {
+///     recorder.record(&event).unwrap();
+///     match event {
+///         Event::WindowCloseRequested { .. } => app.quit(),
+///         _ => {}
+///     }
+/// });
+/// ```
+pub struct EventRecorder {
+    writer: Box<dyn Write>,
+    start: Instant,
+}
+
+impl EventRecorder {
+    /// Creates a file to record to, replacing the file if it exists.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+            start: Instant::now(),
+        }
+    }
+
+    /// Writes an event to the recording.
+    /// `User` events are skipped because their data can't be serialized.
+    /// Recording an event for a window that's closed is an error.
+    /// The recording is flushed after each `Draw` and `Quit`
+    /// so it's kept up to the last frame if the program crashes.
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        if let Event::User { .. } = event {
+            return Ok(());
+        }
+        let json = serde_json::to_string(event)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        writeln!(self.writer, "{} {}", self.start.elapsed().as_micros(), json)?;
+        match event {
+            Event::Draw { .. } | Event::Quit => self.writer.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How an `EventPlayer` paces the events it replays.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReplaySpeed {
+    /// Events are replayed with the same timing they were recorded with.
+    Original,
+    /// One recorded frame is replayed each frame.
+    Max,
+    /// A recorded frame is replayed for each call to `EventPlayer::step`.
+    Step,
+}
+
+/// Replays a recording by injecting its events with `Application::inject_event`.
+/// Replayed events reach the program and the `StateTracker` like events from the operating system.
+///
+/// A recorded frame is the events recorded before a `Draw`.
+/// `Draw`, `EventsCleared`, `ResumeTimeReached`, `Timer` and `Quit` come from the event loop
+/// so they are not replayed.
+/// ```no_run
+/// use kapp::*;
+///
+/// let (app, event_loop) = initialize();
+/// let window = app.new_window().build().unwrap();
+/// let mut player = EventPlayer::open(&app, "events.txt", &[window.id], ReplaySpeed::Max).unwrap();
+///
+/// event_loop.run(move |event| {
+///     player.handle_event(&event);
+///     match event {
+///         Event::WindowCloseRequested { .. } => app.quit(),
+///         _ => {}
+///     }
+/// });
+/// ```
+pub struct EventPlayer {
+    application: Application,
+    windows: Vec<WindowId>,
+    speed: ReplaySpeed,
+    start: Instant,
+    // `None` marks the end of a recorded frame.
+    events: VecDeque<(Duration, Option<Event>)>,
+}
+
+impl EventPlayer {
+    /// Opens a recording to replay.
+    /// Recorded events are replayed to the window created in the same order
+    /// as the recorded window, so the program should create its windows as it did when recording.
+    /// Events for recorded windows that aren't open are skipped.
+    /// `windows` are the windows to request frames for while replaying.
+    pub fn open(
+        application: &Application,
+        path: impl AsRef<Path>,
+        windows: &[WindowId],
+        speed: ReplaySpeed,
+    ) -> io::Result<Self> {
+        Self::new(
+            application,
+            BufReader::new(File::open(path)?),
+            windows,
+            speed,
+        )
+    }
+
+    pub fn new(
+        application: &Application,
+        reader: impl BufRead,
+        windows: &[WindowId],
+        speed: ReplaySpeed,
+    ) -> io::Result<Self> {
+        let mut events = VecDeque::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let event = line_to_event(&line).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid event on line {}: {}", i + 1, error),
+                )
+            })?;
+            match event {
+                RecordedLine::Event(time, event) => events.push_back((time, Some(event))),
+                RecordedLine::FrameEnd(time) => events.push_back((time, None)),
+                RecordedLine::Skipped => {}
+            }
+        }
+
+        // Request a frame to start the replay.
+        for window_id in windows {
+            application
+                .platform_application
+                .borrow_mut()
+                .redraw_window(*window_id);
+        }
+
+        Ok(Self {
+            application: application.clone(),
+            windows: windows.to_vec(),
+            speed,
+            start: Instant::now(),
+            events,
+        })
+    }
+
+    /// Call with each event the program receives.
+    /// Replays events in response to `Draw` unless the speed is `ReplaySpeed::Step`.
+    pub fn handle_event(&mut self, event: &Event) {
+        if let Event::Draw { .. } = event {
+            match self.speed {
+                ReplaySpeed::Original => {
+                    let elapsed = self.start.elapsed();
+                    while let Some((time, _)) = self.events.front() {
+                        if *time > elapsed {
+                            break;
+                        }
+                        if let Some((_, Some(event))) = self.events.pop_front() {
+                            self.application.inject_event(event);
+                        }
+                    }
+                    self.request_frame();
+                }
+                ReplaySpeed::Max => self.step(),
+                ReplaySpeed::Step => {}
+            }
+        }
+    }
+
+    /// Replays the next recorded frame.
+    /// Frames without events are skipped.
+    pub fn step(&mut self) {
+        while let Some((_, None)) = self.events.front() {
+            self.events.pop_front();
+        }
+        while let Some((_, Some(event))) = self.events.pop_front() {
+            self.application.inject_event(event);
+        }
+        self.request_frame();
+    }
+
+    /// Returns true if all recorded events have been replayed.
+    pub fn finished(&self) -> bool {
+        self.events.iter().all(|(_, event)| event.is_none())
+    }
+
+    // Keep frames coming while there are events left to replay.
+    fn request_frame(&self) {
+        if !self.finished() {
+            for window_id in &self.windows {
+                self.application
+                    .platform_application
+                    .borrow_mut()
+                    .redraw_window(*window_id);
+            }
+        }
+    }
+}
+
+enum RecordedLine {
+    Event(Duration, Event),
+    /// A `Draw`, which ends a recorded frame.
+    FrameEnd(Duration),
+    /// An event that isn't replayed.
+    Skipped,
+}
+
+/// Parses a recorded line.
+fn line_to_event(line: &str) -> Result<RecordedLine, String> {
+    let (time, json) = line.split_once(' ').ok_or("missing time")?;
+    let time = Duration::from_micros(time.parse().map_err(|_| "invalid time")?);
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+    // A `Draw` ends a frame even if its window isn't open.
+    if value.get("Draw").is_some() {
+        return Ok(RecordedLine::FrameEnd(time));
+    }
+    match serde_json::from_value(value) {
+        Ok(Event::EventsCleared | Event::ResumeTimeReached | Event::Timer { .. } | Event::Quit) => {
+            Ok(RecordedLine::Skipped)
+        }
+        Ok(event) => Ok(RecordedLine::Event(time, event)),
+        // Skip events for windows that aren't being replayed to.
+        Err(error) if error.to_string().starts_with("unknown window") => Ok(RecordedLine::Skipped),
+        Err(error) => Err(error.to_string()),
+    }
+}
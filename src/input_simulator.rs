@@ -0,0 +1,370 @@
+use crate::platform::*;
+use crate::Application;
+use std::time::{Duration, Instant};
+
+/// Simulates user input by injecting the same sequences of events that
+/// the operating system sends for it.
+/// Useful for testing programs without a person at the keyboard.
+///
+/// Pointer coordinates are in physical pixels relative to the window.
+/// ```no_run
+/// use kapp::*;
+///
+/// let (app, event_loop) = initialize();
+/// let window = app.new_window().build().unwrap();
+///
+/// let mut simulator = InputSimulator::new(&app, window.id);
+/// simulator.type_string("Hello");
+/// simulator.click(10.0, 20.0, PointerButton::Primary);
+/// simulator.key_chord(&[Key::LeftControl, Key::S]);
+///
+/// event_loop.run(move |event| println!("{:?}", event));
+/// ```
+pub struct InputSimulator {
+    application: Application,
+    window_id: WindowId,
+    start: Instant,
+    pointer_position: (f64, f64),
+    modifiers: Modifiers,
+}
+
+impl InputSimulator {
+    pub fn new(application: &Application, window_id: WindowId) -> Self {
+        Self {
+            application: application.clone(),
+            window_id,
+            start: Instant::now(),
+            pointer_position: (0.0, 0.0),
+            modifiers: Modifiers::empty(),
+        }
+    }
+
+    fn timestamp(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    // Modifiers change with the key events that change them, like they do on every platform.
+    fn update_modifiers(&mut self, key: Key, pressed: bool) {
+        let modifiers = self.modifiers.after_key(key, pressed);
+        if modifiers != self.modifiers {
+            self.modifiers = modifiers;
+            self.application
+                .inject_event(Event::ModifiersChanged { modifiers });
+        }
+    }
+
+    /// Keys are pressed on a US keyboard layout, which determines their logical key.
+    /// Simulated keys don't have a scancode.
+    pub fn press_key(&mut self, key: Key) {
+        self.update_modifiers(key, true);
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::KeyDown {
+            key,
+            scancode: 0,
+            logical_key: logical_key(key),
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+
+    pub fn release_key(&mut self, key: Key) {
+        self.update_modifiers(key, false);
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::KeyUp {
+            key,
+            scancode: 0,
+            logical_key: logical_key(key),
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+
+    /// Presses the keys in order then releases them in reverse order.
+    /// For example `&[Key::LeftControl, Key::C]` for copy.
+    pub fn key_chord(&mut self, keys: &[Key]) {
+        for key in keys {
+            self.press_key(*key);
+        }
+        for key in keys.iter().rev() {
+            self.release_key(*key);
+        }
+    }
+
+    /// Types each character of a string with a US keyboard layout.
+    /// Characters without a key on that layout only send `CharacterReceived`.
+    pub fn type_string(&mut self, string: &str) {
+        for character in string.chars() {
+            let key = character_to_key(character);
+            if let Some((key, shift)) = key {
+                if shift {
+                    self.press_key(Key::LeftShift);
+                }
+                self.press_key(key);
+            }
+            self.application.inject_event(Event::CharacterReceived {
+                character,
+                window_id: self.window_id,
+            });
+            if let Some((key, shift)) = key {
+                self.release_key(key);
+                if shift {
+                    self.release_key(Key::LeftShift);
+                }
+            }
+        }
+    }
+
+    /// Moves the pointer to a position without any buttons pressed.
+    pub fn move_pointer(&mut self, x: f64, y: f64) {
+        let timestamp = self.timestamp();
+        let (last_x, last_y) = self.pointer_position;
+        self.application.inject_event(Event::MouseMotion {
+            delta_x: x - last_x,
+            delta_y: y - last_y,
+            window_id: self.window_id,
+            timestamp,
+        });
+        self.application.inject_event(Event::PointerMoved {
+            x,
+            y,
+            source: PointerSource::Mouse,
+            pointer_id: 0,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: self.window_id,
+            timestamp,
+        });
+        self.pointer_position = (x, y);
+    }
+
+    pub fn pointer_down(&mut self, button: PointerButton) {
+        let (x, y) = self.pointer_position;
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::PointerDown {
+            x,
+            y,
+            source: PointerSource::Mouse,
+            pointer_id: 0,
+            button,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+
+    pub fn pointer_up(&mut self, button: PointerButton) {
+        let (x, y) = self.pointer_position;
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::PointerUp {
+            x,
+            y,
+            source: PointerSource::Mouse,
+            pointer_id: 0,
+            button,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+
+    /// Moves the pointer to a position then presses and releases a button.
+    pub fn click(&mut self, x: f64, y: f64, button: PointerButton) {
+        self.move_pointer(x, y);
+        self.pointer_down(button);
+        self.pointer_up(button);
+    }
+
+    /// Presses a button at one position, moves the pointer to another in steps,
+    /// then releases the button.
+    pub fn drag(&mut self, from: (f64, f64), to: (f64, f64), button: PointerButton) {
+        const STEPS: usize = 10;
+
+        self.move_pointer(from.0, from.1);
+        self.pointer_down(button);
+        for i in 1..=STEPS {
+            let t = i as f64 / STEPS as f64;
+            self.move_pointer(from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        }
+        self.pointer_up(button);
+    }
+
+    /// Touches are told apart by their `pointer_id`, and are pressed with full pressure.
+    pub fn touch_down(&mut self, pointer_id: u64, x: f64, y: f64) {
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::PointerDown {
+            x,
+            y,
+            source: PointerSource::Touch,
+            pointer_id,
+            button: PointerButton::Primary,
+            pressure: 1.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+
+    pub fn touch_move(&mut self, pointer_id: u64, x: f64, y: f64) {
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::PointerMoved {
+            x,
+            y,
+            source: PointerSource::Touch,
+            pointer_id,
+            pressure: 1.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: self.window_id,
+            timestamp,
+        });
+    }
+
+    pub fn touch_up(&mut self, pointer_id: u64, x: f64, y: f64) {
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::PointerUp {
+            x,
+            y,
+            source: PointerSource::Touch,
+            pointer_id,
+            button: PointerButton::Primary,
+            pressure: 0.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            contact_width: 0.0,
+            contact_height: 0.0,
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+
+    pub fn scroll(&mut self, delta_x: f64, delta_y: f64) {
+        let timestamp = self.timestamp();
+        self.application.inject_event(Event::Scroll {
+            delta_x,
+            delta_y,
+            window_id: self.window_id,
+            modifiers: self.modifiers,
+            timestamp,
+        });
+    }
+}
+
+/// The characters typed by each key on a US keyboard.
+const US_LAYOUT: [(char, Key); 50] = [
+    ('a', Key::A),
+    ('b', Key::B),
+    ('c', Key::C),
+    ('d', Key::D),
+    ('e', Key::E),
+    ('f', Key::F),
+    ('g', Key::G),
+    ('h', Key::H),
+    ('i', Key::I),
+    ('j', Key::J),
+    ('k', Key::K),
+    ('l', Key::L),
+    ('m', Key::M),
+    ('n', Key::N),
+    ('o', Key::O),
+    ('p', Key::P),
+    ('q', Key::Q),
+    ('r', Key::R),
+    ('s', Key::S),
+    ('t', Key::T),
+    ('u', Key::U),
+    ('v', Key::V),
+    ('w', Key::W),
+    ('x', Key::X),
+    ('y', Key::Y),
+    ('z', Key::Z),
+    ('1', Key::Digit1),
+    ('2', Key::Digit2),
+    ('3', Key::Digit3),
+    ('4', Key::Digit4),
+    ('5', Key::Digit5),
+    ('6', Key::Digit6),
+    ('7', Key::Digit7),
+    ('8', Key::Digit8),
+    ('9', Key::Digit9),
+    ('0', Key::Digit0),
+    (' ', Key::Space),
+    ('\n', Key::Return),
+    ('\t', Key::Tab),
+    ('-', Key::Minus),
+    ('=', Key::Equal),
+    ('[', Key::LeftBracket),
+    (']', Key::RightBracket),
+    ('\\', Key::Backslash),
+    (';', Key::Semicolon),
+    ('\'', Key::Quote),
+    ('`', Key::Backquote),
+    (',', Key::Comma),
+    ('.', Key::Period),
+    ('/', Key::Slash),
+];
+
+/// The characters typed by each key on a US keyboard while shift is held.
+const US_LAYOUT_SHIFTED: [(char, Key); 21] = [
+    ('!', Key::Digit1),
+    ('@', Key::Digit2),
+    ('#', Key::Digit3),
+    ('$', Key::Digit4),
+    ('%', Key::Digit5),
+    ('^', Key::Digit6),
+    ('&', Key::Digit7),
+    ('*', Key::Digit8),
+    ('(', Key::Digit9),
+    (')', Key::Digit0),
+    ('_', Key::Minus),
+    ('+', Key::Equal),
+    ('{', Key::LeftBracket),
+    ('}', Key::RightBracket),
+    ('|', Key::Backslash),
+    (':', Key::Semicolon),
+    ('"', Key::Quote),
+    ('~', Key::Backquote),
+    ('<', Key::Comma),
+    ('>', Key::Period),
+    ('?', Key::Slash),
+];
+
+/// The key that types a character on a US keyboard, and if shift must be held.
+fn character_to_key(character: char) -> Option<(Key, bool)> {
+    let shift = character.is_ascii_uppercase();
+    let lowercase = character.to_ascii_lowercase();
+    if let Some((_, key)) = US_LAYOUT.iter().find(|(c, _)| *c == lowercase) {
+        return Some((*key, shift));
+    }
+    US_LAYOUT_SHIFTED
+        .iter()
+        .find(|(c, _)| *c == character)
+        .map(|(_, key)| (*key, true))
+}
+
+/// The logical key for a key on a US keyboard.
+fn logical_key(key: Key) -> LogicalKey {
+    let character = US_LAYOUT.iter().find(|(_, k)| *k == key).map(|(c, _)| *c);
+    LogicalKey::from_character(character, key)
+}
@@ -1,4 +1,4 @@
-//! Cross platform windows, input, and GL context creation for Windows, Mac, and Web.
+//! Cross platform windows, input, and GL context creation for Windows, Mac, Web, and Linux.
 //!
 //! # Hello Window
 //! ```no_run
@@ -49,24 +49,44 @@
 //!
 //! # GL Rendering
 //! If the `gl_context` feature is enabled then a GLContext can be created for rendering with GL.
+//! It's available on macOS, Windows, and web. On Linux a GL context can be created for a window
+//! with another library through `raw-window-handle`.
 //! See the `simple_gl.rs` example.
 mod application;
 mod async_application;
+mod clipboard;
+#[cfg(feature = "serde")]
+mod event_recording;
+mod input_simulator;
 mod state_tracker;
 mod window;
 mod window_builder;
 
 use kapp_platforms::prelude as platform;
 
-#[cfg(feature = "gl_context")]
+#[cfg(all(feature = "gl_context", not(target_os = "linux")))]
 pub use kapp_gl_context::prelude::*;
 
-pub use platform::{Cursor, Event, Key, PointerButton, PointerSource, WindowId};
+pub use platform::{
+    ClipboardImage, ClipboardSelection, ControlFlow, Cursor, CustomCursor, DropData, Event,
+    EventLoopProxy, Fullscreen, Icon, Key, LogicalKey, Modifiers, PointerButton, PointerSource,
+    Screen, ScreenId, TimerId, UserEvent, VideoMode, WindowId,
+};
+
+#[cfg(feature = "headless")]
+pub use platform::{advance_time, queue_event, set_screens, window_record, WindowRecord};
 
 pub use application::{initialize, Application, EventLoop};
 
 pub use async_application::*;
 
-pub use state_tracker::StateTracker;
+pub use clipboard::Clipboard;
+
+#[cfg(feature = "serde")]
+pub use event_recording::{EventPlayer, EventRecorder, ReplaySpeed};
+
+pub use input_simulator::InputSimulator;
+
+pub use state_tracker::{StateTracker, Touch, WindowState};
 pub use window::Window;
 pub use window_builder::WindowBuilder;
@@ -1,21 +1,66 @@
-use crate::{Event, Key, PointerButton};
+use crate::{Event, Fullscreen, Key, Modifiers, PointerButton, PointerSource, WindowId};
 use std::collections::HashMap;
 use std::time::Duration;
 
 // In the future this could be extended to track:
-// * Window positions and status.
-// * Window scale factors
 // * Window color spaces
 
+/// A touch that is currently pressed down.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Touch {
+    pub pointer_id: u64,
+    pub x: f64,
+    pub y: f64,
+    pub pressure: f64,
+    pub window_id: WindowId,
+}
+
+/// A window's state as of the last events for it.
+/// Sizes and positions are in physical coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WindowState {
+    pub size: (u32, u32),
+    pub position: (u32, u32),
+    pub scale: f64,
+    pub focused: bool,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub fullscreen: Fullscreen,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            size: (0, 0),
+            position: (0, 0),
+            scale: 1.0,
+            focused: false,
+            minimized: false,
+            maximized: false,
+            fullscreen: Fullscreen::Off,
+        }
+    }
+}
+
 /// Tracks key and pointer input state based on events.
+/// Queries ending in `_in_window` only consider input sent to one window.
+/// Keys and buttons belong to the window they were pressed in.
+/// Touches also press the pointer button and move the pointer like a mouse would.
+/// The button is released when the last touch ends.
+/// Window states are updated by the `Window*` events for windows added with `add_window`.
 pub struct StateTracker {
-    keys_down_since_last_frame: HashMap<Key, Duration>, // Key was pressed since the last clear for any window.
-    keys_pressed: HashMap<Key, Duration>,
-    pointer_buttons_down_since_last_frame: HashMap<PointerButton, Duration>, // pointer was pressed since the last clear for any window.
-    pointer_buttons_released_since_last_frame: HashMap<PointerButton, Duration>, // pointer was pressed since the last clear for any window.
-    pointer_buttons_pressed: HashMap<PointerButton, Duration>,
+    keys_down_since_last_frame: HashMap<Key, (Duration, WindowId)>, // Key was pressed since the last clear for any window.
+    keys_pressed: HashMap<Key, (Duration, WindowId)>,
+    pointer_buttons_down_since_last_frame: HashMap<PointerButton, (Duration, WindowId)>, // pointer was pressed since the last clear for any window.
+    pointer_buttons_released_since_last_frame: HashMap<PointerButton, (Duration, WindowId)>, // pointer was pressed since the last clear for any window.
+    pointer_buttons_pressed: HashMap<PointerButton, (Duration, WindowId)>,
     pointer_position: (f64, f64),
+    pointer_positions: HashMap<WindowId, (f64, f64)>,
+    pointer_window: Option<WindowId>,
+    touches: Vec<Touch>, // Kept in the order the touches started.
     mouse_motion: (f64, f64),
+    modifiers: Modifiers,
+    windows: HashMap<WindowId, WindowState>,
 }
 
 impl StateTracker {
@@ -27,37 +72,188 @@ impl StateTracker {
             pointer_buttons_released_since_last_frame: HashMap::with_capacity(16),
             pointer_buttons_pressed: HashMap::with_capacity(16),
             pointer_position: (0., 0.),
+            pointer_positions: HashMap::new(),
+            pointer_window: None,
+            touches: Vec::new(),
             mouse_motion: (0., 0.),
+            modifiers: Modifiers::empty(),
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Starts tracking a window from its state when it was created.
+    /// Events for windows that aren't tracked don't change any window state.
+    pub fn add_window(&mut self, window_id: WindowId, state: WindowState) {
+        self.windows.insert(window_id, state);
+    }
+
+    /// Stops tracking a window when it's closed.
+    pub fn remove_window(&mut self, window_id: WindowId) {
+        self.windows.remove(&window_id);
+        self.pointer_positions.remove(&window_id);
+        if self.pointer_window == Some(window_id) {
+            self.pointer_window = None;
         }
     }
 
     pub fn handle_event(&mut self, event: &Event) {
         match event {
-            Event::KeyDown { key, timestamp } => {
-                self.keys_pressed.insert(*key, *timestamp);
-                self.keys_down_since_last_frame.insert(*key, *timestamp);
+            Event::KeyDown {
+                key,
+                window_id,
+                modifiers,
+                timestamp,
+                ..
+            } => {
+                self.keys_pressed.insert(*key, (*timestamp, *window_id));
+                self.keys_down_since_last_frame
+                    .insert(*key, (*timestamp, *window_id));
+                self.modifiers = *modifiers;
             }
-            Event::KeyUp { key, .. } => {
+            Event::KeyUp { key, modifiers, .. } => {
                 self.keys_pressed.remove(&key);
+                self.modifiers = *modifiers;
             }
+            Event::KeyRepeat { modifiers, .. }
+            | Event::Scroll { modifiers, .. }
+            | Event::ModifiersChanged { modifiers } => self.modifiers = *modifiers,
             Event::PointerDown {
-                button, timestamp, ..
+                x,
+                y,
+                source,
+                pointer_id,
+                button,
+                pressure,
+                window_id,
+                modifiers,
+                timestamp,
+                ..
             } => {
-                self.pointer_buttons_pressed.insert(*button, *timestamp);
+                if *source == PointerSource::Touch {
+                    self.touches.retain(|t| t.pointer_id != *pointer_id);
+                    self.touches.push(Touch {
+                        pointer_id: *pointer_id,
+                        x: *x,
+                        y: *y,
+                        pressure: *pressure,
+                        window_id: *window_id,
+                    });
+                }
+                self.pointer_buttons_pressed
+                    .insert(*button, (*timestamp, *window_id));
                 self.pointer_buttons_down_since_last_frame
-                    .insert(*button, *timestamp);
+                    .insert(*button, (*timestamp, *window_id));
+                self.modifiers = *modifiers;
             }
             Event::PointerUp {
-                button, timestamp, ..
+                source,
+                pointer_id,
+                button,
+                window_id,
+                modifiers,
+                timestamp,
+                ..
             } => {
+                self.modifiers = *modifiers;
+                if *source == PointerSource::Touch {
+                    self.touches.retain(|t| t.pointer_id != *pointer_id);
+                    // The button stays pressed until the last touch ends.
+                    if !self.touches.is_empty() {
+                        return;
+                    }
+                }
                 self.pointer_buttons_released_since_last_frame
-                    .insert(*button, *timestamp);
+                    .insert(*button, (*timestamp, *window_id));
                 self.pointer_buttons_pressed.remove(&button);
             }
-            Event::PointerMoved { x, y, .. } => self.pointer_position = (*x, *y),
+            Event::PointerMoved {
+                x,
+                y,
+                source,
+                pointer_id,
+                pressure,
+                window_id,
+                ..
+            } => {
+                if *source == PointerSource::Touch {
+                    if let Some(touch) = self
+                        .touches
+                        .iter_mut()
+                        .find(|t| t.pointer_id == *pointer_id)
+                    {
+                        touch.x = *x;
+                        touch.y = *y;
+                        touch.pressure = *pressure;
+                    }
+                }
+                self.pointer_position = (*x, *y);
+                self.pointer_positions.insert(*window_id, (*x, *y));
+                self.pointer_window = Some(*window_id);
+            }
             Event::MouseMotion {
                 delta_x, delta_y, ..
             } => self.mouse_motion = (self.mouse_motion.0 + delta_x, self.mouse_motion.1 + delta_y),
+            Event::WindowMinimized { window_id } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.minimized = true;
+                }
+            }
+            Event::WindowMaximized { window_id } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.minimized = false;
+                    window.maximized = true;
+                    window.fullscreen = Fullscreen::Off;
+                }
+            }
+            Event::WindowFullscreened {
+                fullscreen,
+                window_id,
+            } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.minimized = false;
+                    window.fullscreen = *fullscreen;
+                }
+            }
+            Event::WindowRestored {
+                fullscreen,
+                window_id,
+            } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.minimized = false;
+                    window.maximized = false;
+                    window.fullscreen = *fullscreen;
+                }
+            }
+            Event::WindowResized {
+                width,
+                height,
+                window_id,
+            } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.size = (*width, *height);
+                }
+            }
+            Event::WindowMoved { x, y, window_id } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.position = (*x, *y);
+                }
+            }
+            Event::WindowGainedFocus { window_id } => {
+                // Only one window has focus at a time.
+                for (id, window) in self.windows.iter_mut() {
+                    window.focused = id == window_id;
+                }
+            }
+            Event::WindowLostFocus { window_id } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.focused = false;
+                }
+            }
+            Event::WindowScaleChanged { scale, window_id } => {
+                if let Some(window) = self.windows.get_mut(window_id) {
+                    window.scale = *scale;
+                }
+            }
             _ => {}
         };
     }
@@ -76,14 +272,11 @@ impl StateTracker {
     }
 
     /// Returns true if all the keys specified been pressed since the last call to clear.
-    /// Right now this doesn't work perfectly for keyboard shortcuts because
-    /// the different modifier keys are split out into their left and right versions.
+    /// Modifier keys are split out into their left and right versions,
+    /// so use `modifiers` to check the modifiers of keyboard shortcuts.
     pub fn keys_down(&self, keys: &[Key]) -> bool {
-        let mut pressed = true;
-        for key in keys {
-            pressed |= self.keys_down_since_last_frame.contains_key(&key)
-        }
-        pressed
+        keys.iter()
+            .all(|key| self.keys_down_since_last_frame.contains_key(key))
     }
 
     /// Returns if the key is currently down
@@ -112,7 +305,89 @@ impl StateTracker {
         self.pointer_position
     }
 
+    /// Returns true if the key has been pressed in the window since the last call to clear.
+    pub fn key_down_in_window(&self, window_id: WindowId, key: Key) -> bool {
+        in_window(&self.keys_down_since_last_frame, &key, window_id)
+    }
+
+    /// Returns if the key is currently down and was pressed in the window.
+    pub fn key_in_window(&self, window_id: WindowId, key: Key) -> bool {
+        in_window(&self.keys_pressed, &key, window_id)
+    }
+
+    /// Returns true if the pointer button has been pressed in the window since the last call to clear.
+    pub fn pointer_button_down_in_window(
+        &self,
+        window_id: WindowId,
+        button: PointerButton,
+    ) -> bool {
+        in_window(
+            &self.pointer_buttons_down_since_last_frame,
+            &button,
+            window_id,
+        )
+    }
+
+    /// Returns true if the pointer button has been released in the window since the last call to clear.
+    pub fn pointer_button_released_in_window(
+        &self,
+        window_id: WindowId,
+        button: PointerButton,
+    ) -> bool {
+        in_window(
+            &self.pointer_buttons_released_since_last_frame,
+            &button,
+            window_id,
+        )
+    }
+
+    /// Returns true if the pointer button is pressed and was pressed in the window.
+    pub fn pointer_button_in_window(&self, window_id: WindowId, button: PointerButton) -> bool {
+        in_window(&self.pointer_buttons_pressed, &button, window_id)
+    }
+
+    /// The last pointer position in the window, or `None` if the pointer hasn't moved over it.
+    pub fn pointer_position_in_window(&self, window_id: WindowId) -> Option<(f64, f64)> {
+        self.pointer_positions.get(&window_id).copied()
+    }
+
+    /// The window the pointer last moved over.
+    pub fn pointer_window(&self) -> Option<WindowId> {
+        self.pointer_window
+    }
+
+    /// The touches currently pressed down, oldest first.
+    pub fn touches(&self) -> &[Touch] {
+        &self.touches
+    }
+
+    /// The touch with the pointer id, if it's pressed down.
+    pub fn touch(&self, pointer_id: u64) -> Option<Touch> {
+        self.touches
+            .iter()
+            .find(|t| t.pointer_id == pointer_id)
+            .copied()
+    }
+
     pub fn mouse_motion(&self) -> (f64, f64) {
         self.mouse_motion
     }
+
+    /// Returns the modifier keys held and lock keys enabled.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// The window's state as of its last event, or `None` if the window isn't tracked.
+    pub fn window_state(&self, window_id: WindowId) -> Option<WindowState> {
+        self.windows.get(&window_id).copied()
+    }
+}
+
+fn in_window<T: Eq + std::hash::Hash>(
+    map: &HashMap<T, (Duration, WindowId)>,
+    value: &T,
+    window_id: WindowId,
+) -> bool {
+    map.get(value).map(|(_, w)| *w) == Some(window_id)
 }
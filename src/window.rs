@@ -1,4 +1,5 @@
 use crate::platform::*;
+use crate::StateTracker;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -7,16 +8,19 @@ use std::rc::Rc;
 pub struct Window {
     pub id: WindowId,
     platform_application: Rc<RefCell<PlatformApplication>>,
+    state_tracker: Rc<RefCell<StateTracker>>,
 }
 
 impl Window {
     pub(crate) fn new(
         id: WindowId,
         platform_application: Rc<RefCell<PlatformApplication>>,
+        state_tracker: Rc<RefCell<StateTracker>>,
     ) -> Self {
         Self {
             id,
             platform_application,
+            state_tracker,
         }
     }
 
@@ -39,11 +43,19 @@ impl Window {
             .restore_window(self.id);
     }
 
+    /// Makes the window borderless fullscreen on the screen it's on.
     /// On Web this must be done in response to a user event.
     pub fn fullscreen(&self) {
+        self.set_fullscreen(Fullscreen::default());
+    }
+
+    /// Changes whether and how the window covers a screen.
+    /// Exclusive fullscreen switches the screen to one of its `video_modes`.
+    /// On Web this must be done in response to a user event.
+    pub fn set_fullscreen(&self, fullscreen: Fullscreen) {
         self.platform_application
             .borrow_mut()
-            .fullscreen_window(self.id);
+            .set_window_fullscreen(self.id, fullscreen);
     }
 
     /// Sets the title displayed at the top of the window
@@ -53,6 +65,98 @@ impl Window {
             .set_window_title(self.id, title);
     }
 
+    /// Sets the window's icon from one or more sizes of the same image.
+    /// Platforms pick the size closest to what they display.
+    /// On MacOS windows don't have icons so the application's dock icon is set instead.
+    /// On web the page's favicon is set.
+    /// Unsupported on Wayland, where icons come from the application's desktop file.
+    pub fn set_icon(&self, icon: &[Icon]) {
+        for size in icon {
+            assert_eq!(
+                size.data.len(),
+                size.width as usize * size.height as usize * 4,
+                "Icon data must be 4 bytes per pixel"
+            );
+        }
+        self.platform_application
+            .borrow_mut()
+            .set_window_icon(self.id, icon);
+    }
+
+    /// Sets whether the user can resize the window.
+    /// Unsupported on Web.
+    pub fn set_resizable(&self, resizable: bool) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_resizable(self.id, resizable);
+    }
+
+    /// Sets the smallest size the user can resize the window to, in physical coordinates.
+    /// `None` removes the limit. Unsupported on Web.
+    pub fn set_min_size(&self, size: Option<(u32, u32)>) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_minimum_size(self.id, size);
+    }
+
+    /// Sets the largest size the user can resize the window to, in physical coordinates.
+    /// `None` removes the limit. Unsupported on Web.
+    pub fn set_max_size(&self, size: Option<(u32, u32)>) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_maximum_size(self.id, size);
+    }
+
+    /// Shows or hides the window's titlebar and borders.
+    /// Unsupported on Web.
+    pub fn set_decorations(&self, decorations: bool) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_decorations(self.id, decorations);
+    }
+
+    /// Keeps the window above other windows.
+    /// Unsupported on Wayland and Web.
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_always_on_top(self.id, always_on_top);
+    }
+
+    /// Shows or hides the window.
+    /// Unsupported on Wayland and Web.
+    pub fn set_visible(&self, visible: bool) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_visible(self.id, visible);
+    }
+
+    /// Sets the opacity of the whole window, from 0.0 for invisible to 1.0 for opaque.
+    /// Unsupported on Wayland.
+    pub fn set_opacity(&self, opacity: f32) {
+        self.platform_application
+            .borrow_mut()
+            .set_window_opacity(self.id, opacity.clamp(0.0, 1.0));
+    }
+
+    /// Sets the rectangles that receive pointer input, as (x, y, width, height) in
+    /// physical coordinates relative to the window's content.
+    /// Pointer input elsewhere passes through to what's behind the window,
+    /// which lets overlays be clicked through.
+    /// `None` restores input for the whole window.
+    /// On Windows input only passes through to windows of this application
+    /// unless the region is empty.
+    ///
+    /// Returns false if the platform can't use the region, in which case the whole window
+    /// receives input. On macOS and Web only an empty region can be used.
+    /// SDL can't pass input through windows, because `SDL_SetWindowHitTest` can only mark
+    /// areas for dragging and resizing, so only `None` can be used with SDL.
+    pub fn set_input_region(&self, region: Option<&[(u32, u32, u32, u32)]>) -> bool {
+        self.platform_application
+            .borrow_mut()
+            .set_window_input_region(self.id, region)
+    }
+
     /// Set the lower left corner of the window.
     pub fn set_position(&mut self, x: u32, y: u32) {
         self.platform_application
@@ -93,6 +197,63 @@ impl Window {
             .get_window_scale(self.id)
     }
 
+    /// Get the window's position in physical coordinates.
+    /// Unsupported on Wayland and Web, where it's always (0, 0).
+    pub fn position(&self) -> (u32, u32) {
+        self.platform_application
+            .borrow_mut()
+            .get_window_position(self.id)
+    }
+
+    /// Returns true if the window is minimized.
+    /// Always false on Web, and on Wayland compositors don't say when a window is minimized.
+    pub fn is_minimized(&self) -> bool {
+        self.platform_application
+            .borrow_mut()
+            .is_window_minimized(self.id)
+    }
+
+    /// Returns true if the window is maximized. Always false on Web.
+    pub fn is_maximized(&self) -> bool {
+        self.platform_application
+            .borrow_mut()
+            .is_window_maximized(self.id)
+    }
+
+    /// Returns true if the window is fullscreen in any mode.
+    pub fn is_fullscreen(&self) -> bool {
+        self.platform_application
+            .borrow_mut()
+            .get_window_fullscreen(self.id)
+            != Fullscreen::Off
+    }
+
+    /// Returns true if the window receives keyboard input.
+    pub fn has_focus(&self) -> bool {
+        self.platform_application
+            .borrow_mut()
+            .window_has_focus(self.id)
+    }
+
+    /// Returns true if the window is shown.
+    /// Minimized windows are still visible.
+    pub fn is_visible(&self) -> bool {
+        self.platform_application
+            .borrow_mut()
+            .is_window_visible(self.id)
+    }
+
+    /// Returns the screen the window is mostly on,
+    /// or `None` if the platform doesn't know it yet.
+    pub fn current_screen(&self) -> Option<Screen> {
+        let mut platform_application = self.platform_application.borrow_mut();
+        let screen_id = platform_application.window_screen(self.id)?;
+        platform_application
+            .screens()
+            .into_iter()
+            .find(|screen| screen.id == screen_id)
+    }
+
     /// Requests that this window receive another `Draw` event.
     /// Extra redraw requests will be ignored.
     pub fn request_redraw(&self) {
@@ -105,6 +266,8 @@ impl Window {
 impl Drop for Window {
     fn drop(&mut self) {
         self.platform_application.borrow_mut().close_window(self.id);
+        window_id::unregister_window(self.id);
+        self.state_tracker.borrow_mut().remove_window(self.id);
     }
 }
 
@@ -1,5 +1,5 @@
 use crate::platform::*;
-use crate::{Application, Window};
+use crate::{Application, Window, WindowState};
 
 pub struct WindowBuilder<'a> {
     application: &'a Application,
@@ -17,7 +17,9 @@ impl<'a> WindowBuilder<'a> {
                 maximum_size: None,
                 resizable: true,
                 without_titlebar: false,
+                transparent: false,
                 title: "Untitled".to_string(),
+                icon: Vec::new(),
             },
         }
     }
@@ -27,6 +29,22 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
+    /// Sets the window's icon from 8 bit RGBA pixels, stored row by row from the top left.
+    /// Call this multiple times to provide the icon at multiple sizes.
+    pub fn icon(&mut self, rgba: &[u8], width: u32, height: u32) -> &mut Self {
+        assert_eq!(
+            rgba.len(),
+            width as usize * height as usize * 4,
+            "Icon data must be 4 bytes per pixel"
+        );
+        self.window_parameters.icon.push(Icon {
+            width,
+            height,
+            data: rgba.to_vec(),
+        });
+        self
+    }
+
     /// Specify if the window should be resizably by dragging the corner.
     pub fn resizable(&mut self, resizable: bool) -> &mut Self {
         self.window_parameters.resizable = resizable;
@@ -58,13 +76,25 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
-    /// Only available on MacOS for now.
-    /// This feature isn't well tested and may result in sizing errors.
+    /// Creates the window without a titlebar.
+    /// On MacOS the content extends under a transparent titlebar that keeps its buttons.
+    /// Other platforms create the window without decorations, like `Window::set_decorations(false)`.
+    /// Does nothing on Web.
     pub fn without_titlebar(&mut self) -> &mut Self {
         self.window_parameters.without_titlebar = true;
         self
     }
 
+    /// Lets what's behind the window show through where it's drawn with alpha below 1.
+    /// The GL context also needs to be built as transparent.
+    /// On X11 the window uses a 32-bit ARGB visual, so a GL context for it must use
+    /// a config with alpha. kApp doesn't create GL contexts on Linux yet.
+    /// Unsupported with SDL.
+    pub fn transparent(&mut self, transparent: bool) -> &mut Self {
+        self.window_parameters.transparent = transparent;
+        self
+    }
+
     pub fn build(&mut self) -> Result<Window, ()> {
         // Clamp the window size to the minimum width and height
         if let Some(size) = &mut self.window_parameters.size {
@@ -73,12 +103,29 @@ impl<'a> WindowBuilder<'a> {
             }
         }
 
+        let mut platform_application = self.application.platform_application.borrow_mut();
+        let window_id = platform_application.new_window(&self.window_parameters);
+        window_id::register_window(window_id);
+
+        // Events may not be sent for the window's initial state.
+        let state = WindowState {
+            size: platform_application.get_window_size(window_id),
+            position: platform_application.get_window_position(window_id),
+            scale: platform_application.get_window_scale(window_id),
+            focused: platform_application.window_has_focus(window_id),
+            minimized: platform_application.is_window_minimized(window_id),
+            maximized: platform_application.is_window_maximized(window_id),
+            fullscreen: platform_application.get_window_fullscreen(window_id),
+        };
+        self.application
+            .state_tracker
+            .borrow_mut()
+            .add_window(window_id, state);
+
         Ok(Window::new(
-            self.application
-                .platform_application
-                .borrow_mut()
-                .new_window(&self.window_parameters),
+            window_id,
             self.application.platform_application.clone(),
+            self.application.state_tracker.clone(),
         ))
     }
 }
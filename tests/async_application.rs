@@ -0,0 +1,140 @@
+// Run with `cargo test --no-default-features --features headless`
+use kapp::*;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Default)]
+struct Shared {
+    value: Option<u32>,
+    waker: Option<Waker>,
+}
+
+/// Completes with a value sent from another thread, counting how often it's polled.
+struct FromThread {
+    shared: Arc<Mutex<Shared>>,
+    polls: Rc<RefCell<u32>>,
+}
+
+impl FromThread {
+    fn spawn(value: u32, polls: Rc<RefCell<u32>>) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let sender = shared.clone();
+        thread::spawn(move || {
+            // Gives the event loop time to wait for the value.
+            thread::sleep(Duration::from_millis(50));
+            let mut shared = sender.lock().unwrap();
+            shared.value = Some(value);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        });
+        Self { shared, polls }
+    }
+}
+
+impl Future for FromThread {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        *self.polls.borrow_mut() += 1;
+        let mut shared = self.shared.lock().unwrap();
+        match shared.value {
+            Some(value) => Poll::Ready(value),
+            None => {
+                shared.waker = Some(ctx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[test]
+fn futures_completed_on_other_threads_resume_the_program() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    for character in ['a', 'b', 'c'] {
+        queue_event(Event::CharacterReceived {
+            character,
+            window_id,
+        });
+    }
+
+    let value = Rc::new(RefCell::new(None));
+    let characters = Rc::new(RefCell::new(Vec::new()));
+    let polls = Rc::new(RefCell::new(0));
+    {
+        let value = value.clone();
+        let characters = characters.clone();
+        let polls = polls.clone();
+        event_loop.run_async(app, move |app, events| {
+            let value = value.clone();
+            let characters = characters.clone();
+            let polls = polls.clone();
+            async move {
+                *value.borrow_mut() = Some(FromThread::spawn(7, polls).await);
+                // The events sent while the program waited were queued in order.
+                while characters.borrow().len() < 3 {
+                    if let Event::CharacterReceived { character, .. } = events.next().await {
+                        characters.borrow_mut().push(character);
+                    }
+                }
+                app.quit();
+            }
+        });
+    }
+
+    assert_eq!(*value.borrow(), Some(7));
+    assert!(*polls.borrow() > 1);
+    assert_eq!(*characters.borrow(), ['a', 'b', 'c']);
+}
+
+#[test]
+fn each_clone_of_events_receives_every_event() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    for character in ['a', 'b'] {
+        queue_event(Event::CharacterReceived {
+            character,
+            window_id,
+        });
+    }
+
+    let received = Rc::new(RefCell::new(Vec::new()));
+    {
+        let received = received.clone();
+        event_loop.run_async(app, move |_app, events| {
+            let received = received.clone();
+            async move {
+                // Waiting for 'b' skips the 'a' the other task waits for.
+                {
+                    let events = events.clone();
+                    let received = received.clone();
+                    spawn_local(async move {
+                        events
+                            .next_matching(|e| {
+                                matches!(e, Event::CharacterReceived { character: 'a', .. })
+                            })
+                            .await;
+                        received.borrow_mut().push('a');
+                    });
+                }
+                events
+                    .next_matching(|e| matches!(e, Event::CharacterReceived { character: 'b', .. }))
+                    .await;
+                received.borrow_mut().push('b');
+            }
+        });
+    }
+
+    let mut received = received.borrow().clone();
+    received.sort();
+    assert_eq!(received, ['a', 'b']);
+}
@@ -0,0 +1,155 @@
+// Run with `cargo test --no-default-features --features headless,serde`
+use kapp::*;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// A writer whose output can be read after the recorder that owns it is moved.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Events that come from input rather than from the event loop, as JSON.
+fn input_events(events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .filter(|event| {
+            !matches!(
+                event,
+                Event::Draw { .. } | Event::EventsCleared | Event::Quit
+            )
+        })
+        .map(|event| serde_json::to_string(event).unwrap())
+        .collect()
+}
+
+/// Records the events sent while `queue_input` is queued, then replays the recording
+/// to a new application. Returns the recorded and replayed input events.
+fn record_and_replay(
+    queue_input: impl FnOnce(&Application, WindowId),
+) -> (Vec<String>, Vec<String>) {
+    let buffer = SharedBuffer::default();
+
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    queue_input(&app, window.id);
+    queue_event(Event::WindowCloseRequested {
+        window_id: window.id,
+    });
+    let recorded = Rc::new(RefCell::new(Vec::new()));
+    {
+        let recorded = recorded.clone();
+        let mut recorder = EventRecorder::new(buffer.clone());
+        event_loop.run(move |event| {
+            recorder.record(&event).unwrap();
+            if let Event::WindowCloseRequested { .. } = event {
+                app.quit();
+            }
+            recorded.borrow_mut().push(event);
+        });
+    }
+    // Serialized while the recorded window is still the open window.
+    let recorded = input_events(&recorded.borrow());
+
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let recording = buffer.0.borrow().clone();
+    let mut player =
+        EventPlayer::new(&app, &recording[..], &[window.id], ReplaySpeed::Max).unwrap();
+    let replayed = Rc::new(RefCell::new(Vec::new()));
+    {
+        let replayed = replayed.clone();
+        event_loop.run(move |event| {
+            player.handle_event(&event);
+            replayed.borrow_mut().push(event);
+        });
+    }
+
+    let replayed = input_events(&replayed.borrow());
+    (recorded, replayed)
+}
+
+#[test]
+fn recorded_events_are_replayed() {
+    let (recorded, replayed) = record_and_replay(|_, window_id| {
+        queue_event(Event::WindowGainedFocus { window_id });
+        queue_event(Event::KeyDown {
+            key: Key::A,
+            scancode: 30,
+            logical_key: LogicalKey::Character('a'),
+            window_id,
+            modifiers: Modifiers::default(),
+            timestamp: Duration::from_millis(5),
+        });
+        queue_event(Event::CharacterReceived {
+            character: '\n',
+            window_id,
+        });
+        queue_event(Event::IMEComposition {
+            composition: "with spaces\nand lines".to_string(),
+            window_id,
+        });
+        queue_event(Event::Scroll {
+            delta_x: 0.5,
+            delta_y: -1.0,
+            window_id,
+            modifiers: Modifiers::default(),
+            timestamp: Duration::from_millis(6),
+        });
+    });
+    assert_eq!(recorded.len(), 6);
+    assert_eq!(recorded, replayed);
+}
+
+#[test]
+fn screen_events_are_replayed() {
+    let (recorded, replayed) = record_and_replay(|app, _| {
+        let mut screens = app.screens();
+        screens[0].scale = 2.0;
+        let mut added = screens[0].clone();
+        added.id = ScreenId::new(2 as *mut _);
+        set_screens(vec![screens[0].clone(), added]);
+        set_screens(vec![screens[0].clone()]);
+    });
+    assert!(matches!(
+        recorded[..],
+        [ref changed, ref added, ref removed, _]
+            if changed.starts_with("{\"ScreenChanged\"")
+                && added.starts_with("{\"ScreenAdded\"")
+                && removed.starts_with("{\"ScreenRemoved\"")
+    ));
+    assert_eq!(recorded, replayed);
+}
+
+#[test]
+fn events_for_windows_that_are_not_open_are_skipped() {
+    let recording = "10 {\"CharacterReceived\":{\"character\":\"a\",\"window_id\":3}}\n";
+    let (app, _event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let player = EventPlayer::new(&app, recording.as_bytes(), &[window.id], ReplaySpeed::Max);
+    assert!(player.unwrap().finished());
+}
+
+#[test]
+fn invalid_recordings_are_errors() {
+    let (app, _event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let player = EventPlayer::new(
+        &app,
+        &b"10 NotAnEvent\n"[..],
+        &[window.id],
+        ReplaySpeed::Max,
+    );
+    assert_eq!(player.err().unwrap().kind(), io::ErrorKind::InvalidData);
+}
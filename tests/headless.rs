@@ -0,0 +1,413 @@
+// Run with `cargo test --no-default-features --features headless`
+use kapp::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Runs the event loop until the program quits and returns the events it received,
+/// without the `EventsCleared` sent each iteration.
+fn run_until_quit(app: Application, event_loop: EventLoop) -> Vec<Event> {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let received = events.clone();
+    event_loop.run(move |event| {
+        if let Event::WindowCloseRequested { .. } = event {
+            app.quit();
+        }
+        if !matches!(event, Event::EventsCleared) {
+            received.borrow_mut().push(event);
+        }
+    });
+    let events = events.borrow().clone();
+    events
+}
+
+#[test]
+fn window_state_changes_are_recorded_and_sent() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().size(400, 300).build().unwrap();
+    let window_id = window.id;
+
+    window.set_size(800, 600);
+    window.minimize();
+    assert!(window.is_minimized());
+    window.maximize();
+    assert!(!window.is_minimized());
+    assert!(window.is_maximized());
+    window.set_decorations(false);
+
+    let record = window_record(window_id).unwrap();
+    assert_eq!(record.size, (800, 600));
+    assert!(!record.decorations);
+
+    queue_event(Event::WindowCloseRequested { window_id });
+    let events = run_until_quit(app, event_loop);
+    assert!(matches!(
+        events[..],
+        [
+            Event::WindowResized {
+                width: 800,
+                height: 600,
+                ..
+            },
+            Event::WindowMinimized { .. },
+            Event::WindowMaximized { .. },
+            Event::WindowCloseRequested { .. },
+            Event::Quit,
+        ]
+    ));
+}
+
+#[test]
+fn queued_events_are_sent_in_order() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+
+    queue_event(Event::WindowGainedFocus { window_id });
+    queue_event(Event::CharacterReceived {
+        character: 'a',
+        window_id,
+    });
+    queue_event(Event::CharacterReceived {
+        character: 'b',
+        window_id,
+    });
+    queue_event(Event::WindowCloseRequested { window_id });
+    assert!(!window.has_focus());
+
+    let events = run_until_quit(app, event_loop);
+    assert!(matches!(
+        events[..],
+        [
+            Event::WindowGainedFocus { .. },
+            Event::CharacterReceived { character: 'a', .. },
+            Event::CharacterReceived { character: 'b', .. },
+            Event::WindowCloseRequested { .. },
+            Event::Quit,
+        ]
+    ));
+    assert!(window_record(window_id).unwrap().focused);
+}
+
+#[test]
+fn idle_event_loop_returns_when_nothing_can_wake_it() {
+    let (app, event_loop) = initialize();
+    let _window = app.new_window().build().unwrap();
+
+    let events = run_until_quit(app, event_loop);
+    assert!(matches!(events[..], [Event::Draw { .. }, Event::Quit]));
+}
+
+#[test]
+fn new_applications_start_without_the_previous_ones_state() {
+    let (app, _event_loop) = initialize();
+    let first = app.new_window().build().unwrap();
+    let second = app.new_window().build().unwrap();
+    let first_id = first.id;
+    second.request_redraw();
+    queue_event(Event::WindowGainedFocus {
+        window_id: second.id,
+    });
+    drop((first, second));
+
+    // The queued event and draw request aren't sent, and window ids start again.
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    assert_eq!(window_id, first_id);
+    let events = run_until_quit(app, event_loop);
+    assert!(matches!(
+        events[..],
+        [Event::Draw { window_id: id }, Event::Quit] if id == window_id
+    ));
+}
+
+#[test]
+fn closed_windows_are_forgotten() {
+    let (app, _event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    assert!(app.window_state(window_id).is_some());
+
+    drop(window);
+    assert!(window_record(window_id).is_none());
+    assert!(app.window_state(window_id).is_none());
+}
+
+#[test]
+fn windows_without_a_titlebar_have_no_decorations() {
+    let (app, _event_loop) = initialize();
+    let window = app.new_window().without_titlebar().build().unwrap();
+    assert!(!window_record(window.id).unwrap().decorations);
+}
+
+#[test]
+fn clipboards_store_text_images_and_other_data() {
+    let (app, event_loop) = initialize();
+    let clipboard = app.clipboard();
+    assert_eq!(clipboard.get_text(), None);
+
+    clipboard.set_text("copied");
+    assert_eq!(clipboard.get_text().as_deref(), Some("copied"));
+    assert_eq!(app.primary_selection().get_text(), None);
+
+    let image = ClipboardImage {
+        width: 2,
+        height: 1,
+        data: vec![255, 0, 0, 255, 0, 0, 255, 128],
+    };
+    clipboard.set_image(&image);
+    assert_eq!(clipboard.get_image(), Some(image));
+    assert_eq!(clipboard.get_text(), None);
+
+    app.primary_selection()
+        .set_data("application/x-kapp", &[1, 2, 3]);
+    assert_eq!(
+        app.primary_selection().get_data("application/x-kapp"),
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(app.primary_selection().mime_types(), ["application/x-kapp"]);
+
+    let events = run_until_quit(app, event_loop);
+    assert!(matches!(
+        events[..],
+        [
+            Event::ClipboardChanged {
+                selection: ClipboardSelection::Clipboard
+            },
+            Event::ClipboardChanged {
+                selection: ClipboardSelection::Clipboard
+            },
+            Event::ClipboardChanged {
+                selection: ClipboardSelection::Primary
+            },
+            Event::Quit,
+        ]
+    ));
+}
+
+#[test]
+fn rejected_drops_are_not_delivered() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    for text in ["rejected", "accepted"] {
+        queue_event(Event::DragEntered { window_id });
+        queue_event(Event::Dropped {
+            data: DropData::Text(text.to_string()),
+            window_id,
+        });
+    }
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        let mut first_drag = true;
+        event_loop.run(move |event| {
+            // Only the first drag is rejected.
+            if let Event::DragEntered { .. } = event {
+                app.set_drop_accepted(!first_drag);
+                first_drag = false;
+            }
+            if matches!(event, Event::DragLeft { .. } | Event::Dropped { .. }) {
+                events.borrow_mut().push(event);
+            }
+        });
+    }
+    assert!(matches!(
+        events.borrow()[..],
+        [
+            Event::DragLeft { .. },
+            Event::Dropped {
+                data: DropData::Text(ref text),
+                ..
+            },
+        ] if text == "accepted"
+    ));
+}
+
+#[test]
+fn custom_cursors_are_created_from_rgba() {
+    let (app, _event_loop) = initialize();
+    let pixels = [255; 4 * 4 * 4];
+    let first = app.create_cursor(&pixels, 4, 4, (0, 0));
+    let second = app.create_cursor(&pixels, 4, 4, (3, 3));
+    assert_ne!(first, second);
+    app.set_cursor(Cursor::Custom(second));
+    app.set_cursor(Cursor::ZoomIn);
+}
+
+#[test]
+#[should_panic(expected = "hotspot")]
+fn custom_cursor_hotspots_must_be_within_the_cursor() {
+    let (app, _event_loop) = initialize();
+    app.create_cursor(&[255; 4 * 4 * 4], 4, 4, (4, 0));
+}
+
+#[test]
+fn window_icons_are_set_at_every_size() {
+    let (app, _event_loop) = initialize();
+    let window = app
+        .new_window()
+        .icon(&[255; 16 * 16 * 4], 16, 16)
+        .icon(&[255; 32 * 32 * 4], 32, 32)
+        .build()
+        .unwrap();
+    let sizes = |window: &Window| -> Vec<(u32, u32)> {
+        let record = window_record(window.id).unwrap();
+        record
+            .icon
+            .iter()
+            .map(|icon| (icon.width, icon.height))
+            .collect()
+    };
+    assert_eq!(sizes(&window), [(16, 16), (32, 32)]);
+
+    window.set_icon(&[Icon {
+        width: 1,
+        height: 1,
+        data: vec![0, 0, 0, 255],
+    }]);
+    assert_eq!(sizes(&window), [(1, 1)]);
+}
+
+#[test]
+fn screens_are_listed_and_changes_are_sent() {
+    let (app, event_loop) = initialize();
+    let mut window = app.new_window().position(10, 10).build().unwrap();
+    let screens = app.screens();
+    assert_eq!(screens.len(), 1);
+    assert_eq!(screens[0].size, (1920, 1080));
+    assert_eq!(window.current_screen(), Some(screens[0].clone()));
+
+    // A second screen to the right of the first.
+    let mut added = screens[0].clone();
+    added.id = ScreenId::new(2 as *mut _);
+    added.position = (1920, 0);
+    added.work_area_position = (1920, 0);
+    set_screens(vec![screens[0].clone(), added.clone()]);
+    window.set_position(2000, 10);
+    assert_eq!(window.current_screen(), Some(added.clone()));
+
+    set_screens(vec![screens[0].clone()]);
+    drop(window);
+    let events = run_until_quit(app, event_loop);
+    let screen_events: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::ScreenAdded { screen_id } => Some((true, *screen_id)),
+            Event::ScreenRemoved { screen_id } => Some((false, *screen_id)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(screen_events, [(true, added.id), (false, added.id)]);
+}
+
+#[test]
+fn fullscreen_changes_report_the_mode() {
+    let (app, event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    let screen = app.screens().remove(0);
+    let mode = screen.video_modes[1];
+    assert_eq!(mode.size, (1280, 720));
+
+    window.set_fullscreen(Fullscreen::Exclusive(mode));
+    assert!(window.is_fullscreen());
+    window.set_fullscreen(Fullscreen::Borderless(None));
+    window.set_fullscreen(Fullscreen::Off);
+    assert!(!window.is_fullscreen());
+
+    queue_event(Event::WindowCloseRequested { window_id });
+    let events = run_until_quit(app, event_loop);
+    let fullscreen_events: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::WindowFullscreened { fullscreen, .. } => Some((true, *fullscreen)),
+            Event::WindowRestored { fullscreen, .. } => Some((false, *fullscreen)),
+            _ => None,
+        })
+        .collect();
+    // Borderless fullscreen reports the screen it covers.
+    assert_eq!(
+        fullscreen_events,
+        [
+            (true, Fullscreen::Exclusive(mode)),
+            (true, Fullscreen::Borderless(Some(screen.id))),
+            (false, Fullscreen::Off),
+        ]
+    );
+}
+
+#[test]
+fn window_state_can_be_queried() {
+    let (app, event_loop) = initialize();
+    let mut window = app.new_window().position(20, 30).build().unwrap();
+    let window_id = window.id;
+    assert_eq!(window.position(), (20, 30));
+    assert!(!window.is_minimized());
+    assert!(!window.is_maximized());
+    assert!(!window.is_fullscreen());
+    assert!(window.is_visible());
+
+    window.set_position(40, 50);
+    assert_eq!(window.position(), (40, 50));
+    window.minimize();
+    assert!(window.is_minimized());
+    window.restore();
+    assert!(!window.is_minimized());
+
+    // Focus changes once the platform reports it.
+    queue_event(Event::WindowGainedFocus { window_id });
+    queue_event(Event::WindowCloseRequested { window_id });
+    assert!(!window.has_focus());
+    run_until_quit(app, event_loop);
+    assert!(window.has_focus());
+}
+
+#[test]
+fn window_attributes_can_be_changed() {
+    let (app, _event_loop) = initialize();
+    let window = app
+        .new_window()
+        .resizable(false)
+        .minimum_size(100, 100)
+        .build()
+        .unwrap();
+    let record = window_record(window.id).unwrap();
+    assert!(!record.resizable);
+    assert_eq!(record.minimum_size, Some((100, 100)));
+
+    window.set_resizable(true);
+    window.set_min_size(None);
+    window.set_max_size(Some((800, 600)));
+    window.set_decorations(false);
+    window.set_always_on_top(true);
+    window.set_visible(false);
+    let record = window_record(window.id).unwrap();
+    assert!(record.resizable);
+    assert_eq!(record.minimum_size, None);
+    assert_eq!(record.maximum_size, Some((800, 600)));
+    assert!(!record.decorations);
+    assert!(record.always_on_top);
+    assert!(!window.is_visible());
+}
+
+#[test]
+fn transparency_opacity_and_input_regions_are_recorded() {
+    let (app, _event_loop) = initialize();
+    let window = app.new_window().transparent(true).build().unwrap();
+    assert!(window_record(window.id).unwrap().transparent);
+
+    window.set_opacity(1.5);
+    assert_eq!(window_record(window.id).unwrap().opacity, 1.0);
+    window.set_opacity(0.5);
+    assert_eq!(window_record(window.id).unwrap().opacity, 0.5);
+
+    assert!(window.set_input_region(Some(&[(0, 0, 10, 20)])));
+    assert_eq!(
+        window_record(window.id).unwrap().input_region,
+        Some(vec![(0, 0, 10, 20)])
+    );
+    assert!(window.set_input_region(None));
+    assert_eq!(window_record(window.id).unwrap().input_region, None);
+}
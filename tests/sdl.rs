@@ -0,0 +1,32 @@
+// Run with `cargo test --features SDL --test sdl`
+// SDL's dummy video driver is used, so no display is needed.
+use kapp::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+#[test]
+fn user_events_are_not_confused_with_pending_timers() {
+    std::env::set_var("SDL_VIDEODRIVER", "dummy");
+    let (app, event_loop) = initialize();
+    // Proxies and timers both push SDL user events, so the timer must be pending
+    // when the proxy wakes the event loop.
+    let timer = app.set_timer(Duration::from_secs(60), false);
+    let proxy = app.proxy();
+    std::thread::spawn(move || proxy.send_user_event(Box::new(7u32)));
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        event_loop.run(move |event| match event {
+            Event::User { data } => {
+                events.borrow_mut().push(data.take_as::<u32>());
+                app.cancel_timer(timer);
+                app.quit();
+            }
+            Event::Timer { .. } => events.borrow_mut().push(None),
+            _ => {}
+        });
+    }
+    assert_eq!(*events.borrow(), [Some(7)]);
+}
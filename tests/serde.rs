@@ -0,0 +1,44 @@
+// Run with `cargo test --no-default-features --features headless,serde`
+use kapp::*;
+
+#[test]
+fn window_ids_are_serialized_in_creation_order() {
+    let (app, _event_loop) = initialize();
+    let first = app.new_window().build().unwrap();
+    let second = app.new_window().build().unwrap();
+    assert_eq!(serde_json::to_string(&first.id).unwrap(), "0");
+    assert_eq!(serde_json::to_string(&second.id).unwrap(), "1");
+
+    let json = serde_json::to_string(&Event::WindowMinimized {
+        window_id: second.id,
+    })
+    .unwrap();
+    assert!(matches!(
+        serde_json::from_str(&json).unwrap(),
+        Event::WindowMinimized { window_id } if window_id == second.id
+    ));
+}
+
+#[test]
+fn closed_and_unknown_windows_are_errors() {
+    let (app, _event_loop) = initialize();
+    let window = app.new_window().build().unwrap();
+    let window_id = window.id;
+    drop(window);
+
+    assert!(serde_json::to_string(&window_id).is_err());
+    let error = serde_json::from_str::<WindowId>("0").unwrap_err();
+    assert!(error.to_string().contains("unknown window 0"));
+}
+
+#[test]
+fn keys_buttons_and_cursors_round_trip() {
+    let key: Key = serde_json::from_str(&serde_json::to_string(&Key::LeftShift).unwrap()).unwrap();
+    assert_eq!(key, Key::LeftShift);
+    let button: PointerButton =
+        serde_json::from_str(&serde_json::to_string(&PointerButton::Secondary).unwrap()).unwrap();
+    assert_eq!(button, PointerButton::Secondary);
+    let cursor: Cursor =
+        serde_json::from_str(&serde_json::to_string(&Cursor::PointingHand).unwrap()).unwrap();
+    assert_eq!(cursor, Cursor::PointingHand);
+}
@@ -0,0 +1,169 @@
+use kapp::*;
+use std::time::Duration;
+
+fn window() -> WindowId {
+    WindowId::new(std::ptr::dangling_mut())
+}
+
+fn key(key: Key, down: bool, modifiers: Modifiers) -> Event {
+    if down {
+        Event::KeyDown {
+            key,
+            scancode: 0,
+            logical_key: LogicalKey::Key(key),
+            window_id: window(),
+            modifiers,
+            timestamp: Duration::from_millis(1),
+        }
+    } else {
+        Event::KeyUp {
+            key,
+            scancode: 0,
+            logical_key: LogicalKey::Key(key),
+            window_id: window(),
+            modifiers,
+            timestamp: Duration::from_millis(2),
+        }
+    }
+}
+
+fn touch(pointer_id: u64, down: bool) -> Event {
+    if down {
+        Event::PointerDown {
+            x: 10.,
+            y: 20.,
+            source: PointerSource::Touch,
+            pointer_id,
+            button: PointerButton::Primary,
+            pressure: 1.0,
+            tilt_x: 0.,
+            tilt_y: 0.,
+            contact_width: 1.,
+            contact_height: 1.,
+            window_id: window(),
+            modifiers: Modifiers::default(),
+            timestamp: Duration::from_millis(pointer_id),
+        }
+    } else {
+        Event::PointerUp {
+            x: 10.,
+            y: 20.,
+            source: PointerSource::Touch,
+            pointer_id,
+            button: PointerButton::Primary,
+            pressure: 1.0,
+            tilt_x: 0.,
+            tilt_y: 0.,
+            contact_width: 1.,
+            contact_height: 1.,
+            window_id: window(),
+            modifiers: Modifiers::default(),
+            timestamp: Duration::from_millis(pointer_id),
+        }
+    }
+}
+
+#[test]
+fn keys_down_is_true_only_when_every_key_is_down() {
+    let mut state = StateTracker::new();
+    state.handle_event(&key(Key::LeftControl, true, Modifiers::CONTROL));
+    state.handle_event(&key(Key::S, true, Modifiers::CONTROL));
+    assert!(state.keys_down(&[Key::LeftControl, Key::S]));
+    assert!(!state.keys_down(&[Key::LeftControl, Key::A]));
+    assert!(state.modifiers().control());
+
+    state.clear();
+    state.handle_event(&key(Key::LeftControl, false, Modifiers::empty()));
+    assert!(!state.keys_down(&[Key::LeftControl, Key::S]));
+    assert!(state.modifiers().is_empty());
+}
+
+#[test]
+fn modifiers_are_tracked_from_modifier_events() {
+    let mut state = StateTracker::new();
+    state.handle_event(&Event::ModifiersChanged {
+        modifiers: Modifiers::SHIFT | Modifiers::CAPS_LOCK,
+    });
+    assert!(state.modifiers().shift());
+    assert!(state.modifiers().caps_lock());
+    assert!(!state.modifiers().alt());
+
+    // Lock keys toggle when pressed, other modifiers are set while held.
+    let modifiers = Modifiers::CAPS_LOCK.after_key(Key::CapsLock, true);
+    assert!(modifiers.is_empty());
+    let modifiers = modifiers.after_key(Key::RightAlt, true);
+    assert_eq!(modifiers, Modifiers::ALT);
+    assert!(modifiers.after_key(Key::RightAlt, false).is_empty());
+}
+
+#[test]
+fn primary_button_is_pressed_until_the_last_touch_ends() {
+    let mut state = StateTracker::new();
+    state.handle_event(&touch(1, true));
+    state.handle_event(&touch(2, true));
+    state.handle_event(&touch(1, false));
+    assert!(state.pointer_button(PointerButton::Primary));
+    assert!(!state.pointer_button_released(PointerButton::Primary));
+    assert_eq!(state.touches().len(), 1);
+
+    state.handle_event(&touch(2, false));
+    assert!(!state.pointer_button(PointerButton::Primary));
+    assert!(state.pointer_button_released(PointerButton::Primary));
+    assert!(state.touches().is_empty());
+}
+
+#[test]
+fn touches_are_tracked_by_pointer_id() {
+    let mut state = StateTracker::new();
+    state.handle_event(&touch(1, true));
+    state.handle_event(&touch(2, true));
+    state.handle_event(&Event::PointerMoved {
+        x: 30.,
+        y: 40.,
+        source: PointerSource::Touch,
+        pointer_id: 2,
+        pressure: 0.5,
+        tilt_x: 0.,
+        tilt_y: 0.,
+        contact_width: 1.,
+        contact_height: 1.,
+        window_id: window(),
+        timestamp: Duration::from_millis(3),
+    });
+
+    let touch = state.touch(2).unwrap();
+    assert_eq!((touch.x, touch.y, touch.pressure), (30., 40., 0.5));
+    assert_eq!(state.touch(1).unwrap().x, 10.);
+    let ids: Vec<u64> = state.touches().iter().map(|t| t.pointer_id).collect();
+    assert_eq!(ids, [1, 2]);
+}
+
+#[test]
+fn input_is_tracked_per_window() {
+    let mut state = StateTracker::new();
+    let other_window = WindowId::new(2 as *mut _);
+    state.handle_event(&key(Key::A, true, Modifiers::empty()));
+    assert!(state.key_in_window(window(), Key::A));
+    assert!(state.key_down_in_window(window(), Key::A));
+    assert!(!state.key_in_window(other_window, Key::A));
+
+    state.handle_event(&touch(1, true));
+    assert!(state.pointer_button_in_window(window(), PointerButton::Primary));
+    assert!(!state.pointer_button_in_window(other_window, PointerButton::Primary));
+    assert_eq!(state.pointer_position_in_window(other_window), None);
+}
+
+#[test]
+fn only_tracked_windows_have_state() {
+    let mut state = StateTracker::new();
+    let window_id = window();
+    state.handle_event(&Event::WindowMinimized { window_id });
+    assert_eq!(state.window_state(window_id), None);
+
+    state.add_window(window_id, WindowState::default());
+    state.handle_event(&Event::WindowMinimized { window_id });
+    assert!(state.window_state(window_id).unwrap().minimized);
+
+    state.remove_window(window_id);
+    assert_eq!(state.window_state(window_id), None);
+}
@@ -0,0 +1,95 @@
+// Run with `cargo test --no-default-features --features headless`
+use kapp::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Runs the event loop until it's idle and returns the timers that were sent,
+/// with `None` for each `Event::Wake`, which marks a point in the queue.
+fn run_until_idle(event_loop: EventLoop) -> Vec<Option<TimerId>> {
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let received = sent.clone();
+    event_loop.run(move |event| match event {
+        Event::Timer { timer_id } => received.borrow_mut().push(Some(timer_id)),
+        Event::Wake => received.borrow_mut().push(None),
+        _ => {}
+    });
+    let sent = sent.borrow().clone();
+    sent
+}
+
+fn advance_millis(milliseconds: u64) {
+    advance_time(Duration::from_millis(milliseconds));
+}
+
+#[test]
+fn one_shot_timer_is_sent_once_when_due() {
+    let (app, event_loop) = initialize();
+    let timer = app.set_timer(Duration::from_millis(500), false);
+
+    advance_millis(400);
+    queue_event(Event::Wake);
+    advance_millis(100);
+    queue_event(Event::Wake);
+    advance_millis(1000);
+
+    assert_eq!(run_until_idle(event_loop), [None, Some(timer), None]);
+}
+
+#[test]
+fn repeating_timer_is_sent_each_time_it_is_due() {
+    let (app, event_loop) = initialize();
+    let slow = app.set_timer(Duration::from_millis(300), true);
+    let fast = app.set_timer(Duration::from_millis(200), true);
+
+    advance_millis(700);
+    queue_event(Event::Wake);
+    advance_millis(200);
+
+    assert_eq!(
+        run_until_idle(event_loop),
+        [
+            Some(fast), // 200
+            Some(slow), // 300
+            Some(fast), // 400
+            Some(slow), // 600
+            Some(fast), // 600
+            None,
+            Some(fast), // 800
+            Some(slow), // 900
+        ]
+    );
+}
+
+#[test]
+fn cancelled_timer_is_not_sent_after_it_is_queued() {
+    let (app, event_loop) = initialize();
+    let cancelled = app.set_timer(Duration::from_millis(100), true);
+    let kept = app.set_timer(Duration::from_millis(100), false);
+
+    advance_millis(250);
+    app.cancel_timer(cancelled);
+    advance_millis(100);
+
+    assert_eq!(run_until_idle(event_loop), [Some(kept)]);
+}
+
+#[test]
+fn user_events_are_sent_while_a_timer_is_pending() {
+    let (app, event_loop) = initialize();
+    // The timer isn't due, so only the user event is sent.
+    app.set_timer(Duration::from_millis(500), false);
+    let proxy = app.proxy();
+    std::thread::spawn(move || proxy.send_user_event(Box::new(7u32)))
+        .join()
+        .unwrap();
+
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let received = sent.clone();
+    event_loop.run(move |event| match event {
+        Event::User { data } => received.borrow_mut().push(data.take_as::<u32>()),
+        Event::Timer { .. } => received.borrow_mut().push(None),
+        _ => {}
+    });
+    assert_eq!(*sent.borrow(), [Some(7)]);
+}
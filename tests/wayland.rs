@@ -0,0 +1,91 @@
+// Runs the Wayland backend against weston's headless backend, started for the test.
+// The test is skipped if weston isn't installed.
+#![cfg(all(target_os = "linux", not(any(feature = "headless", feature = "SDL"))))]
+use kapp::*;
+use std::cell::RefCell;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::rc::Rc;
+use std::time::Duration;
+
+const SOCKET: &str = "kapp-test";
+
+/// Stops the compositor and removes its runtime directory when the test ends.
+struct Weston {
+    child: Child,
+    runtime_dir: PathBuf,
+}
+
+impl Drop for Weston {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.runtime_dir);
+    }
+}
+
+/// Starts weston in a new runtime directory and points the program at it.
+fn start_weston() -> Option<Weston> {
+    // Wayland requires the runtime directory to only be accessible by the user.
+    let runtime_dir = std::env::temp_dir().join(format!("kapp-weston-{}", std::process::id()));
+    std::fs::create_dir_all(&runtime_dir).ok()?;
+    std::fs::set_permissions(&runtime_dir, std::fs::Permissions::from_mode(0o700)).ok()?;
+    let child = Command::new("weston")
+        .args(["--backend=headless-backend.so", "--idle-time=0"])
+        .arg(format!("--socket={}", SOCKET))
+        .env("XDG_RUNTIME_DIR", &runtime_dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let weston = Weston { child, runtime_dir };
+
+    // The socket is created once the compositor is ready for clients.
+    let socket = weston.runtime_dir.join(SOCKET);
+    for _ in 0..100 {
+        if socket.exists() {
+            std::env::set_var("XDG_RUNTIME_DIR", &weston.runtime_dir);
+            std::env::set_var("WAYLAND_DISPLAY", SOCKET);
+            return Some(weston);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    None
+}
+
+#[test]
+fn window_opens_draws_and_closes() {
+    let _weston = match start_weston() {
+        Some(weston) => weston,
+        None => {
+            eprintln!("Skipping the Wayland test because weston couldn't be started");
+            return;
+        }
+    };
+
+    let (app, event_loop) = initialize();
+    let window = app.new_window().title("kapp Wayland test").build().unwrap();
+    let window_id = window.id;
+    window.request_redraw();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        let mut window = Some(window);
+        event_loop.run(move |event| {
+            if let Event::Draw { .. } = event {
+                // Closes the window once it's drawn.
+                window.take();
+                app.quit();
+            }
+            events.borrow_mut().push(event);
+        });
+    }
+
+    let events = events.borrow();
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, Event::Draw { window_id: id } if *id == window_id)));
+    assert!(matches!(events.last(), Some(Event::Quit)));
+}
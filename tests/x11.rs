@@ -0,0 +1,74 @@
+// Runs the X11 backend against an Xvfb server started for the test.
+// The test is skipped if Xvfb isn't installed.
+#![cfg(all(target_os = "linux", not(any(feature = "headless", feature = "SDL"))))]
+use kapp::*;
+use std::cell::RefCell;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::rc::Rc;
+
+/// Stops the X server when the test ends.
+struct Xvfb(Child);
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Starts Xvfb on a free display and points the program at it.
+fn start_xvfb() -> Option<Xvfb> {
+    // Xvfb writes the display it picked to the file descriptor once it's ready.
+    let child = Command::new("Xvfb")
+        .args(["-displayfd", "1", "-nolisten", "tcp"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let mut xvfb = Xvfb(child);
+    let mut display = String::new();
+    BufReader::new(xvfb.0.stdout.take()?)
+        .read_line(&mut display)
+        .ok()?;
+    std::env::set_var("DISPLAY", format!(":{}", display.trim()));
+    // Otherwise a Wayland session would be used instead.
+    std::env::remove_var("WAYLAND_DISPLAY");
+    Some(xvfb)
+}
+
+#[test]
+fn window_opens_draws_and_closes() {
+    let _xvfb = match start_xvfb() {
+        Some(xvfb) => xvfb,
+        None => {
+            eprintln!("Skipping the X11 test because Xvfb isn't installed");
+            return;
+        }
+    };
+
+    let (app, event_loop) = initialize();
+    let window = app.new_window().title("kapp X11 test").build().unwrap();
+    let window_id = window.id;
+    window.request_redraw();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    {
+        let events = events.clone();
+        let mut window = Some(window);
+        event_loop.run(move |event| {
+            if let Event::Draw { .. } = event {
+                // Closes the window once it's drawn.
+                window.take();
+                app.quit();
+            }
+            events.borrow_mut().push(event);
+        });
+    }
+
+    let events = events.borrow();
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, Event::Draw { window_id: id } if *id == window_id)));
+    assert!(matches!(events.last(), Some(Event::Quit)));
+}